@@ -0,0 +1,18 @@
+use serde::{de, Deserialize, Deserializer};
+use std::str::FromStr;
+
+/// Deserializes a list of strings using the same format accepted
+/// on the command line (e.g. `<column name>,<delimiter>`)
+pub fn deserialize_cmd_inputs<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| {
+            s.parse::<T>()
+                .map_err(|err| de::Error::custom(format!("\"{}\": {}", s, err)))
+        })
+        .collect()
+}
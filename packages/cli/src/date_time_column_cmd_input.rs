@@ -0,0 +1,45 @@
+use sds_core::data_block::{DateTimeColumnSpec, DateTimeGranularity};
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+const HOUR_BUCKET_PREFIX: &str = "hour_bucket=";
+
+const FORMAT_ERROR: &str = "wrong format, expected: <column name>,<year|quarter|month|week|day_of_week|hour_bucket=<size>>[,<input format>]";
+
+#[derive(Debug)]
+pub struct DateTimeColumnCmdInput {
+    pub column_name: String,
+    pub spec: DateTimeColumnSpec,
+}
+
+impl FromStr for DateTimeColumnCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, DELIMITER);
+
+        if let (Some(column_name), Some(granularity)) = (parts.next(), parts.next()) {
+            if !column_name.is_empty() {
+                let granularity = match granularity.trim().to_lowercase().as_str() {
+                    "year" => DateTimeGranularity::Year,
+                    "quarter" => DateTimeGranularity::Quarter,
+                    "month" => DateTimeGranularity::Month,
+                    "week" => DateTimeGranularity::Week,
+                    "day_of_week" => DateTimeGranularity::DayOfWeek,
+                    g if g.starts_with(HOUR_BUCKET_PREFIX) => DateTimeGranularity::HourBucket(
+                        g[HOUR_BUCKET_PREFIX.len()..]
+                            .parse()
+                            .map_err(|_| FORMAT_ERROR.to_owned())?,
+                    ),
+                    _ => return Err(FORMAT_ERROR.to_owned()),
+                };
+                return Ok(DateTimeColumnCmdInput {
+                    column_name: column_name.to_owned(),
+                    spec: DateTimeColumnSpec::new(parts.next().map(|f| f.to_owned()), granularity),
+                });
+            }
+        }
+        Err(FORMAT_ERROR.to_owned())
+    }
+}
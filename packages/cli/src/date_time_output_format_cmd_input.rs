@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+#[derive(Debug)]
+pub struct DateTimeOutputFormatCmdInput {
+    pub column_name: String,
+    pub output_format: String,
+}
+
+impl FromStr for DateTimeOutputFormatCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((column_name, output_format)) = s.split_once(DELIMITER) {
+            if !column_name.is_empty() && !output_format.is_empty() {
+                return Ok(DateTimeOutputFormatCmdInput {
+                    column_name: column_name.to_owned(),
+                    output_format: output_format.to_owned(),
+                });
+            }
+        }
+        Err("wrong format, expected: <column name>,<output format>".to_owned())
+    }
+}
@@ -0,0 +1,181 @@
+use sds_core::processing::evaluator::{
+    privacy::PrivacyEvaluationReport, EvaluationReport, WorkloadAccuracy,
+};
+use std::{io::Error, path::Path};
+
+const TSV_DELIMITER: char = '\t';
+
+/// Writes the evaluation report to `output_dir`, either as a single json file
+/// or as a set of tsv files. Returns the paths of the written files
+/// # Arguments
+/// * `report` - Evaluation report to be written
+/// * `output_dir` - Directory where the files are written (created if needed)
+/// * `json` - Whether to write a single json file instead of tsv files
+pub fn write_evaluation_report(
+    report: &mut EvaluationReport,
+    output_dir: &Path,
+    json: bool,
+) -> Result<Vec<String>, Error> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let output_path = |file_name: &str| output_dir.join(file_name).to_string_lossy().into_owned();
+
+    if json {
+        let report_path = output_path("evaluation_report.json");
+
+        report.write_to_json(&report_path)?;
+        return Ok(vec![report_path]);
+    }
+
+    let summary_path = output_path("summary.tsv");
+    let metrics_by_length_path = output_path("metrics_by_length.tsv");
+    let preservation_by_count_path = output_path("preservation_by_count.tsv");
+    let preservation_by_length_path = output_path("preservation_by_length.tsv");
+    let rare_combinations_path = output_path("rare_combinations.tsv");
+
+    report.write_summary(&summary_path, TSV_DELIMITER)?;
+    report.write_metrics_by_length(&metrics_by_length_path, TSV_DELIMITER)?;
+    report
+        .preservation_by_count
+        .write_preservation_by_count(&preservation_by_count_path, TSV_DELIMITER)?;
+    report
+        .preservation_by_length
+        .write_preservation_by_length(&preservation_by_length_path, TSV_DELIMITER)?;
+    report
+        .rare_combinations
+        .write_rare_combinations(&rare_combinations_path, TSV_DELIMITER)?;
+
+    Ok(vec![
+        summary_path,
+        metrics_by_length_path,
+        preservation_by_count_path,
+        preservation_by_length_path,
+        rare_combinations_path,
+    ])
+}
+
+/// Writes the privacy evaluation report to `output_dir`, either as a single json file
+/// or as a set of tsv files. Returns the paths of the written files
+/// # Arguments
+/// * `report` - Privacy evaluation report to be written
+/// * `output_dir` - Directory where the files are written (created if needed)
+/// * `json` - Whether to write a single json file instead of tsv files
+pub fn write_privacy_evaluation_report(
+    report: &PrivacyEvaluationReport,
+    output_dir: &Path,
+    json: bool,
+) -> Result<Vec<String>, Error> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let output_path = |file_name: &str| output_dir.join(file_name).to_string_lossy().into_owned();
+
+    if json {
+        let report_path = output_path("privacy_report.json");
+
+        report.write_to_json(&report_path)?;
+        return Ok(vec![report_path]);
+    }
+
+    let summary_path = output_path("privacy_summary.tsv");
+    let attribute_inference_path = output_path("attribute_inference.tsv");
+
+    report.write_summary(&summary_path, TSV_DELIMITER)?;
+    report.write_attribute_inference(&attribute_inference_path, TSV_DELIMITER)?;
+
+    Ok(vec![summary_path, attribute_inference_path])
+}
+
+/// Writes the workload accuracy to `output_dir`, either as a json or a tsv file.
+/// Returns the path of the written file
+/// # Arguments
+/// * `workload_accuracy` - Accuracy of each query in the workload
+/// * `output_dir` - Directory where the file is written (created if needed)
+/// * `json` - Whether to write a json file instead of a tsv file
+pub fn write_workload_accuracy(
+    workload_accuracy: &WorkloadAccuracy,
+    output_dir: &Path,
+    json: bool,
+) -> Result<Vec<String>, Error> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let output_path = |file_name: &str| output_dir.join(file_name).to_string_lossy().into_owned();
+
+    if json {
+        let workload_accuracy_path = output_path("workload_accuracy.json");
+
+        workload_accuracy.write_to_json(&workload_accuracy_path)?;
+        return Ok(vec![workload_accuracy_path]);
+    }
+
+    let workload_accuracy_path = output_path("workload_accuracy.tsv");
+
+    workload_accuracy.write_workload_accuracy(&workload_accuracy_path, TSV_DELIMITER, ",")?;
+
+    Ok(vec![workload_accuracy_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sds_core::{
+        data_block::{CsvDataBlockCreator, DataBlockCreateOptions, DataBlockCreator},
+        processing::{aggregator::Aggregator, evaluator::Evaluator},
+        utils::reporting::LoggerProgressReporter,
+    };
+    use std::fs::{read_to_string, remove_dir_all};
+
+    fn test_evaluation_report() -> EvaluationReport {
+        let records: String = (0..100)
+            .map(|i| format!("{},{},{}\n", i % 2, i % 3, i % 5))
+            .collect();
+        let data_block = CsvDataBlockCreator::create(
+            Ok(csv::ReaderBuilder::new().from_reader(format!("A,B,C\n{}", records).as_bytes())),
+            &DataBlockCreateOptions::default(),
+        )
+        .unwrap();
+        let aggregated_data = Aggregator::new(data_block)
+            .aggregate(3, &mut None::<LoggerProgressReporter>)
+            .unwrap();
+
+        // synthetic data is the same as the sensitive one
+        Evaluator::default().calc_evaluation_report(
+            &aggregated_data,
+            &aggregated_data,
+            10,
+            ";",
+            true,
+        )
+    }
+
+    #[test]
+    fn validate_evaluation_report_outputs() {
+        let output_dir =
+            std::env::temp_dir().join(format!("sds-cli-evaluation-{}", std::process::id()));
+        let mut report = test_evaluation_report();
+
+        assert!(report.record_expansion_percentage == 0.0);
+        assert!(report.number_of_fabricated_combinations == 0);
+        assert!(report.number_of_suppressed_combinations == 0);
+
+        let tsv_outputs = write_evaluation_report(&mut report, &output_dir, false).unwrap();
+        let json_outputs = write_evaluation_report(&mut report, &output_dir, true).unwrap();
+
+        assert!(tsv_outputs.len() == 5);
+        assert!(tsv_outputs.iter().all(|path| path.ends_with(".tsv")));
+        assert!(tsv_outputs
+            .iter()
+            .all(|path| !read_to_string(path).unwrap().is_empty()));
+        assert!(read_to_string(&tsv_outputs[0])
+            .unwrap()
+            .contains("record_expansion_percentage\t0"));
+        assert!(json_outputs.len() == 1);
+
+        let json_report: serde_json::Value =
+            serde_json::from_str(&read_to_string(&json_outputs[0]).unwrap()).unwrap();
+
+        assert!(json_report["reporting_length"] == 3);
+        assert!(json_report["rare_combinations"].is_array());
+
+        remove_dir_all(output_dir).unwrap();
+    }
+}
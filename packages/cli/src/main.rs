@@ -1,17 +1,42 @@
+mod cmd_input_deserializer;
+mod date_time_column_cmd_input;
+mod date_time_output_format_cmd_input;
+mod evaluation_output;
 mod multi_value_column_cmd_input;
+mod numeric_binning_cmd_input;
+mod pipeline_config;
+mod pipeline_runner;
+mod sensitive_data_cmd_input;
 
-use log::{error, log_enabled, trace, Level::Debug};
-use multi_value_column_cmd_input::MultiValueColumnCmdInput;
+use date_time_output_format_cmd_input::DateTimeOutputFormatCmdInput;
+use evaluation_output::{
+    write_evaluation_report, write_privacy_evaluation_report, write_workload_accuracy,
+};
+use log::{error, info, log_enabled, trace, warn, Level::Debug};
+use pipeline_runner::PipelineRunner;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlockCreator},
-    dp::{DpParameters, NoisyCountThreshold},
+    data_block::{
+        data_derived_binned_columns, CsvDataBlockCreator, DataBlock, DataBlockCreateOptions,
+        DataBlockCreator, DataBlockHeadersSlice, NumericBinOutputMode, SubjectRecordsMode,
+    },
+    dp::{
+        AccuracyMode, DpParameters, DpPlanTarget, DpPlanner, NoisyCountThreshold,
+        DEFAULT_RDP_ORDERS,
+    },
     processing::{
-        aggregator::{AggregatedData, Aggregator},
-        generator::{Generator, OversamplingParameters},
+        aggregator::{
+            AggregatedData, AggregatedDataRelease, Aggregator, AGGREGATED_DATA_RELEASE_PREFIX,
+        },
+        evaluator::{privacy::PrivacyEvaluator, Evaluator},
+        generator::{
+            ConstraintRules, GeneratedData, Generator, OversamplingParameters, ParentChildLink,
+            SynthesisConditions,
+        },
     },
     utils::{reporting::LoggerProgressReporter, threading::set_number_of_threads},
 };
-use std::{process, sync::Arc};
+use sensitive_data_cmd_input::SensitiveDataCmdInput;
+use std::{collections::HashMap, fs::File, io::Read, path::Path, process, sync::Arc};
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
@@ -39,6 +64,21 @@ enum Command {
         )]
         long_form: bool,
 
+        #[structopt(
+            long = "numeric-bins-output",
+            help = "how binned numeric columns are written to the synthetic microdata file",
+            possible_values = &["label", "representative", "uniform"],
+            case_insensitive = true,
+            default_value = "label"
+        )]
+        numeric_bins_output: String,
+
+        #[structopt(
+            long = "date-time-output-formats",
+            help = "<column name>,<output format> used to render generalized date/time columns (can be set multiple times)"
+        )]
+        date_time_output_formats: Vec<DateTimeOutputFormatCmdInput>,
+
         #[structopt(
             long = "cache-max-size",
             help = "maximum cache size (# of combinations)",
@@ -49,7 +89,7 @@ enum Command {
         #[structopt(
             long = "mode",
             help = "synthesis mode",
-            possible_values = &["row_seeded", "unseeded", "value_seeded", "aggregate_seeded"],
+            possible_values = &["row_seeded", "unseeded", "value_seeded", "aggregate_seeded", "marginal_model"],
             case_insensitive = true,
             default_value = "row_seeded"
         )]
@@ -57,7 +97,7 @@ enum Command {
 
         #[structopt(
             long = "aggregates-json",
-            help = "json file generated on the aggregate step (optional on the \"value_seeded\" mode, required on \"aggregate_seeded\" mode)"
+            help = "json (binary or release) file generated on the aggregate step (optional on the \"value_seeded\" mode, required on \"aggregate_seeded\" and \"marginal_model\" modes)"
         )]
         aggregates_json: Option<String>,
 
@@ -98,10 +138,89 @@ enum Command {
 
         #[structopt(
             long = "target-number-of-records",
-            help = "total number of records do be synthesized (\"aggregate_seeded\" mode), if not provided, sample from all available counts",
+            help = "total number of records do be synthesized (\"aggregate_seeded\" and \"marginal_model\" modes), if not provided, sample from all available counts (or the number of aggregated records on \"marginal_model\" mode)",
             requires = "aggregates-json"
         )]
         target_number_of_records: Option<usize>,
+
+        #[structopt(
+            long = "max-clique-size",
+            help = "maximum number of columns on each clique of the graphical model fit to the aggregates (\"marginal_model\" mode), from 1 to 3, defaults to 2",
+            requires = "aggregates-json"
+        )]
+        max_clique_size: Option<usize>,
+
+        #[structopt(
+            long = "required-values",
+            help = "only synthesize records containing all these values, formatted as {header_name}:{value} (\"row_seeded\" and \"aggregate_seeded\" modes)",
+            conflicts_with = "target-counts"
+        )]
+        required_values: Option<Vec<String>>,
+
+        #[structopt(
+            long = "target-counts",
+            help = "number of records to be synthesized containing each value, formatted as {header_name}:{value}={count} (\"row_seeded\" and \"aggregate_seeded\" modes)"
+        )]
+        target_counts: Option<Vec<String>>,
+
+        #[structopt(
+            long = "constraint-rules",
+            help = "file with rules forbidding invalid combinations, one per line as \"deny {header_name}:{value} & {header_name}:{value}\", \"if {header_name}:{value} then {header_name}:{value}\" or \"column {header_name} requires {header_name}\" (enforced on \"row_seeded\", \"value_seeded\" and \"aggregate_seeded\" modes, violations are reported for all modes)"
+        )]
+        constraint_rules: Option<String>,
+    },
+    GenerateParentChild {
+        #[structopt(
+            long = "child-path",
+            help = "sensitive microdata path of the child table (the sensitive microdata is the parent table)"
+        )]
+        child_path: String,
+
+        #[structopt(
+            long = "child-delimiter",
+            help = "csv delimiter for the child table microdata file",
+            default_value = "\t"
+        )]
+        child_delimiter: String,
+
+        #[structopt(
+            long = "child-use-columns",
+            help = "use this column of the child table (can be set multiple times)"
+        )]
+        child_use_columns: Vec<String>,
+
+        #[structopt(
+            long = "foreign-key",
+            help = "column of the child table referencing the parent key (the parent key is set with --subject-id)"
+        )]
+        foreign_key: String,
+
+        #[structopt(
+            long = "max-children-per-parent",
+            help = "maximum number of children of each parent, children over it are ignored",
+            default_value = "10"
+        )]
+        max_children_per_parent: usize,
+
+        #[structopt(long = "synthetic-path", help = "synthetic parent table path")]
+        synthetic_path: String,
+
+        #[structopt(long = "child-synthetic-path", help = "synthetic child table path")]
+        child_synthetic_path: String,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the generated synthetic microdata files",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "cache-max-size",
+            help = "maximum cache size (# of combinations)",
+            default_value = "100000"
+        )]
+        cache_max_size: usize,
     },
     Aggregate {
         #[structopt(long = "aggregates-path", help = "generated aggregates file path")]
@@ -195,11 +314,294 @@ enum Command {
         )]
         number_of_records_epsilon_proportion: Option<f64>,
 
+        #[structopt(
+            long = "max-records-per-subject",
+            help = "maximum number of records sampled from each subject when --keep-subject-records is set (default is 1)",
+            requires = "dp"
+        )]
+        max_records_per_subject: Option<usize>,
+
+        #[structopt(
+            long = "least-squares-consistency",
+            help = "project the noisy counts onto a consistent set with weighted least squares (reduces the error without spending extra budget)",
+            requires = "dp"
+        )]
+        least_squares_consistency: bool,
+
+        #[structopt(
+            long = "discrete-noise",
+            help = "sample noise exactly from the discrete gaussian/laplace instead of floating point distributions",
+            requires = "dp"
+        )]
+        discrete_noise: bool,
+
+        #[structopt(
+            long = "candidate-min-upper-bound",
+            help = "only generate candidate combinations whose count upper bound (derived from the noisy sub combination counts) is at least this value",
+            requires = "dp"
+        )]
+        candidate_min_upper_bound: Option<f64>,
+
+        #[structopt(
+            long = "max-candidates-per-len",
+            help = "maximum number of candidate combinations generated for each combination length, keeping the highest count upper bounds",
+            requires = "dp"
+        )]
+        max_candidates_per_len: Option<usize>,
+
+        #[structopt(
+            long = "column-weights",
+            help = "privacy budget weight by column, formatted as {header_name}={weight} (marginals involving columns with higher weights receive less noise)",
+            requires = "dp"
+        )]
+        column_weights: Option<Vec<String>>,
+
+        #[structopt(
+            long = "excluded-column-pairs",
+            help = "pairs of columns that never appear together in the same combination, formatted as {header_name};{header_name}",
+            requires = "dp"
+        )]
+        excluded_column_pairs: Option<Vec<String>>,
+
+        #[structopt(
+            long = "confidence-level",
+            help = "write the approximate confidence interval of each noisy count to the aggregates file with this confidence level (e.g. 0.95), the intervals do not describe counts made consistent with --least-squares-consistency",
+            requires = "dp",
+            conflicts_with = "least-squares-consistency"
+        )]
+        confidence_level: Option<f64>,
+
         #[structopt(
             long = "aggregates-json",
             help = "serialize aggregated data to json file (sensitive)"
         )]
         aggregates_json: Option<String>,
+
+        #[structopt(
+            long = "aggregates-binary",
+            help = "serialize aggregated data to a compact binary file (sensitive), which can be used wherever an aggregates json file is expected"
+        )]
+        aggregates_binary: Option<String>,
+
+        #[structopt(
+            long = "aggregates-compression",
+            help = "compression applied to the aggregates binary file",
+            possible_values = &["none", "gzip", "zstd"],
+            case_insensitive = true,
+            default_value = "zstd",
+            requires = "aggregates-binary"
+        )]
+        aggregates_compression: String,
+
+        #[structopt(
+            long = "aggregates-release",
+            help = "serialize only the DP-safe fields of the aggregated data to a versioned json release, which can be used wherever an aggregates json file is expected for synthesis",
+            requires = "dp"
+        )]
+        aggregates_release: Option<String>,
+    },
+    Plan {
+        #[structopt(long = "plan-path", help = "planned DP parameters json file path")]
+        plan_path: String,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of attribute combination for which aggregate counts are precomputed"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "number-of-records",
+            help = "expected number of records (only the order of magnitude matters, the sensitive data is not read)"
+        )]
+        number_of_records: usize,
+
+        #[structopt(long = "number-of-columns", help = "number of columns of each record")]
+        number_of_columns: usize,
+
+        #[structopt(
+            long = "values-per-column",
+            help = "average number of distinct values per column"
+        )]
+        values_per_column: f64,
+
+        #[structopt(
+            long = "accuracy-mode",
+            help = "how to split the budget across combination lengths",
+            possible_values = &["prioritize_long_combinations", "prioritize_short_combinations", "balanced"],
+            case_insensitive = true,
+            default_value = "balanced",
+            conflicts_with = "sigma-proportions"
+        )]
+        accuracy_mode: String,
+
+        #[structopt(
+            long = "sigma-proportions",
+            help = "custom proportion to split sigma across combination lengths"
+        )]
+        sigma_proportions: Option<Vec<f64>>,
+
+        #[structopt(
+            long = "noise-epsilon",
+            help = "privacy budget to solve the noise standard deviation by combination length from",
+            required_unless = "noise-standard-deviations",
+            conflicts_with = "noise-standard-deviations"
+        )]
+        noise_epsilon: Option<f64>,
+
+        #[structopt(
+            long = "noise-standard-deviations",
+            help = "maximum noise standard deviation by combination length (starting at 1-counts, values <= 0 are not constrained) to solve the privacy budget from"
+        )]
+        noise_standard_deviations: Option<Vec<f64>>,
+
+        #[structopt(
+            long = "noise-delta",
+            help = "delta used to generate noise that will be added to the aggregate counts [default: 1/(ln(number of records) * number of records)]"
+        )]
+        noise_delta: Option<f64>,
+
+        #[structopt(
+            long = "sensitivities-epsilon-proportion",
+            help = "proportion of epsilon used to generate noise during sensitivity filter selection"
+        )]
+        sensitivities_epsilon_proportion: f64,
+
+        #[structopt(
+            long = "number-of-records-epsilon-proportion",
+            help = "proportion of epsilon used to add noise to the protected number of records in the aggregated data (default is 0.005)"
+        )]
+        number_of_records_epsilon_proportion: Option<f64>,
+
+        #[structopt(
+            long = "noise-threshold-type",
+            help = "threshold type, could be fixed or adaptive",
+            possible_values = &["fixed", "adaptive"],
+            case_insensitive = true,
+            default_value = "fixed",
+        )]
+        noise_threshold_type: String,
+
+        #[structopt(
+            long = "noise-threshold-values",
+            help = "value used as the count threshold filter, starting at 2-counts (meaning will change based on \"noise-threshold-type\")"
+        )]
+        noise_threshold_values: Option<Vec<f64>>,
+
+        #[structopt(
+            long = "max-records-per-subject",
+            help = "maximum number of records sampled from each subject for user-level DP (default is 1)"
+        )]
+        max_records_per_subject: Option<usize>,
+
+        #[structopt(
+            long = "discrete-noise",
+            help = "plan for noise sampled from the discrete gaussian instead of floating point distributions"
+        )]
+        discrete_noise: bool,
+    },
+    Evaluate {
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata path (aggregated with the same reporting length as the sensitive data)",
+            required_unless = "synthetic-aggregates-json"
+        )]
+        synthetic_path: Option<String>,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "sensitive-aggregates-json",
+            help = "json (or binary) file with the sensitive aggregated data (generated on the aggregate step with --not-protect), used instead of aggregating the sensitive microdata"
+        )]
+        sensitive_aggregates_json: Option<String>,
+
+        #[structopt(
+            long = "synthetic-aggregates-json",
+            help = "json (or binary) file with the synthetic aggregated data, used instead of aggregating the synthetic microdata"
+        )]
+        synthetic_aggregates_json: Option<String>,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of attribute combination evaluated when aggregating the sensitive microdata (0 means all)",
+            default_value = "0"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "output-dir",
+            help = "directory where the evaluation results are written"
+        )]
+        output_dir: String,
+
+        #[structopt(
+            long = "output-format",
+            help = "format of the evaluation results",
+            possible_values = &["tsv", "json"],
+            case_insensitive = true,
+            default_value = "tsv"
+        )]
+        output_format: String,
+
+        #[structopt(
+            long = "combination-delimiter",
+            help = "delimiter used to join the attributes of the reported rare combinations",
+            default_value = ";"
+        )]
+        combination_delimiter: String,
+
+        #[structopt(
+            long = "not-protect",
+            help = "do not round down the sensitive counts of the reported rare combinations to the nearest multiple of resolution"
+        )]
+        not_protect: bool,
+
+        #[structopt(
+            long = "privacy",
+            help = "also run privacy attacks (distance to closest record, membership and attribute inference) comparing the synthetic microdata with the sensitive microdata",
+            requires = "synthetic-path"
+        )]
+        privacy: bool,
+
+        #[structopt(
+            long = "holdout-path",
+            help = "sensitive records left out of synthesis (read with the same options as the sensitive microdata), enabling the membership inference attack",
+            requires = "privacy"
+        )]
+        holdout_path: Option<String>,
+
+        #[structopt(
+            long = "quasi-identifiers",
+            help = "column known by the attacker on the attribute inference attack (can be set multiple times)",
+            requires = "privacy"
+        )]
+        quasi_identifiers: Vec<String>,
+
+        #[structopt(
+            long = "target-columns",
+            help = "column the attacker tries to infer on the attribute inference attack (can be set multiple times, defaults to every column that is not a quasi-identifier)",
+            requires = "quasi-identifiers"
+        )]
+        target_columns: Vec<String>,
+
+        #[structopt(
+            long = "workload-query",
+            help = "marginal query whose sensitive and synthetic answers are compared for every cell, formatted as comma separated column names (e.g. \"age,region,diagnosis\", can be set multiple times)"
+        )]
+        workload_queries: Vec<String>,
+    },
+    Run {
+        #[structopt(
+            long = "config",
+            help = "pipeline config file (toml, or json if the extension is .json) declaring the input, DP parameters and the steps to run (aggregate, protect, generate and evaluate)"
+        )]
+        config: String,
     },
 }
 
@@ -210,46 +612,20 @@ struct Cli {
     cmd: Command,
 
     /* common parameters */
-    #[structopt(long = "sensitive-path", help = "sensitive microdata path")]
-    sensitive_path: String,
-
-    #[structopt(
-        long = "sensitive-delimiter",
-        help = "csv delimiter for the sensitive microdata file",
-        default_value = "\t"
-    )]
-    sensitive_delimiter: String,
+    #[structopt(flatten)]
+    sensitive_data: SensitiveDataCmdInput,
 
     #[structopt(
         long = "resolution",
-        help = "minimum threshold to build/evaluate synthetic microdata"
+        help = "minimum threshold to build/evaluate synthetic microdata (required by all subcommands, except run)"
     )]
-    resolution: usize,
+    resolution: Option<usize>,
 
     #[structopt(
-        long = "record-limit",
-        help = "take only these first records (0 means all)",
-        default_value = "0"
+        long = "seed",
+        help = "seed used for noise generation and data synthesis, the same seed and parameters produce the same outputs (default is a random seed)"
     )]
-    record_limit: usize,
-
-    #[structopt(long = "subject-id", help = "column indicating the subject id")]
-    subject_id: Option<String>,
-
-    #[structopt(
-        long = "use-columns",
-        help = "use this column (can be set multiple times)"
-    )]
-    use_columns: Vec<String>,
-
-    #[structopt(long = "multi-value-columns", help = "<column name>,<delimiter>")]
-    multi_value_columns: Vec<MultiValueColumnCmdInput>,
-
-    #[structopt(
-        long = "sensitive-zeros",
-        help = "columns where zeros should not be ignored (can be set multiple times)"
-    )]
-    sensitive_zeros: Vec<String>,
+    seed: Option<u64>,
 
     #[structopt(
         long = "n-threads",
@@ -274,215 +650,1035 @@ fn main() {
         set_number_of_threads(n_threads);
     }
 
-    match CsvDataBlockCreator::create(
-        csv::ReaderBuilder::new()
-            .delimiter(cli.sensitive_delimiter.chars().next().unwrap() as u8)
-            .from_path(cli.sensitive_path),
-        cli.subject_id,
-        &cli.use_columns,
-        &cli.multi_value_columns
-            .iter()
-            .map(|mvc| (mvc.column_name.clone(), mvc.attr_delimiter.clone()))
-            .collect(),
-        &cli.sensitive_zeros,
-        cli.record_limit,
-    ) {
-        Ok(data_block) => match cli.cmd {
-            Command::Generate {
-                synthetic_path,
-                synthetic_delimiter,
-                join_multi_value_columns,
-                long_form,
-                cache_max_size,
-                mode,
-                aggregates_json,
-                oversampling_ratio,
-                oversampling_tries,
-                use_synthetic_counts,
-                weight_selection_percentile,
-                aggregate_counts_scale_factor,
-                target_number_of_records,
-            } => {
-                let aggregated_data = aggregates_json.map(|json_path| {
-                    match AggregatedData::read_from_json(&json_path) {
-                        Ok(data) => Arc::new(data),
-                        Err(err) => {
-                            error!("error reading aggregates json file: {}", err);
-                            process::exit(1);
-                        }
-                    }
-                });
+    let seed = cli.seed;
 
-                if (oversampling_ratio.is_some()
-                    || oversampling_tries.is_some()
-                    || mode == "aggregate_seeded")
-                    && aggregated_data.is_none()
-                {
-                    error!("aggregates json file should be provided");
+    let resolution = cli.resolution;
+    let sensitive_data = cli.sensitive_data;
+    let required_resolution = || match resolution {
+        Some(resolution) => resolution,
+        None => {
+            error!("resolution should be provided");
+            process::exit(1);
+        }
+    };
+
+    match cli.cmd {
+        Command::Generate {
+            synthetic_path,
+            synthetic_delimiter,
+            join_multi_value_columns,
+            long_form,
+            numeric_bins_output,
+            date_time_output_formats,
+            cache_max_size,
+            mode,
+            aggregates_json,
+            oversampling_ratio,
+            oversampling_tries,
+            use_synthetic_counts,
+            weight_selection_percentile,
+            aggregate_counts_scale_factor,
+            target_number_of_records,
+            max_clique_size,
+            required_values,
+            target_counts,
+            constraint_rules,
+        } => {
+            let resolution = required_resolution();
+            let data_block = read_sensitive_data_block(&sensitive_data);
+            let aggregated_data =
+                aggregates_json.map(|json_path| Arc::new(read_aggregates_json(&json_path)));
+
+            if (oversampling_ratio.is_some()
+                || oversampling_tries.is_some()
+                || mode == "aggregate_seeded"
+                || mode == "marginal_model")
+                && aggregated_data.is_none()
+            {
+                error!("aggregates json file should be provided");
+                process::exit(1);
+            }
+
+            let oversampling_parameters =
+                if oversampling_ratio.is_some() || oversampling_tries.is_some() {
+                    Some(OversamplingParameters::new(
+                        aggregated_data.clone().unwrap(),
+                        oversampling_ratio,
+                        oversampling_tries,
+                    ))
+                } else {
+                    None
+                };
+            if (required_values.is_some() || target_counts.is_some())
+                && mode != "row_seeded"
+                && mode != "aggregate_seeded"
+            {
+                error!("conditions are only supported on \"row_seeded\" and \"aggregate_seeded\" modes");
+                process::exit(1);
+            }
+
+            let conditions = parse_synthesis_conditions(
+                match &aggregated_data {
+                    Some(aggregated_data) if mode == "aggregate_seeded" => &aggregated_data.headers,
+                    _ => &data_block.headers,
+                },
+                required_values,
+                target_counts,
+            );
+            let constraint_rules =
+                constraint_rules.map(|path| read_constraint_rules(&path, &data_block.headers));
+            let generator = match &constraint_rules {
+                Some(constraint_rules) => {
+                    Generator::new(seed).with_constraint_rules(constraint_rules.clone())
+                }
+                None => Generator::new(seed),
+            };
+            let generated_data = match mode.as_str() {
+                "unseeded" => generator.generate_unseeded(
+                    &data_block,
+                    resolution,
+                    cache_max_size,
+                    "",
+                    &mut progress_reporter,
+                ),
+                "row_seeded" => generator.generate_row_seeded_with_conditions(
+                    &data_block,
+                    resolution,
+                    cache_max_size,
+                    "",
+                    conditions,
+                    &mut progress_reporter,
+                ),
+                "value_seeded" => generator.generate_value_seeded(
+                    &data_block,
+                    resolution,
+                    cache_max_size,
+                    "",
+                    oversampling_parameters,
+                    &mut progress_reporter,
+                ),
+                "aggregate_seeded" => generator.generate_aggregate_seeded_with_conditions(
+                    "",
+                    aggregated_data.unwrap(),
+                    use_synthetic_counts,
+                    weight_selection_percentile,
+                    aggregate_counts_scale_factor,
+                    target_number_of_records,
+                    conditions,
+                    &mut progress_reporter,
+                ),
+                "marginal_model" => generator.generate_marginal_model(
+                    "",
+                    aggregated_data.unwrap(),
+                    max_clique_size,
+                    target_number_of_records,
+                    &mut progress_reporter,
+                ),
+                _ => {
+                    error!("invalid mode");
                     process::exit(1);
                 }
+            };
 
-                let oversampling_parameters =
-                    if oversampling_ratio.is_some() || oversampling_tries.is_some() {
-                        Some(OversamplingParameters::new(
-                            aggregated_data.clone().unwrap(),
-                            oversampling_ratio,
-                            oversampling_tries,
-                        ))
-                    } else {
-                        None
-                    };
-                let generator = Generator::default();
-                let generated_data = match mode.as_str() {
-                    "unseeded" => generator.generate_unseeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        &mut progress_reporter,
-                    ),
-                    "row_seeded" => generator.generate_row_seeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        &mut progress_reporter,
-                    ),
-                    "value_seeded" => generator.generate_value_seeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        oversampling_parameters,
-                        &mut progress_reporter,
+            let numeric_bins_output_mode = match numeric_bins_output.as_str() {
+                "representative" => NumericBinOutputMode::Representative,
+                "uniform" => NumericBinOutputMode::UniformSample,
+                _ => NumericBinOutputMode::Label,
+            };
+
+            if let Err(err) = generated_data.map(|mut gd| {
+                if let Some(constraint_rules) = &constraint_rules {
+                    let violations = constraint_rules.count_violations(&gd, "");
+
+                    for (rule, count) in constraint_rules
+                        .rules()
+                        .iter()
+                        .zip(violations.violations_by_rule.iter())
+                    {
+                        if *count > 0 {
+                            warn!(
+                                "{} synthetic records violate \"{}\"",
+                                count,
+                                rule.as_str_using_headers(constraint_rules.headers())
+                            );
+                        }
+                    }
+                    info!(
+                        "{} synthetic records violate the constraint rules",
+                        violations.number_of_records_with_violations
+                    );
+                }
+                gd.map_numeric_bins(numeric_bins_output_mode, seed);
+                gd.write_synthetic_data(
+                    &synthetic_path,
+                    synthetic_delimiter.chars().next().unwrap(),
+                    "",
+                    join_multi_value_columns,
+                    long_form,
+                    Some(
+                        date_time_output_formats
+                            .iter()
+                            .map(|f| (f.column_name.clone(), f.output_format.clone()))
+                            .collect(),
                     ),
-                    "aggregate_seeded" => generator.generate_aggregate_seeded(
+                )
+            }) {
+                error!("error writing output file: {}", err);
+                process::exit(1);
+            }
+        }
+        Command::GenerateParentChild {
+            child_path,
+            child_delimiter,
+            child_use_columns,
+            foreign_key,
+            max_children_per_parent,
+            synthetic_path,
+            child_synthetic_path,
+            synthetic_delimiter,
+            cache_max_size,
+        } => {
+            let resolution = required_resolution();
+            let parent_key = match &sensitive_data.subject_id {
+                Some(parent_key) if !sensitive_data.keep_subject_records => parent_key.clone(),
+                _ => {
+                    error!("the parent key should be set with --subject-id (without --keep-subject-records)");
+                    process::exit(1);
+                }
+            };
+            let parent_data_block = read_sensitive_data_block(&sensitive_data);
+            let child_data_block = match CsvDataBlockCreator::create(
+                csv::ReaderBuilder::new()
+                    .delimiter(child_delimiter.chars().next().unwrap() as u8)
+                    .from_path(&child_path),
+                &DataBlockCreateOptions {
+                    subject_id: Some(foreign_key.clone()),
+                    subject_records_mode: SubjectRecordsMode::Keep,
+                    use_columns: child_use_columns.clone(),
+                    sensitive_zeros: sensitive_data.sensitive_zeros.clone(),
+                    ..DataBlockCreateOptions::default()
+                },
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from child path: {}", err);
+                    process::exit(1);
+                }
+            };
+            let link = match ParentChildLink::new(
+                parent_data_block,
+                child_data_block,
+                &parent_key,
+                &foreign_key,
+            ) {
+                Ok(link) => link,
+                Err(err) => {
+                    error!("error linking the parent and child tables: {}", err);
+                    process::exit(1);
+                }
+            };
+            let generated_data = Generator::new(seed).generate_parent_child(
+                &link,
+                resolution,
+                cache_max_size,
+                max_children_per_parent,
+                "",
+                &mut progress_reporter,
+            );
+
+            if let Err(err) = generated_data.map(|gd| {
+                gd.parents
+                    .write_synthetic_data(
+                        &synthetic_path,
+                        synthetic_delimiter.chars().next().unwrap(),
                         "",
-                        aggregated_data.unwrap(),
-                        use_synthetic_counts,
-                        weight_selection_percentile,
-                        aggregate_counts_scale_factor,
-                        target_number_of_records,
-                        &mut progress_reporter,
-                    ),
+                        false,
+                        false,
+                        None,
+                    )
+                    .and_then(|_| {
+                        gd.children.write_synthetic_data(
+                            &child_synthetic_path,
+                            synthetic_delimiter.chars().next().unwrap(),
+                            "",
+                            false,
+                            false,
+                            None,
+                        )
+                    })
+            }) {
+                error!("error writing output file: {}", err);
+                process::exit(1);
+            }
+        }
+        Command::Aggregate {
+            aggregates_path,
+            aggregates_delimiter,
+            reporting_length,
+            not_protect,
+            records_sensitivity_path,
+            sensitivities_percentile,
+            sensitivities_epsilon_proportion,
+            dp,
+            noise_delta,
+            noise_epsilon,
+            noise_threshold_type,
+            noise_threshold_values,
+            sigma_proportions,
+            number_of_records_epsilon_proportion,
+            max_records_per_subject,
+            least_squares_consistency,
+            discrete_noise,
+            candidate_min_upper_bound,
+            max_candidates_per_len,
+            column_weights,
+            excluded_column_pairs,
+            confidence_level,
+            aggregates_json,
+            aggregates_binary,
+            aggregates_compression,
+            aggregates_release,
+        } => {
+            let resolution = required_resolution();
+            let data_block = read_sensitive_data_block(&sensitive_data);
+            let mut aggregator = Aggregator::new(data_block.clone());
+            let mut dp_parameters = None;
+            let aggregated_data = if dp {
+                let data_derived_columns =
+                    data_derived_binned_columns(&data_block.numeric_bins_metadata_map);
+
+                if !data_derived_columns.is_empty() {
+                    error!(
+                        "numeric columns binned from the data can not be aggregated with DP, use \"--numeric-bins <column name>,edges,...\" for: {}",
+                        data_derived_columns
+                            .iter()
+                            .map(|column| column.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", ")
+                    );
+                    process::exit(1);
+                }
+
+                let n_records_f64 = data_block.number_of_records() as f64;
+                let delta = noise_delta.unwrap_or(1.0 / (n_records_f64.ln() * n_records_f64));
+                let thresholds_map = noise_threshold_values
+                    .unwrap()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (i + 2, *t))
+                    .collect();
+
+                let threshold = match noise_threshold_type.as_str() {
+                    "fixed" => NoisyCountThreshold::Fixed(thresholds_map),
+                    "adaptive" => NoisyCountThreshold::Adaptive(thresholds_map),
                     _ => {
-                        error!("invalid mode");
+                        error!("invalid noise threshold type");
                         process::exit(1);
                     }
                 };
 
-                if let Err(err) = generated_data.map(|gd| {
-                    gd.write_synthetic_data(
-                        &synthetic_path,
-                        synthetic_delimiter.chars().next().unwrap(),
-                        "",
-                        join_multi_value_columns,
-                        long_form,
+                let parameters = dp_parameters.insert(DpParameters {
+                    sigma_proportions,
+                    number_of_records_epsilon_proportion,
+                    seed,
+                    max_records_per_subject,
+                    use_least_squares_consistency: Some(least_squares_consistency),
+                    use_discrete_noise: Some(discrete_noise),
+                    candidate_min_upper_bound,
+                    max_candidates_per_len,
+                    column_weights: parse_column_weights(&data_block.headers, column_weights),
+                    excluded_column_pairs: parse_excluded_column_pairs(
+                        &data_block.headers,
+                        excluded_column_pairs,
+                    ),
+                    ..DpParameters::new(
+                        noise_epsilon.unwrap(),
+                        delta,
+                        sensitivities_percentile.unwrap(),
+                        sensitivities_epsilon_proportion.unwrap(),
                     )
-                }) {
+                });
+
+                match aggregator.aggregate_with_dp(
+                    reporting_length,
+                    parameters,
+                    threshold,
+                    &mut progress_reporter,
+                ) {
+                    Err(err) => {
+                        error!("error making aggregates noisy: {}", err);
+                        process::exit(1);
+                    }
+                    Ok(ad) => {
+                        if let Some(accountant) = &ad.privacy_accountant {
+                            let report = accountant.report(&DEFAULT_RDP_ORDERS);
+
+                            info!(
+                                    "total privacy spend: rho = {} (zCDP), epsilon = {}, delta = {} (approximate DP)",
+                                    report.rho, report.epsilon, report.delta
+                                );
+                        }
+                        ad
+                    }
+                }
+            } else {
+                match aggregator.aggregate(reporting_length, &mut progress_reporter) {
+                    Ok(mut aggregated_data) => {
+                        if !not_protect {
+                            aggregated_data.protect_with_k_anonymity(resolution);
+                        }
+                        aggregated_data
+                    }
+                    Err(err) => {
+                        error!("data aggregation error: {}", err);
+                        process::exit(1);
+                    }
+                }
+            };
+
+            if let Err(err) = aggregated_data.write_aggregates_count(
+                &aggregates_path,
+                aggregates_delimiter.chars().next().unwrap(),
+                ";",
+                None,
+                confidence_level,
+            ) {
+                error!("error writing output file: {}", err);
+                process::exit(1);
+            }
+
+            if let Some(json_path) = aggregates_json {
+                if let Err(err) = aggregated_data.write_to_json(&json_path) {
+                    error!("error writing aggregates json file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(binary_path) = aggregates_binary {
+                if let Err(err) =
+                    aggregated_data.write_to_binary(&binary_path, &aggregates_compression)
+                {
+                    error!("error writing aggregates binary file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(release_path) = aggregates_release {
+                if let Err(err) =
+                    aggregated_data.write_to_release_json(&release_path, dp_parameters)
+                {
+                    error!("error writing aggregates release file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(path) = records_sensitivity_path {
+                if let Err(err) = aggregated_data.write_records_sensitivity(&path, '\t') {
                     error!("error writing output file: {}", err);
                     process::exit(1);
                 }
             }
-            Command::Aggregate {
-                aggregates_path,
-                aggregates_delimiter,
+        }
+        Command::Plan {
+            plan_path,
+            reporting_length,
+            number_of_records,
+            number_of_columns,
+            values_per_column,
+            accuracy_mode,
+            sigma_proportions,
+            noise_epsilon,
+            noise_standard_deviations,
+            noise_delta,
+            sensitivities_epsilon_proportion,
+            number_of_records_epsilon_proportion,
+            noise_threshold_type,
+            noise_threshold_values,
+            max_records_per_subject,
+            discrete_noise,
+        } => {
+            let accuracy_mode = match sigma_proportions {
+                Some(sigma_proportions) => AccuracyMode::Custom(sigma_proportions),
+                None => AccuracyMode::from_name(&accuracy_mode).unwrap(),
+            };
+            let target = parse_plan_target(noise_epsilon, noise_standard_deviations);
+            let thresholds_map = noise_threshold_values
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (i + 2, *t))
+                .collect();
+            let threshold = match noise_threshold_type.as_str() {
+                "fixed" => NoisyCountThreshold::Fixed(thresholds_map),
+                "adaptive" => NoisyCountThreshold::Adaptive(thresholds_map),
+                _ => {
+                    error!("invalid noise threshold type");
+                    process::exit(1);
+                }
+            };
+            let plan = DpPlanner::new(
                 reporting_length,
-                not_protect,
-                records_sensitivity_path,
-                sensitivities_percentile,
-                sensitivities_epsilon_proportion,
-                dp,
+                &accuracy_mode,
+                number_of_records,
+                number_of_columns,
+                values_per_column,
                 noise_delta,
-                noise_epsilon,
-                noise_threshold_type,
-                noise_threshold_values,
-                sigma_proportions,
+                sensitivities_epsilon_proportion,
                 number_of_records_epsilon_proportion,
-                aggregates_json,
-            } => {
-                let mut aggregator = Aggregator::new(data_block.clone());
-                let aggregated_data = if dp {
-                    let n_records_f64 = data_block.number_of_records() as f64;
-                    let delta = noise_delta.unwrap_or(1.0 / (n_records_f64.ln() * n_records_f64));
-                    let thresholds_map = noise_threshold_values
-                        .unwrap()
-                        .iter()
-                        .enumerate()
-                        .map(|(i, t)| (i + 2, *t))
-                        .collect();
-
-                    let threshold = match noise_threshold_type.as_str() {
-                        "fixed" => NoisyCountThreshold::Fixed(thresholds_map),
-                        "adaptive" => NoisyCountThreshold::Adaptive(thresholds_map),
-                        _ => {
-                            error!("invalid noise threshold type");
-                            process::exit(1);
-                        }
-                    };
-
-                    match aggregator.aggregate_with_dp(
-                        reporting_length,
-                        &DpParameters::new(
-                            noise_epsilon.unwrap(),
-                            delta,
-                            sensitivities_percentile.unwrap(),
-                            sensitivities_epsilon_proportion.unwrap(),
-                            sigma_proportions,
-                            number_of_records_epsilon_proportion,
-                        ),
-                        threshold,
-                        &mut progress_reporter,
-                    ) {
-                        Err(err) => {
-                            error!("error making aggregates noisy: {}", err);
-                            process::exit(1);
-                        }
-                        Ok(ad) => ad,
+            )
+            .and_then(|planner| {
+                planner
+                    .with_max_records_per_subject(max_records_per_subject.unwrap_or(1))
+                    .with_discrete_noise(discrete_noise)
+                    .plan(&target, &threshold)
+            });
+
+            match plan {
+                Ok(plan) => {
+                    info!(
+                        "planned epsilon = {}, delta = {}, sigma proportions = {:?}",
+                        plan.epsilon, plan.delta, plan.sigma_proportions
+                    );
+                    for plan_by_len in plan.by_len.iter() {
+                        info!(
+                            "{}-counts: noise standard deviation = {:.2}, threshold = {:.2}, expected fabricated combinations = {:.2}",
+                            plan_by_len.combination_length,
+                            plan_by_len.noise_standard_deviation,
+                            plan_by_len.threshold,
+                            plan_by_len.expected_fabricated_combinations
+                        );
                     }
-                } else {
-                    match aggregator.aggregate(reporting_length, &mut progress_reporter) {
-                        Ok(mut aggregated_data) => {
-                            if !not_protect {
-                                aggregated_data.protect_with_k_anonymity(cli.resolution);
-                            }
-                            aggregated_data
-                        }
-                        Err(err) => {
-                            error!("data aggregation error: {}", err);
-                            process::exit(1);
-                        }
+                    if let Err(err) = plan.write_to_json(&plan_path) {
+                        error!("error writing plan json file: {}", err);
+                        process::exit(1);
                     }
-                };
-
-                if let Err(err) = aggregated_data.write_aggregates_count(
-                    &aggregates_path,
-                    aggregates_delimiter.chars().next().unwrap(),
-                    ";",
-                    None,
-                ) {
-                    error!("error writing output file: {}", err);
+                }
+                Err(err) => {
+                    error!("error planning DP parameters: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Evaluate {
+            synthetic_path,
+            synthetic_delimiter,
+            sensitive_aggregates_json,
+            synthetic_aggregates_json,
+            reporting_length,
+            output_dir,
+            output_format,
+            combination_delimiter,
+            not_protect,
+            privacy,
+            holdout_path,
+            quasi_identifiers,
+            target_columns,
+            workload_queries,
+        } => {
+            let resolution = required_resolution();
+            let needs_microdata = privacy || !workload_queries.is_empty();
+            let sensitive_data_block = if sensitive_aggregates_json.is_none() || needs_microdata {
+                Some(read_sensitive_data_block(&sensitive_data))
+            } else {
+                None
+            };
+            let synthetic_data_block = match &synthetic_path {
+                Some(path) if synthetic_aggregates_json.is_none() || needs_microdata => Some(
+                    read_synthetic_data_block(&sensitive_data, path, &synthetic_delimiter),
+                ),
+                _ => None,
+            };
+            let sensitive_aggregated_data = match (sensitive_aggregates_json, &sensitive_data_block)
+            {
+                (Some(json_path), _) => read_aggregates_json(&json_path),
+                (None, Some(data_block)) => aggregate_for_evaluation(
+                    data_block.clone(),
+                    reporting_length,
+                    &mut progress_reporter,
+                ),
+                (None, None) => unreachable!(),
+            };
+            let synthetic_aggregated_data = match (synthetic_aggregates_json, &synthetic_data_block)
+            {
+                (Some(json_path), _) => read_aggregates_json(&json_path),
+                (None, Some(data_block)) => aggregate_for_evaluation(
+                    data_block.clone(),
+                    sensitive_aggregated_data.reporting_length,
+                    &mut progress_reporter,
+                ),
+                (None, None) => {
+                    error!(
+                        "synthetic microdata path or synthetic aggregates json should be provided"
+                    );
                     process::exit(1);
                 }
+            };
+
+            if sensitive_aggregated_data.reporting_length
+                != synthetic_aggregated_data.reporting_length
+            {
+                warn!(
+                    "sensitive reporting length ({}) differs from the synthetic reporting length ({})",
+                    sensitive_aggregated_data.reporting_length,
+                    synthetic_aggregated_data.reporting_length
+                );
+            }
+
+            let evaluator = Evaluator::default();
+            let mut report = evaluator.calc_evaluation_report(
+                &sensitive_aggregated_data,
+                &synthetic_aggregated_data,
+                resolution,
+                &combination_delimiter,
+                !not_protect,
+            );
+
+            if let Err(err) = write_evaluation_report(
+                &mut report,
+                Path::new(&output_dir),
+                output_format == "json",
+            ) {
+                error!("error writing output file: {}", err);
+                process::exit(1);
+            }
 
-                if let Some(json_path) = aggregates_json {
-                    if let Err(err) = aggregated_data.write_to_json(&json_path) {
-                        error!("error writing aggregates json file: {}", err);
+            info!(
+                "leakage count: {}, mean proportional error: {}, record expansion: {}%",
+                report.total_leakage_count(),
+                report.mean_proportional_error,
+                report.record_expansion_percentage
+            );
+
+            if !workload_queries.is_empty() {
+                let workload: Vec<Vec<String>> = workload_queries
+                    .iter()
+                    .map(|query| query.split(',').map(|c| c.trim().to_owned()).collect())
+                    .collect();
+                let data_block = sensitive_data_block.as_ref().unwrap();
+                let workload_accuracy = match &synthetic_data_block {
+                    Some(synthetic_data_block) => evaluator.calc_workload_accuracy(
+                        &workload,
+                        data_block,
+                        synthetic_data_block,
+                    ),
+                    None => evaluator.calc_workload_accuracy_with_aggregates(
+                        &workload,
+                        data_block,
+                        &synthetic_aggregated_data,
+                    ),
+                };
+
+                match workload_accuracy.and_then(|workload_accuracy| {
+                    write_workload_accuracy(
+                        &workload_accuracy,
+                        Path::new(&output_dir),
+                        output_format == "json",
+                    )
+                    .map_err(|err| format!("error writing output file: {}", err))?;
+                    Ok(workload_accuracy)
+                }) {
+                    Ok(workload_accuracy) => {
+                        info!(
+                            "workload mean total variation distance: {}",
+                            workload_accuracy.calc_mean_total_variation_distance()
+                        );
+                    }
+                    Err(err) => {
+                        error!("workload evaluation error: {}", err);
                         process::exit(1);
                     }
                 }
+            }
+
+            if privacy {
+                let data_block = sensitive_data_block.as_ref().unwrap();
+                let synthetic_data_block = synthetic_data_block.as_ref().unwrap();
+                let holdout_data_block = holdout_path.map(|path| {
+                    match sensitive_data.read_sensitive_data_block(&path) {
+                        Ok(data_block) => data_block,
+                        Err(err) => {
+                            error!("error generating holdout data block from path: {}", err);
+                            process::exit(1);
+                        }
+                    }
+                });
+                let generated_data = GeneratedData::new(
+                    synthetic_data_block.to_raw_data(&Arc::new(String::default())),
+                    synthetic_data_block.number_of_records() as f64
+                        / data_block.number_of_records() as f64,
+                    synthetic_data_block.multi_value_column_metadata_map.clone(),
+                    Default::default(),
+                    Default::default(),
+                );
+                let privacy_report = PrivacyEvaluator::new(&quasi_identifiers, &target_columns)
+                    .calc_privacy_evaluation_report(
+                        data_block,
+                        holdout_data_block.as_deref(),
+                        &generated_data,
+                        "",
+                    );
 
-                if let Some(path) = records_sensitivity_path {
-                    if let Err(err) = aggregated_data.write_records_sensitivity(&path, '\t') {
-                        error!("error writing output file: {}", err);
+                match privacy_report.and_then(|privacy_report| {
+                    write_privacy_evaluation_report(
+                        &privacy_report,
+                        Path::new(&output_dir),
+                        output_format == "json",
+                    )
+                    .map_err(|err| format!("error writing output file: {}", err))?;
+                    Ok(privacy_report)
+                }) {
+                    Ok(privacy_report) => {
+                        info!(
+                            "synthetic exact matches: {}%, membership inference advantage: {}",
+                            privacy_report.synthetic_dcr.exact_match_percentage,
+                            privacy_report
+                                .membership_inference
+                                .map(|mi| mi.advantage.to_string())
+                                .unwrap_or_else(|| "-".to_owned())
+                        );
+                    }
+                    Err(err) => {
+                        error!("privacy evaluation error: {}", err);
                         process::exit(1);
                     }
                 }
             }
-        },
+        }
+        Command::Run { config } => {
+            let result = PipelineRunner::new(&config)
+                .and_then(|mut runner| runner.run(&config, &mut progress_reporter));
+
+            match result {
+                Ok(manifest) => {
+                    if let Some(report) = manifest.privacy_report {
+                        info!(
+                            "total privacy spend: rho = {} (zCDP), epsilon = {}, delta = {} (approximate DP)",
+                            report.rho, report.epsilon, report.delta
+                        );
+                    }
+                    info!("pipeline completed with seed {}", manifest.seed);
+                }
+                Err(err) => {
+                    error!("pipeline error: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+    };
+}
+
+fn read_sensitive_data_block(sensitive_data: &SensitiveDataCmdInput) -> Arc<DataBlock> {
+    let sensitive_path = match &sensitive_data.sensitive_path {
+        Some(path) => path,
+        None => {
+            error!("sensitive microdata path should be provided");
+            process::exit(1);
+        }
+    };
+
+    match sensitive_data.read_sensitive_data_block(sensitive_path) {
+        Ok(data_block) => data_block,
         Err(err) => {
             error!("error generating data block from path: {}", err);
             process::exit(1);
         }
+    }
+}
+
+fn read_synthetic_data_block(
+    sensitive_data: &SensitiveDataCmdInput,
+    synthetic_path: &str,
+    synthetic_delimiter: &str,
+) -> Arc<DataBlock> {
+    match sensitive_data.read_synthetic_data_block(synthetic_path, synthetic_delimiter, true) {
+        Ok(data_block) => data_block,
+        Err(err) => {
+            error!("error generating synthetic data block from path: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_synthesis_conditions(
+    headers: &DataBlockHeadersSlice,
+    required_values: Option<Vec<String>>,
+    target_counts: Option<Vec<String>>,
+) -> Option<SynthesisConditions> {
+    let conditions = if let Some(required_values) = required_values {
+        required_values
+            .iter()
+            .map(|value| SynthesisConditions::parse_value(headers, value))
+            .collect::<Result<Vec<_>, String>>()
+            .and_then(SynthesisConditions::new_required_values)
+    } else if let Some(target_counts) = target_counts {
+        target_counts
+            .iter()
+            .map(|target_count| {
+                let (value, count) = target_count.rsplit_once('=').ok_or_else(|| {
+                    format!(
+                        "\"{}\" should be formatted as {{header_name}}:{{value}}={{count}}",
+                        target_count
+                    )
+                })?;
+                let count = count
+                    .parse::<usize>()
+                    .map_err(|err| format!("invalid count on \"{}\": {}", target_count, err))?;
+
+                Ok((SynthesisConditions::parse_value(headers, value)?, count))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .and_then(SynthesisConditions::new_target_counts)
+    } else {
+        return None;
     };
+
+    match conditions {
+        Ok(conditions) => Some(conditions),
+        Err(err) => {
+            error!("invalid synthesis conditions: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Builds the planner target, either the privacy budget or the noise standard
+/// deviation by combination length (values <= 0 are not constrained)
+fn parse_plan_target(
+    noise_epsilon: Option<f64>,
+    noise_standard_deviations: Option<Vec<f64>>,
+) -> DpPlanTarget {
+    match noise_epsilon {
+        Some(epsilon) => DpPlanTarget::Epsilon(epsilon),
+        None => DpPlanTarget::NoiseStandardDeviation(
+            noise_standard_deviations
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+                .filter(|(_, std)| **std > 0.0)
+                .map(|(i, std)| (i + 1, *std))
+                .collect(),
+        ),
+    }
+}
+
+fn check_column_name(headers: &DataBlockHeadersSlice, column_name: &str) -> Result<(), String> {
+    if headers.iter().any(|header| **header == column_name) {
+        Ok(())
+    } else {
+        Err(format!("column \"{}\" not found", column_name))
+    }
+}
+
+fn parse_column_weights(
+    headers: &DataBlockHeadersSlice,
+    column_weights: Option<Vec<String>>,
+) -> Option<HashMap<String, f64>> {
+    let column_weights = column_weights?
+        .iter()
+        .map(|column_weight| {
+            let (column_name, weight) = column_weight.rsplit_once('=').ok_or_else(|| {
+                format!(
+                    "\"{}\" should be formatted as {{header_name}}={{weight}}",
+                    column_weight
+                )
+            })?;
+            let weight = weight
+                .parse::<f64>()
+                .map_err(|err| format!("invalid weight on \"{}\": {}", column_weight, err))?;
+
+            if weight <= 0.0 {
+                return Err(format!("weight on \"{}\" must be > 0", column_weight));
+            }
+            check_column_name(headers, column_name)?;
+            Ok((column_name.to_owned(), weight))
+        })
+        .collect::<Result<HashMap<_, _>, String>>();
+
+    match column_weights {
+        Ok(column_weights) => Some(column_weights),
+        Err(err) => {
+            error!("invalid column weights: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_excluded_column_pairs(
+    headers: &DataBlockHeadersSlice,
+    excluded_column_pairs: Option<Vec<String>>,
+) -> Option<Vec<(String, String)>> {
+    let excluded_column_pairs = excluded_column_pairs?
+        .iter()
+        .map(|column_pair| {
+            let (column_name_a, column_name_b) = column_pair.split_once(';').ok_or_else(|| {
+                format!(
+                    "\"{}\" should be formatted as {{header_name}};{{header_name}}",
+                    column_pair
+                )
+            })?;
+
+            check_column_name(headers, column_name_a)?;
+            check_column_name(headers, column_name_b)?;
+            Ok((column_name_a.to_owned(), column_name_b.to_owned()))
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    match excluded_column_pairs {
+        Ok(excluded_column_pairs) => Some(excluded_column_pairs),
+        Err(err) => {
+            error!("invalid excluded column pairs: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn read_constraint_rules(path: &str, headers: &DataBlockHeadersSlice) -> ConstraintRules {
+    match std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|rules_str| ConstraintRules::from_str_using_headers(headers, &rules_str))
+    {
+        Ok(constraint_rules) => constraint_rules,
+        Err(err) => {
+            error!("error reading constraint rules: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn read_aggregates_json(json_path: &str) -> AggregatedData {
+    // files written with --aggregates-binary and --aggregates-release are accepted as well
+    let mut prefix = [0u8; AGGREGATED_DATA_RELEASE_PREFIX.len()];
+    let prefix_len = File::open(json_path)
+        .and_then(|mut file| file.read(&mut prefix))
+        .unwrap_or(0);
+    let aggregated_data = if AggregatedData::is_binary(&prefix[..prefix_len]) {
+        AggregatedData::read_from_binary(json_path)
+    } else if AggregatedDataRelease::is_release(&prefix[..prefix_len]) {
+        AggregatedData::read_from_release_json(json_path)
+    } else {
+        AggregatedData::read_from_json(json_path)
+    };
+
+    match aggregated_data {
+        Ok(data) => data,
+        Err(err) => {
+            error!("error reading aggregates json file: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn aggregate_for_evaluation(
+    data_block: Arc<DataBlock>,
+    reporting_length: usize,
+    progress_reporter: &mut Option<LoggerProgressReporter>,
+) -> AggregatedData {
+    // evaluation compares the raw counts, so they are not protected here
+    match Aggregator::new(data_block).aggregate(reporting_length, progress_reporter) {
+        Ok(aggregated_data) => aggregated_data,
+        Err(err) => {
+            error!("data aggregation error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN_ARGS: [&str; 12] = [
+        "sds-cli",
+        "plan",
+        "--plan-path",
+        "plan.json",
+        "--reporting-length",
+        "3",
+        "--number-of-records",
+        "10000",
+        "--number-of-columns",
+        "8",
+        "--values-per-column",
+        "5",
+    ];
+
+    fn parse_plan_args(args: &[&str]) -> Result<Cli, structopt::clap::Error> {
+        Cli::from_iter_safe(
+            PLAN_ARGS
+                .iter()
+                .chain(["--sensitivities-epsilon-proportion", "0.1"].iter())
+                .chain(args.iter()),
+        )
+    }
+
+    #[test]
+    fn validate_plan_args() {
+        match parse_plan_args(&[
+            "--noise-epsilon",
+            "4",
+            "--max-records-per-subject",
+            "2",
+            "--discrete-noise",
+        ])
+        .unwrap()
+        .cmd
+        {
+            Command::Plan {
+                noise_epsilon,
+                noise_standard_deviations,
+                max_records_per_subject,
+                discrete_noise,
+                noise_threshold_type,
+                ..
+            } => {
+                assert!(noise_epsilon == Some(4.0));
+                assert!(noise_standard_deviations.is_none());
+                assert!(max_records_per_subject == Some(2));
+                assert!(discrete_noise);
+                assert!(noise_threshold_type == "fixed");
+            }
+            _ => panic!("expected the plan command"),
+        }
+
+        // either the budget or the noise should be set, but not both
+        assert!(parse_plan_args(&[]).is_err());
+        assert!(parse_plan_args(&[
+            "--noise-epsilon",
+            "4",
+            "--noise-standard-deviations",
+            "1",
+            "2"
+        ])
+        .is_err());
+        // the accuracy mode and custom sigma proportions conflict
+        assert!(parse_plan_args(&[
+            "--noise-epsilon",
+            "4",
+            "--accuracy-mode",
+            "balanced",
+            "--sigma-proportions",
+            "1",
+            "1",
+            "1"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn validate_plan_target() {
+        assert!(matches!(
+            parse_plan_target(Some(4.0), None),
+            DpPlanTarget::Epsilon(epsilon) if epsilon == 4.0
+        ));
+
+        match parse_plan_target(None, Some(vec![2.0, 0.0, 1.0])) {
+            DpPlanTarget::NoiseStandardDeviation(noise_standard_deviations) => {
+                // lengths start at 1 and values <= 0 are not constrained
+                assert!(noise_standard_deviations.len() == 2);
+                assert!(noise_standard_deviations[&1] == 2.0);
+                assert!(noise_standard_deviations[&3] == 1.0);
+            }
+            _ => panic!("expected a noise standard deviation target"),
+        }
+    }
 }
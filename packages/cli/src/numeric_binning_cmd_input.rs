@@ -0,0 +1,52 @@
+use sds_core::data_block::NumericBinningStrategy;
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+const FORMAT_ERROR: &str =
+    "wrong format, expected: <column name>,<width|quantile|edges>,<value>[,<value>...]";
+
+#[derive(Debug)]
+pub struct NumericBinningCmdInput {
+    pub column_name: String,
+    pub strategy: NumericBinningStrategy,
+}
+
+impl FromStr for NumericBinningCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, DELIMITER);
+
+        if let (Some(column_name), Some(strategy_type), Some(values)) =
+            (parts.next(), parts.next(), parts.next())
+        {
+            if !column_name.is_empty() {
+                let strategy = match strategy_type.trim().to_lowercase().as_str() {
+                    "width" => values
+                        .trim()
+                        .parse::<f64>()
+                        .map(NumericBinningStrategy::FixedWidth)
+                        .map_err(|err| err.to_string())?,
+                    "quantile" => values
+                        .trim()
+                        .parse::<usize>()
+                        .map(NumericBinningStrategy::Quantile)
+                        .map_err(|err| err.to_string())?,
+                    "edges" => values
+                        .split(DELIMITER)
+                        .map(|v| v.trim().parse::<f64>())
+                        .collect::<Result<Vec<f64>, _>>()
+                        .map(NumericBinningStrategy::Edges)
+                        .map_err(|err| err.to_string())?,
+                    _ => return Err(FORMAT_ERROR.to_owned()),
+                };
+                return Ok(NumericBinningCmdInput {
+                    column_name: column_name.to_owned(),
+                    strategy,
+                });
+            }
+        }
+        Err(FORMAT_ERROR.to_owned())
+    }
+}
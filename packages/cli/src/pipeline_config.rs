@@ -0,0 +1,439 @@
+use crate::{
+    cmd_input_deserializer::deserialize_cmd_inputs,
+    date_time_output_format_cmd_input::DateTimeOutputFormatCmdInput,
+    sensitive_data_cmd_input::SensitiveDataCmdInput,
+};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+const DEFAULT_CACHE_MAX_SIZE: usize = 100000;
+
+fn default_delimiter() -> String {
+    "\t".to_owned()
+}
+
+fn default_combination_delimiter() -> String {
+    ";".to_owned()
+}
+
+fn default_cache_max_size() -> usize {
+    DEFAULT_CACHE_MAX_SIZE
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoiseThresholdType {
+    #[default]
+    Fixed,
+    Adaptive,
+}
+
+/// Differential privacy parameters, the same as the ones
+/// accepted by the `aggregate --dp` subcommand
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineDpConfig {
+    pub noise_epsilon: f64,
+    pub noise_delta: Option<f64>,
+    pub sensitivities_percentile: usize,
+    pub sensitivities_epsilon_proportion: f64,
+    #[serde(default)]
+    pub noise_threshold_type: NoiseThresholdType,
+    pub noise_threshold_values: Vec<f64>,
+    pub sigma_proportions: Option<Vec<f64>>,
+    pub number_of_records_epsilon_proportion: Option<f64>,
+    pub max_records_per_subject: Option<usize>,
+    #[serde(default)]
+    pub least_squares_consistency: bool,
+    #[serde(default)]
+    pub discrete_noise: bool,
+    pub candidate_min_upper_bound: Option<f64>,
+    pub max_candidates_per_len: Option<usize>,
+    pub column_weights: Option<HashMap<String, f64>>,
+    pub excluded_column_pairs: Option<Vec<(String, String)>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerateMode {
+    RowSeeded,
+    Unseeded,
+    ValueSeeded,
+    AggregateSeeded,
+    MarginalModel,
+}
+
+impl GenerateMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenerateMode::RowSeeded => "row_seeded",
+            GenerateMode::Unseeded => "unseeded",
+            GenerateMode::ValueSeeded => "value_seeded",
+            GenerateMode::AggregateSeeded => "aggregate_seeded",
+            GenerateMode::MarginalModel => "marginal_model",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericBinsOutput {
+    #[default]
+    Label,
+    Representative,
+    Uniform,
+}
+
+/// Parameters of a generate step, the same as the ones
+/// accepted by the `generate` subcommand
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateStepConfig {
+    /// Name used to identify the synthetic data (defaults to the mode)
+    pub name: Option<String>,
+    pub mode: GenerateMode,
+    #[serde(default = "default_delimiter")]
+    pub synthetic_delimiter: String,
+    #[serde(default)]
+    pub join_multi_value_columns: bool,
+    #[serde(default)]
+    pub long_form: bool,
+    #[serde(default)]
+    pub numeric_bins_output: NumericBinsOutput,
+    #[serde(default, deserialize_with = "deserialize_cmd_inputs")]
+    pub date_time_output_formats: Vec<DateTimeOutputFormatCmdInput>,
+    #[serde(default = "default_cache_max_size")]
+    pub cache_max_size: usize,
+    pub oversampling_ratio: Option<f64>,
+    pub oversampling_tries: Option<usize>,
+    #[serde(default)]
+    pub use_synthetic_counts: bool,
+    pub weight_selection_percentile: Option<usize>,
+    pub aggregate_counts_scale_factor: Option<f64>,
+    pub target_number_of_records: Option<usize>,
+    pub max_clique_size: Option<usize>,
+}
+
+impl GenerateStepConfig {
+    /// Name used to identify the synthetic data
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.mode.as_str())
+    }
+}
+
+/// Single step of the pipeline, steps run in the declared order
+#[derive(Deserialize, Debug)]
+#[serde(tag = "step", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PipelineStep {
+    /// Aggregates the sensitive data (with DP if the `dp` section is set)
+    Aggregate {
+        #[serde(default)]
+        reporting_length: usize,
+    },
+    /// Protects the current aggregates with k-anonymity using the pipeline resolution
+    Protect,
+    /// Generates synthetic data
+    Generate(GenerateStepConfig),
+    /// Evaluates all the synthetic data generated so far against the sensitive data
+    Evaluate {
+        /// Defaults to the reporting length of the last aggregate step (or all)
+        reporting_length: Option<usize>,
+        #[serde(default = "default_combination_delimiter")]
+        combination_delimiter: String,
+        #[serde(default)]
+        not_protect: bool,
+    },
+}
+
+impl PipelineStep {
+    /// Name of the step type, as written in the config file
+    pub fn step_type(&self) -> &'static str {
+        match self {
+            PipelineStep::Aggregate { .. } => "aggregate",
+            PipelineStep::Protect => "protect",
+            PipelineStep::Generate(_) => "generate",
+            PipelineStep::Evaluate { .. } => "evaluate",
+        }
+    }
+}
+
+/// Pipeline declared in a toml or json file, chaining
+/// the aggregate, protect, generate and evaluate steps
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+    /// Run directory where all the outputs and the manifest are written
+    pub output_dir: String,
+    pub resolution: usize,
+    /// A random seed is drawn (and recorded in the manifest) if not set.
+    /// When set, the DP noise can also be reproduced from it, so the
+    /// config file should then be kept secret
+    pub seed: Option<u64>,
+    pub n_threads: Option<usize>,
+    pub input: SensitiveDataCmdInput,
+    pub dp: Option<PipelineDpConfig>,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl PipelineConfig {
+    /// Reads the pipeline from a file, `.json` files are parsed as json
+    /// and everything else as toml
+    /// # Arguments
+    /// * `config_path` - Path to the config file
+    pub fn read(config_path: &str) -> Result<PipelineConfig, String> {
+        let content = std::fs::read_to_string(config_path)
+            .map_err(|err| format!("error reading config file: {}", err))?;
+        let is_json = Path::new(config_path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        PipelineConfig::parse(&content, is_json)
+    }
+
+    /// Parses and validates the pipeline from the config file content
+    /// # Arguments
+    /// * `content` - Config file content
+    /// * `is_json` - Whether the content is json (otherwise toml)
+    pub fn parse(content: &str, is_json: bool) -> Result<PipelineConfig, String> {
+        let config: PipelineConfig = if is_json {
+            serde_json::from_str(content).map_err(|err| err.to_string())
+        } else {
+            toml::from_str(content).map_err(|err| err.to_string())
+        }
+        .map_err(|err| format!("invalid config file: {}", err))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.input.sensitive_path.is_none() {
+            return Err("invalid config file: missing field `input.sensitive_path`".to_owned());
+        }
+        if self.steps.is_empty() {
+            return Err("invalid config file: at least one step should be declared".to_owned());
+        }
+
+        let mut has_aggregates = false;
+        let mut has_long_form = false;
+        let mut synthetic_names: HashSet<&str> = HashSet::default();
+
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                PipelineStep::Aggregate { .. } => has_aggregates = true,
+                PipelineStep::Protect => {
+                    if !has_aggregates {
+                        return Err(format!(
+                            "step {}: protect requires a previous aggregate step",
+                            index + 1
+                        ));
+                    }
+                }
+                PipelineStep::Generate(generate) => {
+                    let needs_aggregates = generate.mode == GenerateMode::AggregateSeeded
+                        || generate.mode == GenerateMode::MarginalModel
+                        || generate.oversampling_ratio.is_some()
+                        || generate.oversampling_tries.is_some();
+
+                    if needs_aggregates && !has_aggregates {
+                        return Err(format!(
+                            "step {}: generate ({}) requires a previous aggregate step",
+                            index + 1,
+                            generate.mode.as_str()
+                        ));
+                    }
+                    has_long_form |= generate.long_form;
+
+                    if !synthetic_names.insert(generate.name()) {
+                        return Err(format!(
+                            "step {}: synthetic data named \"{}\" was already generated, set a different name",
+                            index + 1,
+                            generate.name()
+                        ));
+                    }
+                }
+                PipelineStep::Evaluate { .. } => {
+                    if has_long_form {
+                        return Err(format!(
+                            "step {}: evaluate does not support synthetic data generated with long_form",
+                            index + 1
+                        ));
+                    }
+                    if synthetic_names.is_empty() {
+                        return Err(format!(
+                            "step {}: evaluate requires a previous generate step",
+                            index + 1
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML_CONFIG: &str = r#"
+output_dir = "run"
+resolution = 10
+seed = 42
+
+[input]
+sensitive_path = "sensitive.csv"
+sensitive_delimiter = ","
+use_columns = ["A", "B"]
+
+[dp]
+noise_epsilon = 4.0
+sensitivities_percentile = 99
+sensitivities_epsilon_proportion = 0.1
+noise_threshold_type = "adaptive"
+noise_threshold_values = [0.5, 0.5]
+
+[[steps]]
+step = "aggregate"
+reporting_length = 3
+
+[[steps]]
+step = "generate"
+mode = "aggregate_seeded"
+
+[[steps]]
+step = "generate"
+name = "unseeded_bins"
+mode = "unseeded"
+numeric_bins_output = "uniform"
+
+[[steps]]
+step = "evaluate"
+"#;
+
+    fn parse_toml_steps(steps: &str) -> Result<PipelineConfig, String> {
+        PipelineConfig::parse(
+            &format!(
+                "output_dir = \"run\"\nresolution = 10\n[input]\nsensitive_path = \"sensitive.csv\"\n{}",
+                steps
+            ),
+            false,
+        )
+    }
+
+    #[test]
+    fn valid_toml_config() {
+        let config = PipelineConfig::parse(TOML_CONFIG, false).unwrap();
+        let dp = config.dp.as_ref().unwrap();
+
+        assert!(config.output_dir == "run");
+        assert!(config.resolution == 10);
+        assert!(config.seed == Some(42));
+        assert!(config.input.sensitive_delimiter == ",");
+        assert!(config.input.use_columns == vec!["A".to_owned(), "B".to_owned()]);
+        assert!(dp.noise_threshold_type == NoiseThresholdType::Adaptive);
+        assert!(dp.noise_delta.is_none());
+        assert!(!dp.least_squares_consistency);
+        assert!(config.steps.len() == 4);
+        assert!(matches!(
+            config.steps[0],
+            PipelineStep::Aggregate {
+                reporting_length: 3
+            }
+        ));
+
+        match (&config.steps[1], &config.steps[2]) {
+            (PipelineStep::Generate(aggregate_seeded), PipelineStep::Generate(unseeded)) => {
+                assert!(aggregate_seeded.name() == "aggregate_seeded");
+                assert!(aggregate_seeded.synthetic_delimiter == "\t");
+                assert!(aggregate_seeded.cache_max_size == DEFAULT_CACHE_MAX_SIZE);
+                assert!(unseeded.name() == "unseeded_bins");
+                assert!(unseeded.numeric_bins_output == NumericBinsOutput::Uniform);
+            }
+            _ => panic!("expected two generate steps"),
+        }
+
+        match &config.steps[3] {
+            PipelineStep::Evaluate {
+                reporting_length,
+                combination_delimiter,
+                not_protect,
+            } => {
+                assert!(reporting_length.is_none());
+                assert!(combination_delimiter == ";");
+                assert!(!not_protect);
+            }
+            _ => panic!("expected an evaluate step"),
+        }
+    }
+
+    #[test]
+    fn valid_json_config() {
+        let config = PipelineConfig::parse(
+            r#"{
+                "output_dir": "run",
+                "resolution": 5,
+                "input": { "sensitive_path": "sensitive.csv" },
+                "steps": [
+                    { "step": "generate", "mode": "row_seeded" },
+                    { "step": "evaluate", "reporting_length": 2 }
+                ]
+            }"#,
+            true,
+        )
+        .unwrap();
+
+        assert!(config.seed.is_none());
+        assert!(config.dp.is_none());
+        assert!(config.input.sensitive_delimiter == "\t");
+        assert!(matches!(
+            config.steps[1],
+            PipelineStep::Evaluate {
+                reporting_length: Some(2),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn invalid_config() {
+        // unknown fields are rejected
+        assert!(parse_toml_steps("unknown = 1\n[[steps]]\nstep = \"aggregate\"").is_err());
+        assert!(parse_toml_steps("[[steps]]\nstep = \"aggregate\"\nunknown = 1").is_err());
+        // no steps
+        assert!(parse_toml_steps("").is_err());
+        // missing sensitive path
+        assert!(PipelineConfig::parse(
+            "output_dir = \"run\"\nresolution = 10\n[input]\n[[steps]]\nstep = \"aggregate\"",
+            false
+        )
+        .is_err());
+        // steps that need previous ones
+        assert!(parse_toml_steps("[[steps]]\nstep = \"protect\"").is_err());
+        assert!(
+            parse_toml_steps("[[steps]]\nstep = \"generate\"\nmode = \"marginal_model\"").is_err()
+        );
+        assert!(parse_toml_steps(
+            "[[steps]]\nstep = \"generate\"\nmode = \"value_seeded\"\noversampling_ratio = 0.1"
+        )
+        .is_err());
+        assert!(parse_toml_steps("[[steps]]\nstep = \"evaluate\"").is_err());
+        // synthetic data names must be unique
+        assert!(parse_toml_steps(
+            "[[steps]]\nstep = \"generate\"\nmode = \"unseeded\"\n[[steps]]\nstep = \"generate\"\nmode = \"unseeded\""
+        )
+        .is_err());
+        // long form synthetic data can not be evaluated
+        assert!(parse_toml_steps(
+            "[[steps]]\nstep = \"generate\"\nmode = \"unseeded\"\nlong_form = true\n[[steps]]\nstep = \"evaluate\""
+        )
+        .is_err());
+        assert!(parse_toml_steps(
+            "[[steps]]\nstep = \"aggregate\"\n[[steps]]\nstep = \"protect\"\n[[steps]]\nstep = \"generate\"\nmode = \"marginal_model\""
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,740 @@
+use crate::{
+    evaluation_output::write_evaluation_report,
+    pipeline_config::{
+        GenerateMode, GenerateStepConfig, NoiseThresholdType, NumericBinsOutput, PipelineConfig,
+        PipelineDpConfig, PipelineStep,
+    },
+};
+use log::info;
+use rand::Rng;
+use sds_core::{
+    data_block::{data_derived_binned_columns, DataBlock, NumericBinOutputMode},
+    dp::{DpParameters, NoisyCountThreshold, PrivacyReport, DEFAULT_RDP_ORDERS},
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        evaluator::Evaluator,
+        generator::{Generator, OversamplingParameters},
+    },
+    utils::{
+        random::new_rng_for_stream, reporting::LoggerProgressReporter,
+        threading::set_number_of_threads,
+    },
+};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Independent random streams derived from the run seed, so the
+/// stages of the pipeline do not share the same random sequence
+#[derive(Clone, Copy)]
+enum SeedStream {
+    DpNoise = 0,
+    Synthesis = 1,
+    NumericBins = 2,
+}
+
+/// Derives the seed of `stream` from the run `seed`
+fn derive_seed(seed: u64, stream: SeedStream) -> u64 {
+    new_rng_for_stream(seed, stream as u64).gen()
+}
+
+/// Outputs of a single pipeline step
+#[derive(Serialize)]
+pub struct StepManifest {
+    /// Step position in the config file (starting in 1)
+    pub index: usize,
+    /// Step type (aggregate, protect, generate or evaluate)
+    pub step: &'static str,
+    /// Synthetic data name (generate steps only)
+    pub name: Option<String>,
+    /// Paths of all the files written by the step
+    pub outputs: Vec<String>,
+    /// How long the step took to run
+    pub elapsed_seconds: f64,
+}
+
+/// Description of a pipeline run, written to the run directory
+/// so the run can be reproduced and its outputs located
+#[derive(Serialize)]
+pub struct PipelineManifest {
+    /// Version of the CLI that executed the pipeline
+    pub sds_cli_version: &'static str,
+    /// Copy of the config file stored in the run directory
+    pub config_path: String,
+    /// Seed the data synthesis and numeric bins sampling seeds are derived from
+    /// (the DP noise seed is only derived from it when the seed is set on the config)
+    pub seed: u64,
+    /// Unix timestamp (seconds) of when the run started
+    pub started_at: u64,
+    /// Unix timestamp (seconds) of when the run finished
+    pub finished_at: u64,
+    /// Whether all the steps completed successfully
+    pub completed: bool,
+    /// Error that stopped the run, if any
+    pub error: Option<String>,
+    /// Total privacy spend of the DP aggregates, if any
+    pub privacy_report: Option<PrivacyReport>,
+    /// Executed steps, in order
+    pub steps: Vec<StepManifest>,
+}
+
+struct SyntheticDataOutput {
+    name: String,
+    path: String,
+    delimiter: String,
+    join_multi_value_columns: bool,
+}
+
+/// Runs the steps declared on a pipeline config file
+pub struct PipelineRunner {
+    config: PipelineConfig,
+    run_dir: PathBuf,
+    seed: u64,
+    noise_seed: u64,
+    data_block: Option<Arc<DataBlock>>,
+    aggregated_data: Option<Arc<AggregatedData>>,
+    aggregates_reporting_length: usize,
+    synthetic_data: Vec<SyntheticDataOutput>,
+    privacy_report: Option<PrivacyReport>,
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+impl PipelineRunner {
+    /// Returns a new PipelineRunner, reading and validating the config file
+    /// # Arguments
+    /// * `config_path` - Path to the pipeline config file (toml or json)
+    pub fn new(config_path: &str) -> Result<PipelineRunner, String> {
+        let config = PipelineConfig::read(config_path)?;
+        let run_dir = PathBuf::from(&config.output_dir);
+        // draw a seed, so the synthesis can be reproduced from the manifest
+        let seed = config.seed.unwrap_or_else(rand::random);
+        // a drawn noise seed is never recorded, otherwise anyone
+        // with the manifest could reproduce (and remove) the DP noise
+        let noise_seed = match config.seed {
+            Some(seed) => derive_seed(seed, SeedStream::DpNoise),
+            None => rand::random(),
+        };
+
+        Ok(PipelineRunner {
+            config,
+            run_dir,
+            seed,
+            noise_seed,
+            data_block: None,
+            aggregated_data: None,
+            aggregates_reporting_length: 0,
+            synthetic_data: Vec::default(),
+            privacy_report: None,
+        })
+    }
+
+    /// Runs all the steps, writing the outputs and the manifest to the run directory.
+    /// The manifest is also written if a step fails
+    /// # Arguments
+    /// * `config_path` - Path to the pipeline config file, copied to the run directory
+    /// * `progress_reporter` - Will be used to report the processing progress
+    pub fn run(
+        &mut self,
+        config_path: &str,
+        progress_reporter: &mut Option<LoggerProgressReporter>,
+    ) -> Result<PipelineManifest, String> {
+        let started_at = unix_timestamp();
+
+        if let Some(n_threads) = self.config.n_threads {
+            set_number_of_threads(n_threads);
+        }
+
+        std::fs::create_dir_all(&self.run_dir)
+            .map_err(|err| format!("error creating run directory: {}", err))?;
+
+        let config_copy_path = self.run_dir.join(format!(
+            "config.{}",
+            Path::new(config_path)
+                .extension()
+                .map_or("toml".to_owned(), |ext| ext
+                    .to_string_lossy()
+                    .to_lowercase())
+        ));
+
+        std::fs::copy(config_path, &config_copy_path)
+            .map_err(|err| format!("error copying config file: {}", err))?;
+
+        let mut steps = Vec::default();
+        let result = self.run_steps(&mut steps, progress_reporter);
+        let manifest = PipelineManifest {
+            sds_cli_version: env!("CARGO_PKG_VERSION"),
+            config_path: path_to_string(&config_copy_path),
+            seed: self.seed,
+            started_at,
+            finished_at: unix_timestamp(),
+            completed: result.is_ok(),
+            error: result.err(),
+            privacy_report: self.privacy_report.take(),
+            steps,
+        };
+        let manifest_path = path_to_string(&self.run_dir.join(MANIFEST_FILE_NAME));
+
+        info!("writing file: {}", manifest_path);
+
+        std::fs::File::create(&manifest_path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &manifest).map_err(|err| err.to_string())
+            })
+            .map_err(|err| format!("error writing manifest: {}", err))?;
+
+        match &manifest.error {
+            Some(err) => Err(err.clone()),
+            None => Ok(manifest),
+        }
+    }
+
+    fn run_steps(
+        &mut self,
+        steps: &mut Vec<StepManifest>,
+        progress_reporter: &mut Option<LoggerProgressReporter>,
+    ) -> Result<(), String> {
+        let sensitive_path = self.config.input.sensitive_path.clone().unwrap_or_default();
+
+        self.data_block = Some(
+            self.config
+                .input
+                .read_sensitive_data_block(&sensitive_path)
+                .map_err(|err| format!("error generating data block from path: {}", err))?,
+        );
+
+        let pipeline_steps = std::mem::take(&mut self.config.steps);
+
+        for (i, step) in pipeline_steps.iter().enumerate() {
+            let index = i + 1;
+            let name = match step {
+                PipelineStep::Generate(generate) => Some(generate.name().to_owned()),
+                _ => None,
+            };
+            let step_dir = self.run_dir.join(match &name {
+                Some(name) => format!("{:02}_{}_{}", index, step.step_type(), name),
+                None => format!("{:02}_{}", index, step.step_type()),
+            });
+
+            info!("running step {}: {}", index, step.step_type());
+
+            let start = Instant::now();
+
+            std::fs::create_dir_all(&step_dir)
+                .map_err(|err| format!("error creating step directory: {}", err))?;
+
+            let outputs = match step {
+                PipelineStep::Aggregate { reporting_length } => {
+                    self.aggregate(*reporting_length, &step_dir, progress_reporter)
+                }
+                PipelineStep::Protect => self.protect(&step_dir),
+                PipelineStep::Generate(generate) => {
+                    self.generate(generate, &step_dir, progress_reporter)
+                }
+                PipelineStep::Evaluate {
+                    reporting_length,
+                    combination_delimiter,
+                    not_protect,
+                } => self.evaluate(
+                    reporting_length.unwrap_or(self.aggregates_reporting_length),
+                    combination_delimiter,
+                    *not_protect,
+                    &step_dir,
+                    progress_reporter,
+                ),
+            }
+            .map_err(|err| format!("step {} ({}): {}", index, step.step_type(), err))?;
+
+            steps.push(StepManifest {
+                index,
+                step: step.step_type(),
+                name,
+                outputs,
+                elapsed_seconds: start.elapsed().as_secs_f64(),
+            });
+        }
+        Ok(())
+    }
+
+    fn data_block(&self) -> Arc<DataBlock> {
+        self.data_block
+            .clone()
+            .expect("data block should be read before running the steps")
+    }
+
+    fn write_aggregated_data(
+        aggregated_data: &AggregatedData,
+        step_dir: &Path,
+    ) -> Result<Vec<String>, String> {
+        let aggregates_path = path_to_string(&step_dir.join("aggregates.tsv"));
+        let aggregates_json_path = path_to_string(&step_dir.join("aggregates.json"));
+
+        aggregated_data
+            .write_aggregates_count(&aggregates_path, '\t', ";", None, None)
+            .map_err(|err| format!("error writing output file: {}", err))?;
+        aggregated_data
+            .write_to_json(&aggregates_json_path)
+            .map_err(|err| format!("error writing aggregates json file: {}", err))?;
+
+        Ok(vec![aggregates_path, aggregates_json_path])
+    }
+
+    /// Column weights, excluded pairs and numeric bins can only be checked once the data is read
+    fn validate_dp_columns(dp: &PipelineDpConfig, data_block: &DataBlock) -> Result<(), String> {
+        if let Some(column_name) =
+            data_derived_binned_columns(&data_block.numeric_bins_metadata_map).first()
+        {
+            return Err(format!(
+                "dp: numeric column \"{}\" must be binned with explicit edges",
+                column_name
+            ));
+        }
+
+        let weighted_columns = dp
+            .column_weights
+            .iter()
+            .flatten()
+            .map(|(column_name, weight)| {
+                if *weight > 0.0 {
+                    Ok(column_name)
+                } else {
+                    Err(format!(
+                        "dp: weight of column \"{}\" must be > 0",
+                        column_name
+                    ))
+                }
+            });
+        let excluded_columns = dp
+            .excluded_column_pairs
+            .iter()
+            .flatten()
+            .flat_map(|(column_name_a, column_name_b)| [Ok(column_name_a), Ok(column_name_b)]);
+
+        for column_name in weighted_columns.chain(excluded_columns) {
+            let column_name = column_name?;
+
+            if !data_block
+                .headers
+                .iter()
+                .any(|header| **header == *column_name)
+            {
+                return Err(format!("dp: column \"{}\" not found", column_name));
+            }
+        }
+        Ok(())
+    }
+
+    fn dp_threshold(dp: &PipelineDpConfig) -> NoisyCountThreshold {
+        let thresholds_map = dp
+            .noise_threshold_values
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (i + 2, *t))
+            .collect();
+
+        match dp.noise_threshold_type {
+            NoiseThresholdType::Fixed => NoisyCountThreshold::Fixed(thresholds_map),
+            NoiseThresholdType::Adaptive => NoisyCountThreshold::Adaptive(thresholds_map),
+        }
+    }
+
+    fn aggregate(
+        &mut self,
+        reporting_length: usize,
+        step_dir: &Path,
+        progress_reporter: &mut Option<LoggerProgressReporter>,
+    ) -> Result<Vec<String>, String> {
+        let data_block = self.data_block();
+        let mut aggregator = Aggregator::new(data_block.clone());
+        let aggregated_data = match &self.config.dp {
+            Some(dp) => {
+                Self::validate_dp_columns(dp, &data_block)?;
+
+                let n_records_f64 = data_block.number_of_records() as f64;
+                let delta = dp
+                    .noise_delta
+                    .unwrap_or(1.0 / (n_records_f64.ln() * n_records_f64));
+                let aggregated_data = aggregator
+                    .aggregate_with_dp(
+                        reporting_length,
+                        &DpParameters {
+                            sigma_proportions: dp.sigma_proportions.clone(),
+                            number_of_records_epsilon_proportion: dp
+                                .number_of_records_epsilon_proportion,
+                            seed: Some(self.noise_seed),
+                            max_records_per_subject: dp.max_records_per_subject,
+                            use_least_squares_consistency: Some(dp.least_squares_consistency),
+                            use_discrete_noise: Some(dp.discrete_noise),
+                            candidate_min_upper_bound: dp.candidate_min_upper_bound,
+                            max_candidates_per_len: dp.max_candidates_per_len,
+                            column_weights: dp.column_weights.clone(),
+                            excluded_column_pairs: dp.excluded_column_pairs.clone(),
+                            ..DpParameters::new(
+                                dp.noise_epsilon,
+                                delta,
+                                dp.sensitivities_percentile,
+                                dp.sensitivities_epsilon_proportion,
+                            )
+                        },
+                        Self::dp_threshold(dp),
+                        progress_reporter,
+                    )
+                    .map_err(|err| format!("error making aggregates noisy: {}", err))?;
+
+                if let Some(accountant) = &aggregated_data.privacy_accountant {
+                    self.privacy_report = Some(accountant.report(&DEFAULT_RDP_ORDERS));
+                }
+                aggregated_data
+            }
+            None => aggregator
+                .aggregate(reporting_length, progress_reporter)
+                .map_err(|err| format!("data aggregation error: {}", err))?,
+        };
+        let outputs = Self::write_aggregated_data(&aggregated_data, step_dir)?;
+
+        self.aggregates_reporting_length = aggregated_data.reporting_length;
+        self.aggregated_data = Some(Arc::new(aggregated_data));
+        Ok(outputs)
+    }
+
+    fn protect(&mut self, step_dir: &Path) -> Result<Vec<String>, String> {
+        let resolution = self.config.resolution;
+        let aggregated_data = self
+            .aggregated_data
+            .as_mut()
+            .ok_or_else(|| "there are no aggregates to protect".to_owned())?;
+
+        Arc::make_mut(aggregated_data).protect_with_k_anonymity(resolution);
+        Self::write_aggregated_data(aggregated_data, step_dir)
+    }
+
+    fn generate(
+        &mut self,
+        generate: &GenerateStepConfig,
+        step_dir: &Path,
+        progress_reporter: &mut Option<LoggerProgressReporter>,
+    ) -> Result<Vec<String>, String> {
+        let data_block = self.data_block();
+        let resolution = self.config.resolution;
+        let oversampling_parameters =
+            if generate.oversampling_ratio.is_some() || generate.oversampling_tries.is_some() {
+                Some(OversamplingParameters::new(
+                    self.aggregated_data
+                        .clone()
+                        .ok_or_else(|| "aggregates should be generated first".to_owned())?,
+                    generate.oversampling_ratio,
+                    generate.oversampling_tries,
+                ))
+            } else {
+                None
+            };
+        let generator = Generator::new(Some(derive_seed(self.seed, SeedStream::Synthesis)));
+        let generated_data = match generate.mode {
+            GenerateMode::Unseeded => generator.generate_unseeded(
+                &data_block,
+                resolution,
+                generate.cache_max_size,
+                "",
+                progress_reporter,
+            ),
+            GenerateMode::RowSeeded => generator.generate_row_seeded(
+                &data_block,
+                resolution,
+                generate.cache_max_size,
+                "",
+                progress_reporter,
+            ),
+            GenerateMode::ValueSeeded => generator.generate_value_seeded(
+                &data_block,
+                resolution,
+                generate.cache_max_size,
+                "",
+                oversampling_parameters,
+                progress_reporter,
+            ),
+            GenerateMode::AggregateSeeded => generator.generate_aggregate_seeded(
+                "",
+                self.aggregated_data
+                    .clone()
+                    .ok_or_else(|| "aggregates should be generated first".to_owned())?,
+                generate.use_synthetic_counts,
+                generate.weight_selection_percentile,
+                generate.aggregate_counts_scale_factor,
+                generate.target_number_of_records,
+                progress_reporter,
+            ),
+            GenerateMode::MarginalModel => generator.generate_marginal_model(
+                "",
+                self.aggregated_data
+                    .clone()
+                    .ok_or_else(|| "aggregates should be generated first".to_owned())?,
+                generate.max_clique_size,
+                generate.target_number_of_records,
+                progress_reporter,
+            ),
+        };
+        let numeric_bins_output_mode = match generate.numeric_bins_output {
+            NumericBinsOutput::Label => NumericBinOutputMode::Label,
+            NumericBinsOutput::Representative => NumericBinOutputMode::Representative,
+            NumericBinsOutput::Uniform => NumericBinOutputMode::UniformSample,
+        };
+        let synthetic_path = path_to_string(&step_dir.join("synthetic.tsv"));
+        let mut generated_data =
+            generated_data.map_err(|err| format!("data synthesis error: {}", err))?;
+
+        generated_data.map_numeric_bins(
+            numeric_bins_output_mode,
+            Some(derive_seed(self.seed, SeedStream::NumericBins)),
+        );
+        generated_data
+            .write_synthetic_data(
+                &synthetic_path,
+                generate.synthetic_delimiter.chars().next().unwrap(),
+                "",
+                generate.join_multi_value_columns,
+                generate.long_form,
+                Some(
+                    generate
+                        .date_time_output_formats
+                        .iter()
+                        .map(|f| (f.column_name.clone(), f.output_format.clone()))
+                        .collect(),
+                ),
+            )
+            .map_err(|err| format!("error writing output file: {}", err))?;
+
+        self.synthetic_data.push(SyntheticDataOutput {
+            name: generate.name().to_owned(),
+            path: synthetic_path.clone(),
+            delimiter: generate.synthetic_delimiter.clone(),
+            join_multi_value_columns: generate.join_multi_value_columns,
+        });
+        Ok(vec![synthetic_path])
+    }
+
+    fn evaluate(
+        &mut self,
+        reporting_length: usize,
+        combination_delimiter: &str,
+        not_protect: bool,
+        step_dir: &Path,
+        progress_reporter: &mut Option<LoggerProgressReporter>,
+    ) -> Result<Vec<String>, String> {
+        let evaluator = Evaluator::default();
+        // evaluation compares the raw counts, so they are not protected here
+        let sensitive_aggregated_data = Aggregator::new(self.data_block())
+            .aggregate(reporting_length, progress_reporter)
+            .map_err(|err| format!("data aggregation error: {}", err))?;
+        let mut outputs = Vec::default();
+
+        for synthetic in self.synthetic_data.iter() {
+            let synthetic_data_block = self
+                .config
+                .input
+                .read_synthetic_data_block(
+                    &synthetic.path,
+                    &synthetic.delimiter,
+                    synthetic.join_multi_value_columns,
+                )
+                .map_err(|err| format!("error generating synthetic data block: {}", err))?;
+            let synthetic_aggregated_data = Aggregator::new(synthetic_data_block)
+                .aggregate(
+                    sensitive_aggregated_data.reporting_length,
+                    progress_reporter,
+                )
+                .map_err(|err| format!("data aggregation error: {}", err))?;
+            let mut report = evaluator.calc_evaluation_report(
+                &sensitive_aggregated_data,
+                &synthetic_aggregated_data,
+                self.config.resolution,
+                combination_delimiter,
+                !not_protect,
+            );
+            let report_dir = step_dir.join(&synthetic.name);
+
+            for json in [false, true] {
+                outputs.extend(
+                    write_evaluation_report(&mut report, &report_dir, json)
+                        .map_err(|err| format!("error writing output file: {}", err))?,
+                );
+            }
+
+            info!(
+                "{}: leakage count: {}, mean proportional error: {}, record expansion: {}%",
+                synthetic.name,
+                report.total_leakage_count(),
+                report.mean_proportional_error,
+                report.record_expansion_percentage
+            );
+        }
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+
+    /// Creates an empty test directory with the sensitive data and
+    /// a pipeline config file, returning the config path
+    fn write_test_pipeline(test_name: &str, seed: Option<u64>) -> (PathBuf, String) {
+        let test_dir =
+            std::env::temp_dir().join(format!("sds-cli-{}-{}", test_name, std::process::id()));
+        let sensitive_path = test_dir.join("sensitive.csv");
+        let config_path = test_dir.join("pipeline.toml");
+        let records: String = (0..200)
+            .map(|i| format!("{},{},{}\n", i % 2, i % 3, i % 5))
+            .collect();
+
+        let _ = remove_dir_all(&test_dir);
+        create_dir_all(&test_dir).unwrap();
+        write(&sensitive_path, format!("A,B,C\n{}", records)).unwrap();
+        write(
+            &config_path,
+            format!(
+                r#"
+output_dir = "{}"
+resolution = 2
+{}
+
+[input]
+sensitive_path = "{}"
+sensitive_delimiter = ","
+
+[dp]
+noise_epsilon = 10.0
+noise_delta = 0.001
+sensitivities_percentile = 99
+sensitivities_epsilon_proportion = 0.1
+noise_threshold_values = [1.0, 1.0]
+
+[[steps]]
+step = "aggregate"
+reporting_length = 3
+
+[[steps]]
+step = "generate"
+mode = "aggregate_seeded"
+
+[[steps]]
+step = "generate"
+mode = "row_seeded"
+"#,
+                test_dir.join("run").to_string_lossy(),
+                seed.map_or(String::default(), |seed| format!("seed = {}", seed)),
+                sensitive_path.to_string_lossy()
+            ),
+        )
+        .unwrap();
+        (test_dir, config_path.to_string_lossy().into_owned())
+    }
+
+    /// Runs the pipeline, returning the written manifest
+    /// and the outputs of the generate steps
+    fn run_test_pipeline(config_path: &str) -> (Value, Vec<String>) {
+        let manifest = PipelineRunner::new(config_path)
+            .unwrap()
+            .run(config_path, &mut None)
+            .unwrap();
+        let written_manifest: Value = serde_json::from_str(
+            &read_to_string(Path::new(&manifest.config_path).with_file_name(MANIFEST_FILE_NAME))
+                .unwrap(),
+        )
+        .unwrap();
+        let synthetic_data = manifest
+            .steps
+            .iter()
+            .filter(|step| step.step == "generate")
+            .map(|step| read_to_string(&step.outputs[0]).unwrap())
+            .collect();
+
+        (written_manifest, synthetic_data)
+    }
+
+    #[test]
+    fn validate_derived_seeds() {
+        let streams = [
+            SeedStream::DpNoise,
+            SeedStream::Synthesis,
+            SeedStream::NumericBins,
+        ];
+
+        for stream in streams {
+            assert!(derive_seed(42, stream) == derive_seed(42, stream));
+            assert!(derive_seed(42, stream) != derive_seed(43, stream));
+        }
+        // streams do not share the same seed
+        for (i, stream_a) in streams.iter().enumerate() {
+            for stream_b in streams.iter().skip(i + 1) {
+                assert!(derive_seed(42, *stream_a) != derive_seed(42, *stream_b));
+            }
+        }
+    }
+
+    #[test]
+    fn validate_noise_seed_is_not_derived_from_drawn_seed() {
+        let (drawn_seed_dir, drawn_seed_config_path) = write_test_pipeline("drawn-seed", None);
+        let (set_seed_dir, set_seed_config_path) = write_test_pipeline("set-seed", Some(42));
+        let drawn_seed_runner = PipelineRunner::new(&drawn_seed_config_path).unwrap();
+        let set_seed_runner = PipelineRunner::new(&set_seed_config_path).unwrap();
+
+        assert!(
+            drawn_seed_runner.noise_seed
+                != derive_seed(drawn_seed_runner.seed, SeedStream::DpNoise)
+        );
+        assert!(set_seed_runner.seed == 42);
+        assert!(set_seed_runner.noise_seed == derive_seed(42, SeedStream::DpNoise));
+
+        remove_dir_all(drawn_seed_dir).unwrap();
+        remove_dir_all(set_seed_dir).unwrap();
+    }
+
+    #[test]
+    fn validate_manifest_contents() {
+        let (test_dir, config_path) = write_test_pipeline("manifest", None);
+        let (manifest, _) = run_test_pipeline(&config_path);
+        let fields = manifest.as_object().unwrap();
+
+        assert!(fields["completed"] == true);
+        assert!(fields["seed"].is_u64());
+        assert!(fields["privacy_report"]["epsilon"].as_f64().unwrap() <= 10.0 + 1e-6);
+        assert!(fields["steps"].as_array().unwrap().len() == 3);
+        // the noise seed is never recorded
+        assert!(fields.keys().all(|key| !key.contains("noise")));
+        assert!(!manifest.to_string().contains("noise_seed"));
+
+        remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    fn validate_same_seed_same_synthetic_data() {
+        let (test_dir_a, config_path_a) = write_test_pipeline("same-seed-a", Some(42));
+        let (test_dir_b, config_path_b) = write_test_pipeline("same-seed-b", Some(42));
+        let (manifest_a, synthetic_data_a) = run_test_pipeline(&config_path_a);
+        let (manifest_b, synthetic_data_b) = run_test_pipeline(&config_path_b);
+
+        assert!(manifest_a["seed"] == 42 && manifest_b["seed"] == 42);
+        assert!(manifest_a["privacy_report"] == manifest_b["privacy_report"]);
+        assert!(synthetic_data_a.len() == 2);
+        assert!(synthetic_data_a == synthetic_data_b);
+
+        remove_dir_all(test_dir_a).unwrap();
+        remove_dir_all(test_dir_b).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    cmd_input_deserializer::deserialize_cmd_inputs,
+    date_time_column_cmd_input::DateTimeColumnCmdInput,
+    multi_value_column_cmd_input::MultiValueColumnCmdInput,
+    numeric_binning_cmd_input::NumericBinningCmdInput,
+};
+use sds_core::data_block::{
+    CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreateOptions,
+    DataBlockCreator, SubjectRecordsMode,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc};
+use structopt::StructOpt;
+
+fn default_delimiter() -> String {
+    "\t".to_owned()
+}
+
+#[derive(StructOpt, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SensitiveDataCmdInput {
+    #[structopt(long = "sensitive-path", help = "sensitive microdata path")]
+    pub sensitive_path: Option<String>,
+
+    #[structopt(
+        long = "sensitive-delimiter",
+        help = "csv delimiter for the sensitive microdata file",
+        default_value = "\t"
+    )]
+    #[serde(default = "default_delimiter")]
+    pub sensitive_delimiter: String,
+
+    #[structopt(
+        long = "record-limit",
+        help = "take only these first records (0 means all)",
+        default_value = "0"
+    )]
+    #[serde(default)]
+    pub record_limit: usize,
+
+    #[structopt(long = "subject-id", help = "column indicating the subject id")]
+    pub subject_id: Option<String>,
+
+    #[structopt(
+        long = "keep-subject-records",
+        help = "keep records with the same subject id as separate records instead of joining them (with --dp, each subject contribution is bounded)",
+        requires = "subject-id"
+    )]
+    #[serde(default)]
+    pub keep_subject_records: bool,
+
+    #[structopt(
+        long = "use-columns",
+        help = "use this column (can be set multiple times)"
+    )]
+    #[serde(default)]
+    pub use_columns: Vec<String>,
+
+    #[structopt(long = "multi-value-columns", help = "<column name>,<delimiter>")]
+    #[serde(default, deserialize_with = "deserialize_cmd_inputs")]
+    pub multi_value_columns: Vec<MultiValueColumnCmdInput>,
+
+    #[structopt(
+        long = "sensitive-zeros",
+        help = "columns where zeros should not be ignored (can be set multiple times)"
+    )]
+    #[serde(default)]
+    pub sensitive_zeros: Vec<String>,
+
+    #[structopt(
+        long = "numeric-bins",
+        help = "<column name>,<width|quantile|edges>,<value>[,<value>...] (can be set multiple times, only edges can be used with DP)"
+    )]
+    #[serde(default, deserialize_with = "deserialize_cmd_inputs")]
+    pub numeric_bins: Vec<NumericBinningCmdInput>,
+
+    #[structopt(
+        long = "date-time-columns",
+        help = "<column name>,<year|quarter|month|week|day_of_week|hour_bucket=<size>>[,<input format>] (can be set multiple times)"
+    )]
+    #[serde(default, deserialize_with = "deserialize_cmd_inputs")]
+    pub date_time_columns: Vec<DateTimeColumnCmdInput>,
+}
+
+impl SensitiveDataCmdInput {
+    /// Reads the data block from the sensitive microdata,
+    /// applying all the configured transformations
+    pub fn read_sensitive_data_block(
+        &self,
+        sensitive_path: &str,
+    ) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+        CsvDataBlockCreator::create(
+            csv::ReaderBuilder::new()
+                .delimiter(self.sensitive_delimiter.chars().next().unwrap() as u8)
+                .from_path(sensitive_path),
+            &DataBlockCreateOptions {
+                subject_id: self.subject_id.clone(),
+                subject_records_mode: if self.keep_subject_records {
+                    SubjectRecordsMode::Keep
+                } else {
+                    SubjectRecordsMode::Join
+                },
+                use_columns: self.use_columns.clone(),
+                multi_value_columns: self.multi_value_columns_map(),
+                sensitive_zeros: self.sensitive_zeros.clone(),
+                numeric_binning: self
+                    .numeric_bins
+                    .iter()
+                    .map(|nb| (nb.column_name.clone(), nb.strategy.clone()))
+                    .collect(),
+                date_time_columns: self
+                    .date_time_columns
+                    .iter()
+                    .map(|dt| (dt.column_name.clone(), dt.spec.clone()))
+                    .collect(),
+                record_limit: self.record_limit,
+            },
+        )
+    }
+
+    /// Reads the data block from synthetic microdata generated out of the sensitive one.
+    /// Synthetic records are already subject joined, projected to the used columns
+    /// and binned, so only the multi value columns and sensitive zeros are applied
+    /// # Arguments
+    /// * `synthetic_path` - Path to the synthetic microdata
+    /// * `synthetic_delimiter` - Csv delimiter for the synthetic microdata file
+    /// * `split_multi_value_columns` - Whether multi value columns were joined back together on the synthetic file
+    pub fn read_synthetic_data_block(
+        &self,
+        synthetic_path: &str,
+        synthetic_delimiter: &str,
+        split_multi_value_columns: bool,
+    ) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+        CsvDataBlockCreator::create(
+            csv::ReaderBuilder::new()
+                .delimiter(synthetic_delimiter.chars().next().unwrap() as u8)
+                .from_path(synthetic_path),
+            &DataBlockCreateOptions {
+                multi_value_columns: if split_multi_value_columns {
+                    self.multi_value_columns_map()
+                } else {
+                    HashMap::default()
+                },
+                sensitive_zeros: self.sensitive_zeros.clone(),
+                ..DataBlockCreateOptions::default()
+            },
+        )
+    }
+
+    fn multi_value_columns_map(&self) -> HashMap<String, String> {
+        self.multi_value_columns
+            .iter()
+            .map(|mvc| (mvc.column_name.clone(), mvc.attr_delimiter.clone()))
+            .collect()
+    }
+}
@@ -1,13 +1,13 @@
 use super::{
     typedefs::{
         AttributeRows, AttributeRowsByColumnMap, AttributeRowsMap, ColumnIndexByName,
-        DataBlockHeaders, DataBlockRecords,
+        DataBlockHeaders, DataBlockRecords, RecordsBySubject, SubjectIds, SubjectIndexByRecord,
     },
     value::DataBlockValue,
-    MultiValueColumnMetadataMap, RawData, RawDataMultiValueColumnJoiner,
+    DateTimeMetadataMap, MultiValueColumnMetadataMap, NumericBinsMetadataMap, RawData,
+    RawDataMultiValueColumnJoiner,
 };
 use fnv::FnvHashMap;
-use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -19,6 +19,27 @@ use crate::{
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 
+/// Data used to create a new DataBlock, the parts that are not
+/// available can be left to their defaults with the struct update syntax
+/// (e.g. `DataBlockParts { headers, records, ..DataBlockParts::default() }`)
+#[derive(Default)]
+pub struct DataBlockParts {
+    /// Vector of strings representing the data headers
+    pub headers: DataBlockHeaders,
+    /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
+    pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized binned numeric header name to its computed bins
+    pub numeric_bins_metadata_map: NumericBinsMetadataMap,
+    /// Maps a normalized date/time header name to its granularity
+    pub date_time_metadata_map: DateTimeMetadataMap,
+    /// Vector of data records, where each record represents a row (headers not included)
+    pub records: DataBlockRecords,
+    /// Subject index of each record (if records of the same subject were kept separate)
+    pub subject_index_by_record: Option<SubjectIndexByRecord>,
+    /// ID of each subject (if a subject ID column is used)
+    pub subject_ids: Option<SubjectIds>,
+}
+
 /// Represents a data block that can be derived from a particular dataset.
 /// The goal of this is to allow data processing to handle with memory references
 /// to the data block instead of copying data around
@@ -29,8 +50,18 @@ pub struct DataBlock {
     pub headers: DataBlockHeaders,
     /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
     pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized binned numeric header name to its computed bins
+    pub numeric_bins_metadata_map: NumericBinsMetadataMap,
+    /// Maps a normalized date/time header name to the granularity its values were generalized to
+    pub date_time_metadata_map: DateTimeMetadataMap,
     /// Vector of data records, where each record represents a row (headers not included)
     pub records: DataBlockRecords,
+    /// Subject index of each record, only set when records with the same
+    /// subject ID are kept as separate records
+    pub subject_index_by_record: Option<SubjectIndexByRecord>,
+    /// ID of each subject, only set when a subject ID column is used
+    /// (when records with the same ID are joined, the subject index is the record index)
+    pub subject_ids: Option<SubjectIds>,
 }
 
 impl DataBlock {
@@ -39,29 +70,49 @@ impl DataBlock {
         DataBlock {
             headers: DataBlockHeaders::default(),
             multi_value_column_metadata_map: MultiValueColumnMetadataMap::default(),
+            numeric_bins_metadata_map: NumericBinsMetadataMap::default(),
+            date_time_metadata_map: DateTimeMetadataMap::default(),
             records: DataBlockRecords::default(),
+            subject_index_by_record: None,
+            subject_ids: None,
         }
     }
 
     /// Returns a new DataBlock
     /// # Arguments
-    /// * `headers` - Vector of string representing the data headers
-    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name (such as A_a1) to
-    /// its corresponding metadata
-    /// * `records` - Vector of data records, where each record represents a row (headers not included)
+    /// * `parts` - Data the data block is made of
     #[inline]
-    pub fn new(
-        headers: DataBlockHeaders,
-        multi_value_column_metadata_map: MultiValueColumnMetadataMap,
-        records: DataBlockRecords,
-    ) -> DataBlock {
+    pub fn new(parts: DataBlockParts) -> DataBlock {
         DataBlock {
-            headers,
-            multi_value_column_metadata_map,
-            records,
+            headers: parts.headers,
+            multi_value_column_metadata_map: parts.multi_value_column_metadata_map,
+            numeric_bins_metadata_map: parts.numeric_bins_metadata_map,
+            date_time_metadata_map: parts.date_time_metadata_map,
+            records: parts.records,
+            subject_index_by_record: parts.subject_index_by_record,
+            subject_ids: parts.subject_ids,
         }
     }
 
+    /// Groups the record indexes by subject (the vector index is the subject index).
+    /// Returns `None` if records of the same subject were not kept separate
+    #[inline]
+    pub fn calc_records_by_subject(&self) -> Option<RecordsBySubject> {
+        self.subject_index_by_record
+            .as_ref()
+            .map(|subject_index_by_record| {
+                let mut records_by_subject = RecordsBySubject::default();
+
+                for (record_index, subject_index) in subject_index_by_record.iter().enumerate() {
+                    if *subject_index >= records_by_subject.len() {
+                        records_by_subject.resize_with(*subject_index + 1, Vec::default);
+                    }
+                    records_by_subject[*subject_index].push(record_index);
+                }
+                records_by_subject
+            })
+    }
+
     /// Returns a map of column name -> column index
     #[inline]
     pub fn calc_column_index_by_name(&self) -> ColumnIndexByName {
@@ -154,7 +205,7 @@ impl DataBlock {
                         empty_value.clone(),
                     )))
                     .or_insert_with(RecordsSet::default)
-                    .remove(&i);
+                    .remove(i);
             }
         }
 
@@ -166,7 +217,7 @@ impl DataBlock {
                     column_index,
                     attr_rows
                         .drain()
-                        .map(|(value, mut rows_set)| (value, rows_set.drain().sorted().collect()))
+                        .map(|(value, rows_set)| (value, rows_set.iter().collect()))
                         .collect(),
                 )
             })
@@ -33,6 +33,22 @@ impl<T: Read> DataBlockCreator for CsvDataBlockCreator<T> {
             })
             .collect::<Result<Vec<CsvRecord>, Error>>()
     }
+
+    /// Streams the records from the CSV reader, reusing the same
+    /// buffer so the raw file is never fully loaded in memory
+    fn read_records<F>(reader: &mut Self::InputType, mut process_record: F) -> Result<(), Error>
+    where
+        F: FnMut(&[&str]) -> bool,
+    {
+        let mut record = StringRecord::new();
+
+        while reader.read_record(&mut record)? {
+            if !process_record(&record.iter().collect::<Vec<&str>>()) {
+                break;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Error that could be generated when creating a data block
@@ -9,6 +9,7 @@ use pyo3::prelude::*;
 
 /// Wrapper for a csv::Error, so the from
 /// trait can be implemented for PyErr
+#[derive(Debug)]
 pub struct CsvIOError {
     error: Error,
 }
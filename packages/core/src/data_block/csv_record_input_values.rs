@@ -1,6 +1,8 @@
 use super::{
-    headers_metadata::DataBlockHeadersMetadata, input_value::DataBlockInputValue, CsvRecord,
+    headers_metadata::DataBlockHeadersMetadata, input_value::DataBlockInputValue,
+    values_interner::ValuesInterner,
 };
+use log::debug;
 use std::{collections::HashSet, sync::Arc};
 
 use crate::utils::strings::{normalize_reserved_delimiters, transform_for_insensitive_cmp};
@@ -29,13 +31,14 @@ impl CsvRecordInputValues {
         value: &str,
         header_index: usize,
         sensitive_zeros_set: &HashSet<usize>,
+        values_interner: &mut ValuesInterner,
     ) -> Option<Arc<String>> {
-        let normalized_value = Arc::new(normalize_reserved_delimiters(value));
+        let normalized_value = normalize_reserved_delimiters(value);
 
         if !normalized_value.is_empty()
-            && (sensitive_zeros_set.contains(&header_index) || *normalized_value != "0")
+            && (sensitive_zeros_set.contains(&header_index) || normalized_value != "0")
         {
-            Some(normalized_value)
+            Some(values_interner.intern(header_index, normalized_value))
         } else {
             None
         }
@@ -43,9 +46,10 @@ impl CsvRecordInputValues {
 
     #[inline]
     fn record_value_to_input_value(
-        record_val: String,
+        record_val: &str,
         header_index: usize,
         headers_metadata: &DataBlockHeadersMetadata,
+        values_interner: &mut ValuesInterner,
     ) -> DataBlockInputValue {
         if let Some(value_delimiter) = headers_metadata.multi_value_columns_map.get(&header_index) {
             DataBlockInputValue::MultiValue(
@@ -56,6 +60,7 @@ impl CsvRecordInputValues {
                             value,
                             header_index,
                             &headers_metadata.sensitive_zeros_set,
+                            values_interner,
                         )
                     })
                     .collect(),
@@ -63,54 +68,116 @@ impl CsvRecordInputValues {
         } else {
             DataBlockInputValue::SingleValue(
                 Self::remove_non_sensitive_zeros_and_normalize(
-                    &record_val,
+                    record_val,
                     header_index,
                     &headers_metadata.sensitive_zeros_set,
+                    values_interner,
                 )
-                .unwrap_or_else(|| Arc::new("".to_owned())),
+                .unwrap_or_else(|| values_interner.intern(header_index, "".to_owned())),
             )
         }
     }
 
-    /// Creates the vector of record inputs based on the raw CSV input
-    pub fn create_records_input_values(
-        mut records: Vec<CsvRecord>,
+    /// Creates the record input values from a raw record,
+    /// interning the values using `values_interner`
+    /// # Arguments
+    /// * `record` - Raw record values (indexed by the original header index)
+    /// * `headers_metadata` - Metadata about the headers being parsed
+    /// * `values_interner` - Interner used to share the values memory
+    #[inline]
+    pub fn from_raw_record<S: AsRef<str>>(
+        record: &[S],
         headers_metadata: &DataBlockHeadersMetadata,
+        values_interner: &mut ValuesInterner,
+    ) -> CsvRecordInputValues {
+        CsvRecordInputValues::new(
+            headers_metadata
+                .subject_id_index
+                .map(|index| transform_for_insensitive_cmp(record[index].as_ref()))
+                .unwrap_or_default(),
+            record
+                .iter()
+                .enumerate()
+                .filter_map(|(header_index, record_val)| {
+                    if headers_metadata.use_columns_set.contains(&header_index) {
+                        Some(Self::record_value_to_input_value(
+                            record_val.as_ref(),
+                            header_index,
+                            headers_metadata,
+                            values_interner,
+                        ))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Creates the record inputs one raw record at a time, interning their values,
+/// so the raw input does not need to be fully loaded in memory.
+///
+/// Every record input is still kept until the data block is created (joining
+/// subjects, generalizing dates, binning numeric columns and creating the multi-value
+/// headers need all of them), so memory grows with the distinct values plus one
+/// shared reference per cell, instead of with the raw input size
+pub struct CsvRecordInputValuesCreator<'metadata> {
+    headers_metadata: &'metadata DataBlockHeadersMetadata,
+    record_limit: usize,
+    values_interner: ValuesInterner,
+    records_inputs: Vec<CsvRecordInputValues>,
+}
+
+impl<'metadata> CsvRecordInputValuesCreator<'metadata> {
+    /// Creates a new CsvRecordInputValuesCreator
+    /// # Arguments
+    /// * `headers_metadata` - Metadata about the headers being parsed
+    /// * `record_limit` - Use only the first `record_limit` records (0 means all)
+    #[inline]
+    pub fn new(
+        headers_metadata: &'metadata DataBlockHeadersMetadata,
         record_limit: usize,
-    ) -> Vec<CsvRecordInputValues> {
-        let records_to_take = if record_limit > 0 {
-            record_limit
-        } else {
-            // take all records
-            records.len()
-        };
+    ) -> CsvRecordInputValuesCreator<'metadata> {
+        CsvRecordInputValuesCreator {
+            headers_metadata,
+            record_limit,
+            values_interner: ValuesInterner::new(),
+            records_inputs: Vec::default(),
+        }
+    }
 
-        records
-            .drain(..)
-            .take(records_to_take)
-            .map(|mut record| {
-                CsvRecordInputValues::new(
-                    headers_metadata
-                        .subject_id_index
-                        .map(|index| transform_for_insensitive_cmp(&record[index]))
-                        .unwrap_or_default(),
-                    record
-                        .drain(..)
-                        .enumerate()
-                        .filter_map(|(header_index, record_val)| {
-                            if headers_metadata.use_columns_set.contains(&header_index) {
-                                Some(Self::record_value_to_input_value(
-                                    record_val,
-                                    header_index,
-                                    headers_metadata,
-                                ))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect(),
-                )
-            })
-            .collect()
+    /// Whether more records should still be added
+    #[inline]
+    pub fn needs_more_records(&self) -> bool {
+        self.record_limit == 0 || self.records_inputs.len() < self.record_limit
+    }
+
+    /// Maps a raw record to its input values and stores it.
+    /// Returns whether more records should still be added
+    /// # Arguments
+    /// * `record` - Raw record values (indexed by the original header index)
+    #[inline]
+    pub fn add_record<S: AsRef<str>>(&mut self, record: &[S]) -> bool {
+        if self.needs_more_records() {
+            self.records_inputs
+                .push(CsvRecordInputValues::from_raw_record(
+                    record,
+                    self.headers_metadata,
+                    &mut self.values_interner,
+                ));
+        }
+        self.needs_more_records()
+    }
+
+    /// Returns the created record inputs
+    #[inline]
+    pub fn into_records_inputs(self) -> Vec<CsvRecordInputValues> {
+        debug!(
+            "{} records read with {} distinct values",
+            self.records_inputs.len(),
+            self.values_interner.len()
+        );
+        self.records_inputs
     }
 }
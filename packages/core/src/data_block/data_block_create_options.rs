@@ -0,0 +1,25 @@
+use super::{DateTimeColumnSpec, NumericBinningStrategy, SubjectRecordsMode};
+use std::collections::HashMap;
+
+/// Options used to create a data block, the ones that are not set
+/// can be left to their defaults with the struct update syntax
+/// (e.g. `DataBlockCreateOptions { record_limit: 100, ..DataBlockCreateOptions::default() }`)
+#[derive(Debug, Clone, Default)]
+pub struct DataBlockCreateOptions {
+    /// Optional name of an ID column, used to join records with the same id
+    pub subject_id: Option<String>,
+    /// Whether records with the same id should be joined or kept separate
+    pub subject_records_mode: SubjectRecordsMode,
+    /// Column names to be used (empty means all)
+    pub use_columns: Vec<String>,
+    /// Multi value column names and their corresponding delimiters
+    pub multi_value_columns: HashMap<String, String>,
+    /// Column names with sensitive zeros
+    pub sensitive_zeros: Vec<String>,
+    /// Numeric column names to be binned and their binning strategies
+    pub numeric_binning: HashMap<String, NumericBinningStrategy>,
+    /// Date/time column names and how they should be generalized
+    pub date_time_columns: HashMap<String, DateTimeColumnSpec>,
+    /// Use only the first `record_limit` records (0 means all)
+    pub record_limit: usize,
+}
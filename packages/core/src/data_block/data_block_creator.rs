@@ -1,14 +1,17 @@
 use super::{
-    block::DataBlock,
-    csv_record_input_values::CsvRecordInputValues,
+    block::{DataBlock, DataBlockParts},
+    csv_record_input_values::{CsvRecordInputValues, CsvRecordInputValuesCreator},
+    date_time_generalizer::DateTimeColumnsGeneralizer,
     headers_metadata::DataBlockHeadersMetadata,
     input_value::DataBlockInputValue,
+    numeric_binner::NumericColumnsBinner,
     record::DataBlockRecord,
     subject_id_joiner::SubjectIdJoiner,
     typedefs::{CsvRecord, DataBlockRecords},
     value::DataBlockValue,
-    DataBlockCreatorError, DataBlockHeadersSlice,
+    DataBlockCreateOptions, DataBlockCreatorError, DataBlockHeadersSlice,
 };
+use fnv::FnvHashSet;
 use itertools::Itertools;
 use std::{collections::HashMap, fmt::Display, sync::Arc};
 
@@ -35,6 +38,22 @@ where
             .enumerate()
             .map(|(i, h)| (h.clone(), i))
             .collect();
+        // share the same value between records, instead of allocating one per occurrence
+        let mut interned_values: FnvHashSet<Arc<DataBlockValue>> = FnvHashSet::default();
+        let mut intern_value = |column_index: usize, value: Arc<String>| {
+            let value = DataBlockValue::new(column_index, value);
+
+            match interned_values.get(&value) {
+                Some(interned_value) => interned_value.clone(),
+                None => {
+                    let interned_value = Arc::new(value);
+
+                    interned_values.insert(interned_value.clone());
+                    interned_value
+                }
+            }
+        };
+        let multi_value_present = Arc::new("1".to_owned());
 
         records_inputs
             .drain(..)
@@ -47,22 +66,22 @@ where
                     match input_value {
                         DataBlockInputValue::SingleValue(value) => {
                             if !value.is_empty() {
-                                result_records.push(Arc::new(DataBlockValue::new(
+                                result_records.push(intern_value(
                                     header_index_by_name[normalized_header],
                                     value,
-                                )));
+                                ));
                             }
                         }
                         DataBlockInputValue::MultiValue(mut values) => {
                             result_records.extend(values.drain().sorted().map(|value| {
-                                Arc::new(DataBlockValue::new(
+                                intern_value(
                                     header_index_by_name
                                         [&DataBlockHeadersMetadata::format_multi_value_header(
                                             normalized_header,
                                             &value,
                                         )],
-                                    Arc::new("1".to_owned()),
-                                ))
+                                    multi_value_present.clone(),
+                                )
                             }));
                         }
                     }
@@ -72,40 +91,67 @@ where
             .collect()
     }
 
+    /// Creates the data block from the input
+    ///
+    /// Rows are read with `read_records` and their values interned as they are read,
+    /// but all the rows are kept until the data block is created, since subjects,
+    /// date/time columns, numeric bins and multi-value headers depend on every row
+    /// # Arguments
+    /// * `input_res` - Creator input (or the error generated while creating the input)
+    /// * `options` - Columns to be used and how their values should be processed
     #[inline]
     fn create(
         input_res: Result<Self::InputType, Self::ErrorType>,
-        subject_id: Option<String>,
-        use_columns: &[String],
-        multi_value_columns: &HashMap<String, String>,
-        sensitive_zeros: &[String],
-        record_limit: usize,
+        options: &DataBlockCreateOptions,
     ) -> Result<Arc<DataBlock>, DataBlockCreatorError<Self::ErrorType>> {
         let mut input = input_res.map_err(DataBlockCreatorError::ParsingError)?;
         let headers_metadata = DataBlockHeadersMetadata::new(
             Self::get_headers(&mut input).map_err(DataBlockCreatorError::ParsingError)?,
-            subject_id,
-            use_columns,
-            multi_value_columns,
-            sensitive_zeros,
+            options.subject_id.clone(),
+            &options.use_columns,
+            &options.multi_value_columns,
+            &options.sensitive_zeros,
+            &options.numeric_binning,
+            &options.date_time_columns,
         );
-        let records_inputs = SubjectIdJoiner::join_records_by_subject_id(
-            CsvRecordInputValues::create_records_input_values(
-                Self::get_records(&mut input).map_err(DataBlockCreatorError::ParsingError)?,
+        let mut records_inputs_creator =
+            CsvRecordInputValuesCreator::new(&headers_metadata, options.record_limit);
+
+        Self::read_records(&mut input, |record| {
+            records_inputs_creator.add_record(record)
+        })
+        .map_err(DataBlockCreatorError::ParsingError)?;
+
+        let (mut records_inputs, subject_index_by_record) =
+            SubjectIdJoiner::join_records_by_subject_id(
+                records_inputs_creator.into_records_inputs(),
                 &headers_metadata,
-                record_limit,
-            ),
+                options.subject_records_mode,
+            )?;
+        let subject_ids = SubjectIdJoiner::<Self::ErrorType>::calc_subject_ids(
+            &records_inputs,
+            &headers_metadata,
+            &subject_index_by_record,
+        );
+        let date_time_metadata_map = DateTimeColumnsGeneralizer::generalize_date_time_columns(
+            &mut records_inputs,
             &headers_metadata,
         )?;
+        let numeric_bins_metadata_map =
+            NumericColumnsBinner::bin_numeric_columns(&mut records_inputs, &headers_metadata)?;
         let (headers, multi_value_column_metadata_map) =
             headers_metadata.create_headers_and_multi_value_columns_metadata(&records_inputs);
         let records = Self::create_records(&headers, &headers_metadata, records_inputs);
 
-        Ok(Arc::new(DataBlock::new(
+        Ok(Arc::new(DataBlock::new(DataBlockParts {
             headers,
             multi_value_column_metadata_map,
+            numeric_bins_metadata_map,
+            date_time_metadata_map,
             records,
-        )))
+            subject_index_by_record,
+            subject_ids,
+        })))
     }
 
     /// Should be implemented to return the CsvRecords representing the headers
@@ -113,4 +159,28 @@ where
 
     /// Should be implemented to return the vector of CsvRecords representing rows
     fn get_records(input: &mut Self::InputType) -> Result<Vec<CsvRecord>, Self::ErrorType>;
+
+    /// Reads the rows one at a time, passing each one to `process_record`,
+    /// until all rows are read or `process_record` returns `false`.
+    ///
+    /// The default implementation loads all the rows with `get_records` first.
+    /// Creators able to read the input incrementally should override this,
+    /// so the raw rows never need to be fully loaded in memory
+    /// # Arguments
+    /// * `input` - Creator input
+    /// * `process_record` - Called with the values of each row (indexed by column index)
+    fn read_records<F>(
+        input: &mut Self::InputType,
+        mut process_record: F,
+    ) -> Result<(), Self::ErrorType>
+    where
+        F: FnMut(&[&str]) -> bool,
+    {
+        for record in Self::get_records(input)? {
+            if !process_record(&record.iter().map(|value| value.as_str()).collect_vec()) {
+                break;
+            }
+        }
+        Ok(())
+    }
 }
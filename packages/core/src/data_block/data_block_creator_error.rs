@@ -18,6 +18,10 @@ where
     /// This is generated while trying to join records using the
     /// Subject ID
     JoinRecordsByIdError(String),
+    /// This is generated while trying to bin numeric columns
+    NumericBinningError(String),
+    /// This is generated while trying to parse and generalize date/time columns
+    DateTimeGeneralizationError(String),
 }
 
 impl<T> Display for DataBlockCreatorError<T>
@@ -31,6 +35,8 @@ where
             match self {
                 DataBlockCreatorError::ParsingError(err) => format!("{err}"),
                 DataBlockCreatorError::JoinRecordsByIdError(err) => err.clone(),
+                DataBlockCreatorError::NumericBinningError(err) => err.clone(),
+                DataBlockCreatorError::DateTimeGeneralizationError(err) => err.clone(),
             }
         )
     }
@@ -45,6 +51,8 @@ where
         PyIOError::new_err(match err {
             DataBlockCreatorError::ParsingError(err) => format!("{err}"),
             DataBlockCreatorError::JoinRecordsByIdError(err) => err,
+            DataBlockCreatorError::NumericBinningError(err) => err,
+            DataBlockCreatorError::DateTimeGeneralizationError(err) => err,
         })
     }
 }
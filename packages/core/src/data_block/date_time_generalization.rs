@@ -0,0 +1,220 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Formats tried (in order) to parse date/time values,
+/// when an input format is not provided
+const DEFAULT_DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Formats tried (in order) to parse date only values,
+/// when an input format is not provided
+const DEFAULT_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+/// Ordered week days, used to parse the day of week labels back
+const WEEK_DAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "value")]
+/// Granularity used to generalize date/time values
+pub enum DateTimeGranularity {
+    /// Generalize to the year (e.g. `2021`)
+    Year,
+    /// Generalize to the year quarter (e.g. `2021-Q3`)
+    Quarter,
+    /// Generalize to the year month (e.g. `2021-07`)
+    Month,
+    /// Generalize to the ISO week (e.g. `2021-W07`)
+    Week,
+    /// Generalize to the day of week (e.g. `Monday`)
+    DayOfWeek,
+    /// Generalize to buckets of hours with this size (e.g. `08h-11h` for a size of 4),
+    /// should be a value between 1 and 24
+    HourBucket(u32),
+}
+
+impl DateTimeGranularity {
+    /// Generalizes the date/time to a label representing this granularity
+    /// # Arguments
+    /// * `date_time` - Date/time to be generalized
+    pub fn generalize(&self, date_time: &NaiveDateTime) -> String {
+        match self {
+            DateTimeGranularity::Year => format!("{:04}", date_time.year()),
+            DateTimeGranularity::Quarter => {
+                format!("{:04}-Q{}", date_time.year(), date_time.month0() / 3 + 1)
+            }
+            DateTimeGranularity::Month => {
+                format!("{:04}-{:02}", date_time.year(), date_time.month())
+            }
+            DateTimeGranularity::Week => {
+                let week = date_time.iso_week();
+                format!("{:04}-W{:02}", week.year(), week.week())
+            }
+            DateTimeGranularity::DayOfWeek => Self::week_day_name(date_time.weekday()).to_owned(),
+            DateTimeGranularity::HourBucket(size) => {
+                let start = (date_time.hour() / size) * size;
+                format!("{:02}h-{:02}h", start, (start + size - 1).min(23))
+            }
+        }
+    }
+
+    /// Parses a label generated by `generalize` back to the date/time
+    /// representing the beginning of the generalized period
+    /// (day of week and hour buckets are mapped to reference dates)
+    /// # Arguments
+    /// * `label` - Label to be parsed
+    pub fn parse_label(&self, label: &str) -> Option<NaiveDateTime> {
+        let date = match self {
+            DateTimeGranularity::Year => NaiveDate::from_ymd_opt(label.parse().ok()?, 1, 1),
+            DateTimeGranularity::Quarter => {
+                let (year, quarter) = label.split_once("-Q")?;
+                let quarter: u32 = quarter.parse().ok()?;
+
+                if !(1..=4).contains(&quarter) {
+                    return None;
+                }
+                NaiveDate::from_ymd_opt(year.parse().ok()?, (quarter - 1) * 3 + 1, 1)
+            }
+            DateTimeGranularity::Month => {
+                let (year, month) = label.split_once('-')?;
+                NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)
+            }
+            DateTimeGranularity::Week => {
+                let (year, week) = label.split_once("-W")?;
+                NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+            }
+            DateTimeGranularity::DayOfWeek => {
+                let week_day = WEEK_DAYS
+                    .iter()
+                    .find(|wd| Self::week_day_name(**wd) == label)?;
+                // 2001-01-01 is a Monday
+                NaiveDate::from_ymd_opt(2001, 1, 1 + week_day.num_days_from_monday())
+            }
+            DateTimeGranularity::HourBucket(_) => {
+                let hour = label.get(0..2)?.parse().ok()?;
+                return NaiveDate::from_ymd_opt(1970, 1, 1)?.and_hms_opt(hour, 0, 0);
+            }
+        };
+        date?.and_hms_opt(0, 0, 0)
+    }
+
+    /// Validates the granularity parameters
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            DateTimeGranularity::HourBucket(size) if *size == 0 || *size > 24 => Err(format!(
+                "hour bucket size must be between 1 and 24, got {size}"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn week_day_name(week_day: Weekday) -> &'static str {
+        match week_day {
+            Weekday::Mon => "Monday",
+            Weekday::Tue => "Tuesday",
+            Weekday::Wed => "Wednesday",
+            Weekday::Thu => "Thursday",
+            Weekday::Fri => "Friday",
+            Weekday::Sat => "Saturday",
+            Weekday::Sun => "Sunday",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// Specifies how a date/time column should be parsed and generalized
+pub struct DateTimeColumnSpec {
+    /// Format used to parse the column values (`chrono` strftime syntax),
+    /// if `None` common ISO 8601 formats will be tried
+    #[serde(default)]
+    pub input_format: Option<String>,
+    /// Granularity the column values will be generalized to
+    pub granularity: DateTimeGranularity,
+}
+
+impl DateTimeColumnSpec {
+    /// Creates a new DateTimeColumnSpec
+    /// # Arguments
+    /// * `input_format` - Format used to parse the column values (`None` tries ISO 8601 formats)
+    /// * `granularity` - Granularity the column values will be generalized to
+    #[inline]
+    pub fn new(input_format: Option<String>, granularity: DateTimeGranularity) -> Self {
+        DateTimeColumnSpec {
+            input_format,
+            granularity,
+        }
+    }
+
+    /// Parses a date/time value using the configured input format
+    /// (date only values are set to midnight, and time zone offsets are dropped)
+    /// # Arguments
+    /// * `value` - Value to be parsed
+    pub fn parse(&self, value: &str) -> Option<NaiveDateTime> {
+        match &self.input_format {
+            Some(format) => Self::parse_with_formats(value, &[format], &[format]),
+            None => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|date_time| date_time.naive_local())
+                .or_else(|| {
+                    Self::parse_with_formats(
+                        value,
+                        &DEFAULT_DATE_TIME_FORMATS,
+                        &DEFAULT_DATE_FORMATS,
+                    )
+                }),
+        }
+    }
+
+    #[inline]
+    fn parse_with_formats<S: AsRef<str>>(
+        value: &str,
+        date_time_formats: &[S],
+        date_formats: &[S],
+    ) -> Option<NaiveDateTime> {
+        date_time_formats
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format.as_ref()).ok())
+            .or_else(|| {
+                date_formats.iter().find_map(|format| {
+                    NaiveDate::parse_from_str(value, format.as_ref())
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+            })
+    }
+}
+
+/// Renders a generalized date/time label using the provided output
+/// format (`chrono` strftime syntax).
+///
+/// Returns `None` if the label or the format are not valid
+/// # Arguments
+/// * `label` - Generalized label
+/// * `granularity` - Granularity used to generate the label
+/// * `output_format` - Format used to render the beginning of the generalized period
+pub fn format_date_time_label(
+    label: &str,
+    granularity: &DateTimeGranularity,
+    output_format: &str,
+) -> Option<String> {
+    let date_time = granularity.parse_label(label)?;
+    let mut result = String::new();
+
+    // invalid format strings are reported as write errors
+    write!(result, "{}", date_time.format(output_format)).ok()?;
+    Some(result)
+}
@@ -0,0 +1,102 @@
+use super::{
+    csv_record_input_values::CsvRecordInputValues, headers_metadata::DataBlockHeadersMetadata,
+    input_value::DataBlockInputValue, DataBlockCreatorError, DateTimeColumnSpec,
+    DateTimeMetadataMap,
+};
+use fnv::FnvHashMap;
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
+
+use crate::utils::strings::denormalize_reserved_delimiters;
+
+/// Parses the values of date/time columns and replaces them
+/// by the label of the period they fall into
+pub struct DateTimeColumnsGeneralizer<ErrorType>
+where
+    ErrorType: Display,
+{
+    phantom: PhantomData<ErrorType>,
+}
+
+impl<ErrorType> DateTimeColumnsGeneralizer<ErrorType>
+where
+    ErrorType: Display,
+{
+    #[inline]
+    fn generalize_column(
+        records_inputs: &mut [CsvRecordInputValues],
+        index: usize,
+        header: &str,
+        spec: &DateTimeColumnSpec,
+    ) -> Result<(), DataBlockCreatorError<ErrorType>> {
+        // reuse the same label reference for repeated values
+        let mut labels: FnvHashMap<Arc<String>, Arc<String>> = FnvHashMap::default();
+
+        spec.granularity.validate().map_err(|err| {
+            DataBlockCreatorError::DateTimeGeneralizationError(format!(
+                "error generalizing column {header}: {err}"
+            ))
+        })?;
+
+        for record_input in records_inputs.iter_mut() {
+            match &mut record_input.values[index] {
+                DataBlockInputValue::SingleValue(value) => {
+                    if !value.is_empty() {
+                        let label = match labels.get(value) {
+                            Some(label) => label.clone(),
+                            None => {
+                                let date_time = spec
+                                    .parse(&denormalize_reserved_delimiters(value))
+                                    .ok_or_else(|| {
+                                        DataBlockCreatorError::DateTimeGeneralizationError(
+                                            format!(
+                                                "invalid date/time value \"{value}\" on column {header}"
+                                            ),
+                                        )
+                                    })?;
+                                let label = Arc::new(spec.granularity.generalize(&date_time));
+
+                                labels.insert(value.clone(), label.clone());
+                                label
+                            }
+                        };
+                        *value = label;
+                    }
+                }
+                DataBlockInputValue::MultiValue(_) => {
+                    return Err(DataBlockCreatorError::DateTimeGeneralizationError(format!(
+                        "multi value column {header} can not be used as date/time"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the `records_inputs` vector and, for every date/time column
+    /// set in the `headers_metadata`, replaces the values by their generalized
+    /// labels.
+    ///
+    /// Returns the granularity used for each column mapped by the normalized header name
+    #[inline]
+    pub fn generalize_date_time_columns(
+        records_inputs: &mut [CsvRecordInputValues],
+        headers_metadata: &DataBlockHeadersMetadata,
+    ) -> Result<DateTimeMetadataMap, DataBlockCreatorError<ErrorType>> {
+        let mut date_time_metadata_map = DateTimeMetadataMap::default();
+
+        for (index, header) in headers_metadata
+            .normalized_headers_to_be_used
+            .iter()
+            .enumerate()
+        {
+            if let Some(spec) = headers_metadata
+                .date_time_normalized_names_specs_map
+                .get(&**header)
+            {
+                Self::generalize_column(records_inputs, index, header, spec)?;
+                date_time_metadata_map.insert(header.clone(), spec.granularity.clone());
+            }
+        }
+        Ok(date_time_metadata_map)
+    }
+}
@@ -1,7 +1,7 @@
 use super::{
     csv_record_input_values::CsvRecordInputValues, input_value::DataBlockInputValue, CsvRecord,
-    CsvRecordRef, CsvRecordSlice, DataBlockHeaders, MultiValueColumnMetadata,
-    MultiValueColumnMetadataMap,
+    CsvRecordRef, CsvRecordSlice, DataBlockHeaders, DateTimeColumnSpec, MultiValueColumnMetadata,
+    MultiValueColumnMetadataMap, NumericBinningStrategy,
 };
 use fnv::FnvHashSet;
 use itertools::Itertools;
@@ -29,6 +29,11 @@ pub struct DataBlockHeadersMetadata {
     pub multi_value_columns_map: HashMap<usize, String>,
     /// Normalized header names of the multi value columns and its delimiters
     pub multi_value_column_normalized_names_delimiters_map: HashMap<String, String>,
+    /// Normalized header names of the numeric columns that should be binned
+    /// and their corresponding binning strategies
+    pub numeric_binning_normalized_names_strategies_map: HashMap<String, NumericBinningStrategy>,
+    /// Normalized header names of the date/time columns and their corresponding specs
+    pub date_time_normalized_names_specs_map: HashMap<String, DateTimeColumnSpec>,
 }
 
 impl DataBlockHeadersMetadata {
@@ -143,6 +148,56 @@ impl DataBlockHeadersMetadata {
             .collect()
     }
 
+    #[inline]
+    fn gen_numeric_binning_normalized_names_strategies_map(
+        raw_headers: &CsvRecordSlice,
+        use_columns_set: &HashSet<usize>,
+        numeric_binning: &HashMap<String, NumericBinningStrategy>,
+    ) -> HashMap<String, NumericBinningStrategy> {
+        let numeric_binning_str_map: HashMap<String, &NumericBinningStrategy> = numeric_binning
+            .iter()
+            .map(|(c, strategy)| (transform_for_insensitive_cmp(c), strategy))
+            .collect();
+        raw_headers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| {
+                if use_columns_set.contains(&i) {
+                    numeric_binning_str_map
+                        .get(&transform_for_insensitive_cmp(h))
+                        .map(|strategy| (normalize_reserved_delimiters(h), (*strategy).clone()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn gen_date_time_normalized_names_specs_map(
+        raw_headers: &CsvRecordSlice,
+        use_columns_set: &HashSet<usize>,
+        date_time_columns: &HashMap<String, DateTimeColumnSpec>,
+    ) -> HashMap<String, DateTimeColumnSpec> {
+        let date_time_columns_str_map: HashMap<String, &DateTimeColumnSpec> = date_time_columns
+            .iter()
+            .map(|(c, spec)| (transform_for_insensitive_cmp(c), spec))
+            .collect();
+        raw_headers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| {
+                if use_columns_set.contains(&i) {
+                    date_time_columns_str_map
+                        .get(&transform_for_insensitive_cmp(h))
+                        .map(|spec| (normalize_reserved_delimiters(h), (*spec).clone()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
     /// Creates the headers metadata
     /// # Arguments
     /// * `raw_headers` - Raw headers parsed from the CSV/TSV file
@@ -152,6 +207,8 @@ impl DataBlockHeadersMetadata {
     /// * `multi_value_columns` - Column names with multi value columns and
     /// their corresponding delimiters
     /// * `sensitive_zeros` - Column names with sensitive zeros
+    /// * `numeric_binning` - Numeric column names to be binned and their binning strategies
+    /// * `date_time_columns` - Date/time column names and how they should be generalized
     #[inline]
     pub fn new(
         raw_headers: CsvRecord,
@@ -159,6 +216,8 @@ impl DataBlockHeadersMetadata {
         use_columns: &[String],
         multi_value_columns: &HashMap<String, String>,
         sensitive_zeros: &[String],
+        numeric_binning: &HashMap<String, NumericBinningStrategy>,
+        date_time_columns: &HashMap<String, DateTimeColumnSpec>,
     ) -> Self {
         let subject_id_index = Self::find_subject_id_index(&raw_headers, subject_id);
         let use_columns_set = Self::gen_use_columns_set(&raw_headers, use_columns);
@@ -172,6 +231,17 @@ impl DataBlockHeadersMetadata {
                 &raw_headers,
                 &multi_value_columns_map,
             );
+        let numeric_binning_normalized_names_strategies_map =
+            Self::gen_numeric_binning_normalized_names_strategies_map(
+                &raw_headers,
+                &use_columns_set,
+                numeric_binning,
+            );
+        let date_time_normalized_names_specs_map = Self::gen_date_time_normalized_names_specs_map(
+            &raw_headers,
+            &use_columns_set,
+            date_time_columns,
+        );
 
         DataBlockHeadersMetadata {
             subject_id_index,
@@ -180,6 +250,8 @@ impl DataBlockHeadersMetadata {
             sensitive_zeros_set,
             multi_value_columns_map,
             multi_value_column_normalized_names_delimiters_map,
+            numeric_binning_normalized_names_strategies_map,
+            date_time_normalized_names_specs_map,
         }
     }
 
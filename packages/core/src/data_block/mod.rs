@@ -2,24 +2,35 @@ mod block;
 mod csv_block_creator;
 mod csv_io_error;
 mod csv_record_input_values;
+mod data_block_create_options;
 mod data_block_creator;
 mod data_block_creator_error;
+mod date_time_generalization;
+mod date_time_generalizer;
 mod headers_metadata;
 mod input_value;
 mod multi_value_column_metadata;
+mod numeric_binner;
+mod numeric_binning;
 mod raw_data;
 mod record;
 mod subject_id_joiner;
+mod subject_records_mode;
 mod typedefs;
 mod value;
+mod values_interner;
 
 pub use block::*;
 pub use csv_block_creator::*;
 pub use csv_io_error::*;
+pub use data_block_create_options::*;
 pub use data_block_creator::*;
 pub use data_block_creator_error::*;
+pub use date_time_generalization::*;
 pub use multi_value_column_metadata::*;
+pub use numeric_binning::*;
 pub use raw_data::*;
 pub use record::*;
+pub use subject_records_mode::*;
 pub use typedefs::*;
 pub use value::*;
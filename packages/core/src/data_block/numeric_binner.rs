@@ -0,0 +1,113 @@
+use super::{
+    csv_record_input_values::CsvRecordInputValues, headers_metadata::DataBlockHeadersMetadata,
+    input_value::DataBlockInputValue, DataBlockCreatorError, NumericBinningStrategy,
+    NumericBinsMetadataMap, NumericColumnBins,
+};
+use std::{fmt::Display, marker::PhantomData};
+
+/// Replaces the values of numeric columns by the
+/// label of the bin (range) they fall into
+pub struct NumericColumnsBinner<ErrorType>
+where
+    ErrorType: Display,
+{
+    phantom: PhantomData<ErrorType>,
+}
+
+impl<ErrorType> NumericColumnsBinner<ErrorType>
+where
+    ErrorType: Display,
+{
+    #[inline]
+    fn parse_column_values(
+        records_inputs: &[CsvRecordInputValues],
+        index: usize,
+        header: &str,
+    ) -> Result<Vec<f64>, DataBlockCreatorError<ErrorType>> {
+        let mut values = Vec::with_capacity(records_inputs.len());
+
+        for record_input in records_inputs.iter() {
+            match &record_input.values[index] {
+                DataBlockInputValue::SingleValue(value) => {
+                    if !value.is_empty() {
+                        match value.parse::<f64>() {
+                            Ok(parsed) if parsed.is_finite() => values.push(parsed),
+                            _ => {
+                                return Err(DataBlockCreatorError::NumericBinningError(format!(
+                                    "invalid numeric value \"{value}\" on binned column {header}"
+                                )))
+                            }
+                        }
+                    }
+                }
+                DataBlockInputValue::MultiValue(_) => {
+                    return Err(DataBlockCreatorError::NumericBinningError(format!(
+                        "multi value column {header} can not be binned"
+                    )))
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    #[inline]
+    fn bin_column(
+        records_inputs: &mut [CsvRecordInputValues],
+        index: usize,
+        header: &str,
+        strategy: &NumericBinningStrategy,
+    ) -> Result<NumericColumnBins, DataBlockCreatorError<ErrorType>> {
+        let column_bins = NumericColumnBins::new(
+            strategy,
+            &Self::parse_column_values(records_inputs, index, header)?,
+        )
+        .map_err(|err| {
+            DataBlockCreatorError::NumericBinningError(format!(
+                "error binning column {header}: {err}"
+            ))
+        })?;
+
+        for record_input in records_inputs.iter_mut() {
+            if let DataBlockInputValue::SingleValue(value) = &mut record_input.values[index] {
+                if !value.is_empty() {
+                    // values have already been validated when parsing
+                    *value = column_bins
+                        .find_bin(value.parse::<f64>().unwrap())
+                        .label
+                        .clone();
+                }
+            }
+        }
+        Ok(column_bins)
+    }
+
+    /// Takes the `records_inputs` vector and, for every column with
+    /// a binning strategy set in the `headers_metadata`, replaces the numeric
+    /// values by the corresponding bin labels.
+    ///
+    /// Returns the computed bins mapped by the normalized header name
+    #[inline]
+    pub fn bin_numeric_columns(
+        records_inputs: &mut [CsvRecordInputValues],
+        headers_metadata: &DataBlockHeadersMetadata,
+    ) -> Result<NumericBinsMetadataMap, DataBlockCreatorError<ErrorType>> {
+        let mut numeric_bins_metadata_map = NumericBinsMetadataMap::default();
+
+        for (index, header) in headers_metadata
+            .normalized_headers_to_be_used
+            .iter()
+            .enumerate()
+        {
+            if let Some(strategy) = headers_metadata
+                .numeric_binning_normalized_names_strategies_map
+                .get(&**header)
+            {
+                numeric_bins_metadata_map.insert(
+                    header.clone(),
+                    Self::bin_column(records_inputs, index, header, strategy)?,
+                );
+            }
+        }
+        Ok(numeric_bins_metadata_map)
+    }
+}
@@ -0,0 +1,268 @@
+use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::NumericBinsMetadataMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "value")]
+/// Strategy used to split a numeric column into ranges (bins)
+pub enum NumericBinningStrategy {
+    /// Bins with the same width, aligned to multiples of the width
+    /// (this should be a value greater than 0)
+    FixedWidth(f64),
+    /// Split the column values into this number of bins, each one
+    /// containing approximately the same number of records
+    Quantile(usize),
+    /// User supplied bin edges (should be strictly increasing and contain
+    /// at least 2 values), values outside the edges range fall into
+    /// the first/last bin
+    Edges(Vec<f64>),
+}
+
+impl NumericBinningStrategy {
+    /// Whether the bin edges are computed from the column values.
+    ///
+    /// Fixed width bins span the column min/max and quantile edges are
+    /// column values, so in both cases the edges (and the bin labels)
+    /// disclose sensitive values and can not be used with DP
+    #[inline]
+    pub fn is_data_derived(&self) -> bool {
+        !matches!(self, NumericBinningStrategy::Edges(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a binned value should be written to the synthetic output
+pub enum NumericBinOutputMode {
+    /// Keep the bin label (e.g. `[10, 20)`)
+    Label,
+    /// Replace the bin label by the bin representative value (its midpoint)
+    Representative,
+    /// Replace the bin label by a value uniformly sampled inside the bin
+    UniformSample,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A single numeric range `[lower, upper)`, or `[lower, upper]`
+/// if this is the last bin of the column
+pub struct NumericBin {
+    /// Lower edge of the bin (inclusive)
+    pub lower: f64,
+    /// Upper edge of the bin
+    pub upper: f64,
+    /// Label used as the data block value for this bin
+    pub label: Arc<String>,
+}
+
+impl NumericBin {
+    /// Creates a new NumericBin
+    /// # Arguments
+    /// * `lower` - Lower edge of the bin (inclusive)
+    /// * `upper` - Upper edge of the bin
+    /// * `upper_inclusive` - Whether the upper edge is included in the bin or not
+    #[inline]
+    pub fn new(lower: f64, upper: f64, upper_inclusive: bool) -> NumericBin {
+        NumericBin {
+            lower,
+            upper,
+            label: Arc::new(format!(
+                "[{}, {}{}",
+                format_bin_edge(lower),
+                format_bin_edge(upper),
+                if upper_inclusive { "]" } else { ")" }
+            )),
+        }
+    }
+
+    /// Representative value for the bin (its midpoint)
+    #[inline]
+    pub fn representative_value(&self) -> f64 {
+        (self.lower + self.upper) / 2.0
+    }
+
+    /// Samples a value uniformly inside the bin
+    /// # Arguments
+    /// * `rng` - Random number generator used to sample the value
+    #[inline]
+    pub fn sample_uniform_value<R: Rng>(&self, rng: &mut R) -> f64 {
+        if self.lower < self.upper {
+            rng.gen_range(self.lower..self.upper)
+        } else {
+            self.lower
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Ordered and contiguous bins computed for a numeric column
+pub struct NumericColumnBins {
+    /// Bins ordered by their edges
+    pub bins: Vec<NumericBin>,
+    /// Whether the bin edges were computed from the column values
+    /// (when missing, the bins are assumed to be data derived)
+    #[serde(default = "default_data_derived")]
+    pub data_derived: bool,
+}
+
+#[inline]
+fn default_data_derived() -> bool {
+    true
+}
+
+// bin edges are validated to be finite, so equality is total
+impl Eq for NumericColumnBins {}
+
+impl NumericColumnBins {
+    /// Computes the bins for a numeric column
+    /// # Arguments
+    /// * `strategy` - Strategy used to compute the bins
+    /// * `values` - All the numeric values present in the column
+    pub fn new(strategy: &NumericBinningStrategy, values: &[f64]) -> Result<Self, String> {
+        let edges = match strategy {
+            NumericBinningStrategy::FixedWidth(width) => Self::fixed_width_edges(*width, values)?,
+            NumericBinningStrategy::Quantile(number_of_bins) => {
+                Self::quantile_edges(*number_of_bins, values)?
+            }
+            NumericBinningStrategy::Edges(edges) => Self::validate_edges(edges)?,
+        };
+
+        Ok(Self::from_edges(&edges, strategy.is_data_derived()))
+    }
+
+    #[inline]
+    fn from_edges(edges: &[f64], data_derived: bool) -> Self {
+        let last_index = edges.len().saturating_sub(2);
+
+        NumericColumnBins {
+            bins: edges
+                .iter()
+                .tuple_windows()
+                .enumerate()
+                .map(|(i, (lower, upper))| NumericBin::new(*lower, *upper, i == last_index))
+                .collect(),
+            data_derived,
+        }
+    }
+
+    #[inline]
+    fn fixed_width_edges(width: f64, values: &[f64]) -> Result<Vec<f64>, String> {
+        if !width.is_finite() || width <= 0.0 {
+            return Err(format!("fixed bin width must be > 0, got {width}"));
+        }
+
+        let (min, max) = Self::min_max(values);
+        let first_bin = (min / width).floor() as i64;
+        let last_bin = ((max / width).floor() as i64).max(first_bin);
+
+        Ok((first_bin..=(last_bin + 1))
+            .map(|bin| (bin as f64) * width)
+            .collect())
+    }
+
+    #[inline]
+    fn quantile_edges(number_of_bins: usize, values: &[f64]) -> Result<Vec<f64>, String> {
+        if number_of_bins == 0 {
+            return Err("number of quantile bins must be > 0".to_owned());
+        }
+
+        let sorted_values = values
+            .iter()
+            .sorted_by(|a, b| a.partial_cmp(b).unwrap())
+            .collect_vec();
+
+        if sorted_values.is_empty() {
+            return Ok(vec![0.0, 0.0]);
+        }
+
+        let mut edges: Vec<f64> = (0..number_of_bins)
+            .map(|i| *sorted_values[i * sorted_values.len() / number_of_bins])
+            .dedup()
+            .collect();
+
+        // the last edge closes the last bin
+        edges.push(*sorted_values[sorted_values.len() - 1]);
+
+        if edges.len() > 2 && edges[edges.len() - 1] == edges[edges.len() - 2] {
+            edges.remove(edges.len() - 2);
+        }
+        Ok(edges)
+    }
+
+    #[inline]
+    fn validate_edges(edges: &[f64]) -> Result<Vec<f64>, String> {
+        if edges.len() < 2 {
+            return Err("at least 2 bin edges must be provided".to_owned());
+        }
+        if edges.iter().any(|e| !e.is_finite()) {
+            return Err("bin edges must be finite numbers".to_owned());
+        }
+        if edges.iter().tuple_windows().any(|(a, b)| a >= b) {
+            return Err("bin edges must be strictly increasing".to_owned());
+        }
+        Ok(edges.to_vec())
+    }
+
+    #[inline]
+    fn min_max(values: &[f64]) -> (f64, f64) {
+        if values.is_empty() {
+            (0.0, 0.0)
+        } else {
+            values.iter().fold((f64::MAX, f64::MIN), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            })
+        }
+    }
+
+    /// Returns the bin where `value` falls into
+    /// (values outside the bins range fall into the first/last bin)
+    /// # Arguments
+    /// * `value` - Value to look for
+    #[inline]
+    pub fn find_bin(&self, value: f64) -> &NumericBin {
+        let index = self
+            .bins
+            .partition_point(|bin| bin.lower <= value)
+            .saturating_sub(1);
+        &self.bins[index]
+    }
+
+    /// Returns the bin with the given label (if any)
+    /// # Arguments
+    /// * `label` - Bin label to look for
+    #[inline]
+    pub fn find_bin_by_label(&self, label: &str) -> Option<&NumericBin> {
+        self.bins.iter().find(|bin| *bin.label == label)
+    }
+}
+
+/// Returns the sorted names of the columns whose bins were computed from
+/// the column values (see `NumericBinningStrategy::is_data_derived`)
+/// # Arguments
+/// * `numeric_bins_metadata_map` - Maps a normalized binned numeric header name to its computed bins
+#[inline]
+pub fn data_derived_binned_columns(
+    numeric_bins_metadata_map: &NumericBinsMetadataMap,
+) -> Vec<Arc<String>> {
+    numeric_bins_metadata_map
+        .iter()
+        .filter(|(_, column_bins)| column_bins.data_derived)
+        .map(|(header, _)| header.clone())
+        .sorted()
+        .collect()
+}
+
+/// Formats a bin edge (or a value generated from a bin) removing
+/// floating point noise introduced by the bin computations
+#[inline]
+pub fn format_bin_edge(value: f64) -> String {
+    let rounded = (value * 1e9).round() / 1e9;
+
+    if rounded == 0.0 {
+        // avoid "-0"
+        "0".to_owned()
+    } else {
+        rounded.to_string()
+    }
+}
@@ -1,8 +1,9 @@
 use super::{
     csv_record_input_values::CsvRecordInputValues, headers_metadata::DataBlockHeadersMetadata,
-    input_value::DataBlockInputValue, DataBlockCreatorError,
+    input_value::DataBlockInputValue, DataBlockCreatorError, SubjectIds, SubjectIndexByRecord,
+    SubjectRecordsMode,
 };
-use fnv::FnvHashSet;
+use fnv::{FnvHashMap, FnvHashSet};
 use itertools::Itertools;
 use std::{collections::HashSet, fmt::Display, marker::PhantomData, sync::Arc};
 
@@ -157,22 +158,98 @@ where
         Ok(result)
     }
 
+    #[inline]
+    fn calc_subject_index_by_record(
+        records_inputs: &[CsvRecordInputValues],
+    ) -> SubjectIndexByRecord {
+        let mut subject_index_by_id: FnvHashMap<&str, usize> = FnvHashMap::default();
+
+        // subjects are indexed in the order they first appear
+        records_inputs
+            .iter()
+            .map(|record_input| {
+                let next_index = subject_index_by_id.len();
+
+                *subject_index_by_id
+                    .entry(&record_input.id)
+                    .or_insert(next_index)
+            })
+            .collect()
+    }
+
+    /// Returns the ID of each subject (indexed by subject index), or `None`
+    /// if there is no subject ID column.
+    ///
+    /// This should be called after the records are joined or kept separate,
+    /// so the subject indexes match `subject_index_by_record` (or the record
+    /// indexes, when records are joined)
+    #[inline]
+    pub fn calc_subject_ids(
+        records_inputs: &[CsvRecordInputValues],
+        headers_metadata: &DataBlockHeadersMetadata,
+        subject_index_by_record: &Option<SubjectIndexByRecord>,
+    ) -> Option<SubjectIds> {
+        headers_metadata.subject_id_index?;
+
+        match subject_index_by_record {
+            Some(subject_index_by_record) => {
+                let mut subject_ids = SubjectIds::default();
+
+                for (record_input, subject_index) in
+                    records_inputs.iter().zip(subject_index_by_record.iter())
+                {
+                    // subjects are indexed in the order they first appear
+                    if *subject_index == subject_ids.len() {
+                        subject_ids.push(Arc::new(record_input.id.clone()));
+                    }
+                }
+                Some(subject_ids)
+            }
+            None => Some(
+                records_inputs
+                    .iter()
+                    .map(|record_input| Arc::new(record_input.id.clone()))
+                    .collect(),
+            ),
+        }
+    }
+
     /// Takes the `records_input` vector, the `header_metadata` with
-    /// information about the subject id and multi value columns, and tries
-    /// to join records with same id
+    /// information about the subject id and multi value columns, and
+    /// either tries to join records with same id or keeps them
+    /// separate (based on `subject_records_mode`).
+    ///
+    /// Returns the resulting records and, when records are kept separate,
+    /// the subject index of each one of them
     #[inline]
     pub fn join_records_by_subject_id(
         records_inputs: Vec<CsvRecordInputValues>,
         headers_metadata: &DataBlockHeadersMetadata,
-    ) -> Result<Vec<CsvRecordInputValues>, DataBlockCreatorError<ErrorType>> {
+        subject_records_mode: SubjectRecordsMode,
+    ) -> Result<
+        (Vec<CsvRecordInputValues>, Option<SubjectIndexByRecord>),
+        DataBlockCreatorError<ErrorType>,
+    > {
         if headers_metadata.subject_id_index.is_some() && !records_inputs.is_empty() {
             Self::validate_subject_ids(&records_inputs)?;
-            Self::validate_multi_value_columns_and_group_records_by_id(
-                records_inputs,
-                headers_metadata,
-            )
+
+            match subject_records_mode {
+                SubjectRecordsMode::Join => Ok((
+                    Self::validate_multi_value_columns_and_group_records_by_id(
+                        records_inputs,
+                        headers_metadata,
+                    )?,
+                    None,
+                )),
+                SubjectRecordsMode::Keep => {
+                    let subject_index_by_record =
+                        Self::calc_subject_index_by_record(&records_inputs);
+
+                    Ok((records_inputs, Some(subject_index_by_record)))
+                }
+            }
         } else {
-            Ok(records_inputs)
+            Ok((records_inputs, None))
         }
     }
 }
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// How records sharing the same subject ID should be handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SubjectRecordsMode {
+    /// Join all the records of a subject into a single record
+    /// (columns with different values need to be multi-value columns)
+    #[default]
+    Join,
+    /// Keep every record, but track the subject each one belongs to
+    /// (so each subject contribution can be bounded with user-level DP)
+    Keep,
+}
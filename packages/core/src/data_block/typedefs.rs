@@ -1,6 +1,9 @@
-use super::{record::DataBlockRecord, value::DataBlockValue, MultiValueColumnMetadata};
+use super::{
+    record::DataBlockRecord, value::DataBlockValue, DateTimeGranularity, MultiValueColumnMetadata,
+    NumericColumnBins,
+};
 use fnv::FnvHashMap;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::processing::evaluator::CombinationComparison;
 
@@ -31,9 +34,28 @@ pub type DataBlockHeadersSlice = CsvRecordRefSlice;
 /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
 pub type MultiValueColumnMetadataMap = FnvHashMap<Arc<String>, MultiValueColumnMetadata>;
 
+/// Maps a normalized binned numeric header name to its computed bins
+pub type NumericBinsMetadataMap = FnvHashMap<Arc<String>, NumericColumnBins>;
+
+/// Maps a normalized date/time header name to the granularity its values were generalized to
+pub type DateTimeMetadataMap = FnvHashMap<Arc<String>, DateTimeGranularity>;
+
+/// Maps a date/time header name to the format used to render its values
+/// (`chrono` strftime syntax)
+pub type DateTimeOutputFormats = HashMap<String, String>;
+
 /// Vector of data block records, where each record represents a row
 pub type DataBlockRecords = Vec<Arc<DataBlockRecord>>;
 
+/// Subject index of each data block record (indexed by record index)
+pub type SubjectIndexByRecord = Vec<usize>;
+
+/// ID of each subject (indexed by subject index)
+pub type SubjectIds = Vec<Arc<String>>;
+
+/// Record indexes grouped by subject (indexed by subject index)
+pub type RecordsBySubject = Vec<Vec<usize>>;
+
 /// HashMap with a data block value as key and all the attribute row indexes where it occurs as value
 pub type AttributeRowsMap = FnvHashMap<Arc<DataBlockValue>, AttributeRows>;
 
@@ -1,7 +1,10 @@
 use super::typedefs::DataBlockHeadersSlice;
+use fnv::FnvHasher;
 use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
     fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
     str::FromStr,
     sync::Arc,
 };
@@ -10,12 +13,19 @@ use std::{
 pub const COLUMN_VALUE_DELIMITER: char = ':';
 
 /// Represents a value of a given data block for a particular row and column
-#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+///
+/// The hash of the column index and value is computed once, so hashing
+/// does not go through the value content. Equality and ordering still
+/// compare the content, unless both values share the same memory
+/// (values of the same data block are interned while it is created)
+#[derive(Debug)]
 pub struct DataBlockValue {
     /// Column index this value belongs to starting in '0'
     pub column_index: usize,
     /// Value stored on the CSV file for a given row at `column_index`
     pub value: Arc<String>,
+    /// Hash of the column index and value content
+    content_hash: u64,
 }
 
 impl DataBlockValue {
@@ -25,8 +35,14 @@ impl DataBlockValue {
     /// * `value` - Value stored on the CSV file for a given row at `column_index`
     #[inline]
     pub fn new(column_index: usize, value: Arc<String>) -> DataBlockValue {
+        let mut hasher = FnvHasher::default();
+
+        column_index.hash(&mut hasher);
+        value.hash(&mut hasher);
+
         DataBlockValue {
             column_index,
+            content_hash: hasher.finish(),
             value,
         }
     }
@@ -44,6 +60,39 @@ impl DataBlockValue {
             headers[self.column_index], COLUMN_VALUE_DELIMITER, self.value
         )
     }
+
+    /// Parses a data block value formatted as `{header_name}:{block_value}`
+    /// (the inverse of `as_str_using_headers`)
+    /// # Arguments
+    /// * `headers` - data block headers used to look up the column index
+    /// * `str_value` - value to be parsed
+    pub fn from_str_using_headers(
+        headers: &DataBlockHeadersSlice,
+        str_value: &str,
+    ) -> Result<DataBlockValue, ParseDataBlockValueError> {
+        // header names might contain the delimiter, so pick the longest one matching
+        headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| {
+                str_value.len() > header.len()
+                    && str_value.starts_with(header.as_str())
+                    && str_value[header.len()..].starts_with(COLUMN_VALUE_DELIMITER)
+            })
+            .max_by_key(|(_, header)| header.len())
+            .map(|(column_index, header)| {
+                DataBlockValue::new(
+                    column_index,
+                    Arc::new(str_value[header.len() + 1..].to_owned()),
+                )
+            })
+            .ok_or_else(|| {
+                ParseDataBlockValueError::new(format!(
+                    "\"{}\" should be formatted as {{header_name}}{}{{value}} using one of the headers",
+                    str_value, COLUMN_VALUE_DELIMITER
+                ))
+            })
+    }
 }
 
 impl Display for DataBlockValue {
@@ -98,3 +147,78 @@ impl FromStr for DataBlockValue {
         }
     }
 }
+
+impl PartialEq for DataBlockValue {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.column_index == other.column_index
+            && self.content_hash == other.content_hash
+            && (Arc::ptr_eq(&self.value, &other.value) || self.value == other.value)
+    }
+}
+
+impl Eq for DataBlockValue {}
+
+impl Hash for DataBlockValue {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.content_hash);
+    }
+}
+
+impl PartialOrd for DataBlockValue {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DataBlockValue {
+    /// Values are ordered by column index first, so value
+    /// combinations are always sorted by column, and then by content
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.column_index.cmp(&other.column_index).then_with(|| {
+            if Arc::ptr_eq(&self.value, &other.value) {
+                Ordering::Equal
+            } else {
+                self.value.cmp(&other.value)
+            }
+        })
+    }
+}
+
+/// Serialized representation of a DataBlockValue
+/// (the content hash is recomputed when deserializing)
+#[derive(Serialize, Deserialize)]
+struct SerializedDataBlockValue {
+    column_index: usize,
+    value: Arc<String>,
+}
+
+impl Serialize for DataBlockValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SerializedDataBlockValue {
+            column_index: self.column_index,
+            value: self.value.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataBlockValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let serialized = SerializedDataBlockValue::deserialize(deserializer)?;
+
+        Ok(DataBlockValue::new(
+            serialized.column_index,
+            serialized.value,
+        ))
+    }
+}
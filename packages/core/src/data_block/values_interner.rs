@@ -0,0 +1,49 @@
+use fnv::{FnvHashMap, FnvHashSet};
+use std::sync::Arc;
+
+/// Distinct values of each column read from the input.
+///
+/// Values are interned here, so every occurrence of the same value
+/// on a column shares the same memory (values are still stored and
+/// compared as strings, they are not encoded as integer ids)
+#[derive(Debug, Default)]
+pub struct ValuesInterner {
+    values_by_column: FnvHashMap<usize, FnvHashSet<Arc<String>>>,
+}
+
+impl ValuesInterner {
+    /// Creates a new empty interner
+    #[inline]
+    pub fn new() -> ValuesInterner {
+        ValuesInterner::default()
+    }
+
+    /// Returns the shared reference for `value` on the given column,
+    /// adding it to the interner if it is not there yet
+    /// # Arguments
+    /// * `column_index` - Index of the column the value belongs to
+    /// * `value` - Value to be interned
+    #[inline]
+    pub fn intern(&mut self, column_index: usize, value: String) -> Arc<String> {
+        let column_values = self.values_by_column.entry(column_index).or_default();
+
+        match column_values.get(&value) {
+            Some(interned_value) => interned_value.clone(),
+            None => {
+                let interned_value = Arc::new(value);
+
+                column_values.insert(interned_value.clone());
+                interned_value
+            }
+        }
+    }
+
+    /// Total number of distinct values across all columns
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values_by_column
+            .values()
+            .map(|values| values.len())
+            .sum()
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Controls how the privacy budget is split across combination lengths
+/// during the aggregation with differential privacy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AccuracyMode {
+    /// More budget is spent on longer combinations
+    /// (`sigma_k = sigma_1 / k`)
+    PrioritizeLongCombinations,
+    /// More budget is spent on shorter combinations
+    /// (`sigma_k = sigma_R / (R - k + 1)`, where `R` is the reporting length)
+    PrioritizeShortCombinations,
+    /// The budget is evenly split across combination lengths
+    /// (`sigma_k = sigma_1`)
+    Balanced,
+    /// Custom sigma proportions by combination length
+    Custom(Vec<f64>),
+}
+
+impl AccuracyMode {
+    /// Sigma proportions by combination length, as expected by `DpParameters`
+    /// # Arguments
+    /// * `reporting_length` - Maximum combination length
+    pub fn sigma_proportions(&self, reporting_length: usize) -> Vec<f64> {
+        match self {
+            AccuracyMode::PrioritizeLongCombinations => (0..reporting_length)
+                .map(|i| 1.0 / ((i + 1) as f64))
+                .collect(),
+            AccuracyMode::PrioritizeShortCombinations => (0..reporting_length)
+                .map(|i| 1.0 / ((reporting_length - i) as f64))
+                .collect(),
+            AccuracyMode::Balanced => {
+                let mut sigma_proportions = Vec::default();
+                sigma_proportions.resize_with(reporting_length, || 1.0);
+                sigma_proportions
+            }
+            AccuracyMode::Custom(sigma_proportions) => sigma_proportions.clone(),
+        }
+    }
+
+    /// Parses an accuracy mode name (`prioritize_long_combinations`,
+    /// `prioritize_short_combinations` or `balanced`)
+    /// # Arguments
+    /// * `name` - Name of the accuracy mode
+    pub fn from_name(name: &str) -> Result<AccuracyMode, String> {
+        match name.to_lowercase().as_str() {
+            "prioritize_long_combinations" => Ok(AccuracyMode::PrioritizeLongCombinations),
+            "prioritize_short_combinations" => Ok(AccuracyMode::PrioritizeShortCombinations),
+            "balanced" => Ok(AccuracyMode::Balanced),
+            _ => Err(format!("invalid accuracy mode: {}", name)),
+        }
+    }
+}
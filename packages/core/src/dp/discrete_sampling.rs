@@ -0,0 +1,187 @@
+use rand::{prelude::Distribution, Rng};
+
+/// Denominator used to represent the noise parameters as exact rationals
+/// (`2^20`, so parameters are rounded up with a precision of about `1e-6`)
+pub const DISCRETE_NOISE_PARAMETER_DENOMINATOR: u128 = 1 << 20;
+
+/// Rounds `value` up to a rational with denominator `DISCRETE_NOISE_PARAMETER_DENOMINATOR`,
+/// so the sampled noise is never smaller than the requested one
+#[inline]
+fn to_numerator(value: f64) -> u128 {
+    assert!(
+        value.is_finite() && value > 0.0,
+        "discrete noise parameters must be finite and > 0"
+    );
+    ((value * DISCRETE_NOISE_PARAMETER_DENOMINATOR as f64).ceil() as u128).max(1)
+}
+
+/// Integer square root (`floor(sqrt(value))`)
+#[inline]
+fn isqrt(value: u128) -> u128 {
+    let mut root = (value as f64).sqrt() as u128;
+
+    // fix the floating point estimate
+    while root * root > value {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+    root
+}
+
+/// Samples `true` with probability `num / den` (`num <= den`)
+#[inline]
+fn sample_bernoulli<R: Rng + ?Sized>(num: u128, den: u128, rng: &mut R) -> bool {
+    rng.gen_range(0..den) < num
+}
+
+/// Samples `true` with probability `exp(-num / den)`, for `num / den` in `[0, 1]`
+#[inline]
+fn sample_bernoulli_exp_unit<R: Rng + ?Sized>(num: u128, den: u128, rng: &mut R) -> bool {
+    let mut k: u128 = 1;
+
+    // the probability of going on decreases factorially,
+    // so this should stop after a few iterations
+    while sample_bernoulli(num, den.saturating_mul(k), rng) {
+        k += 1;
+    }
+    k % 2 == 1
+}
+
+/// Samples `true` with probability `exp(-num / den)`
+#[inline]
+fn sample_bernoulli_exp<R: Rng + ?Sized>(mut num: u128, den: u128, rng: &mut R) -> bool {
+    while num > den {
+        if !sample_bernoulli_exp_unit(1, 1, rng) {
+            return false;
+        }
+        num -= den;
+    }
+    sample_bernoulli_exp_unit(num, den, rng)
+}
+
+/// Samples from the discrete Laplace with scale `scale_num / scale_den`
+#[inline]
+fn sample_discrete_laplace<R: Rng + ?Sized>(scale_num: u128, scale_den: u128, rng: &mut R) -> i128 {
+    loop {
+        let u = rng.gen_range(0..scale_num);
+
+        if !sample_bernoulli_exp(u, scale_num, rng) {
+            continue;
+        }
+
+        let mut v: u128 = 0;
+
+        while sample_bernoulli_exp_unit(1, 1, rng) {
+            v += 1;
+        }
+
+        let y = (u + scale_num * v) / scale_den;
+        let negative: bool = rng.gen();
+
+        if negative && y == 0 {
+            continue;
+        }
+        return if negative { -(y as i128) } else { y as i128 };
+    }
+}
+
+/// Integer-valued discrete Laplace distribution,
+/// where `P[X = x]` is proportional to `exp(-|x| / scale)`.
+///
+/// Samples are drawn with the exact rejection sampling from
+/// Canonne, Kamath and Steinke ("The Discrete Gaussian for Differential Privacy"),
+/// which only uses integer arithmetic and uniform integers, so the noise
+/// does not leak through floating point artifacts.
+/// With scale `sensitivity / epsilon` it satisfies `epsilon`-DP
+#[derive(Debug, Clone)]
+pub struct DiscreteLaplace {
+    scale_num: u128,
+}
+
+impl DiscreteLaplace {
+    /// Creates a new DiscreteLaplace
+    /// # Arguments
+    /// * `scale` - Scale of the distribution (rounded up to a rational with denominator `DISCRETE_NOISE_PARAMETER_DENOMINATOR`)
+    #[inline]
+    pub fn new(scale: f64) -> DiscreteLaplace {
+        DiscreteLaplace {
+            scale_num: to_numerator(scale),
+        }
+    }
+}
+
+impl Distribution<i64> for DiscreteLaplace {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        // noise is added to f64 counts, so it has to fit into an i64
+        sample_discrete_laplace(self.scale_num, DISCRETE_NOISE_PARAMETER_DENOMINATOR, rng) as i64
+    }
+}
+
+/// Integer-valued discrete Gaussian distribution,
+/// where `P[X = x]` is proportional to `exp(-x^2 / (2 * sigma^2))`.
+///
+/// Samples are drawn with the exact rejection sampling from
+/// Canonne, Kamath and Steinke ("The Discrete Gaussian for Differential Privacy").
+/// Adding it to a query with L2 sensitivity `l2_sensitivity` satisfies
+/// `l2_sensitivity^2 / (2 * sigma^2)`-zCDP, the same as the continuous Gaussian,
+/// so the privacy accounting does not change
+#[derive(Debug, Clone)]
+pub struct DiscreteGaussian {
+    sigma_squared_num: u128,
+    laplace_scale: u128,
+    acceptance_den: u128,
+}
+
+impl DiscreteGaussian {
+    /// Creates a new DiscreteGaussian
+    /// # Arguments
+    /// * `sigma` - Standard deviation parameter (`sigma^2` is rounded up to a rational with denominator `DISCRETE_NOISE_PARAMETER_DENOMINATOR`)
+    #[inline]
+    pub fn new(sigma: f64) -> DiscreteGaussian {
+        let sigma_squared_num = to_numerator(sigma * sigma);
+        let laplace_scale = isqrt(sigma_squared_num / DISCRETE_NOISE_PARAMETER_DENOMINATOR) + 1;
+
+        DiscreteGaussian {
+            sigma_squared_num,
+            laplace_scale,
+            acceptance_den: (2 * sigma_squared_num)
+                .checked_mul(DISCRETE_NOISE_PARAMETER_DENOMINATOR)
+                .and_then(|v| v.checked_mul(laplace_scale * laplace_scale))
+                .expect("sigma is too large for discrete gaussian sampling"),
+        }
+    }
+
+    /// Samples `true` with probability `exp(-(|y| - sigma^2 / t)^2 / (2 * sigma^2))`,
+    /// where `t` is the discrete Laplace scale
+    #[inline]
+    fn sample_acceptance<R: Rng + ?Sized>(&self, y: i128, rng: &mut R) -> bool {
+        // with sigma^2 = s / den, the exponent is (|y| * t * den - s)^2 / (2 * s * den * t^2)
+        match y
+            .unsigned_abs()
+            .checked_mul(self.laplace_scale * DISCRETE_NOISE_PARAMETER_DENOMINATOR)
+            .map(|v| v.abs_diff(self.sigma_squared_num))
+            .and_then(|diff| diff.checked_mul(diff))
+        {
+            Some(num) => sample_bernoulli_exp(num, self.acceptance_den, rng),
+            // only happens when |y| is many standard deviations away from 0,
+            // where the acceptance probability is negligible
+            None => false,
+        }
+    }
+}
+
+impl Distribution<i64> for DiscreteGaussian {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        loop {
+            let y = sample_discrete_laplace(self.laplace_scale, 1, rng);
+
+            if self.sample_acceptance(y, rng) {
+                return y as i64;
+            }
+        }
+    }
+}
@@ -1,11 +1,20 @@
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Default epsilon proportion used to add noise to the protected number of records
 /// in the aggregated data
 pub const DEFAULT_NUMBER_OF_RECORDS_EPSILON_PROPORTION: f64 = 0.005;
 
+/// Default maximum number of records each subject can contribute with
+/// when records of the same subject are kept separate (user-level DP)
+pub const DEFAULT_MAX_RECORDS_PER_SUBJECT: usize = 1;
+
+/// Confidence used to derive the upper bound of a candidate combination count
+/// from the noisy counts of its sub combinations when pruning candidates
+pub const DEFAULT_CANDIDATE_UPPER_BOUND_CONFIDENCE: f64 = 0.95;
+
 /// Parameters for aggregate generation with differential privacy
 #[cfg_attr(feature = "pyo3", pyclass)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -30,78 +39,155 @@ pub struct DpParameters {
     /// Proportion of epsilon used to add noise to the protected number of records in
     /// the aggregated data (default is 0.005)
     pub number_of_records_epsilon_proportion: Option<f64>,
+    /// Seed used to initialize the random number generator used to sample noise,
+    /// the same seed and inputs will always produce the same noisy aggregates
+    /// - If `None` a random seed will be used
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// When records of the same subject are kept separate, at most this many
+    /// records are randomly sampled from each subject, and sensitivity is computed per
+    /// subject instead of per record (default is 1)
+    /// - Ignored if the data block does not track subjects
+    #[serde(default)]
+    pub max_records_per_subject: Option<usize>,
+    /// If `true`, the noisy counts are projected onto a consistent set with weighted
+    /// least squares before being normalized, which reduces the error without spending
+    /// extra privacy budget (default is `false`)
+    #[serde(default)]
+    pub use_least_squares_consistency: Option<bool>,
+    /// If `true`, noise is sampled exactly from the discrete Gaussian and discrete Laplace
+    /// distributions instead of their floating point counterparts, which avoids
+    /// leaking information through floating point artifacts (default is `false`)
+    #[serde(default)]
+    pub use_discrete_noise: Option<bool>,
+    /// If set, candidate combinations of length `k > 1` are only generated when the
+    /// upper bound of their count is at least this value. The bound is derived from the
+    /// noisy `(k - 1)`-counts (a combination is never more frequent than its sub combinations),
+    /// so pruning spends no extra privacy budget
+    /// - If `None` candidates are not pruned by their upper bound
+    #[serde(default)]
+    pub candidate_min_upper_bound: Option<f64>,
+    /// If set, at most this many candidate combinations are generated for each
+    /// length `k > 1`, keeping the ones with the highest count upper bounds
+    /// - If `None` the number of candidates is not limited
+    #[serde(default)]
+    pub max_candidates_per_len: Option<usize>,
+    /// Privacy budget weight by column name (columns not present have weight 1).
+    /// The noise of each marginal is divided by the highest weight among its columns,
+    /// and the noise of all marginals is rescaled to keep the same privacy budget, so
+    /// marginals involving columns with higher weights are more accurate
+    /// - If `None` all marginals of the same length receive the same noise
+    #[serde(default)]
+    pub column_weights: Option<HashMap<String, f64>>,
+    /// Pairs of column names that never appear together in the same combination
+    /// - If `None` no pair of columns is excluded
+    #[serde(default)]
+    pub excluded_column_pairs: Option<Vec<(String, String)>>,
 }
 
-#[cfg_attr(feature = "pyo3", pymethods)]
 impl DpParameters {
     #[inline]
-    #[cfg(feature = "pyo3")]
-    #[new]
-    /// Creates a new DpParameters structure
+    /// Creates a new DpParameters structure with the required parameters,
+    /// all the optional ones are `None`, so they can be set with the struct update syntax
+    /// (e.g. `DpParameters { seed: Some(42), ..DpParameters::new(1.0, 0.001, 99, 0.1) }`)
     /// # Arguments
-    /// * `epsilon` - Overall privacy budget used between
-    /// percentile filtering and noisy generation by combination length
+    /// * `epsilon` - Overall privacy budget used between percentile filtering and noisy generation by combination length
     /// * `delta` - Delta value used for noisy generation by combination length
     /// * `percentile_percentage` - Percentage used to calculate the percentile that filters sensitivity
-    /// * `percentile_epsilon_proportion` - Maximum proportion to consume of the total privacy budget (0.1 means 10%)
-    /// during the sensitivity filter stage
-    /// * `sigma_proportions` - `epsilon` and `percentile_epsilon_proportion` will be used to infer the
-    /// sigma value by combination length. This parameters
-    /// controls how the budget being split across combination lengths
-    /// (e.g. \[1.0, 2.0, 3.0\] means that `sigma_2 = 2.0 * sigma_1` and `sigma_3 = 3.0 * sigma_1`)
-    ///     - If `None` all the sigma values will be the same
-    /// * `number_of_records_epsilon_proportion` - Proportion of epsilon used to add noise to the protected number of records
-    /// in the aggregated data (default is 0.005)
+    /// * `percentile_epsilon_proportion` - Maximum proportion to consume of the total privacy budget (0.1 means 10%) during the sensitivity filter stage
     pub fn new(
         epsilon: f64,
         delta: f64,
         percentile_percentage: usize,
         percentile_epsilon_proportion: f64,
-        sigma_proportions: Option<Vec<f64>>,
-        number_of_records_epsilon_proportion: Option<f64>,
     ) -> Self {
         DpParameters {
             epsilon,
             delta,
             percentile_percentage,
             percentile_epsilon_proportion,
-            sigma_proportions,
-            number_of_records_epsilon_proportion,
+            sigma_proportions: None,
+            number_of_records_epsilon_proportion: None,
+            seed: None,
+            max_records_per_subject: None,
+            use_least_squares_consistency: None,
+            use_discrete_noise: None,
+            candidate_min_upper_bound: None,
+            max_candidates_per_len: None,
+            column_weights: None,
+            excluded_column_pairs: None,
         }
     }
+}
 
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl DpParameters {
     #[inline]
-    #[cfg(not(feature = "pyo3"))]
-    /// Creates a new DpParameters structure
+    #[new]
+    #[args(
+        sigma_proportions = "None",
+        number_of_records_epsilon_proportion = "None",
+        seed = "None",
+        max_records_per_subject = "None",
+        use_least_squares_consistency = "None",
+        use_discrete_noise = "None",
+        candidate_min_upper_bound = "None",
+        max_candidates_per_len = "None",
+        column_weights = "None",
+        excluded_column_pairs = "None"
+    )]
+    /// Creates a new DpParameters structure, the optional parameters
+    /// can also be passed as keyword arguments
     /// # Arguments
-    /// * `epsilon` - Overall privacy budget used between
-    /// percentile filtering and noisy generation by combination length
+    /// * `epsilon` - Overall privacy budget used between percentile filtering and noisy generation by combination length
     /// * `delta` - Delta value used for noisy generation by combination length
     /// * `percentile_percentage` - Percentage used to calculate the percentile that filters sensitivity
-    /// * `percentile_epsilon_proportion` - Maximum proportion to consume of the total privacy budget (0.1 means 10%)
-    /// during the sensitivity filter stage
-    /// * `sigma_proportions` - `epsilon` and `percentile_epsilon_proportion` will be used to infer the
-    /// sigma value by combination length. This parameters
-    /// controls how the budget being split across combination lengths
-    /// (e.g. \[1.0, 2.0, 3.0\] means that `sigma_2 = 2.0 * sigma_1` and `sigma_3 = 3.0 * sigma_1`)
-    ///     - If `None` all the sigma values will be the same
-    /// * `number_of_records_epsilon_proportion` - Proportion of epsilon used to add noise to the protected number of records
-    /// in the aggregated data (default is 0.005)
-    pub fn new(
+    /// * `percentile_epsilon_proportion` - Maximum proportion to consume of the total privacy budget (0.1 means 10%) during the sensitivity filter stage
+    /// * `sigma_proportions` - Proportions used to split sigma across combination lengths (if `None` all the sigma values will be the same)
+    /// * `number_of_records_epsilon_proportion` - Proportion of epsilon used to add noise to the protected number of records (default is 0.005)
+    /// * `seed` - Seed used to make the noise reproducible (if `None` a random seed will be used)
+    /// * `max_records_per_subject` - Maximum number of records sampled from each subject for user-level DP (default is 1)
+    /// * `use_least_squares_consistency` - Whether the noisy counts should be made consistent with weighted least squares (default is `false`)
+    /// * `use_discrete_noise` - Whether noise should be sampled from the discrete Gaussian/Laplace instead of floating point distributions (default is `false`)
+    /// * `candidate_min_upper_bound` - Minimum count upper bound, derived from the noisy sub combination counts, for a candidate combination to be generated (if `None` candidates are not pruned)
+    /// * `max_candidates_per_len` - Maximum number of candidate combinations generated by length, keeping the highest upper bounds (if `None` there is no limit)
+    /// * `column_weights` - Privacy budget weight by column name, marginals involving columns with higher weights receive less noise (if `None` all weights are 1)
+    /// * `excluded_column_pairs` - Pairs of column names that never appear together in the same combination (if `None` no pair is excluded)
+    #[allow(clippy::too_many_arguments)]
+    pub fn constructor(
         epsilon: f64,
         delta: f64,
         percentile_percentage: usize,
         percentile_epsilon_proportion: f64,
         sigma_proportions: Option<Vec<f64>>,
         number_of_records_epsilon_proportion: Option<f64>,
+        seed: Option<u64>,
+        max_records_per_subject: Option<usize>,
+        use_least_squares_consistency: Option<bool>,
+        use_discrete_noise: Option<bool>,
+        candidate_min_upper_bound: Option<f64>,
+        max_candidates_per_len: Option<usize>,
+        column_weights: Option<HashMap<String, f64>>,
+        excluded_column_pairs: Option<Vec<(String, String)>>,
     ) -> Self {
         DpParameters {
-            epsilon,
-            delta,
-            percentile_percentage,
-            percentile_epsilon_proportion,
             sigma_proportions,
             number_of_records_epsilon_proportion,
+            seed,
+            max_records_per_subject,
+            use_least_squares_consistency,
+            use_discrete_noise,
+            candidate_min_upper_bound,
+            max_candidates_per_len,
+            column_weights,
+            excluded_column_pairs,
+            ..DpParameters::new(
+                epsilon,
+                delta,
+                percentile_percentage,
+                percentile_epsilon_proportion,
+            )
         }
     }
 }
@@ -0,0 +1,346 @@
+use super::{
+    AccuracyMode, InputValueByLen, NoiseAggregator, NoisyCountThreshold,
+    DEFAULT_NUMBER_OF_RECORDS_EPSILON_PROPORTION,
+};
+use log::info;
+use serde::Serialize;
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::io::{BufWriter, Error};
+
+use crate::utils::math::binomial;
+
+/// What the planner solves the DP parameters from
+#[derive(Debug, Clone)]
+pub enum DpPlanTarget {
+    /// Total privacy budget, the noise by combination length is solved from it
+    Epsilon(f64),
+    /// Maximum noise standard deviation by combination length
+    /// (lengths that are not present are not constrained),
+    /// the smallest privacy budget meeting all of them is solved from it
+    NoiseStandardDeviation(InputValueByLen<f64>),
+}
+
+/// Planned noise and its expected effect for a single combination length
+#[derive(Debug, Clone, Serialize)]
+pub struct DpPlanByLen {
+    /// Combination length
+    pub combination_length: usize,
+    /// Noise scale by unit of L2 sensitivity
+    pub sigma: f64,
+    /// Upper bound of the number of combinations of this length a record can contribute to
+    pub l1_sensitivity: usize,
+    /// Standard deviation of the noise added to each count
+    pub noise_standard_deviation: f64,
+    /// Threshold the noisy counts need to exceed in order to be kept
+    pub threshold: f64,
+    /// Probability of a combination not present in the data being reported
+    pub fabrication_probability: f64,
+    /// Expected number of distinct combinations present in the data
+    pub expected_combinations: f64,
+    /// Expected number of combinations not present in the data that are reported
+    pub expected_fabricated_combinations: f64,
+}
+
+/// DP parameters solved by the planner
+#[derive(Debug, Clone, Serialize)]
+pub struct DpPlan {
+    /// Total privacy budget
+    pub epsilon: f64,
+    /// Delta used for noisy generation by combination length
+    pub delta: f64,
+    /// Epsilon used by the DP-percentile of each combination length
+    pub percentile_epsilon: f64,
+    /// Epsilon used to protect the number of records
+    pub number_of_records_epsilon: f64,
+    /// Sigma proportions by combination length
+    pub sigma_proportions: Vec<f64>,
+    /// Planned noise for each combination length (sorted by length)
+    pub by_len: Vec<DpPlanByLen>,
+}
+
+impl DpPlan {
+    /// Serializes the plan to a json file
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    pub fn write_to_json(&self, file_path: &str) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(std::fs::File::create(file_path)?),
+            &self,
+        )?)
+    }
+}
+
+/// Chooses the privacy budget or the noise by combination length before
+/// running the aggregation, without touching the sensitive data.
+///
+/// Only the data shape is used: the number of records, the number of columns
+/// and the average number of distinct values per column. The sensitivity
+/// is bounded by the number of column combinations (the DP-percentile
+/// usually selects a smaller one, resulting in less noise) and the number of
+/// combinations is estimated assuming independent and uniformly distributed values.
+///
+/// By default each subject is assumed to contribute with a single record and
+/// the noise to be sampled from floating point distributions, use
+/// `with_max_records_per_subject` and `with_discrete_noise` to match
+/// the aggregation parameters otherwise
+#[derive(Debug, Clone)]
+pub struct DpPlanner {
+    reporting_length: usize,
+    sigma_proportions: Vec<f64>,
+    number_of_records: usize,
+    number_of_columns: usize,
+    values_per_column: f64,
+    delta: f64,
+    percentile_epsilon_proportion: f64,
+    number_of_records_epsilon_proportion: f64,
+    max_records_per_subject: usize,
+    use_discrete_noise: bool,
+}
+
+impl DpPlanner {
+    /// Creates a new DpPlanner, failing if the parameters are not valid
+    /// # Arguments
+    /// * `reporting_length` - Maximum combination length
+    /// * `accuracy_mode` - How the budget is split across combination lengths
+    /// * `number_of_records` - Expected number of records
+    /// * `number_of_columns` - Number of columns (attributes) of each record
+    /// * `values_per_column` - Average number of distinct values per column
+    /// * `delta` - Delta value used for noisy generation by combination length (default is `1 / (ln(number_of_records) * number_of_records)`)
+    /// * `percentile_epsilon_proportion` - Proportion of the budget used by the sensitivity filter stage
+    /// * `number_of_records_epsilon_proportion` - Proportion of the budget used to protect the number of records (default is 0.005)
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        reporting_length: usize,
+        accuracy_mode: &AccuracyMode,
+        number_of_records: usize,
+        number_of_columns: usize,
+        values_per_column: f64,
+        delta: Option<f64>,
+        percentile_epsilon_proportion: f64,
+        number_of_records_epsilon_proportion: Option<f64>,
+    ) -> Result<DpPlanner, String> {
+        let sigma_proportions = accuracy_mode.sigma_proportions(reporting_length);
+        let number_of_records_epsilon_proportion = number_of_records_epsilon_proportion
+            .unwrap_or(DEFAULT_NUMBER_OF_RECORDS_EPSILON_PROPORTION);
+        let delta = match delta {
+            Some(delta) => delta,
+            None if number_of_records > 1 => {
+                let n_records_f64 = number_of_records as f64;
+                1.0 / (n_records_f64.ln() * n_records_f64)
+            }
+            None => return Err("number of records must be > 1 to infer delta".to_owned()),
+        };
+
+        if reporting_length == 0 || reporting_length > number_of_columns {
+            return Err(
+                "reporting length must be > 0 and not greater than the number of columns"
+                    .to_owned(),
+            );
+        }
+        if sigma_proportions.len() != reporting_length
+            || sigma_proportions.iter().any(|p| *p <= 0.0)
+        {
+            return Err(
+                "sigma proportions must be > 0 and their length must match the reporting length"
+                    .to_owned(),
+            );
+        }
+        if number_of_records == 0 || values_per_column < 1.0 {
+            return Err("number of records must be > 0 and values per column >= 1".to_owned());
+        }
+        if delta <= 0.0 || delta >= 1.0 {
+            return Err("delta must be > 0 and < 1".to_owned());
+        }
+        if percentile_epsilon_proportion <= 0.0
+            || number_of_records_epsilon_proportion <= 0.0
+            || percentile_epsilon_proportion + number_of_records_epsilon_proportion >= 1.0
+        {
+            return Err("percentile and number of records epsilon proportions must be > 0 and their sum must be < 1".to_owned());
+        }
+
+        Ok(DpPlanner {
+            reporting_length,
+            sigma_proportions,
+            number_of_records,
+            number_of_columns,
+            values_per_column,
+            delta,
+            percentile_epsilon_proportion,
+            number_of_records_epsilon_proportion,
+            max_records_per_subject: 1,
+            use_discrete_noise: false,
+        })
+    }
+
+    /// Returns the planner for user-level DP, where each subject contributes
+    /// with up to `max_records_per_subject` records
+    /// # Arguments
+    /// * `max_records_per_subject` - Maximum number of records sampled from each subject
+    #[inline]
+    pub fn with_max_records_per_subject(mut self, max_records_per_subject: usize) -> DpPlanner {
+        self.max_records_per_subject = max_records_per_subject.max(1);
+        self
+    }
+
+    /// Returns the planner for noise sampled from the discrete gaussian
+    /// # Arguments
+    /// * `use_discrete_noise` - Whether the aggregation samples discrete noise
+    #[inline]
+    pub fn with_discrete_noise(mut self, use_discrete_noise: bool) -> DpPlanner {
+        self.use_discrete_noise = use_discrete_noise;
+        self
+    }
+
+    /// Solves the DP parameters from the target and estimates the expected
+    /// number of fabricated combinations by length using the threshold
+    /// # Arguments
+    /// * `target` - Privacy budget or maximum noise by combination length
+    /// * `threshold` - Threshold used to filter noisy counts
+    pub fn plan(
+        &self,
+        target: &DpPlanTarget,
+        threshold: &NoisyCountThreshold,
+    ) -> Result<DpPlan, String> {
+        let l1_sensitivities: Vec<usize> = (1..=self.reporting_length)
+            .map(|comb_len| binomial(self.number_of_columns, comb_len))
+            .collect();
+        let epsilon = match target {
+            DpPlanTarget::Epsilon(epsilon) if *epsilon > 0.0 => *epsilon,
+            DpPlanTarget::Epsilon(_) => return Err("epsilon must be > 0".to_owned()),
+            DpPlanTarget::NoiseStandardDeviation(stds) => {
+                self.solve_epsilon(stds, &l1_sensitivities)?
+            }
+        };
+        let (percentile_epsilon, number_of_records_epsilon, sigmas) =
+            NoiseAggregator::calc_percentile_epsilon_number_of_records_epsilon_and_sigma_by_len(
+                self.reporting_length,
+                epsilon,
+                self.delta,
+                self.percentile_epsilon_proportion,
+                self.number_of_records_epsilon_proportion,
+                &Some(self.sigma_proportions.clone()),
+            );
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let by_len = sigmas
+            .iter()
+            .zip(l1_sensitivities.iter())
+            .enumerate()
+            .map(|(i, (sigma, l1_sensitivity))| {
+                let comb_len = i + 1;
+                let noise_standard_deviation = sigma * self.calc_l2_sensitivity(*l1_sensitivity);
+                let threshold = NoiseAggregator::calc_threshold_with_contribution(
+                    threshold,
+                    self.delta,
+                    *l1_sensitivity,
+                    comb_len,
+                    noise_standard_deviation,
+                    self.max_contribution_per_combination(*l1_sensitivity),
+                    self.use_discrete_noise,
+                );
+                // 1-counts are only generated from the values present in the data,
+                // so they are never fabricated
+                let fabrication_probability = if comb_len == 1 {
+                    0.0
+                } else {
+                    // noisy counts rounding to 0 are also removed
+                    normal.sf(threshold.max(0.5) / noise_standard_deviation)
+                };
+                let (expected_combinations, expected_missing_combinations) =
+                    self.estimate_combinations(comb_len);
+
+                DpPlanByLen {
+                    combination_length: comb_len,
+                    sigma: *sigma,
+                    l1_sensitivity: *l1_sensitivity,
+                    noise_standard_deviation,
+                    threshold,
+                    fabrication_probability,
+                    expected_combinations,
+                    expected_fabricated_combinations: fabrication_probability
+                        * expected_missing_combinations,
+                }
+            })
+            .collect();
+
+        Ok(DpPlan {
+            epsilon,
+            delta: self.delta,
+            percentile_epsilon,
+            number_of_records_epsilon,
+            sigma_proportions: self.sigma_proportions.clone(),
+            by_len,
+        })
+    }
+
+    /// Smallest epsilon whose noise is not greater than the target for any length
+    #[inline]
+    fn solve_epsilon(
+        &self,
+        stds: &InputValueByLen<f64>,
+        l1_sensitivities: &[usize],
+    ) -> Result<f64, String> {
+        let mut base_sigma = f64::INFINITY;
+
+        for (comb_len, std) in stds.iter() {
+            if *comb_len == 0 || *comb_len > self.reporting_length || *std <= 0.0 {
+                return Err(format!(
+                    "invalid noise standard deviation target for length {}: {}",
+                    comb_len, std
+                ));
+            }
+            base_sigma = base_sigma.min(
+                std / (self.sigma_proportions[comb_len - 1]
+                    * self.calc_l2_sensitivity(l1_sensitivities[comb_len - 1])),
+            );
+        }
+
+        if !base_sigma.is_finite() {
+            return Err("at least one noise standard deviation target is required".to_owned());
+        }
+
+        // inverts the budget split done by the noise aggregator:
+        // sum(1 / sigma_k^2) / 2 = rho * (1 - percentile_proportion - number_of_records_proportion)
+        let k: f64 = self.sigma_proportions.iter().map(|p| 1.0 / (p * p)).sum();
+        let rho = k
+            / (2.0
+                * base_sigma
+                * base_sigma
+                * (1.0
+                    - self.percentile_epsilon_proportion
+                    - self.number_of_records_epsilon_proportion));
+        let ln_delta = (2.0 / self.delta).ln();
+
+        // rho = (sqrt(epsilon + ln(2 / delta)) - sqrt(ln(2 / delta)))^2
+        Ok(rho + 2.0 * (rho * ln_delta).sqrt())
+    }
+
+    /// Maximum count a single subject can add to the same combination
+    #[inline]
+    fn max_contribution_per_combination(&self, l1_sensitivity: usize) -> usize {
+        self.max_records_per_subject.min(l1_sensitivity)
+    }
+
+    /// Same L2 sensitivity used by the noise aggregator without column weights
+    #[inline]
+    fn calc_l2_sensitivity(&self, l1_sensitivity: usize) -> f64 {
+        ((l1_sensitivity * self.max_contribution_per_combination(l1_sensitivity)) as f64).sqrt()
+    }
+
+    /// Expected number of combinations present and missing in the data
+    #[inline]
+    fn estimate_combinations(&self, comb_len: usize) -> (f64, f64) {
+        let possible_combinations = (binomial(self.number_of_columns, comb_len) as f64)
+            * self.values_per_column.powi(comb_len as i32);
+        // probability of no record containing a given combination
+        let missing_probability = ((self.number_of_records as f64)
+            * (-self.values_per_column.powi(-(comb_len as i32))).ln_1p())
+        .exp();
+
+        (
+            possible_combinations * (1.0 - missing_probability),
+            possible_combinations * missing_probability,
+        )
+    }
+}
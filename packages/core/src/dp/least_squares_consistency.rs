@@ -0,0 +1,172 @@
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::{debug, info};
+use std::sync::Arc;
+
+use crate::{
+    processing::aggregator::{AggregatesCountMap, ValueCombination},
+    utils::time::ElapsedDurationLogger,
+};
+
+/// Default maximum number of iterations used to project the noisy counts
+pub const DEFAULT_LEAST_SQUARES_CONSISTENCY_MAX_ITERATIONS: usize = 100;
+
+/// Default tolerance used to stop the projection (maximum count change in an iteration)
+pub const DEFAULT_LEAST_SQUARES_CONSISTENCY_TOLERANCE: f64 = 1e-3;
+
+/// Weight used for combination lengths without a known noise variance
+const DEFAULT_WEIGHT: f64 = 1.0;
+
+/// Post-processing step that projects the noisy counts onto a
+/// consistent set of counts using weighted least squares:
+/// - a combination count should never be bigger than its parent counts
+/// - counts should not be negative
+///
+/// The parents of a combination are the combination without one of its attributes.
+///
+/// Each count is weighted by the inverse of the noise variance of its
+/// combination length, so less noisy lengths move less.
+/// Since this only post-processes already protected counts, no
+/// privacy budget is spent
+pub struct LeastSquaresConsistency {
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl LeastSquaresConsistency {
+    /// Creates a new LeastSquaresConsistency
+    /// # Arguments
+    /// * `max_iterations` - Maximum number of iterations over all the constraints
+    /// * `tolerance` - Stop when no count changes more than this in an iteration
+    #[inline]
+    pub fn new(max_iterations: usize, tolerance: f64) -> LeastSquaresConsistency {
+        LeastSquaresConsistency {
+            max_iterations,
+            tolerance,
+        }
+    }
+
+    #[inline]
+    fn gen_parent_child_pairs(
+        combinations: &[Arc<ValueCombination>],
+        index_by_combination: &FnvHashMap<Arc<ValueCombination>, usize>,
+    ) -> Vec<(usize, usize)> {
+        combinations
+            .iter()
+            .enumerate()
+            .filter(|(_, comb)| comb.len() > 1)
+            .flat_map(|(child_index, comb)| {
+                comb.iter()
+                    .combinations(comb.len() - 1)
+                    .filter_map(|mut parent| {
+                        index_by_combination
+                            .get(&ValueCombination::new(parent.drain(..).cloned().collect()))
+                            .map(|parent_index| (*parent_index, child_index))
+                    })
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    /// Weighted projection of `(parent, child)` onto `child <= parent`.
+    /// Returns the updated `(parent, child)` values
+    #[inline]
+    fn project_pair(parent: f64, child: f64, parent_weight: f64, child_weight: f64) -> (f64, f64) {
+        if child > parent {
+            let step = (child - parent) / (1.0 / parent_weight + 1.0 / child_weight);
+
+            (parent + step / parent_weight, child - step / child_weight)
+        } else {
+            (parent, child)
+        }
+    }
+
+    /// Projects the counts on `aggregates_count` onto a consistent set
+    /// (counts are rounded back to integers at the end)
+    /// # Arguments
+    /// * `aggregates_count` - Noisy counts to be projected
+    /// * `noise_variance_by_len` - Variance of the noise added to each combination length (missing lengths use the same weight)
+    pub fn project(
+        &self,
+        aggregates_count: &mut AggregatesCountMap,
+        noise_variance_by_len: &FnvHashMap<usize, f64>,
+    ) {
+        info!("projecting noisy counts with least squares consistency");
+        let _duration_logger = ElapsedDurationLogger::new("least squares consistency");
+
+        let combinations: Vec<Arc<ValueCombination>> = aggregates_count
+            .keys()
+            .cloned()
+            .sorted_by_key(|comb| comb.len())
+            .collect();
+        let index_by_combination: FnvHashMap<Arc<ValueCombination>, usize> = combinations
+            .iter()
+            .enumerate()
+            .map(|(i, comb)| (comb.clone(), i))
+            .collect();
+        let weights: Vec<f64> = combinations
+            .iter()
+            .map(|comb| {
+                noise_variance_by_len
+                    .get(&comb.len())
+                    .filter(|variance| **variance > 0.0)
+                    .map(|variance| 1.0 / variance)
+                    .unwrap_or(DEFAULT_WEIGHT)
+            })
+            .collect();
+        let pairs = Self::gen_parent_child_pairs(&combinations, &index_by_combination);
+        let mut counts: Vec<f64> = combinations
+            .iter()
+            .map(|comb| aggregates_count[comb].count as f64)
+            .collect();
+        // Dykstra's corrections, so the result converges to the
+        // least squares projection onto the intersection of the constraints
+        let mut pair_corrections: Vec<(f64, f64)> = vec![(0.0, 0.0); pairs.len()];
+        let mut non_negative_corrections: Vec<f64> = vec![0.0; counts.len()];
+
+        for iteration in 1..=self.max_iterations {
+            let mut max_change: f64 = 0.0;
+
+            for ((parent_index, child_index), correction) in
+                pairs.iter().zip(pair_corrections.iter_mut())
+            {
+                let parent = counts[*parent_index] + correction.0;
+                let child = counts[*child_index] + correction.1;
+                let (new_parent, new_child) = Self::project_pair(
+                    parent,
+                    child,
+                    weights[*parent_index],
+                    weights[*child_index],
+                );
+
+                max_change = max_change
+                    .max((new_parent - counts[*parent_index]).abs())
+                    .max((new_child - counts[*child_index]).abs());
+                *correction = (parent - new_parent, child - new_child);
+                counts[*parent_index] = new_parent;
+                counts[*child_index] = new_child;
+            }
+
+            for (count, correction) in counts.iter_mut().zip(non_negative_corrections.iter_mut()) {
+                let value = *count + *correction;
+                let new_value = value.max(0.0);
+
+                max_change = max_change.max((new_value - *count).abs());
+                *correction = value - new_value;
+                *count = new_value;
+            }
+
+            if max_change <= self.tolerance {
+                debug!(
+                    "least squares consistency converged after {} iterations",
+                    iteration
+                );
+                break;
+            }
+        }
+
+        for (comb, count) in combinations.iter().zip(counts.iter()) {
+            aggregates_count.get_mut(comb).unwrap().count = count.round() as usize;
+        }
+    }
+}
@@ -1,19 +1,29 @@
+mod accuracy_mode;
 mod analytic_gaussian;
+mod discrete_sampling;
 mod dp_parameters;
+mod dp_planner;
+mod least_squares_consistency;
 mod noise_aggregator;
 mod noisy_count_threshold;
 mod percentile;
+mod privacy_accountant;
 mod stats_error;
 mod typedefs;
 
 #[cfg(feature = "pyo3")]
 mod register_pyo3;
 
+pub use accuracy_mode::*;
 pub use analytic_gaussian::*;
+pub use discrete_sampling::*;
 pub use dp_parameters::*;
+pub use dp_planner::*;
+pub use least_squares_consistency::*;
 pub use noise_aggregator::*;
 pub use noisy_count_threshold::*;
 pub use percentile::*;
+pub use privacy_accountant::*;
 pub use stats_error::*;
 pub use typedefs::*;
 
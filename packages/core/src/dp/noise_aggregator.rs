@@ -1,29 +1,36 @@
 use super::{
-    CombinationsByRecord, DpParameters, DpPercentile, NoisyCountThreshold,
-    DEFAULT_NUMBER_OF_RECORDS_EPSILON_PROPORTION,
+    CombinationsByRecord, DiscreteGaussian, DiscreteLaplace, DpParameters, DpPercentile,
+    NoisyCountThreshold, PrivacyAccountant, PrivacyMechanism, PrivacyMechanismInvocation,
+    PrivacyStage, DEFAULT_CANDIDATE_UPPER_BOUND_CONFIDENCE,
+    DEFAULT_LEAST_SQUARES_CONSISTENCY_MAX_ITERATIONS, DEFAULT_LEAST_SQUARES_CONSISTENCY_TOLERANCE,
+    DEFAULT_MAX_RECORDS_PER_SUBJECT, DEFAULT_NUMBER_OF_RECORDS_EPSILON_PROPORTION,
 };
-use fnv::FnvHashSet;
+use fnv::{FnvHashMap, FnvHashSet};
 use itertools::Itertools;
 use log::{debug, info, warn};
 use rand::{
     prelude::{Distribution as rand_dist, IteratorRandom},
-    thread_rng,
+    Rng,
 };
 use statrs::distribution::{ContinuousCDF, Laplace, Normal};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-    data_block::{DataBlock, DataBlockValue},
+    data_block::{
+        data_derived_binned_columns, DataBlock, DataBlockHeadersSlice, DataBlockRecord,
+        DataBlockValue, RecordsBySubject,
+    },
     dp::{
         typedefs::{CombinationsCountMap, CombinationsCountMapByLen},
         DEFAULT_TOLERANCE,
     },
     processing::aggregator::{
-        AggregatedCount, AggregatedData, AggregatesCountMap, RecordsSensitivityByLen, RecordsSet,
-        ValueCombination,
+        AggregatedCount, AggregatedData, AggregatedDataParts, AggregatesCountMap, CountNoise,
+        CountNoiseByLen, RecordsSet, ValueCombination,
     },
     utils::{
-        math::calc_percentage,
+        math::{binomial, calc_percentage},
+        random::{new_rng, SeededRng},
         reporting::{ReportProgress, StoppableResult},
     },
 };
@@ -40,11 +47,22 @@ pub struct NoiseAggregator {
     sigmas: Vec<f64>,
     threshold: NoisyCountThreshold,
     number_of_records_epsilon: f64,
+    rng: SeededRng,
+    privacy_accountant: PrivacyAccountant,
+    max_records_per_subject: usize,
+    sampled_records_by_subject: Option<RecordsBySubject>,
+    use_least_squares_consistency: bool,
+    use_discrete_noise: bool,
+    count_noise_by_len: CountNoiseByLen,
+    candidate_min_upper_bound: Option<f64>,
+    max_candidates_per_len: Option<usize>,
+    column_weights: Option<Vec<f64>>,
+    excluded_column_pairs: FnvHashSet<(usize, usize)>,
 }
 
 impl NoiseAggregator {
     #[inline]
-    fn calc_percentile_epsilon_number_of_records_epsilon_and_sigma_by_len(
+    pub(crate) fn calc_percentile_epsilon_number_of_records_epsilon_and_sigma_by_len(
         reporting_length: usize,
         epsilon: f64,
         delta: f64,
@@ -91,7 +109,10 @@ impl NoiseAggregator {
         );
 
         let t = reporting_length as f64;
-        let rho = (epsilon + (2.0 / delta).ln()).sqrt() - (2.0 / delta).ln().sqrt();
+        // rho-zCDP implies (rho + 2 * sqrt(rho * ln(2 / delta)), delta / 2)-DP, the other
+        // delta / 2 is used to threshold the 1-counts, so this is the largest rho
+        // keeping the privacy accountant report within (epsilon, delta)
+        let rho = ((epsilon + (2.0 / delta).ln()).sqrt() - (2.0 / delta).ln().sqrt()).powi(2);
         let k: f64 = proportions.iter().map(|p| 1.0 / (p * p)).sum();
         let percentile_epsilon = (2.0 * rho * percentile_epsilon_proportion / t).sqrt();
         let number_of_records_epsilon = (2.0 * rho * number_of_records_proportion).sqrt();
@@ -115,14 +136,121 @@ impl NoiseAggregator {
         (percentile_epsilon, number_of_records_epsilon, sigmas)
     }
 
+    #[inline]
+    fn sample_records_by_subject<R: Rng>(
+        records_by_subject: Option<RecordsBySubject>,
+        max_records_per_subject: usize,
+        rng: &mut R,
+    ) -> Option<RecordsBySubject> {
+        records_by_subject.map(|mut records_by_subject| {
+            records_by_subject
+                .drain(..)
+                .map(|records| {
+                    if records.len() > max_records_per_subject {
+                        let mut sampled_records = records
+                            .into_iter()
+                            .choose_multiple(rng, max_records_per_subject);
+
+                        sampled_records.sort_unstable();
+                        sampled_records
+                    } else {
+                        records
+                    }
+                })
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn get_column_index(headers: &DataBlockHeadersSlice, column_name: &str) -> usize {
+        headers
+            .iter()
+            .position(|header| **header == column_name)
+            .unwrap_or_else(|| panic!("column \"{}\" not found in the data block", column_name))
+    }
+
+    /// Weight by column index (columns without weight have weight 1)
+    #[inline]
+    fn resolve_column_weights(
+        headers: &DataBlockHeadersSlice,
+        column_weights: &HashMap<String, f64>,
+    ) -> Vec<f64> {
+        let mut weights = Vec::default();
+
+        weights.resize_with(headers.len(), || 1.0);
+
+        for (column_name, weight) in column_weights.iter() {
+            assert!(*weight > 0.0, "column weights must be > 0");
+
+            weights[NoiseAggregator::get_column_index(headers, column_name)] = *weight;
+        }
+        weights
+    }
+
+    /// Excluded pairs of column indexes (the smallest index first)
+    #[inline]
+    fn resolve_excluded_column_pairs(
+        headers: &DataBlockHeadersSlice,
+        excluded_column_pairs: &[(String, String)],
+    ) -> FnvHashSet<(usize, usize)> {
+        excluded_column_pairs
+            .iter()
+            .map(|(column_name_a, column_name_b)| {
+                let a = NoiseAggregator::get_column_index(headers, column_name_a);
+                let b = NoiseAggregator::get_column_index(headers, column_name_b);
+
+                (a.min(b), a.max(b))
+            })
+            .collect()
+    }
+
+    /// The noise of a marginal is divided by the highest weight among its columns
+    #[inline]
+    fn calc_marginal_weight(column_weights: &Option<Vec<f64>>, comb: &ValueCombination) -> f64 {
+        match column_weights {
+            Some(column_weights) => comb
+                .iter()
+                .map(|attr| column_weights[attr.column_index])
+                .reduce(f64::max)
+                .unwrap_or(1.0),
+            None => 1.0,
+        }
+    }
+
+    /// Checks whether adding a value from `column_index` to `comb`
+    /// would join an excluded pair of columns
+    #[inline]
+    fn is_column_pair_excluded(&self, comb: &ValueCombination, column_index: usize) -> bool {
+        !self.excluded_column_pairs.is_empty()
+            && comb.iter().any(|attr| {
+                self.excluded_column_pairs.contains(&(
+                    attr.column_index.min(column_index),
+                    attr.column_index.max(column_index),
+                ))
+            })
+    }
+
+    /// Records that contribute to the aggregates: all of them for record-level DP,
+    /// or the sampled records grouped by subject for user-level DP
+    #[inline]
+    fn contributing_records(&self) -> Vec<&Arc<DataBlockRecord>> {
+        match &self.sampled_records_by_subject {
+            Some(sampled_records_by_subject) => sampled_records_by_subject
+                .iter()
+                .flatten()
+                .map(|record_index| &self.data_block.records[*record_index])
+                .collect(),
+            None => self.data_block.records.iter().collect(),
+        }
+    }
+
     #[inline]
     fn gen_sorted_records(&self) -> Vec<Vec<Arc<DataBlockValue>>> {
-        self.data_block
-            .records
+        self.contributing_records()
             .iter()
             .map(|record| {
                 let mut sorted_attrs = record.values.clone();
-                sorted_attrs.sort_by_key(|k| k.as_str_using_headers(&self.data_block.headers));
+                sorted_attrs.sort_unstable();
                 sorted_attrs
             })
             .collect()
@@ -173,10 +301,13 @@ impl NoiseAggregator {
                     if comb.contains_column(attr.column_index) {
                         // combinations already contains an attribute at that index
                         None
+                    } else if self.is_column_pair_excluded(comb, attr.column_index) {
+                        // these columns should never appear together
+                        None
                     } else {
                         let mut new_comb = (**comb).clone();
 
-                        new_comb.extend((*attr).clone(), &self.data_block.headers);
+                        new_comb.extend((*attr).clone());
 
                         if NoiseAggregator::is_combination_valid(noisy_aggregates_by_len, &new_comb)
                         {
@@ -192,10 +323,138 @@ impl NoiseAggregator {
             .collect()
     }
 
+    /// Upper bound of the count of `comb`: a combination is never more frequent than any
+    /// of its sub combinations, so the smallest noisy `(comb.len() - 1)`-count plus the
+    /// noise `margin` bounds it (with the confidence used to compute the margin).
+    ///
+    /// Returns `None` if any sub combination is not part of `previous_aggregates`
+    #[inline]
+    fn calc_candidate_upper_bound(
+        previous_aggregates: &CombinationsCountMap,
+        comb: &ValueCombination,
+        margin: f64,
+    ) -> Option<f64> {
+        comb.iter()
+            .combinations(comb.len() - 1)
+            .try_fold(f64::INFINITY, |min_count, mut sub_comb| {
+                previous_aggregates
+                    .get(&ValueCombination::new(
+                        sub_comb.drain(..).cloned().collect(),
+                    ))
+                    .map(|count| min_count.min(*count))
+            })
+            .map(|min_count| min_count + margin)
+    }
+
+    /// Keeps the `max_candidates` candidates with the highest upper bounds
+    #[inline]
+    fn keep_top_candidates(
+        candidates: &mut Vec<(Arc<ValueCombination>, f64)>,
+        max_candidates: usize,
+    ) {
+        if candidates.len() > max_candidates {
+            if max_candidates > 0 {
+                candidates.select_nth_unstable_by(max_candidates - 1, |(_, a), (_, b)| {
+                    b.partial_cmp(a).unwrap()
+                });
+            }
+            candidates.truncate(max_candidates);
+        }
+    }
+
+    /// Same candidates as `gen_all_current_aggregates_based_on_previous`, but
+    /// pruned by their count upper bound (`candidate_min_upper_bound`) and limited to
+    /// the highest upper bounds (`max_candidates_per_len`).
+    ///
+    /// Each candidate is only generated once, by extending its prefix with
+    /// an attribute that sorts after it, so the full cross product is never stored
+    fn gen_pruned_current_aggregates_based_on_previous(
+        &self,
+        previous_aggregates: &CombinationsCountMap,
+        current_comb_len: usize,
+    ) -> CombinationsCountMap {
+        let mut distinct_attributes: Vec<&Arc<DataBlockValue>> =
+            NoiseAggregator::get_distinct_attributes(previous_aggregates)
+                .into_iter()
+                .collect();
+        // the noisy counts of the previous length are released,
+        // so using them to prune candidates is just post-processing
+        let margin = self
+            .count_noise_by_len
+            .get(&(current_comb_len - 1))
+            .map(|noise| {
+                // use the noisiest marginal, so the bound holds for all of them
+                (noise.standard_deviation
+                    / noise.column_weights.values().cloned().fold(1.0, f64::min))
+                    * Normal::new(0.0, 1.0)
+                        .unwrap()
+                        .inverse_cdf(DEFAULT_CANDIDATE_UPPER_BOUND_CONFIDENCE)
+            })
+            .unwrap_or(0.0);
+        let min_upper_bound = self.candidate_min_upper_bound.unwrap_or(f64::NEG_INFINITY);
+        let mut candidates: Vec<(Arc<ValueCombination>, f64)> = Vec::default();
+        let mut n_generated: usize = 0;
+
+        distinct_attributes.sort_unstable();
+
+        for comb in previous_aggregates.keys() {
+            let last_attr = comb.last().unwrap();
+
+            for attr in distinct_attributes.iter().filter(|attr| **attr > last_attr) {
+                if comb.contains_column(attr.column_index)
+                    || self.is_column_pair_excluded(comb, attr.column_index)
+                {
+                    continue;
+                }
+
+                let mut new_comb = (**comb).clone();
+
+                new_comb.extend((*attr).clone());
+
+                if let Some(upper_bound) = NoiseAggregator::calc_candidate_upper_bound(
+                    previous_aggregates,
+                    &new_comb,
+                    margin,
+                ) {
+                    n_generated += 1;
+
+                    if upper_bound >= min_upper_bound {
+                        candidates.push((Arc::new(new_comb), upper_bound));
+
+                        // keep memory bounded by twice the allowed candidates
+                        if let Some(max_candidates) = self.max_candidates_per_len {
+                            if candidates.len() >= 2 * max_candidates.max(1) {
+                                NoiseAggregator::keep_top_candidates(
+                                    &mut candidates,
+                                    max_candidates,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(max_candidates) = self.max_candidates_per_len {
+            NoiseAggregator::keep_top_candidates(&mut candidates, max_candidates);
+        }
+
+        info!(
+            "pruned {}-counts candidates: kept {} out of {}",
+            current_comb_len,
+            candidates.len(),
+            n_generated
+        );
+
+        candidates
+            .drain(..)
+            .map(|(comb, _upper_bound)| (comb, 0.0))
+            .collect()
+    }
+
     #[inline]
     fn gen_all_current_aggregates_based_on_single_attributes(&self) -> CombinationsCountMap {
-        self.data_block
-            .records
+        self.contributing_records()
             .iter()
             .flat_map(|record| record.values.iter())
             .unique()
@@ -230,10 +489,84 @@ impl NoiseAggregator {
             .collect()
     }
 
+    /// For user-level DP, merges the combinations of the sampled records
+    /// of each subject, so sensitivity is computed per subject
     #[inline]
-    fn get_max_and_allowed_sensitivities(
+    fn group_combinations_by_subject(
         &self,
+        combinations_by_record: CombinationsByRecord,
+    ) -> CombinationsByRecord {
+        match &self.sampled_records_by_subject {
+            Some(sampled_records_by_subject) => {
+                let mut combinations_by_record_iter = combinations_by_record.into_iter();
+
+                sampled_records_by_subject
+                    .iter()
+                    .map(|records| {
+                        combinations_by_record_iter
+                            .by_ref()
+                            .take(records.len())
+                            .flatten()
+                            .collect()
+                    })
+                    .collect()
+            }
+            None => combinations_by_record,
+        }
+    }
+
+    /// Maximum count a single record (or subject for user-level DP)
+    /// can add to the same combination
+    #[inline]
+    fn max_contribution_per_combination(&self, l1_sensitivity: usize) -> usize {
+        self.max_records_per_subject.min(l1_sensitivity)
+    }
+
+    /// L2 sensitivity of the `comb_len`-counts, each contribution unit
+    /// adds at most `l1_sensitivity` to the counts, but never more than
+    /// `max_contribution_per_combination` to the same combination.
+    ///
+    /// With column weights, the counts are scaled by their marginal weight before
+    /// adding noise, so the worst case fills the marginals with the highest weights first
+    /// (a contribution unit adds at most `max_contribution_per_combination` to each marginal)
+    #[inline]
+    fn calc_l2_sensitivity(&self, l1_sensitivity: usize, comb_len: usize) -> f64 {
+        let max_contribution = self.max_contribution_per_combination(l1_sensitivity);
+
+        match &self.column_weights {
+            Some(column_weights) => {
+                let mut sorted_weights = column_weights.clone();
+                let mut remaining = l1_sensitivity;
+                let mut weighted_l1_sensitivity = 0.0;
+
+                sorted_weights.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+                for (i, weight) in sorted_weights.iter().enumerate() {
+                    if remaining == 0 {
+                        break;
+                    }
+
+                    // marginals whose highest weighted column is the i-th one
+                    // (excluded pairs are still counted, so this is an upper bound)
+                    let contribution = remaining.min(
+                        binomial(sorted_weights.len() - i - 1, comb_len - 1)
+                            .saturating_mul(max_contribution),
+                    );
+
+                    weighted_l1_sensitivity += (contribution as f64) * weight * weight;
+                    remaining -= contribution;
+                }
+                ((max_contribution as f64) * weighted_l1_sensitivity).sqrt()
+            }
+            None => ((l1_sensitivity * max_contribution) as f64).sqrt(),
+        }
+    }
+
+    #[inline]
+    fn get_max_and_allowed_sensitivities(
+        &mut self,
         combinations_by_record: &CombinationsByRecord,
+        comb_len: usize,
     ) -> (usize, usize) {
         let sensitivities: Vec<usize> = combinations_by_record
             .iter()
@@ -241,25 +574,36 @@ impl NoiseAggregator {
             .collect();
         let max_sensitivity = sensitivities.iter().max().cloned().unwrap_or(0);
         let percentile_selector = DpPercentile::new(sensitivities);
+        // the budget split already assigns `percentile_epsilon` to each length
+        let epsilon = self.percentile_epsilon;
         let allowed_sensitivity = percentile_selector
             .kth_percentile_quality_scores_iter(self.percentile_percentage)
-            .get_noisy_max(self.percentile_epsilon / (self.reporting_length as f64))
+            .get_noisy_max(epsilon, &mut self.rng)
             .unwrap_or(0);
 
+        self.privacy_accountant
+            .record(PrivacyMechanismInvocation::new(
+                PrivacyStage::PercentileSelection,
+                Some(comb_len),
+                PrivacyMechanism::ExponentialNoisyMax { epsilon },
+                0.0,
+            ));
+
         (max_sensitivity, allowed_sensitivity)
     }
 
     #[inline]
-    fn increment_count_based_on_sensitivity(
+    fn increment_count_based_on_sensitivity<R: Rng>(
         all_current_aggregates: &mut CombinationsCountMap,
         combinations_by_record: &CombinationsByRecord,
         l1_sensitivity: usize,
+        rng: &mut R,
     ) {
         for combinations in combinations_by_record.iter() {
             if combinations.len() > l1_sensitivity {
                 for comb in combinations
                     .iter()
-                    .choose_multiple(&mut thread_rng(), l1_sensitivity)
+                    .choose_multiple(rng, l1_sensitivity)
                     .drain(..)
                 {
                     (*all_current_aggregates.get_mut(comb).unwrap()) += 1.0;
@@ -273,32 +617,86 @@ impl NoiseAggregator {
     }
 
     #[inline]
-    fn add_gaussian_noise(all_current_aggregates: &mut CombinationsCountMap, current_sigma: f64) {
-        let noise = Normal::new(0.0, 1.0).unwrap();
+    fn add_gaussian_noise<R: Rng>(
+        all_current_aggregates: &mut CombinationsCountMap,
+        current_sigma: f64,
+        column_weights: &Option<Vec<f64>>,
+        use_discrete_noise: bool,
+        rng: &mut R,
+    ) {
+        if use_discrete_noise {
+            // counts are integers, so adding integer noise keeps them exact
+            let mut noise_by_weight: FnvHashMap<u64, DiscreteGaussian> = FnvHashMap::default();
+
+            for (comb, count) in all_current_aggregates.iter_mut() {
+                let weight = NoiseAggregator::calc_marginal_weight(column_weights, comb);
+                let noise = noise_by_weight
+                    .entry(weight.to_bits())
+                    .or_insert_with(|| DiscreteGaussian::new(current_sigma / weight));
+
+                (*count) += noise.sample(rng) as f64;
+            }
+        } else {
+            let noise = Normal::new(0.0, 1.0).unwrap();
 
-        for count in all_current_aggregates.values_mut() {
-            (*count) += current_sigma * noise.sample(&mut thread_rng());
+            for (comb, count) in all_current_aggregates.iter_mut() {
+                (*count) += (current_sigma
+                    / NoiseAggregator::calc_marginal_weight(column_weights, comb))
+                    * noise.sample(rng);
+            }
         }
     }
 
     #[inline]
-    fn calc_threshold(&self, l1_sensitivity: f64, comb_len: usize) -> f64 {
+    fn calc_threshold(
+        &self,
+        l1_sensitivity: usize,
+        comb_len: usize,
+        noise_standard_deviation: f64,
+    ) -> f64 {
+        NoiseAggregator::calc_threshold_with_contribution(
+            &self.threshold,
+            self.delta,
+            l1_sensitivity,
+            comb_len,
+            noise_standard_deviation,
+            self.max_contribution_per_combination(l1_sensitivity),
+            self.use_discrete_noise,
+        )
+    }
+
+    /// Threshold the noisy `comb_len`-counts need to exceed in order to be kept,
+    /// `max_contribution` is the maximum count a single contribution unit
+    /// can add to the same combination
+    #[inline]
+    pub(crate) fn calc_threshold_with_contribution(
+        threshold: &NoisyCountThreshold,
+        delta: f64,
+        l1_sensitivity: usize,
+        comb_len: usize,
+        noise_standard_deviation: f64,
+        max_contribution: usize,
+        use_discrete_noise: bool,
+    ) -> f64 {
         if comb_len == 1 {
-            1.0 + (self.sigmas[0]
-                * l1_sensitivity.sqrt()
-                * Normal::new(0.0, 1.0)
-                    .unwrap()
-                    .inverse_cdf((1.0 - (self.delta / 2.0)).powf(1.0 / l1_sensitivity)))
+            // the discrete gaussian tail is bounded by the continuous one
+            // shifted by 1 (P[X >= m] <= P[N >= m - 1]), so shift the threshold
+            // to keep the delta / 2 guarantee
+            (max_contribution as f64)
+                + (if use_discrete_noise { 1.0 } else { 0.0 })
+                + (noise_standard_deviation
+                    * Normal::new(0.0, 1.0)
+                        .unwrap()
+                        .inverse_cdf((1.0 - (delta / 2.0)).powf(1.0 / l1_sensitivity as f64)))
         } else {
             // thresholds should start at index 2 (1-counts needs to be fixed to guarantee DP)
-            match self.threshold.clone() {
+            match threshold {
                 NoisyCountThreshold::Fixed(thresholds) => {
                     thresholds.get(&comb_len).cloned().unwrap_or(0.0)
                 }
                 NoisyCountThreshold::Adaptive(thresholds) => {
                     // PPF at 0.5 should give threshold = 0
-                    self.sigmas[comb_len - 1]
-                        * l1_sensitivity.sqrt()
+                    noise_standard_deviation
                         // threshold values should be between 0 and 0.5
                         // we are dividing by 2 here to normalize it between 0 and 1.0
                         * Normal::new(0.0, 1.0).unwrap().inverse_cdf(
@@ -315,21 +713,35 @@ impl NoiseAggregator {
     fn retain_based_on_threshold(
         &self,
         all_current_aggregates: &mut CombinationsCountMap,
-        l1_sensitivity: f64,
+        l1_sensitivity: usize,
         comb_len: usize,
+        current_sigma: f64,
     ) {
-        let threshold = self.calc_threshold(l1_sensitivity, comb_len);
-
-        debug!("used threshold = {}", threshold);
+        // the threshold depends on the noise of each marginal
+        let mut threshold_by_weight: FnvHashMap<u64, f64> = FnvHashMap::default();
 
         // make sure to retain combinations that have a count greater than the threshold
         // and also greater than 0, in case the threshold is negative somehow
-        all_current_aggregates.retain(|_comb, count| *count > threshold && *count > 0.0);
+        all_current_aggregates.retain(|comb, count| {
+            let weight = NoiseAggregator::calc_marginal_weight(&self.column_weights, comb);
+            let threshold = *threshold_by_weight
+                .entry(weight.to_bits())
+                .or_insert_with(|| {
+                    self.calc_threshold(l1_sensitivity, comb_len, current_sigma / weight)
+                });
+
+            *count > threshold && *count > 0.0
+        });
+
+        debug!(
+            "used thresholds = {:?}",
+            threshold_by_weight.values().collect::<Vec<_>>()
+        );
     }
 
     #[inline]
     fn add_gaussian_noise_and_retain_based_on_threshold(
-        &self,
+        &mut self,
         all_current_aggregates: &mut CombinationsCountMap,
         combinations_by_record: &CombinationsByRecord,
         comb_len: usize,
@@ -341,18 +753,76 @@ impl NoiseAggregator {
         );
 
         if l1_sensitivity > 0 {
-            let l1_sensitivity_f64 = l1_sensitivity as f64;
-            let current_sigma = self.sigmas[comb_len - 1] * l1_sensitivity_f64.sqrt();
+            let l2_sensitivity = self.calc_l2_sensitivity(l1_sensitivity, comb_len);
+            let current_sigma = self.sigmas[comb_len - 1] * l2_sensitivity;
 
             NoiseAggregator::increment_count_based_on_sensitivity(
                 all_current_aggregates,
                 combinations_by_record,
                 l1_sensitivity,
+                &mut self.rng,
+            );
+
+            NoiseAggregator::add_gaussian_noise(
+                all_current_aggregates,
+                current_sigma,
+                &self.column_weights,
+                self.use_discrete_noise,
+                &mut self.rng,
             );
 
-            NoiseAggregator::add_gaussian_noise(all_current_aggregates, current_sigma);
+            self.retain_based_on_threshold(
+                all_current_aggregates,
+                l1_sensitivity,
+                comb_len,
+                current_sigma,
+            );
+
+            let count_noise = CountNoise {
+                standard_deviation: current_sigma,
+                l1_sensitivity,
+                column_weights: self
+                    .column_weights
+                    .iter()
+                    .flatten()
+                    .enumerate()
+                    .filter(|(_, weight)| **weight != 1.0)
+                    .map(|(column_index, weight)| (column_index, *weight))
+                    .collect(),
+                post_processed: false,
+            };
+
+            if self.column_weights.is_some() {
+                info!(
+                    "noise standard deviation by marginal for {}-counts = {:?}",
+                    comb_len,
+                    all_current_aggregates
+                        .keys()
+                        .map(|comb| comb.iter().map(|attr| attr.column_index).collect_vec())
+                        .unique()
+                        .map(|marginal| {
+                            let standard_deviation =
+                                count_noise.marginal_standard_deviation(marginal.iter().cloned());
 
-            self.retain_based_on_threshold(all_current_aggregates, l1_sensitivity_f64, comb_len);
+                            (marginal, standard_deviation)
+                        })
+                        .collect_vec()
+                );
+            }
+
+            self.count_noise_by_len.insert(comb_len, count_noise);
+
+            self.privacy_accountant
+                .record(PrivacyMechanismInvocation::new(
+                    PrivacyStage::CombinationCounts,
+                    Some(comb_len),
+                    PrivacyMechanism::Gaussian {
+                        sigma: current_sigma,
+                        l2_sensitivity,
+                    },
+                    // 1-counts are thresholded with delta / 2
+                    if comb_len == 1 { self.delta / 2.0 } else { 0.0 },
+                ));
 
             debug!("noise added to {}-counts", comb_len);
         } else {
@@ -365,7 +835,7 @@ impl NoiseAggregator {
     }
 
     #[inline]
-    pub fn protect_number_of_records(&self, number_of_records: usize) -> usize {
+    pub fn protect_number_of_records(&mut self, number_of_records: usize) -> usize {
         info!(
             "protecting reported number of records with epsilon = {}",
             self.number_of_records_epsilon
@@ -376,16 +846,31 @@ impl NoiseAggregator {
             "number of records epsilon should be > 0"
         );
 
-        ((number_of_records as f64)
-            + Laplace::new(0.0, 1.0 / self.number_of_records_epsilon)
-                .unwrap()
-                .sample(&mut thread_rng()))
-        .round() as usize
+        self.privacy_accountant
+            .record(PrivacyMechanismInvocation::new(
+                PrivacyStage::NumberOfRecords,
+                None,
+                PrivacyMechanism::Laplace {
+                    epsilon: self.number_of_records_epsilon,
+                },
+                0.0,
+            ));
+
+        // each subject can add/remove up to max_records_per_subject records
+        let scale = (self.max_records_per_subject as f64) / self.number_of_records_epsilon;
+
+        if self.use_discrete_noise {
+            (number_of_records as i64 + DiscreteLaplace::new(scale).sample(&mut self.rng)).max(0)
+                as usize
+        } else {
+            ((number_of_records as f64) + Laplace::new(0.0, scale).unwrap().sample(&mut self.rng))
+                .round() as usize
+        }
     }
 
     #[inline]
     pub fn build_aggregated_data(
-        &self,
+        &mut self,
         mut noisy_aggregates_by_len: CombinationsCountMapByLen,
     ) -> AggregatedData {
         let mut aggregates_count = AggregatesCountMap::default();
@@ -402,18 +887,36 @@ impl NoiseAggregator {
             }
         }
 
-        let mut aggregated_data = AggregatedData::new(
-            self.data_block.headers.clone(),
-            self.data_block.multi_value_column_metadata_map.clone(),
-            self.data_block.number_of_records(),
-            Some(self.protect_number_of_records(self.data_block.number_of_records())),
+        let protected_number_of_records =
+            self.protect_number_of_records(self.contributing_records().len());
+        let mut aggregated_data = AggregatedData::new(AggregatedDataParts {
+            headers: self.data_block.headers.clone(),
+            multi_value_column_metadata_map: self
+                .data_block
+                .multi_value_column_metadata_map
+                .clone(),
+            numeric_bins_metadata_map: self.data_block.numeric_bins_metadata_map.clone(),
+            date_time_metadata_map: self.data_block.date_time_metadata_map.clone(),
+            number_of_records: self.data_block.number_of_records(),
+            protected_number_of_records: Some(protected_number_of_records),
             aggregates_count,
-            RecordsSensitivityByLen::default(),
-            self.reporting_length,
-        );
+            reporting_length: self.reporting_length,
+            privacy_accountant: Some(self.privacy_accountant.clone()),
+            count_noise_by_len: self.count_noise_by_len.clone(),
+            ..AggregatedDataParts::default()
+        });
 
         aggregated_data.remove_zero_counts();
         aggregated_data.add_missing_parent_combinations();
+
+        if self.use_least_squares_consistency {
+            aggregated_data.enforce_least_squares_consistency(
+                DEFAULT_LEAST_SQUARES_CONSISTENCY_MAX_ITERATIONS,
+                DEFAULT_LEAST_SQUARES_CONSISTENCY_TOLERANCE,
+            );
+            aggregated_data.remove_zero_counts();
+        }
+
         aggregated_data.normalize_noisy_combinations();
 
         aggregated_data
@@ -440,8 +943,15 @@ impl NoiseAggregator {
     /// # Arguments
     /// * `data_block` - The data block to create the noisy aggregates from
     /// * `reporting_length` - Maximum combination length to process
-    /// * `dp_parameters` - Differential privacy parameters
+    /// * `dp_parameters` - Differential privacy parameters (`seed` makes the noise reproducible)
     /// * `threshold` - Threshold used to filter noisy counts
+    ///
+    /// If `data_block` keeps the records of each subject separate, user-level DP is used:
+    /// at most `max_records_per_subject` records are sampled from each subject and
+    /// sensitivity is computed per subject
+    ///
+    /// Numeric columns of `data_block` must be binned with explicit edges, since
+    /// bins computed from the column values would disclose them on the bin labels
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
@@ -461,6 +971,48 @@ impl NoiseAggregator {
                 &dp_parameters.sigma_proportions,
             );
 
+        let data_derived_columns =
+            data_derived_binned_columns(&data_block.numeric_bins_metadata_map);
+
+        assert!(
+            data_derived_columns.is_empty(),
+            "numeric columns binned from the data can not be aggregated with DP, use explicit bin edges for: {}",
+            data_derived_columns.iter().join(", ")
+        );
+
+        let mut rng = new_rng(dp_parameters.seed);
+        let max_records_per_subject = if data_block.subject_index_by_record.is_some() {
+            dp_parameters
+                .max_records_per_subject
+                .unwrap_or(DEFAULT_MAX_RECORDS_PER_SUBJECT)
+        } else {
+            1
+        };
+
+        assert!(
+            max_records_per_subject > 0,
+            "max_records_per_subject must be > 0"
+        );
+
+        let column_weights = dp_parameters.column_weights.as_ref().map(|column_weights| {
+            NoiseAggregator::resolve_column_weights(&data_block.headers, column_weights)
+        });
+        let excluded_column_pairs = dp_parameters
+            .excluded_column_pairs
+            .as_ref()
+            .map(|excluded_column_pairs| {
+                NoiseAggregator::resolve_excluded_column_pairs(
+                    &data_block.headers,
+                    excluded_column_pairs,
+                )
+            })
+            .unwrap_or_default();
+        let sampled_records_by_subject = NoiseAggregator::sample_records_by_subject(
+            data_block.calc_records_by_subject(),
+            max_records_per_subject,
+            &mut rng,
+        );
+
         NoiseAggregator {
             data_block,
             reporting_length,
@@ -470,6 +1022,19 @@ impl NoiseAggregator {
             sigmas,
             threshold,
             number_of_records_epsilon,
+            rng,
+            privacy_accountant: PrivacyAccountant::new(dp_parameters.delta),
+            max_records_per_subject,
+            sampled_records_by_subject,
+            use_least_squares_consistency: dp_parameters
+                .use_least_squares_consistency
+                .unwrap_or(false),
+            use_discrete_noise: dp_parameters.use_discrete_noise.unwrap_or(false),
+            count_noise_by_len: CountNoiseByLen::default(),
+            candidate_min_upper_bound: dp_parameters.candidate_min_upper_bound,
+            max_candidates_per_len: dp_parameters.max_candidates_per_len,
+            column_weights,
+            excluded_column_pairs,
         }
     }
 
@@ -478,16 +1043,24 @@ impl NoiseAggregator {
     /// - All the combinations will be created as a starting 0 count
     /// - Combinations containing any sub combination that is not part of `noisy_aggregates_by_len`
     /// will not be created
+    /// - Combinations are also pruned by their count upper bound if `candidate_min_upper_bound` or `max_candidates_per_len` are set
     pub fn gen_all_current_aggregates(
         &self,
         noisy_aggregates_by_len: &CombinationsCountMapByLen,
         current_comb_len: usize,
     ) -> CombinationsCountMap {
         if let Some(previous_aggregates) = noisy_aggregates_by_len.get(&(current_comb_len - 1)) {
-            self.gen_all_current_aggregates_based_on_previous(
-                noisy_aggregates_by_len,
-                previous_aggregates,
-            )
+            if self.candidate_min_upper_bound.is_some() || self.max_candidates_per_len.is_some() {
+                self.gen_pruned_current_aggregates_based_on_previous(
+                    previous_aggregates,
+                    current_comb_len,
+                )
+            } else {
+                self.gen_all_current_aggregates_based_on_previous(
+                    noisy_aggregates_by_len,
+                    previous_aggregates,
+                )
+            }
         } else {
             self.gen_all_current_aggregates_based_on_single_attributes()
         }
@@ -507,13 +1080,15 @@ impl NoiseAggregator {
         for l in 1..=self.reporting_length {
             let mut all_current_aggregates =
                 self.gen_all_current_aggregates(&noisy_aggregates_by_len, l);
-            let combinations_by_record = NoiseAggregator::gen_valid_combinations_by_record(
-                &sorted_records,
-                l,
-                &all_current_aggregates,
+            let combinations_by_record = self.group_combinations_by_subject(
+                NoiseAggregator::gen_valid_combinations_by_record(
+                    &sorted_records,
+                    l,
+                    &all_current_aggregates,
+                ),
             );
             let (max_sensitivity, allowed_sensitivity) =
-                self.get_max_and_allowed_sensitivities(&combinations_by_record);
+                self.get_max_and_allowed_sensitivities(&combinations_by_record, l);
 
             debug!(
                 "allowed sensitivity for {}-counts is {} out of {}",
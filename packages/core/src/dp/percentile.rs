@@ -1,6 +1,6 @@
 use fnv::FnvHashMap;
 use itertools::Itertools;
-use rand::prelude::Distribution;
+use rand::{prelude::Distribution, Rng};
 use statrs::{
     distribution::Exp,
     statistics::{Data, OrderStatistics},
@@ -121,13 +121,15 @@ impl Default for QualityScoreIter {
 impl QualityScoreIter {
     /// Consumes the quality iterator, applying exponential noise with scale of
     /// `epsilon / 2` and selecting the `relative_value` with highest `score + noise`.
-    pub fn get_noisy_max(&mut self, epsilon: f64) -> Option<usize> {
+    /// # Arguments
+    /// * `epsilon` - Privacy budget used to select the value
+    /// * `rng` - Random number generator used to sample the noise
+    pub fn get_noisy_max<R: Rng>(&mut self, epsilon: f64, rng: &mut R) -> Option<usize> {
         let noise = Exp::new(epsilon / 2.0).ok()?;
-        let mut r = rand::thread_rng();
         let mut max_score: Option<(usize, f64)> = None;
 
         for (i, score) in self.enumerate() {
-            let noisy_score = (score as f64) + noise.sample(&mut r);
+            let noisy_score = (score as f64) + noise.sample(rng);
 
             max_score = Some(if let Some((related_value, score)) = max_score {
                 if noisy_score > score {
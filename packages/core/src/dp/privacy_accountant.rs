@@ -0,0 +1,251 @@
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Default Rényi DP orders used to report the privacy spend
+pub const DEFAULT_RDP_ORDERS: [f64; 15] = [
+    1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0, 5.0, 6.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0,
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+/// Noise mechanism applied to a query over the sensitive data
+pub enum PrivacyMechanism {
+    /// Report noisy max with exponential noise (pure `epsilon`-DP)
+    ExponentialNoisyMax {
+        /// Privacy budget used by the selection
+        epsilon: f64,
+    },
+    /// Gaussian noise added to a query with the given L2 sensitivity
+    Gaussian {
+        /// Standard deviation of the added noise
+        sigma: f64,
+        /// L2 sensitivity of the query
+        l2_sensitivity: f64,
+    },
+    /// Laplace noise added to a query with L1 sensitivity 1 (pure `epsilon`-DP)
+    Laplace {
+        /// Privacy budget used by the query
+        epsilon: f64,
+    },
+}
+
+impl PrivacyMechanism {
+    /// zCDP `rho` satisfied by the mechanism
+    /// (`epsilon`-DP implies `epsilon^2 / 2`-zCDP)
+    #[inline]
+    pub fn rho(&self) -> f64 {
+        match self {
+            PrivacyMechanism::ExponentialNoisyMax { epsilon }
+            | PrivacyMechanism::Laplace { epsilon } => epsilon * epsilon / 2.0,
+            PrivacyMechanism::Gaussian {
+                sigma,
+                l2_sensitivity,
+            } => (l2_sensitivity * l2_sensitivity) / (2.0 * sigma * sigma),
+        }
+    }
+
+    /// Rényi DP epsilon satisfied by the mechanism at order `alpha`
+    /// # Arguments
+    /// * `alpha` - Rényi DP order (should be > 1)
+    #[inline]
+    pub fn rdp_epsilon(&self, alpha: f64) -> f64 {
+        match self {
+            PrivacyMechanism::ExponentialNoisyMax { epsilon }
+            | PrivacyMechanism::Laplace { epsilon } => epsilon.min(alpha * epsilon * epsilon / 2.0),
+            PrivacyMechanism::Gaussian { .. } => alpha * self.rho(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Stage of the DP aggregation that invoked a mechanism
+pub enum PrivacyStage {
+    /// Selection of the allowed sensitivity based on the sensitivity percentile
+    PercentileSelection,
+    /// Noise added to the combination counts
+    CombinationCounts,
+    /// Noise added to the reported number of records
+    NumberOfRecords,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Single mechanism invocation recorded by the privacy accountant
+pub struct PrivacyMechanismInvocation {
+    /// Stage that invoked the mechanism
+    pub stage: PrivacyStage,
+    /// Combination length being processed (if any)
+    pub combination_length: Option<usize>,
+    /// Mechanism that was applied
+    pub mechanism: PrivacyMechanism,
+    /// Delta consumed by the invocation on top of the mechanism guarantee
+    /// (e.g. thresholding counts of attributes that are not known in advance)
+    pub delta: f64,
+}
+
+impl PrivacyMechanismInvocation {
+    /// Creates a new PrivacyMechanismInvocation
+    /// # Arguments
+    /// * `stage` - Stage that invoked the mechanism
+    /// * `combination_length` - Combination length being processed (if any)
+    /// * `mechanism` - Mechanism that was applied
+    /// * `delta` - Delta consumed by the invocation on top of the mechanism guarantee
+    #[inline]
+    pub fn new(
+        stage: PrivacyStage,
+        combination_length: Option<usize>,
+        mechanism: PrivacyMechanism,
+        delta: f64,
+    ) -> PrivacyMechanismInvocation {
+        PrivacyMechanismInvocation {
+            stage,
+            combination_length,
+            mechanism,
+            delta,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Rényi DP epsilon for a given order
+pub struct RdpEpsilon {
+    /// Rényi DP order
+    pub alpha: f64,
+    /// Epsilon at `alpha`
+    pub epsilon: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Total privacy spend reported by the accountant
+pub struct PrivacyReport {
+    /// Total zCDP rho
+    pub rho: f64,
+    /// Total Rényi DP epsilon for each of the reported orders
+    pub rdp: Vec<RdpEpsilon>,
+    /// Approximate DP epsilon (paired with `delta`)
+    pub epsilon: f64,
+    /// Approximate DP delta
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Records every mechanism invoked over the sensitive data and reports
+/// the composed privacy spend under zCDP, Rényi DP and approximate DP
+pub struct PrivacyAccountant {
+    /// Total delta of the approximate DP guarantee
+    pub delta: f64,
+    /// Recorded mechanism invocations (in invocation order)
+    pub invocations: Vec<PrivacyMechanismInvocation>,
+}
+
+impl PrivacyAccountant {
+    /// Creates a new PrivacyAccountant with no recorded invocations
+    /// # Arguments
+    /// * `delta` - Total delta of the approximate DP guarantee
+    #[inline]
+    pub fn new(delta: f64) -> PrivacyAccountant {
+        PrivacyAccountant {
+            delta,
+            invocations: Vec::default(),
+        }
+    }
+
+    /// Records a new mechanism invocation
+    /// # Arguments
+    /// * `invocation` - Invocation to be recorded
+    #[inline]
+    pub fn record(&mut self, invocation: PrivacyMechanismInvocation) {
+        self.invocations.push(invocation);
+    }
+
+    /// Total zCDP rho (zCDP composes additively)
+    #[inline]
+    pub fn total_rho(&self) -> f64 {
+        self.invocations
+            .iter()
+            .map(|invocation| invocation.mechanism.rho())
+            .sum()
+    }
+
+    /// Total Rényi DP epsilon at order `alpha` (RDP composes additively for a fixed order)
+    /// # Arguments
+    /// * `alpha` - Rényi DP order (should be > 1)
+    #[inline]
+    pub fn total_rdp_epsilon(&self, alpha: f64) -> f64 {
+        self.invocations
+            .iter()
+            .map(|invocation| invocation.mechanism.rdp_epsilon(alpha))
+            .sum()
+    }
+
+    /// Variance of the gaussian noise added to the counts of each combination length
+    pub fn calc_noise_variance_by_len(&self) -> FnvHashMap<usize, f64> {
+        self.invocations
+            .iter()
+            .filter_map(
+                |invocation| match (&invocation.mechanism, invocation.combination_length) {
+                    (PrivacyMechanism::Gaussian { sigma, .. }, Some(comb_len)) => {
+                        Some((comb_len, sigma * sigma))
+                    }
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
+    /// Delta consumed by the invocations themselves
+    #[inline]
+    pub fn consumed_delta(&self) -> f64 {
+        self.invocations
+            .iter()
+            .map(|invocation| invocation.delta)
+            .sum()
+    }
+
+    /// Approximate DP epsilon for the total `delta`.
+    ///
+    /// The delta left after the invocations is used to convert both the
+    /// zCDP and the Rényi DP spend, and the smallest epsilon is reported
+    /// # Arguments
+    /// * `orders` - Rényi DP orders to try in the conversion
+    pub fn approximate_dp_epsilon(&self, orders: &[f64]) -> f64 {
+        let conversion_delta = self.delta - self.consumed_delta();
+
+        if conversion_delta <= 0.0 {
+            return f64::INFINITY;
+        }
+
+        let rho = self.total_rho();
+        // rho-zCDP implies (rho + 2 * sqrt(rho * ln(1/delta)), delta)-DP
+        let zcdp_epsilon = rho + 2.0 * (rho * (1.0 / conversion_delta).ln()).sqrt();
+
+        orders
+            .iter()
+            .filter(|alpha| **alpha > 1.0)
+            .map(|alpha| {
+                // (alpha, epsilon)-RDP implies
+                // (epsilon + ln(1 - 1/alpha) - (ln(delta) + ln(alpha)) / (alpha - 1), delta)-DP
+                self.total_rdp_epsilon(*alpha) + (1.0 - 1.0 / alpha).ln()
+                    - (conversion_delta.ln() + alpha.ln()) / (alpha - 1.0)
+            })
+            .fold(zcdp_epsilon, f64::min)
+            .max(0.0)
+    }
+
+    /// Reports the total privacy spend
+    /// # Arguments
+    /// * `orders` - Rényi DP orders to report
+    pub fn report(&self, orders: &[f64]) -> PrivacyReport {
+        PrivacyReport {
+            rho: self.total_rho(),
+            rdp: orders
+                .iter()
+                .map(|alpha| RdpEpsilon {
+                    alpha: *alpha,
+                    epsilon: self.total_rdp_epsilon(*alpha),
+                })
+                .collect(),
+            epsilon: self.approximate_dp_epsilon(orders),
+            delta: self.delta,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::typedefs::RecordsSet;
+use super::records_set::RecordsSet;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "pyo3")]
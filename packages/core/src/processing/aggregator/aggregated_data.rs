@@ -5,7 +5,8 @@ use super::{
         AggregatesCountStringMap, RecordsByLenMap, RecordsSensitivityByLen,
         ALL_SENSITIVITIES_INDEX,
     },
-    AggregatedMetricByDataBlockValue, AggregatedMetricByString, AggregatesCountDataBlockValueMap,
+    AggregatedDataRelease, AggregatedMetricByDataBlockValue, AggregatedMetricByString,
+    AggregatesCompression, AggregatesCountDataBlockValueMap, CountNoiseByLen,
     RecordsByDataBlockValueKey, RecordsByStringKey, RecordsCountByStringKey,
 };
 use fnv::FnvHashMap;
@@ -15,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     fmt::Write as FmtWrite,
-    io::{BufReader, BufWriter, Error, Write},
+    io::{BufReader, BufWriter, Error, ErrorKind, Write},
     sync::Arc,
 };
 
@@ -24,10 +25,14 @@ use pyo3::prelude::*;
 
 use crate::{
     data_block::{
-        DataBlockHeaders, DataBlockValue, MultiValueColumnMetadataMap, COLUMN_VALUE_DELIMITER,
+        DataBlockHeaders, DataBlockValue, DateTimeMetadataMap, MultiValueColumnMetadataMap,
+        NumericBinsMetadataMap, COLUMN_VALUE_DELIMITER,
     },
+    dp::{DpParameters, LeastSquaresConsistency, PrivacyAccountant},
     processing::{
-        aggregator::{typedefs::RecordsSet, value_combination::ValueCombination, AggregatedCount},
+        aggregator::{
+            records_set::RecordsSet, value_combination::ValueCombination, AggregatedCount,
+        },
         generator::AttributeCountMap,
     },
     utils::{math::uround_down, time::ElapsedDurationLogger},
@@ -41,6 +46,12 @@ pub struct AggregatedData {
     pub headers: DataBlockHeaders,
     /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
     pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized binned numeric header name to its computed bins
+    #[serde(default)]
+    pub numeric_bins_metadata_map: NumericBinsMetadataMap,
+    /// Maps a normalized date/time header name to the granularity its values were generalized to
+    #[serde(default)]
+    pub date_time_metadata_map: DateTimeMetadataMap,
     /// Number of records present on the original data
     pub number_of_records: usize,
     /// Number of records protected with K-Anonymity or DP (if any)
@@ -52,6 +63,43 @@ pub struct AggregatedData {
     pub records_sensitivity_by_len: RecordsSensitivityByLen,
     /// Maximum length used to compute attribute combinations
     pub reporting_length: usize,
+    /// Mechanisms invoked to protect the aggregates with DP (if any),
+    /// so the privacy guarantee can be verified by whoever receives them
+    #[serde(default)]
+    pub privacy_accountant: Option<PrivacyAccountant>,
+    /// Noise added to the counts of each combination length when aggregating with DP
+    /// (empty if the counts were not protected with DP)
+    #[serde(default)]
+    pub count_noise_by_len: CountNoiseByLen,
+}
+
+/// Data used to create a new AggregatedData, the parts that are not
+/// available can be left to their defaults with the struct update syntax
+/// (e.g. `AggregatedDataParts { headers, aggregates_count, ..AggregatedDataParts::default() }`)
+#[derive(Default)]
+pub struct AggregatedDataParts {
+    /// Vector of strings representing the data headers
+    pub headers: DataBlockHeaders,
+    /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
+    pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized binned numeric header name to its computed bins
+    pub numeric_bins_metadata_map: NumericBinsMetadataMap,
+    /// Maps a normalized date/time header name to the granularity its values were generalized to
+    pub date_time_metadata_map: DateTimeMetadataMap,
+    /// Number of records present on the original data
+    pub number_of_records: usize,
+    /// Number of records protected with K-Anonymity or DP (if any)
+    pub protected_number_of_records: Option<usize>,
+    /// Computed aggregates count map
+    pub aggregates_count: AggregatesCountMap,
+    /// Computed sensitivity for the records grouped by combination length
+    pub records_sensitivity_by_len: RecordsSensitivityByLen,
+    /// Maximum length used to compute attribute combinations
+    pub reporting_length: usize,
+    /// Mechanisms invoked to protect the aggregates with DP (if any)
+    pub privacy_accountant: Option<PrivacyAccountant>,
+    /// Noise added to the counts of each combination length with DP
+    pub count_noise_by_len: CountNoiseByLen,
 }
 
 impl AggregatedData {
@@ -61,42 +109,35 @@ impl AggregatedData {
         AggregatedData {
             headers: DataBlockHeaders::default(),
             multi_value_column_metadata_map: MultiValueColumnMetadataMap::default(),
+            numeric_bins_metadata_map: NumericBinsMetadataMap::default(),
+            date_time_metadata_map: DateTimeMetadataMap::default(),
             number_of_records: 0,
             protected_number_of_records: None,
             aggregates_count: AggregatesCountMap::default(),
             records_sensitivity_by_len: RecordsSensitivityByLen::default(),
             reporting_length: 0,
+            privacy_accountant: None,
+            count_noise_by_len: CountNoiseByLen::default(),
         }
     }
 
     /// Creates a new AggregatedData struct
     /// # Arguments:
-    /// * `headers` - Vector of strings representing the data headers
-    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name (such as A_a1)
-    /// to its corresponding metadata
-    /// * `number_of_records` - Number of records present on the original data
-    /// * `protected_number_of_records` - Number of records protected with K-Anonymity or DP (if any)
-    /// * `aggregates_count` - Computed aggregates count map
-    /// * `records_sensitivity` - Computed sensitivity for the records
-    /// * `reporting_length` - Maximum length used to compute attribute combinations
+    /// * `parts` - Data the aggregated data is made of
     #[inline]
-    pub fn new(
-        headers: DataBlockHeaders,
-        multi_value_column_metadata_map: MultiValueColumnMetadataMap,
-        number_of_records: usize,
-        protected_number_of_records: Option<usize>,
-        aggregates_count: AggregatesCountMap,
-        records_sensitivity_by_len: RecordsSensitivityByLen,
-        reporting_length: usize,
-    ) -> AggregatedData {
+    pub fn new(parts: AggregatedDataParts) -> AggregatedData {
         AggregatedData {
-            headers,
-            multi_value_column_metadata_map,
-            number_of_records,
-            protected_number_of_records,
-            aggregates_count,
-            records_sensitivity_by_len,
-            reporting_length,
+            headers: parts.headers,
+            multi_value_column_metadata_map: parts.multi_value_column_metadata_map,
+            numeric_bins_metadata_map: parts.numeric_bins_metadata_map,
+            date_time_metadata_map: parts.date_time_metadata_map,
+            number_of_records: parts.number_of_records,
+            protected_number_of_records: parts.protected_number_of_records,
+            aggregates_count: parts.aggregates_count,
+            records_sensitivity_by_len: parts.records_sensitivity_by_len,
+            reporting_length: parts.reporting_length,
+            privacy_accountant: parts.privacy_accountant,
+            count_noise_by_len: parts.count_noise_by_len,
         }
     }
 
@@ -132,7 +173,7 @@ impl AggregatedData {
                     rare_records_per_attribute
                         .entry(value.clone())
                         .or_insert_with(RecordsSet::default)
-                        .extend(&count.contained_in_records);
+                        .union_with(&count.contained_in_records);
                 }
             }
         }
@@ -164,11 +205,14 @@ impl AggregatedData {
     }
 
     #[inline]
-    /// Check if the records len map contains a value across all lengths
-    fn records_by_len_contains(records_by_len: &RecordsByLenMap, value: &usize) -> bool {
-        records_by_len
-            .values()
-            .any(|records| records.contains(value))
+    /// Joins the records of all lengths in a single set
+    fn records_by_len_union(records_by_len: &RecordsByLenMap) -> RecordsSet {
+        let mut all_records = RecordsSet::default();
+
+        for records in records_by_len.values() {
+            all_records.union_with(records);
+        }
+        all_records
     }
 
     #[inline]
@@ -178,19 +222,15 @@ impl AggregatedData {
     /// and remove the other occurrences
     fn keep_records_only_on_shortest_len(records_by_len: &mut RecordsByLenMap) {
         let lengths: Vec<usize> = records_by_len.keys().cloned().sorted().collect();
-        let max_len = lengths.last().copied().unwrap_or(0);
+        let mut records_on_shorter_lens = RecordsSet::default();
 
         // make sure the record will be only present in the shortest len
         // start on the shortest length
         for l in lengths {
-            for r in records_by_len.get(&l).unwrap().clone() {
-                // search all lengths > l and remove the record from there
-                for n in l + 1..=max_len {
-                    if let Some(records) = records_by_len.get_mut(&n) {
-                        records.remove(&r);
-                    }
-                }
-            }
+            let records = records_by_len.get_mut(&l).unwrap();
+
+            records.difference_with(&records_on_shorter_lens);
+            records_on_shorter_lens.union_with(records);
         }
         // retain only non-empty record lists
         records_by_len.retain(|_, records| !records.is_empty());
@@ -214,6 +254,7 @@ impl AggregatedData {
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> Result<(), Error> {
         let n_records;
         let n_records_label;
@@ -227,11 +268,39 @@ impl AggregatedData {
             n_records_label = "count";
         }
 
-        writer.write_all(
-            format!("selections{}{}\n", aggregates_delimiter, n_records_label).as_bytes(),
-        )?;
-        writer
-            .write_all(format!("record_count{}{}\n", aggregates_delimiter, n_records).as_bytes())?;
+        // confidence intervals are only written for counts protected with DP
+        // that were not post-processed after the noise was added
+        let confidence_level = confidence_level.filter(|_| {
+            (1..=self.reporting_length)
+                .any(|comb_len| self.confidence_interval_noise(comb_len).is_some())
+        });
+
+        if confidence_level.is_some() {
+            writer.write_all(
+                format!(
+                    "selections{}{}{}ci_lower{}ci_upper\n",
+                    aggregates_delimiter,
+                    n_records_label,
+                    aggregates_delimiter,
+                    aggregates_delimiter
+                )
+                .as_bytes(),
+            )?;
+            writer.write_all(
+                format!(
+                    "record_count{}{}{}{}\n",
+                    aggregates_delimiter, n_records, aggregates_delimiter, aggregates_delimiter
+                )
+                .as_bytes(),
+            )?;
+        } else {
+            writer.write_all(
+                format!("selections{}{}\n", aggregates_delimiter, n_records_label).as_bytes(),
+            )?;
+            writer.write_all(
+                format!("record_count{}{}\n", aggregates_delimiter, n_records).as_bytes(),
+            )?;
+        }
 
         for (aggregate, count) in self.aggregates_count.iter().sorted_by(|a, b| {
             let len_cmp = a.0.len().cmp(&b.0.len());
@@ -244,9 +313,27 @@ impl AggregatedData {
                 len_cmp
             }
         }) {
+            let confidence_interval = match confidence_level {
+                Some(confidence_level) => match self.confidence_interval_noise(aggregate.len()) {
+                    Some(noise) => {
+                        let interval =
+                            noise.confidence_interval(aggregate, count.count, confidence_level);
+                        format!(
+                            "{}{:.2}{}{:.2}",
+                            aggregates_delimiter,
+                            interval.lower,
+                            aggregates_delimiter,
+                            interval.upper
+                        )
+                    }
+                    None => format!("{}{}", aggregates_delimiter, aggregates_delimiter),
+                },
+                None => String::default(),
+            };
+
             writer.write_all(
                 format!(
-                    "{}{}{}\n",
+                    "{}{}{}{}\n",
                     if case_insensitive {
                         aggregate.as_str_using_headers_case_insensitive_order(
                             &self.headers,
@@ -256,7 +343,8 @@ impl AggregatedData {
                         aggregate.as_str_using_headers(&self.headers, combination_delimiter)
                     },
                     aggregates_delimiter,
-                    count.count
+                    count.count,
+                    confidence_interval
                 )
                 .as_bytes(),
             )?
@@ -474,6 +562,32 @@ impl AggregatedData {
         }
     }
 
+    /// Projects the noisy counts onto a consistent set using weighted least squares
+    /// (see `LeastSquaresConsistency`). This reduces the error introduced by noise
+    /// without spending extra privacy budget.
+    ///
+    /// Each combination length is weighted by the inverse of its noise variance, which is
+    /// taken from the privacy accountant (if there is no accountant, all lengths have the same weight).
+    /// The noise no longer describes the projected counts, so their confidence intervals are
+    /// not computed afterwards
+    /// # Arguments:
+    /// * `max_iterations` - Maximum number of iterations over all the consistency constraints
+    /// * `tolerance` - Stop when no count changes more than this in an iteration
+    pub fn enforce_least_squares_consistency(&mut self, max_iterations: usize, tolerance: f64) {
+        let noise_variance_by_len = self
+            .privacy_accountant
+            .as_ref()
+            .map(|accountant| accountant.calc_noise_variance_by_len())
+            .unwrap_or_default();
+
+        LeastSquaresConsistency::new(max_iterations, tolerance)
+            .project(&mut self.aggregates_count, &noise_variance_by_len);
+
+        for noise in self.count_noise_by_len.values_mut() {
+            noise.post_processed = true;
+        }
+    }
+
     /// Round the aggregated counts down to the nearest multiple of resolution
     /// and remove combinations rounded to a zero count.
     /// (protecting aggregates with k-anon)
@@ -510,7 +624,7 @@ impl AggregatedData {
                 records_with_rare_combs_by_len
                     .entry(agg.len())
                     .or_insert_with(RecordsSet::default)
-                    .extend(&count.contained_in_records);
+                    .union_with(&count.contained_in_records);
             }
         }
         records_with_rare_combs_by_len
@@ -526,7 +640,7 @@ impl AggregatedData {
             .calc_records_with_rare_combinations_by_len(resolution)
             .drain()
         {
-            rare_records.extend(records);
+            rare_records.union_with(&records);
         }
         rare_records.len()
     }
@@ -656,7 +770,7 @@ impl AggregatedData {
                 unique_records_by_len
                     .entry(agg.len())
                     .or_insert_with(RecordsSet::default)
-                    .extend(&count.contained_in_records);
+                    .union_with(&count.contained_in_records);
             }
         }
         unique_records_by_len
@@ -667,7 +781,7 @@ impl AggregatedData {
         let mut unique_records: RecordsSet = RecordsSet::default();
 
         for (_l, records) in self.calc_records_with_unique_combinations_by_len().drain() {
-            unique_records.extend(records);
+            unique_records.union_with(&records);
         }
         unique_records.len()
     }
@@ -683,7 +797,7 @@ impl AggregatedData {
                     unique_records_per_column
                         .entry(self.get_original_header_name(value.column_index))
                         .or_insert_with(RecordsSet::default)
-                        .extend(&count.contained_in_records);
+                        .union_with(&count.contained_in_records);
                 }
             }
         }
@@ -778,13 +892,12 @@ impl AggregatedData {
         AggregatedData::keep_records_only_on_shortest_len(&mut records_with_rare_combs_by_len);
 
         // remove records with unique combinations from the rare map
+        let records_with_unique_combs =
+            AggregatedData::records_by_len_union(&records_with_unique_combs_by_len);
+
         records_with_rare_combs_by_len
             .values_mut()
-            .for_each(|records| {
-                records.retain(|r| {
-                    !AggregatedData::records_by_len_contains(&records_with_unique_combs_by_len, r)
-                });
-            });
+            .for_each(|records| records.difference_with(&records_with_unique_combs));
 
         (
             records_with_unique_combs_by_len,
@@ -877,12 +990,15 @@ impl AggregatedData {
     /// as strings
     /// * `case_insensitive_combinations_order` - True if the attribute combinations ordering should be
     /// sorted using case insensitive comparison
+    /// * `confidence_level` - If set, the approximate confidence interval of each noisy count is also written
+    /// (e.g. 0.95), unless the counts were made consistent after the noise was added
     pub fn write_aggregates_count(
         &self,
         aggregates_path: &str,
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> Result<(), Error> {
         info!("writing file {}", aggregates_path);
 
@@ -893,6 +1009,7 @@ impl AggregatedData {
             aggregates_delimiter,
             combination_delimiter,
             case_insensitive_combinations_order,
+            confidence_level,
         )
     }
 
@@ -903,11 +1020,14 @@ impl AggregatedData {
     /// as strings
     /// * `case_insensitive_combinations_order` - True if the attribute combinations ordering should be
     /// sorted using case insensitive comparison
+    /// * `confidence_level` - If set, the approximate confidence interval of each noisy count is also written
+    /// (e.g. 0.95), unless the counts were made consistent after the noise was added
     pub fn write_aggregates_to_string(
         &self,
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> Result<String, Error> {
         let mut csv_aggregates = Vec::default();
 
@@ -916,6 +1036,7 @@ impl AggregatedData {
             aggregates_delimiter,
             combination_delimiter,
             case_insensitive_combinations_order,
+            confidence_level,
         )?;
 
         Ok(String::from_utf8_lossy(&csv_aggregates).to_string())
@@ -980,4 +1101,66 @@ impl AggregatedData {
     pub fn read_from_json(file_path: &str) -> Result<AggregatedData, Error> {
         AggregatedData::_read_from_json(file_path)
     }
+
+    /// Serializes the aggregated data to a binary file
+    /// (see `write_binary` for the format description)
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    /// * `compression` - Compression applied to the file body (`none`, `gzip` or `zstd`)
+    pub fn write_to_binary(&self, file_path: &str, compression: &str) -> Result<(), Error> {
+        self._write_to_binary(
+            file_path,
+            compression
+                .parse::<AggregatesCompression>()
+                .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?,
+        )
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[staticmethod]
+    /// Deserializes the aggregated data from a binary file
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    pub fn read_from_binary(file_path: &str) -> Result<AggregatedData, Error> {
+        AggregatedData::_read_from_binary(file_path)
+    }
+
+    #[cfg(not(feature = "pyo3"))]
+    /// Deserializes the aggregated data from a binary file
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    pub fn read_from_binary(file_path: &str) -> Result<AggregatedData, Error> {
+        AggregatedData::_read_from_binary(file_path)
+    }
+
+    /// Serializes only the DP-safe fields of the aggregated data to a
+    /// versioned json release (see `AggregatedDataRelease`)
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    /// * `dp_parameters` - Parameters used to protect the aggregates (if `None` they are not included)
+    pub fn write_to_release_json(
+        &self,
+        file_path: &str,
+        dp_parameters: Option<DpParameters>,
+    ) -> Result<(), Error> {
+        self.to_release(dp_parameters.as_ref())?
+            .write_to_json(file_path)
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[staticmethod]
+    /// Deserializes the aggregated data from a json release, so it can be used for synthesis
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    pub fn read_from_release_json(file_path: &str) -> Result<AggregatedData, Error> {
+        Ok(AggregatedDataRelease::read_from_json(file_path)?.into())
+    }
+
+    #[cfg(not(feature = "pyo3"))]
+    /// Deserializes the aggregated data from a json release, so it can be used for synthesis
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    pub fn read_from_release_json(file_path: &str) -> Result<AggregatedData, Error> {
+        Ok(AggregatedDataRelease::read_from_json(file_path)?.into())
+    }
 }
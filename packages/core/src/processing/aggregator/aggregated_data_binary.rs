@@ -0,0 +1,448 @@
+use super::{
+    aggregated_data::{AggregatedData, AggregatedDataParts},
+    records_set::RecordsSet,
+    typedefs::AggregatesCountMap,
+    value_combination::ValueCombination,
+    AggregatedCount, CountNoiseByLen, RecordsSensitivityByLen,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use fnv::FnvHashMap;
+use log::info;
+use ruzstd::{
+    decoding::StreamingDecoder,
+    encoding::{compress, CompressionLevel},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
+    str::FromStr,
+    sync::Arc,
+};
+
+use crate::{
+    data_block::{
+        DataBlockHeaders, DataBlockValue, DateTimeMetadataMap, MultiValueColumnMetadataMap,
+        NumericBinsMetadataMap,
+    },
+    dp::PrivacyAccountant,
+    utils::time::ElapsedDurationLogger,
+};
+
+/// Bytes starting every file written with the aggregated data binary format
+pub const AGGREGATED_DATA_BINARY_MAGIC: &[u8; 6] = b"SDSAGG";
+
+/// Version of the aggregated data binary format written by this library
+/// (files with greater versions cannot be read)
+pub const AGGREGATED_DATA_BINARY_VERSION: u16 = 1;
+
+/// Maximum number of items pre-allocated from a length read from a binary file,
+/// collections grow past it as items are actually read, so truncated or
+/// malicious files can not make the process allocate unbounded memory upfront
+const MAX_PREALLOCATED_ITEMS: usize = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Compression applied to the body of the aggregated data binary format
+pub enum AggregatesCompression {
+    /// Body is not compressed
+    None,
+    /// Body is compressed with gzip
+    Gzip,
+    /// Body is compressed with zstd
+    Zstd,
+}
+
+impl AggregatesCompression {
+    #[inline]
+    fn to_byte(self) -> u8 {
+        match self {
+            AggregatesCompression::None => 0,
+            AggregatesCompression::Gzip => 1,
+            AggregatesCompression::Zstd => 2,
+        }
+    }
+
+    #[inline]
+    fn from_byte(byte: u8) -> Result<AggregatesCompression, Error> {
+        match byte {
+            0 => Ok(AggregatesCompression::None),
+            1 => Ok(AggregatesCompression::Gzip),
+            2 => Ok(AggregatesCompression::Zstd),
+            _ => Err(invalid_data(format!("unknown compression: {}", byte))),
+        }
+    }
+}
+
+impl FromStr for AggregatesCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(AggregatesCompression::None),
+            "gzip" => Ok(AggregatesCompression::Gzip),
+            "zstd" => Ok(AggregatesCompression::Zstd),
+            _ => Err(format!(
+                "invalid compression \"{}\" (expected \"none\", \"gzip\" or \"zstd\")",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for AggregatesCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AggregatesCompression::None => "none",
+                AggregatesCompression::Gzip => "gzip",
+                AggregatesCompression::Zstd => "zstd",
+            }
+        )
+    }
+}
+
+/// Aggregated data fields small enough to be stored as json
+#[derive(Serialize, Deserialize)]
+struct AggregatedDataBinaryMetadata {
+    headers: DataBlockHeaders,
+    multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    numeric_bins_metadata_map: NumericBinsMetadataMap,
+    date_time_metadata_map: DateTimeMetadataMap,
+    number_of_records: usize,
+    protected_number_of_records: Option<usize>,
+    reporting_length: usize,
+    privacy_accountant: Option<PrivacyAccountant>,
+    #[serde(default)]
+    count_noise_by_len: CountNoiseByLen,
+}
+
+impl AggregatedData {
+    /// Serializes the aggregated data with the binary format.
+    ///
+    /// Like `write_to_json`, all the fields are written, including the true number of records,
+    /// the records containing each combination and the records sensitivity, so the output is
+    /// as sensitive as the original data (use `to_release` to share DP aggregates).
+    ///
+    /// The format starts with an uncompressed header (`SDSAGG` magic bytes,
+    /// format version and compression), followed by the (optionally compressed) body:
+    /// a json with the metadata, a dictionary with the distinct values,
+    /// each combination as a tuple of value ids with its count and records,
+    /// and the records sensitivity
+    /// # Arguments:
+    /// * `writer` - Where the aggregated data is written to
+    /// * `compression` - Compression applied to the body
+    pub fn write_binary<W: Write>(
+        &self,
+        mut writer: W,
+        compression: AggregatesCompression,
+    ) -> Result<(), Error> {
+        let body = self.encode_binary_body()?;
+
+        writer.write_all(AGGREGATED_DATA_BINARY_MAGIC)?;
+        writer.write_all(&AGGREGATED_DATA_BINARY_VERSION.to_le_bytes())?;
+        writer.write_all(&[compression.to_byte()])?;
+
+        match compression {
+            AggregatesCompression::None => writer.write_all(&body)?,
+            AggregatesCompression::Gzip => {
+                let mut encoder = GzEncoder::new(&mut writer, Compression::default());
+
+                encoder.write_all(&body)?;
+                encoder.finish()?;
+            }
+            AggregatesCompression::Zstd => {
+                compress(&body[..], &mut writer, CompressionLevel::Fastest)
+            }
+        }
+        writer.flush()
+    }
+
+    /// Deserializes aggregated data written with `write_binary`
+    /// # Arguments:
+    /// * `reader` - Where the aggregated data is read from
+    pub fn read_binary<R: Read>(mut reader: R) -> Result<AggregatedData, Error> {
+        let mut magic = [0u8; 6];
+        let mut version = [0u8; 2];
+        let mut compression = [0u8; 1];
+
+        reader.read_exact(&mut magic)?;
+        if &magic != AGGREGATED_DATA_BINARY_MAGIC {
+            return Err(invalid_data(
+                "not an aggregated data binary file".to_owned(),
+            ));
+        }
+
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+
+        if version > AGGREGATED_DATA_BINARY_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported aggregated data binary version: {} (up to {} is supported)",
+                version, AGGREGATED_DATA_BINARY_VERSION
+            )));
+        }
+
+        reader.read_exact(&mut compression)?;
+        match AggregatesCompression::from_byte(compression[0])? {
+            AggregatesCompression::None => AggregatedData::decode_binary_body(reader),
+            AggregatesCompression::Gzip => {
+                AggregatedData::decode_binary_body(BufReader::new(GzDecoder::new(reader)))
+            }
+            AggregatesCompression::Zstd => AggregatedData::decode_binary_body(BufReader::new(
+                StreamingDecoder::new(reader).map_err(|err| invalid_data(err.to_string()))?,
+            )),
+        }
+    }
+
+    /// Serializes the aggregated data with the binary format to a byte vector
+    /// (see `write_binary`)
+    /// # Arguments:
+    /// * `compression` - Compression applied to the body
+    pub fn to_binary_bytes(&self, compression: AggregatesCompression) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::default();
+
+        self.write_binary(&mut bytes, compression)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes aggregated data from bytes produced by `to_binary_bytes`
+    /// # Arguments:
+    /// * `bytes` - Serialized aggregated data
+    pub fn from_binary_bytes(bytes: &[u8]) -> Result<AggregatedData, Error> {
+        AggregatedData::read_binary(bytes)
+    }
+
+    /// Returns whether the bytes start as the aggregated data binary format
+    /// # Arguments:
+    /// * `bytes` - First bytes of the serialized data
+    #[inline]
+    pub fn is_binary(bytes: &[u8]) -> bool {
+        bytes.starts_with(AGGREGATED_DATA_BINARY_MAGIC)
+    }
+
+    #[inline]
+    pub(super) fn _write_to_binary(
+        &self,
+        file_path: &str,
+        compression: AggregatesCompression,
+    ) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write to binary");
+
+        self.write_binary(
+            BufWriter::new(std::fs::File::create(file_path)?),
+            compression,
+        )
+    }
+
+    #[inline]
+    pub(super) fn _read_from_binary(file_path: &str) -> Result<AggregatedData, Error> {
+        info!("reading file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("read from binary");
+
+        AggregatedData::read_binary(BufReader::new(std::fs::File::open(file_path)?))
+    }
+
+    fn encode_binary_body(&self) -> Result<Vec<u8>, Error> {
+        let mut body = Vec::default();
+        let metadata = serde_json::to_vec(&AggregatedDataBinaryMetadata {
+            headers: self.headers.clone(),
+            multi_value_column_metadata_map: self.multi_value_column_metadata_map.clone(),
+            numeric_bins_metadata_map: self.numeric_bins_metadata_map.clone(),
+            date_time_metadata_map: self.date_time_metadata_map.clone(),
+            number_of_records: self.number_of_records,
+            protected_number_of_records: self.protected_number_of_records,
+            reporting_length: self.reporting_length,
+            privacy_accountant: self.privacy_accountant.clone(),
+            count_noise_by_len: self.count_noise_by_len.clone(),
+        })?;
+
+        write_bytes(&mut body, &metadata)?;
+
+        // dictionary with the distinct values, so combinations only store ids
+        let mut value_ids: FnvHashMap<&DataBlockValue, usize> = FnvHashMap::default();
+        let mut values: Vec<&DataBlockValue> = Vec::default();
+
+        for comb in self.aggregates_count.keys() {
+            for value in comb.iter() {
+                value_ids.entry(value).or_insert_with(|| {
+                    values.push(value);
+                    values.len() - 1
+                });
+            }
+        }
+
+        write_varint(&mut body, values.len())?;
+        for value in values.iter() {
+            write_varint(&mut body, value.column_index)?;
+            write_bytes(&mut body, value.value.as_bytes())?;
+        }
+
+        write_varint(&mut body, self.aggregates_count.len())?;
+        for (comb, count) in self.aggregates_count.iter() {
+            write_varint(&mut body, comb.len())?;
+            for value in comb.iter() {
+                write_varint(&mut body, value_ids[&**value])?;
+            }
+            write_varint(&mut body, count.count)?;
+            write_varint(&mut body, count.contained_in_records.serialized_size())?;
+            count.contained_in_records.serialize_into(&mut body)?;
+        }
+
+        write_varint(&mut body, self.records_sensitivity_by_len.len())?;
+        for records_sensitivity in self.records_sensitivity_by_len.iter() {
+            write_varint(&mut body, records_sensitivity.len())?;
+            for sensitivity in records_sensitivity.iter() {
+                write_varint(&mut body, *sensitivity)?;
+            }
+        }
+        Ok(body)
+    }
+
+    fn decode_binary_body<R: Read>(mut reader: R) -> Result<AggregatedData, Error> {
+        let metadata: AggregatedDataBinaryMetadata =
+            serde_json::from_slice(&read_bytes(&mut reader)?)?;
+        let n_values = read_varint(&mut reader)?;
+        let mut values: Vec<Arc<DataBlockValue>> =
+            Vec::with_capacity(n_values.min(MAX_PREALLOCATED_ITEMS));
+
+        for _ in 0..n_values {
+            let column_index = read_varint(&mut reader)?;
+            let value = String::from_utf8(read_bytes(&mut reader)?)
+                .map_err(|err| invalid_data(err.to_string()))?;
+
+            if column_index >= metadata.headers.len() {
+                return Err(invalid_data(format!(
+                    "value column index {} is out of range",
+                    column_index
+                )));
+            }
+            values.push(Arc::new(DataBlockValue::new(column_index, Arc::new(value))));
+        }
+
+        let n_combinations = read_varint(&mut reader)?;
+        let mut aggregates_count = AggregatesCountMap::default();
+
+        aggregates_count.reserve(n_combinations.min(MAX_PREALLOCATED_ITEMS));
+        for _ in 0..n_combinations {
+            let comb_len = read_varint(&mut reader)?;
+            let mut comb = Vec::with_capacity(comb_len.min(MAX_PREALLOCATED_ITEMS));
+
+            for _ in 0..comb_len {
+                comb.push(
+                    values
+                        .get(read_varint(&mut reader)?)
+                        .ok_or_else(|| invalid_data("value id is out of range".to_owned()))?
+                        .clone(),
+                );
+            }
+
+            let count = read_varint(&mut reader)?;
+            let records_size = read_varint(&mut reader)?;
+            let contained_in_records =
+                RecordsSet::deserialize_from((&mut reader).take(records_size as u64))?;
+
+            aggregates_count.insert(
+                Arc::new(ValueCombination::new(comb)),
+                AggregatedCount {
+                    count,
+                    contained_in_records,
+                },
+            );
+        }
+
+        let n_lengths = read_varint(&mut reader)?;
+        let mut records_sensitivity_by_len =
+            RecordsSensitivityByLen::with_capacity(n_lengths.min(MAX_PREALLOCATED_ITEMS));
+
+        for _ in 0..n_lengths {
+            let n_records = read_varint(&mut reader)?;
+
+            records_sensitivity_by_len.push(
+                (0..n_records)
+                    .map(|_| read_varint(&mut reader))
+                    .collect::<Result<_, Error>>()?,
+            );
+        }
+
+        Ok(AggregatedData::new(AggregatedDataParts {
+            headers: metadata.headers,
+            multi_value_column_metadata_map: metadata.multi_value_column_metadata_map,
+            numeric_bins_metadata_map: metadata.numeric_bins_metadata_map,
+            date_time_metadata_map: metadata.date_time_metadata_map,
+            number_of_records: metadata.number_of_records,
+            protected_number_of_records: metadata.protected_number_of_records,
+            aggregates_count,
+            records_sensitivity_by_len,
+            reporting_length: metadata.reporting_length,
+            privacy_accountant: metadata.privacy_accountant,
+            count_noise_by_len: metadata.count_noise_by_len,
+        }))
+    }
+}
+
+#[inline]
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Writes an unsigned LEB128 integer
+#[inline]
+fn write_varint<W: Write>(writer: &mut W, mut value: usize) -> Result<(), Error> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Reads an unsigned LEB128 integer
+#[inline]
+fn read_varint<R: Read>(reader: &mut R) -> Result<usize, Error> {
+    let mut value: usize = 0;
+    let mut shift = 0;
+    let mut byte = [0u8; 1];
+
+    loop {
+        reader.read_exact(&mut byte)?;
+        if shift >= usize::BITS {
+            return Err(invalid_data("integer is too large".to_owned()));
+        }
+        value |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Writes the length followed by the bytes
+#[inline]
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), Error> {
+    write_varint(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+/// Reads bytes written by `write_bytes`
+#[inline]
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let len = read_varint(reader)?;
+    let mut bytes = Vec::default();
+
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "unexpected end of aggregated data",
+        ));
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,297 @@
+use super::{
+    aggregated_data::{AggregatedData, AggregatedDataParts},
+    records_set::RecordsSet,
+    rows_aggregator::RowsAggregator,
+    value_combination::ValueCombination,
+    AggregatedCount, RecordsSensitivityByLen,
+};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use serde::Serialize;
+use std::{hash::Hash, sync::Arc};
+
+use crate::data_block::{DataBlockHeaders, DataBlockHeadersSlice, DataBlockValue};
+
+/// Count of a single combination on both aggregated data being compared
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CombinationCountDiff {
+    /// Combination formatted as string using the headers
+    pub combination: String,
+    /// Count on the aggregated data being compared from (0 if not present)
+    pub old_count: usize,
+    /// Count on the aggregated data being compared to (0 if not present)
+    pub new_count: usize,
+}
+
+impl CombinationCountDiff {
+    /// Difference between the new and the old count
+    #[inline]
+    pub fn delta(&self) -> isize {
+        self.new_count as isize - self.old_count as isize
+    }
+}
+
+/// Result of comparing two aggregated data, each vector
+/// is sorted by combination length and then by combination
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregatedDataDiff {
+    /// Combinations only present on the new aggregated data
+    pub added: Vec<CombinationCountDiff>,
+    /// Combinations only present on the old aggregated data
+    pub removed: Vec<CombinationCountDiff>,
+    /// Combinations present on both, but with different counts
+    pub changed: Vec<CombinationCountDiff>,
+}
+
+/// Maps the column indexes of some aggregated data to the
+/// column indexes of headers shared with other aggregated data
+struct ColumnAlignment {
+    column_indexes: Vec<usize>,
+    is_identity: bool,
+    aligned_values: FnvHashMap<Arc<DataBlockValue>, Arc<DataBlockValue>>,
+}
+
+impl ColumnAlignment {
+    #[inline]
+    fn new(headers: &DataBlockHeadersSlice, aligned_headers: &DataBlockHeadersSlice) -> Self {
+        let column_indexes: Vec<usize> = headers
+            .iter()
+            .map(|header| {
+                aligned_headers
+                    .iter()
+                    .position(|aligned_header| aligned_header == header)
+                    .unwrap()
+            })
+            .collect();
+
+        ColumnAlignment {
+            is_identity: column_indexes
+                .iter()
+                .enumerate()
+                .all(|(column_index, aligned_index)| column_index == *aligned_index),
+            column_indexes,
+            aligned_values: FnvHashMap::default(),
+        }
+    }
+
+    fn align(&mut self, comb: &Arc<ValueCombination>) -> Arc<ValueCombination> {
+        if self.is_identity {
+            return comb.clone();
+        }
+
+        let column_indexes = &self.column_indexes;
+        let mut values: Vec<Arc<DataBlockValue>> = comb
+            .iter()
+            .map(|value| {
+                self.aligned_values
+                    .entry(value.clone())
+                    .or_insert_with(|| {
+                        Arc::new(DataBlockValue::new(
+                            column_indexes[value.column_index],
+                            value.value.clone(),
+                        ))
+                    })
+                    .clone()
+            })
+            .collect();
+
+        // keep the combination sorted by the aligned column indexes
+        values.sort_unstable();
+        Arc::new(ValueCombination::new(values))
+    }
+}
+
+impl AggregatedData {
+    /// Merges aggregated data computed over different partitions of the records
+    /// (e.g. per month or per site) into a single aggregated data.
+    ///
+    /// Headers are aligned by name, so partitions with different columns can be merged,
+    /// records of `other` are indexed after the records of `self`.
+    /// Fails if any of the aggregated data was protected with k-anonymity or DP,
+    /// if the reporting lengths differ or if a column has different metadata on each one
+    /// # Arguments
+    /// * `other` - Aggregated data to be merged with this one
+    pub fn merge(&self, other: &AggregatedData) -> Result<AggregatedData, String> {
+        if self.protected_number_of_records.is_some()
+            || other.protected_number_of_records.is_some()
+            || self.privacy_accountant.is_some()
+            || other.privacy_accountant.is_some()
+        {
+            return Err(
+                "only aggregated data not protected with k-anonymity or DP can be merged"
+                    .to_owned(),
+            );
+        }
+        if self.reporting_length != other.reporting_length {
+            return Err(format!(
+                "aggregated data with different reporting lengths can not be merged ({} and {})",
+                self.reporting_length, other.reporting_length
+            ));
+        }
+
+        let headers = AggregatedData::align_headers(&self.headers, &other.headers);
+        let mut alignment = ColumnAlignment::new(&other.headers, &headers);
+        let mut aggregates_count = self.aggregates_count.clone();
+
+        RowsAggregator::join_aggregates_count(
+            &mut aggregates_count,
+            other
+                .aggregates_count
+                .iter()
+                .map(|(comb, count)| {
+                    (
+                        alignment.align(comb),
+                        AggregatedCount {
+                            count: count.count,
+                            contained_in_records: count
+                                .contained_in_records
+                                .iter()
+                                .map(|record_index| record_index + self.number_of_records)
+                                .collect::<RecordsSet>(),
+                        },
+                    )
+                })
+                .collect(),
+        );
+
+        Ok(AggregatedData::new(AggregatedDataParts {
+            headers,
+            multi_value_column_metadata_map: merge_metadata_maps(
+                &self.multi_value_column_metadata_map,
+                &other.multi_value_column_metadata_map,
+            )?,
+            numeric_bins_metadata_map: merge_metadata_maps(
+                &self.numeric_bins_metadata_map,
+                &other.numeric_bins_metadata_map,
+            )?,
+            date_time_metadata_map: merge_metadata_maps(
+                &self.date_time_metadata_map,
+                &other.date_time_metadata_map,
+            )?,
+            number_of_records: self.number_of_records + other.number_of_records,
+            aggregates_count,
+            records_sensitivity_by_len: self
+                .records_sensitivity_by_len
+                .iter()
+                .zip(other.records_sensitivity_by_len.iter())
+                .map(|(records_sensitivity, other_records_sensitivity)| {
+                    records_sensitivity
+                        .iter()
+                        .chain(other_records_sensitivity.iter())
+                        .cloned()
+                        .collect()
+                })
+                .collect::<RecordsSensitivityByLen>(),
+            reporting_length: self.reporting_length,
+            ..AggregatedDataParts::default()
+        }))
+    }
+
+    /// Compares the combination counts of this (old) aggregated data with
+    /// the `other` (new) aggregated data (e.g. two releases).
+    ///
+    /// Headers are aligned by name, so aggregated data with different columns can be compared
+    /// # Arguments
+    /// * `other` - Aggregated data to compare to
+    /// * `combination_delimiter` - Delimiter used to join combinations
+    pub fn diff(&self, other: &AggregatedData, combination_delimiter: &str) -> AggregatedDataDiff {
+        let headers = AggregatedData::align_headers(&self.headers, &other.headers);
+        let mut alignment = ColumnAlignment::new(&other.headers, &headers);
+        let other_counts: FnvHashMap<Arc<ValueCombination>, usize> = other
+            .aggregates_count
+            .iter()
+            .map(|(comb, count)| (alignment.align(comb), count.count))
+            .collect();
+        let to_count_diff = |comb: &ValueCombination, old_count: usize, new_count: usize| {
+            (
+                comb.len(),
+                CombinationCountDiff {
+                    combination: comb.as_str_using_headers(&headers, combination_delimiter),
+                    old_count,
+                    new_count,
+                },
+            )
+        };
+
+        let (removed, changed): (Vec<_>, Vec<_>) = self
+            .aggregates_count
+            .iter()
+            .filter_map(|(comb, count)| match other_counts.get(comb) {
+                None => Some(to_count_diff(comb, count.count, 0)),
+                Some(new_count) if *new_count != count.count => {
+                    Some(to_count_diff(comb, count.count, *new_count))
+                }
+                _ => None,
+            })
+            .partition(|(_, count_diff)| count_diff.new_count == 0);
+
+        AggregatedDataDiff {
+            added: sort_count_diffs(
+                other_counts
+                    .iter()
+                    .filter(|(comb, _)| !self.aggregates_count.contains_key(*comb))
+                    .map(|(comb, count)| to_count_diff(comb, 0, *count))
+                    .collect(),
+            ),
+            removed: sort_count_diffs(removed),
+            changed: sort_count_diffs(changed),
+        }
+    }
+
+    /// Headers of `headers` followed by the ones only present on `other_headers`
+    #[inline]
+    fn align_headers(
+        headers: &DataBlockHeadersSlice,
+        other_headers: &DataBlockHeadersSlice,
+    ) -> DataBlockHeaders {
+        headers
+            .iter()
+            .chain(other_headers.iter())
+            .unique()
+            .cloned()
+            .collect()
+    }
+}
+
+#[inline]
+fn sort_count_diffs(
+    mut count_diffs: Vec<(usize, CombinationCountDiff)>,
+) -> Vec<CombinationCountDiff> {
+    count_diffs.sort_by(|(a_len, a), (b_len, b)| {
+        a_len
+            .cmp(b_len)
+            .then_with(|| a.combination.cmp(&b.combination))
+    });
+    count_diffs
+        .drain(..)
+        .map(|(_, count_diff)| count_diff)
+        .collect()
+}
+
+/// Joins two metadata maps, failing if the same column has different metadata on each one
+fn merge_metadata_maps<K, V>(
+    metadata_map: &FnvHashMap<Arc<K>, V>,
+    other_metadata_map: &FnvHashMap<Arc<K>, V>,
+) -> Result<FnvHashMap<Arc<K>, V>, String>
+where
+    K: Eq + Hash + std::fmt::Display,
+    V: Clone + PartialEq,
+{
+    let mut merged = metadata_map.clone();
+
+    for (header_name, metadata) in other_metadata_map.iter() {
+        match merged.get(header_name) {
+            Some(existing) if existing != metadata => {
+                return Err(format!(
+                    "\"{}\" has different metadata on the aggregated data being merged",
+                    header_name
+                ));
+            }
+            Some(_) => {}
+            None => {
+                merged.insert(header_name.clone(), metadata.clone());
+            }
+        }
+    }
+    Ok(merged)
+}
@@ -0,0 +1,205 @@
+use super::{
+    aggregated_data::{AggregatedData, AggregatedDataParts},
+    records_set::RecordsSet,
+    typedefs::AggregatesCountMap,
+    value_combination::ValueCombination,
+    AggregatedCount, CountNoiseByLen,
+};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufReader, BufWriter, Error, ErrorKind},
+    sync::Arc,
+};
+
+use crate::{
+    data_block::{
+        data_derived_binned_columns, DataBlockHeaders, DateTimeMetadataMap,
+        MultiValueColumnMetadataMap,
+    },
+    dp::{DpParameters, PrivacyAccountant},
+    utils::time::ElapsedDurationLogger,
+};
+
+/// Schema version of the aggregated data release written by this library
+/// (releases with greater versions cannot be read)
+pub const AGGREGATED_DATA_RELEASE_SCHEMA_VERSION: u32 = 1;
+
+/// Bytes starting every json file written with `AggregatedDataRelease::write_to_json`
+pub const AGGREGATED_DATA_RELEASE_PREFIX: &[u8] = b"{\"release_schema_version\":";
+
+/// Maps a value combination to its noisy count
+pub type ReleasedAggregatesCountMap = FnvHashMap<Arc<ValueCombination>, usize>;
+
+/// Aggregated data containing only the fields that are safe
+/// to be shared after the aggregates were protected with DP.
+///
+/// The true number of records, the records sensitivity and the records
+/// containing each combination are never part of a release.
+///
+/// Numeric bin labels are part of the released combinations, so releases can only
+/// be built when every numeric column was binned with explicit edges (the bins
+/// metadata is not released, so synthetic data keeps the bin labels)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AggregatedDataRelease {
+    /// Schema version used to write the release
+    /// (kept as the first field, so releases can be detected)
+    pub release_schema_version: u32,
+    /// Vector of strings representing the data headers
+    pub headers: DataBlockHeaders,
+    /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
+    pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized date/time header name to the granularity its values were generalized to
+    #[serde(default)]
+    pub date_time_metadata_map: DateTimeMetadataMap,
+    /// Number of records protected with DP
+    pub protected_number_of_records: usize,
+    /// Maximum length used to compute attribute combinations
+    pub reporting_length: usize,
+    /// Maps a value combination to its noisy count
+    pub aggregates_count: ReleasedAggregatesCountMap,
+    /// Parameters used to protect the aggregates (without the noise seed)
+    #[serde(default)]
+    pub dp_parameters: Option<DpParameters>,
+    /// Mechanisms invoked to protect the aggregates
+    pub privacy_accountant: PrivacyAccountant,
+    /// Noise added to the counts of each combination length
+    #[serde(default)]
+    pub count_noise_by_len: CountNoiseByLen,
+}
+
+impl AggregatedDataRelease {
+    /// Returns whether the bytes start as a json file written with `write_to_json`
+    /// # Arguments:
+    /// * `bytes` - First bytes of the serialized data
+    #[inline]
+    pub fn is_release(bytes: &[u8]) -> bool {
+        bytes.starts_with(AGGREGATED_DATA_RELEASE_PREFIX)
+    }
+
+    /// Serializes the release to a json file
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    pub fn write_to_json(&self, file_path: &str) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write release to json");
+
+        Ok(serde_json::to_writer(
+            BufWriter::new(std::fs::File::create(file_path)?),
+            &self,
+        )?)
+    }
+
+    /// Deserializes a release from a json file, failing if it was
+    /// written with a newer schema version
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    pub fn read_from_json(file_path: &str) -> Result<AggregatedDataRelease, Error> {
+        info!("reading file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("read release from json");
+        let release: AggregatedDataRelease =
+            serde_json::from_reader(BufReader::new(std::fs::File::open(file_path)?))?;
+
+        if release.release_schema_version > AGGREGATED_DATA_RELEASE_SCHEMA_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "unsupported aggregated data release schema version: {} (up to {} is supported)",
+                    release.release_schema_version, AGGREGATED_DATA_RELEASE_SCHEMA_VERSION
+                ),
+            ));
+        }
+        Ok(release)
+    }
+}
+
+impl From<AggregatedDataRelease> for AggregatedData {
+    /// Builds aggregated data that can be used for aggregate seeded and marginal model
+    /// synthesis. The protected number of records is also used as the number of records,
+    /// and neither records nor sensitivities are available
+    fn from(release: AggregatedDataRelease) -> Self {
+        AggregatedData::new(AggregatedDataParts {
+            headers: release.headers,
+            multi_value_column_metadata_map: release.multi_value_column_metadata_map,
+            date_time_metadata_map: release.date_time_metadata_map,
+            number_of_records: release.protected_number_of_records,
+            protected_number_of_records: Some(release.protected_number_of_records),
+            aggregates_count: release
+                .aggregates_count
+                .into_iter()
+                .map(|(comb, count)| {
+                    (
+                        comb,
+                        AggregatedCount {
+                            count,
+                            contained_in_records: RecordsSet::default(),
+                        },
+                    )
+                })
+                .collect::<AggregatesCountMap>(),
+            reporting_length: release.reporting_length,
+            privacy_accountant: Some(release.privacy_accountant),
+            count_noise_by_len: release.count_noise_by_len,
+            ..AggregatedDataParts::default()
+        })
+    }
+}
+
+impl AggregatedData {
+    /// Builds a release with the DP-safe fields of the aggregated data.
+    ///
+    /// Fails if the aggregated data was not protected with DP, or if
+    /// any numeric column was binned from the data (the bin labels would disclose
+    /// sensitive values)
+    /// # Arguments:
+    /// * `dp_parameters` - Parameters used to protect the aggregates, included without the seed (if `None` they are not included)
+    pub fn to_release(
+        &self,
+        dp_parameters: Option<&DpParameters>,
+    ) -> Result<AggregatedDataRelease, Error> {
+        let data_derived_columns = data_derived_binned_columns(&self.numeric_bins_metadata_map);
+
+        if !data_derived_columns.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "numeric columns binned from the data can not be released: {}",
+                    data_derived_columns.iter().join(", ")
+                ),
+            ));
+        }
+
+        match (&self.privacy_accountant, self.protected_number_of_records) {
+            (Some(privacy_accountant), Some(protected_number_of_records)) => {
+                Ok(AggregatedDataRelease {
+                    release_schema_version: AGGREGATED_DATA_RELEASE_SCHEMA_VERSION,
+                    headers: self.headers.clone(),
+                    multi_value_column_metadata_map: self.multi_value_column_metadata_map.clone(),
+                    date_time_metadata_map: self.date_time_metadata_map.clone(),
+                    protected_number_of_records,
+                    reporting_length: self.reporting_length,
+                    aggregates_count: self
+                        .aggregates_count
+                        .iter()
+                        .map(|(comb, count)| (comb.clone(), count.count))
+                        .collect(),
+                    // the seed would allow the noise to be reproduced
+                    dp_parameters: dp_parameters.map(|dp_parameters| DpParameters {
+                        seed: None,
+                        ..dp_parameters.clone()
+                    }),
+                    privacy_accountant: privacy_accountant.clone(),
+                    count_noise_by_len: self.count_noise_by_len.clone(),
+                })
+            }
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "only aggregated data protected with DP can be released",
+            )),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::{aggregated_data::AggregatedData, value_combination::ValueCombination};
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Default confidence level used for the noisy counts confidence intervals
+pub const DEFAULT_CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Noise added to the counts of a combination length when aggregating with DP
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CountNoise {
+    /// Standard deviation of the gaussian noise added to each count
+    /// (`sigma * l2_sensitivity`) of marginals with weight 1
+    pub standard_deviation: f64,
+    /// Allowed sensitivity (maximum number of combinations of this length
+    /// each record or subject contributes with)
+    pub l1_sensitivity: usize,
+    /// Privacy budget weight by column index (columns not present have weight 1).
+    /// The noise of a marginal is divided by the highest weight among its columns
+    #[serde(default)]
+    pub column_weights: FnvHashMap<usize, f64>,
+    /// Whether the counts were adjusted after the noise was added (e.g. to make
+    /// them consistent with each other), in which case the noise no longer describes
+    /// the reported counts and no confidence interval is computed for them
+    #[serde(default)]
+    pub post_processed: bool,
+}
+
+/// Maps a combination length to the noise added to its counts
+pub type CountNoiseByLen = FnvHashMap<usize, CountNoise>;
+
+/// Confidence interval of a noisy count
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CountConfidenceInterval {
+    /// Lower bound (never below 0)
+    pub lower: f64,
+    /// Upper bound
+    pub upper: f64,
+}
+
+/// Maps a value combination formatted as string to the confidence interval of its count
+pub type AggregatesCountConfidenceIntervalStringMap = FnvHashMap<String, CountConfidenceInterval>;
+
+impl CountNoise {
+    /// Standard deviation of the noise added to the counts of a marginal
+    /// # Arguments
+    /// * `column_indexes` - Indexes of the columns forming the marginal
+    #[inline]
+    pub fn marginal_standard_deviation<I: Iterator<Item = usize>>(&self, column_indexes: I) -> f64 {
+        self.standard_deviation
+            / column_indexes
+                .map(|column_index| {
+                    self.column_weights
+                        .get(&column_index)
+                        .cloned()
+                        .unwrap_or(1.0)
+                })
+                .reduce(f64::max)
+                .unwrap_or(1.0)
+    }
+
+    /// Confidence interval of the count of a combination that received this noise
+    /// # Arguments
+    /// * `comb` - Combination the count belongs to
+    /// * `count` - Reported noisy count
+    /// * `confidence_level` - Probability of the interval containing the true count (e.g. 0.95)
+    #[inline]
+    pub fn confidence_interval(
+        &self,
+        comb: &ValueCombination,
+        count: usize,
+        confidence_level: f64,
+    ) -> CountConfidenceInterval {
+        assert!(
+            confidence_level > 0.0 && confidence_level < 1.0,
+            "confidence level must be > 0 and < 1"
+        );
+
+        let margin = self.marginal_standard_deviation(comb.iter().map(|v| v.column_index))
+            * Normal::new(0.0, 1.0)
+                .unwrap()
+                .inverse_cdf(0.5 + confidence_level / 2.0);
+
+        CountConfidenceInterval {
+            lower: (count as f64 - margin).max(0.0),
+            upper: count as f64 + margin,
+        }
+    }
+}
+
+impl AggregatedData {
+    /// Noise used to compute the confidence intervals of the counts with length `comb_len`,
+    /// `None` if they were not protected with DP or were post-processed after the noise was added
+    #[inline]
+    pub(crate) fn confidence_interval_noise(&self, comb_len: usize) -> Option<&CountNoise> {
+        self.count_noise_by_len
+            .get(&comb_len)
+            .filter(|noise| !noise.post_processed)
+    }
+
+    /// Approximate confidence interval of the noisy count of a combination, `None` if the
+    /// combination is not reported, its counts were not protected with DP or were made
+    /// consistent after the noise was added (e.g. with least squares consistency).
+    ///
+    /// Only the noise is taken into account, not the rounding of the noisy count
+    /// # Arguments
+    /// * `comb` - Combination to calculate the interval for
+    /// * `confidence_level` - Probability of the interval containing the true count (e.g. 0.95)
+    pub fn calc_count_confidence_interval(
+        &self,
+        comb: &ValueCombination,
+        confidence_level: f64,
+    ) -> Option<CountConfidenceInterval> {
+        let count = self.aggregates_count.get(comb)?;
+
+        self.confidence_interval_noise(comb.len())
+            .map(|noise| noise.confidence_interval(comb, count.count, confidence_level))
+    }
+
+    /// Builds a map from value combinations formatted as string to the approximate
+    /// confidence interval of its count (combinations whose counts were not protected
+    /// with DP or were post-processed after the noise was added are not included)
+    /// # Arguments:
+    /// * `combination_delimiter` - Delimiter used to join combinations
+    /// * `confidence_level` - Probability of the interval containing the true count (e.g. 0.95)
+    pub fn aggregates_count_confidence_interval_as_str(
+        &self,
+        combination_delimiter: &str,
+        confidence_level: f64,
+    ) -> AggregatesCountConfidenceIntervalStringMap {
+        self.aggregates_count
+            .iter()
+            .filter_map(|(comb, count)| {
+                self.confidence_interval_noise(comb.len()).map(|noise| {
+                    (
+                        comb.as_str_using_headers(&self.headers, combination_delimiter),
+                        noise.confidence_interval(comb, count.count, confidence_level),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Builds a map from the reported marginals (header names joined by
+    /// `combination_delimiter`) to the standard deviation of the noise added to their counts
+    /// (empty if the counts were not protected with DP)
+    /// # Arguments:
+    /// * `combination_delimiter` - Delimiter used to join the header names
+    pub fn marginal_noise_standard_deviation_as_str(
+        &self,
+        combination_delimiter: &str,
+    ) -> FnvHashMap<String, f64> {
+        let marginals: FnvHashSet<Vec<usize>> = self
+            .aggregates_count
+            .keys()
+            .map(|comb| comb.iter().map(|v| v.column_index).collect())
+            .collect();
+
+        marginals
+            .into_iter()
+            .filter_map(|marginal| {
+                self.count_noise_by_len.get(&marginal.len()).map(|noise| {
+                    (
+                        marginal
+                            .iter()
+                            .map(|column_index| self.headers[*column_index].as_str())
+                            .join(combination_delimiter),
+                        noise.marginal_standard_deviation(marginal.iter().cloned()),
+                    )
+                })
+            })
+            .collect()
+    }
+}
@@ -1,4 +1,4 @@
-use super::aggregated_data::AggregatedData;
+use super::aggregated_data::{AggregatedData, AggregatedDataParts};
 use super::rows_aggregator::RowsAggregator;
 use itertools::Itertools;
 use log::info;
@@ -69,15 +69,20 @@ impl Aggregator {
                 result.aggregates_count.len()
             );
 
-            Ok(AggregatedData::new(
-                self.data_block.headers.clone(),
-                self.data_block.multi_value_column_metadata_map.clone(),
-                self.data_block.number_of_records(),
-                None,
-                result.aggregates_count,
-                result.records_sensitivity_by_len,
-                normalized_reporting_length,
-            ))
+            Ok(AggregatedData::new(AggregatedDataParts {
+                headers: self.data_block.headers.clone(),
+                multi_value_column_metadata_map: self
+                    .data_block
+                    .multi_value_column_metadata_map
+                    .clone(),
+                numeric_bins_metadata_map: self.data_block.numeric_bins_metadata_map.clone(),
+                date_time_metadata_map: self.data_block.date_time_metadata_map.clone(),
+                number_of_records: self.data_block.number_of_records(),
+                aggregates_count: result.aggregates_count,
+                records_sensitivity_by_len: result.records_sensitivity_by_len,
+                reporting_length: normalized_reporting_length,
+                ..AggregatedDataParts::default()
+            }))
         })
     }
 
@@ -1,7 +1,12 @@
 mod aggregated_count;
 mod aggregated_data;
+mod aggregated_data_binary;
+mod aggregated_data_merge;
+mod aggregated_data_release;
+mod count_noise;
 mod data_aggregator;
 mod records_analysis_data;
+mod records_set;
 mod rows_aggregator;
 mod typedefs;
 mod value_combination;
@@ -11,8 +16,13 @@ mod register_pyo3;
 
 pub use aggregated_count::*;
 pub use aggregated_data::*;
+pub use aggregated_data_binary::*;
+pub use aggregated_data_merge::*;
+pub use aggregated_data_release::*;
+pub use count_noise::*;
 pub use data_aggregator::*;
 pub use records_analysis_data::*;
+pub use records_set::*;
 pub use typedefs::*;
 pub use value_combination::*;
 
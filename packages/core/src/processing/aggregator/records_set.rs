@@ -0,0 +1,192 @@
+use roaring::RoaringBitmap;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+use std::{
+    fmt,
+    io::{Read, Write},
+};
+
+#[cfg(feature = "pyo3")]
+use pyo3::{prelude::*, types::PySet};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Set of records where the key is the record index starting in 0.
+///
+/// The indexes are stored on a compressed bitmap, so sets
+/// containing many records (e.g. single attribute combinations)
+/// take just a fraction of the memory a hash set would.
+/// Record indexes need to fit into a `u32`.
+pub struct RecordsSet {
+    bitmap: RoaringBitmap,
+}
+
+impl RecordsSet {
+    #[inline]
+    /// Creates a new empty RecordsSet
+    pub fn new() -> RecordsSet {
+        RecordsSet {
+            bitmap: RoaringBitmap::new(),
+        }
+    }
+
+    #[inline]
+    fn to_key(record_index: usize) -> u32 {
+        u32::try_from(record_index).expect("record index does not fit into a records set")
+    }
+
+    #[inline]
+    /// Adds a record to the set, returns whether it was not already present
+    /// # Arguments
+    /// * `record_index` - Index of the record to be added
+    pub fn insert(&mut self, record_index: usize) -> bool {
+        self.bitmap.insert(Self::to_key(record_index))
+    }
+
+    #[inline]
+    /// Removes a record from the set, returns whether it was present
+    /// # Arguments
+    /// * `record_index` - Index of the record to be removed
+    pub fn remove(&mut self, record_index: usize) -> bool {
+        u32::try_from(record_index).is_ok_and(|key| self.bitmap.remove(key))
+    }
+
+    #[inline]
+    /// Returns whether the record is present in the set
+    /// # Arguments
+    /// * `record_index` - Index of the record to be checked
+    pub fn contains(&self, record_index: usize) -> bool {
+        u32::try_from(record_index).is_ok_and(|key| self.bitmap.contains(key))
+    }
+
+    #[inline]
+    /// Adds all the records from `other` to this set
+    /// # Arguments
+    /// * `other` - Set with the records to be added
+    pub fn union_with(&mut self, other: &RecordsSet) {
+        self.bitmap |= &other.bitmap;
+    }
+
+    #[inline]
+    /// Removes all the records present in `other` from this set
+    /// # Arguments
+    /// * `other` - Set with the records to be removed
+    pub fn difference_with(&mut self, other: &RecordsSet) {
+        self.bitmap -= &other.bitmap;
+    }
+
+    #[inline]
+    /// Number of records in the set
+    pub fn len(&self) -> usize {
+        self.bitmap.len() as usize
+    }
+
+    #[inline]
+    /// Whether the set has no records
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.is_empty()
+    }
+
+    #[inline]
+    /// Iterates over the record indexes in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bitmap.iter().map(|key| key as usize)
+    }
+
+    #[inline]
+    /// Number of bytes written by `serialize_into`
+    pub fn serialized_size(&self) -> usize {
+        self.bitmap.serialized_size()
+    }
+
+    #[inline]
+    /// Writes the set using the portable roaring bitmap format
+    /// # Arguments
+    /// * `writer` - Where the set is written to
+    pub fn serialize_into<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.bitmap.serialize_into(writer)
+    }
+
+    #[inline]
+    /// Reads a set written by `serialize_into`
+    /// # Arguments
+    /// * `reader` - Where the set is read from
+    pub fn deserialize_from<R: Read>(reader: R) -> std::io::Result<RecordsSet> {
+        Ok(RecordsSet {
+            bitmap: RoaringBitmap::deserialize_from(reader)?,
+        })
+    }
+}
+
+impl FromIterator<usize> for RecordsSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut records = RecordsSet::new();
+
+        records.extend(iter);
+        records
+    }
+}
+
+impl Extend<usize> for RecordsSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.bitmap.extend(iter.into_iter().map(Self::to_key));
+    }
+}
+
+impl Serialize for RecordsSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // keep the same representation as a plain set of indexes
+        serializer.collect_seq(self.iter())
+    }
+}
+
+struct RecordsSetVisitor;
+
+impl<'de> Visitor<'de> for RecordsSetVisitor {
+    type Value = RecordsSet;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of record indexes")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut records = RecordsSet::new();
+
+        while let Some(record_index) = seq.next_element::<usize>()? {
+            let key = u32::try_from(record_index).map_err(|_| {
+                de::Error::custom(format!(
+                    "record index {} does not fit into a records set",
+                    record_index
+                ))
+            })?;
+            records.bitmap.insert(key);
+        }
+        Ok(records)
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordsSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RecordsSetVisitor)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl IntoPy<PyObject> for RecordsSet {
+    fn into_py(self, py: Python) -> PyObject {
+        // exposed as a regular python set of indexes
+        PySet::new(py, &self.iter().collect::<Vec<usize>>())
+            .expect("failed to create records set")
+            .into()
+    }
+}
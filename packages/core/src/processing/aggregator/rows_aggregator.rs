@@ -8,7 +8,7 @@ use super::{
 };
 use itertools::Itertools;
 use log::info;
-use std::sync::Arc;
+use std::{collections::hash_map::Entry, sync::Arc};
 
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
@@ -149,16 +149,10 @@ impl RowsAggregator {
         // use drain instead of fold, so we do not duplicate memory
         for mut partial_result in partial_results.drain(..) {
             // join aggregated counts
-            for (comb, value) in partial_result.aggregates_count.drain() {
-                let final_count = final_result
-                    .aggregates_count
-                    .entry(comb)
-                    .or_insert_with(AggregatedCount::default);
-                final_count.count += value.count;
-                final_count
-                    .contained_in_records
-                    .extend(value.contained_in_records);
-            }
+            RowsAggregator::join_aggregates_count(
+                &mut final_result.aggregates_count,
+                partial_result.aggregates_count,
+            );
 
             // join records sensitivity
             for (l, mut records_sensitivity) in partial_result
@@ -174,6 +168,33 @@ impl RowsAggregator {
         Ok(final_result)
     }
 
+    /// Adds the counts and records of `aggregates_count` to `final_aggregates_count`
+    /// # Arguments
+    /// * `final_aggregates_count` - Aggregates count map receiving the counts
+    /// * `aggregates_count` - Aggregates count map to be joined (records sets are moved, not copied)
+    #[inline]
+    pub fn join_aggregates_count(
+        final_aggregates_count: &mut AggregatesCountMap,
+        aggregates_count: AggregatesCountMap,
+    ) {
+        for (comb, value) in aggregates_count {
+            match final_aggregates_count.entry(comb) {
+                Entry::Occupied(mut entry) => {
+                    let final_count = entry.get_mut();
+
+                    final_count.count += value.count;
+                    final_count
+                        .contained_in_records
+                        .union_with(&value.contained_in_records);
+                }
+                // move the partial count, so the records set is not copied
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        }
+    }
+
     #[inline]
     fn aggregate_rows<T>(
         &mut self,
@@ -190,7 +211,7 @@ impl RowsAggregator {
 
             // sort the attributes here, so combinations will be already sorted
             // and we do not need to sort entry by entry on the loop below
-            selected_attrs.sort_by_key(|k| k.as_str_using_headers(&self.data_block.headers));
+            selected_attrs.sort_unstable();
 
             for l in 1..=self.reporting_length {
                 for mut c in selected_attrs.iter().combinations(l) {
@@ -1,14 +1,12 @@
 use super::{
-    records_analysis_data::RecordsAnalysis, value_combination::ValueCombination, AggregatedCount,
+    records_analysis_data::RecordsAnalysis, records_set::RecordsSet,
+    value_combination::ValueCombination, AggregatedCount,
 };
 use fnv::{FnvHashMap, FnvHashSet};
 use std::sync::Arc;
 
 use crate::data_block::{DataBlockRecord, DataBlockValue};
 
-/// Set of records where the key is the record index starting in 0
-pub type RecordsSet = FnvHashSet<usize>;
-
 /// Maps a value combination to its aggregated count
 pub type AggregatesCountMap = FnvHashMap<Arc<ValueCombination>, AggregatedCount>;
 
@@ -5,16 +5,14 @@ use serde::{
 };
 use std::{fmt::Display, marker::PhantomData, ops::Deref, str::FromStr, sync::Arc};
 
-use crate::data_block::{
-    DataBlockHeaders, DataBlockHeadersSlice, DataBlockValue, ParseDataBlockValueError,
-};
+use crate::data_block::{DataBlockHeadersSlice, DataBlockValue, ParseDataBlockValueError};
 
 /// Delimiter between attributes that form a value combination
 pub const COMBINATIONS_DELIMITER: char = ';';
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
 /// Wraps a vector of data block values representing a value
-/// combination (sorted by `{header_name}:{block_value}`)
+/// combination (sorted by column index)
 pub struct ValueCombination {
     combination: Vec<Arc<DataBlockValue>>,
 }
@@ -30,7 +28,7 @@ impl ValueCombination {
     /// Creates a new ValueCombination
     /// # Arguments
     /// * `combination` - raw vector of value combinations
-    /// sorted by `{header_name}:{block_value}`
+    ///   sorted by column index
     pub fn new(combination: Vec<Arc<DataBlockValue>>) -> ValueCombination {
         ValueCombination { combination }
     }
@@ -115,10 +113,9 @@ impl ValueCombination {
 
     /// Adds `value` to the value combination, keeping it sorted
     #[inline]
-    pub fn extend(&mut self, value: Arc<DataBlockValue>, headers: &DataBlockHeaders) {
+    pub fn extend(&mut self, value: Arc<DataBlockValue>) {
         self.combination.push(value);
-        self.combination
-            .sort_by_key(|k| k.as_str_using_headers(headers));
+        self.combination.sort_unstable();
     }
 }
 
@@ -138,13 +135,15 @@ impl FromStr for ValueCombination {
     type Err = ParseDataBlockValueError;
 
     /// Creates a new ValueCombination by parsing `str_value`
+    /// (values are sorted by column index, regardless of their order on `str_value`)
     fn from_str(str_value: &str) -> Result<Self, Self::Err> {
-        Ok(ValueCombination::new(
-            str_value
-                .split(COMBINATIONS_DELIMITER)
-                .map(|v| Ok(Arc::new(DataBlockValue::from_str(v)?)))
-                .collect::<Result<Vec<Arc<DataBlockValue>>, Self::Err>>()?,
-        ))
+        let mut combination = str_value
+            .split(COMBINATIONS_DELIMITER)
+            .map(|v| Ok(Arc::new(DataBlockValue::from_str(v)?)))
+            .collect::<Result<Vec<Arc<DataBlockValue>>, Self::Err>>()?;
+
+        combination.sort_unstable();
+        Ok(ValueCombination::new(combination))
     }
 }
 
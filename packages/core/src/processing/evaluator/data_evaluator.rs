@@ -1,16 +1,21 @@
+use super::evaluation_report::EvaluationReport;
 use super::preservation_by_count::{PreservationByCountBucketBins, PreservationByCountBuckets};
 use super::rare_combinations_comparison_data::RareCombinationsComparisonData;
+use super::typedefs::{WorkloadCellCountMap, WorkloadQuery, WorkloadQuerySlice};
+use super::workload_accuracy::{WorkloadAccuracy, WorkloadQueryAccuracy};
 use fnv::{FnvHashMap, FnvHashSet};
 use std::sync::Arc;
 
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 
+use crate::data_block::{DataBlock, DataBlockHeadersSlice};
 use crate::processing::aggregator::{
     AggregatedCountByLenMap, AggregatedData, AggregatedMetricByLenMap, ValueCombination,
 };
 use crate::processing::evaluator::preservation_bucket::PreservationBucket;
 use crate::processing::evaluator::preservation_by_length::PreservationByLengthBuckets;
+use crate::utils::{strings::transform_for_insensitive_cmp, time::ElapsedDurationLogger};
 
 #[cfg_attr(feature = "pyo3", pyclass)]
 /// Evaluates aggregated, sensitive and synthesized data
@@ -22,6 +27,265 @@ impl Evaluator {
         Evaluator {}
     }
 
+    /// Calculates all the evaluation metrics at once, returning them as
+    /// a single report
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Calculated aggregated data for the synthetic data
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `combination_delimiter` - Delimiter used to join combinations and format then as strings
+    /// * `protect` - Whether or not the sensitive counts should be rounded to the nearest smallest multiple of resolution
+    pub fn calc_evaluation_report(
+        &self,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+        resolution: usize,
+        combination_delimiter: &str,
+        protect: bool,
+    ) -> EvaluationReport {
+        let preservation_by_count = self.calc_preservation_by_count(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+            resolution,
+        );
+
+        EvaluationReport {
+            resolution,
+            reporting_length: sensitive_aggregated_data.reporting_length,
+            sensitive_number_of_records: sensitive_aggregated_data.number_of_records,
+            synthetic_number_of_records: synthetic_aggregated_data.number_of_records,
+            record_expansion_percentage: self.calc_record_expansion_percentage(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            leakage_count_by_len: self.calc_leakage_count_by_len(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+                resolution,
+            ),
+            leakage_percentage_by_len: self.calc_leakage_percentage_by_len(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+                resolution,
+            ),
+            number_of_suppressed_combinations: self.calc_number_of_suppressed_combinations(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            percentage_of_suppressed_combinations: self.calc_percentage_of_suppressed_combinations(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            number_of_fabricated_combinations: self.calc_number_of_fabricated_combinations(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            percentage_of_fabricated_combinations: self.calc_percentage_of_fabricated_combinations(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            combinations_count_mean_abs_error: self.calc_combinations_count_mean_abs_error(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            combinations_count_mean_abs_error_by_len: self
+                .calc_combinations_count_mean_abs_error_by_len(
+                    sensitive_aggregated_data,
+                    synthetic_aggregated_data,
+                ),
+            mean_proportional_error: preservation_by_count.calc_mean_proportional_error(),
+            preservation_by_count,
+            preservation_by_length: self.calc_preservation_by_length(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+                resolution,
+            ),
+            rare_combinations: self.compare_synthetic_and_sensitive_rare(
+                synthetic_aggregated_data,
+                sensitive_aggregated_data,
+                resolution,
+                combination_delimiter,
+                protect,
+            ),
+        }
+    }
+
+    /// Compares the exact sensitive answers of each marginal query in the workload
+    /// with the answers computed on the synthetic microdata.
+    /// Queries are answered directly from the records, so they can be longer than
+    /// the reporting length. A record only counts towards a cell when it has a value
+    /// for every query column
+    /// # Arguments
+    /// * `workload` - Marginal queries (column names) to be evaluated
+    /// * `sensitive_data_block` - Sensitive data block
+    /// * `synthetic_data_block` - Data block read from the synthetic microdata
+    pub fn calc_workload_accuracy(
+        &self,
+        workload: &[WorkloadQuery],
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<WorkloadAccuracy, String> {
+        let _duration_logger = ElapsedDurationLogger::new("workload accuracy calculation");
+
+        Ok(WorkloadAccuracy::new(
+            workload
+                .iter()
+                .map(|query| {
+                    Ok(WorkloadQueryAccuracy::new(
+                        self.normalize_query(&sensitive_data_block.headers, query)?,
+                        &self.calc_workload_cell_counts(sensitive_data_block, query)?,
+                        &self.calc_workload_cell_counts(synthetic_data_block, query)?,
+                    ))
+                })
+                .collect::<Result<Vec<WorkloadQueryAccuracy>, String>>()?,
+        ))
+    }
+
+    /// Compares the exact sensitive answers of each marginal query in the workload
+    /// with the answers taken from the (possibly DP) aggregated data.
+    /// Queries longer than the aggregated reporting length cannot be answered
+    /// # Arguments
+    /// * `workload` - Marginal queries (column names) to be evaluated
+    /// * `sensitive_data_block` - Sensitive data block
+    /// * `aggregated_data` - Aggregated data answering the queries
+    pub fn calc_workload_accuracy_with_aggregates(
+        &self,
+        workload: &[WorkloadQuery],
+        sensitive_data_block: &DataBlock,
+        aggregated_data: &AggregatedData,
+    ) -> Result<WorkloadAccuracy, String> {
+        let _duration_logger = ElapsedDurationLogger::new("workload accuracy calculation");
+
+        Ok(WorkloadAccuracy::new(
+            workload
+                .iter()
+                .map(|query| {
+                    Ok(WorkloadQueryAccuracy::new(
+                        self.normalize_query(&sensitive_data_block.headers, query)?,
+                        &self.calc_workload_cell_counts(sensitive_data_block, query)?,
+                        &self.calc_workload_cell_counts_from_aggregates(aggregated_data, query)?,
+                    ))
+                })
+                .collect::<Result<Vec<WorkloadQueryAccuracy>, String>>()?,
+        ))
+    }
+
+    #[inline]
+    fn find_query_columns(
+        &self,
+        headers: &DataBlockHeadersSlice,
+        query: &WorkloadQuerySlice,
+    ) -> Result<Vec<usize>, String> {
+        let column_index_by_header: FnvHashMap<String, usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(column_index, header)| (transform_for_insensitive_cmp(header), column_index))
+            .collect();
+        let columns = query
+            .iter()
+            .map(|column| {
+                column_index_by_header
+                    .get(&transform_for_insensitive_cmp(column))
+                    .cloned()
+                    .ok_or_else(|| format!("query column \"{}\" not found", column))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        if columns.is_empty() {
+            return Err("workload queries should have at least one column".to_owned());
+        }
+        if columns.iter().collect::<FnvHashSet<_>>().len() != columns.len() {
+            return Err(format!(
+                "query \"{}\" has duplicated columns",
+                query.join(",")
+            ));
+        }
+        Ok(columns)
+    }
+
+    #[inline]
+    fn normalize_query(
+        &self,
+        headers: &DataBlockHeadersSlice,
+        query: &WorkloadQuerySlice,
+    ) -> Result<WorkloadQuery, String> {
+        Ok(self
+            .find_query_columns(headers, query)?
+            .iter()
+            .map(|column_index| (*headers[*column_index]).clone())
+            .collect())
+    }
+
+    #[inline]
+    fn calc_workload_cell_counts(
+        &self,
+        data_block: &DataBlock,
+        query: &WorkloadQuerySlice,
+    ) -> Result<WorkloadCellCountMap, String> {
+        let columns = self.find_query_columns(&data_block.headers, query)?;
+        let mut query_position_by_column: Vec<Option<usize>> = vec![None; data_block.headers.len()];
+        let mut cell_counts = WorkloadCellCountMap::default();
+
+        for (position, column_index) in columns.iter().enumerate() {
+            query_position_by_column[*column_index] = Some(position);
+        }
+
+        for record in data_block.records.iter() {
+            let mut cell: Vec<Option<Arc<String>>> = vec![None; columns.len()];
+
+            for value in record.values.iter() {
+                if let Some(position) = query_position_by_column[value.column_index] {
+                    cell[position] = Some(value.value.clone());
+                }
+            }
+
+            if let Some(cell) = cell.into_iter().collect::<Option<Vec<Arc<String>>>>() {
+                *cell_counts.entry(cell).or_default() += 1;
+            }
+        }
+        Ok(cell_counts)
+    }
+
+    #[inline]
+    fn calc_workload_cell_counts_from_aggregates(
+        &self,
+        aggregated_data: &AggregatedData,
+        query: &WorkloadQuerySlice,
+    ) -> Result<WorkloadCellCountMap, String> {
+        let columns = self.find_query_columns(&aggregated_data.headers, query)?;
+
+        if columns.len() > aggregated_data.reporting_length {
+            return Err(format!(
+                "query \"{}\" is longer than the aggregated reporting length ({})",
+                query.join(","),
+                aggregated_data.reporting_length
+            ));
+        }
+
+        let position_by_column: FnvHashMap<usize, usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(position, column_index)| (*column_index, position))
+            .collect();
+        let mut cell_counts = WorkloadCellCountMap::default();
+
+        for (comb, count) in aggregated_data.aggregates_count.iter() {
+            if comb.len() == columns.len()
+                && comb
+                    .iter()
+                    .all(|value| position_by_column.contains_key(&value.column_index))
+            {
+                let mut cell = vec![Arc::default(); columns.len()];
+
+                for value in comb.iter() {
+                    cell[position_by_column[&value.column_index]] = value.value.clone();
+                }
+                cell_counts.insert(cell, count.count);
+            }
+        }
+        Ok(cell_counts)
+    }
+
     fn calc_combinations_abs_error_sum_count_by_len(
         &self,
         sensitive_aggregated_data: &AggregatedData,
@@ -0,0 +1,188 @@
+use super::{
+    preservation_by_count::PreservationByCountBuckets,
+    preservation_by_length::PreservationByLengthBuckets,
+    rare_combinations_comparison_data::RareCombinationsComparisonData,
+};
+use itertools::Itertools;
+use log::info;
+use serde::Serialize;
+use std::io::{BufWriter, Error, Write};
+
+use crate::{
+    processing::aggregator::{AggregatedCountByLenMap, AggregatedMetricByLenMap},
+    utils::time::ElapsedDurationLogger,
+};
+
+#[derive(Serialize)]
+/// Utility and privacy metrics comparing the synthetic data
+/// with the sensitive data, gathered so they can be exported together
+/// (e.g. to gate pipelines on utility thresholds)
+pub struct EvaluationReport {
+    /// Reporting resolution used for the evaluation
+    pub resolution: usize,
+    /// Maximum combination length used for the evaluation
+    pub reporting_length: usize,
+    /// Number of records on the sensitive data
+    pub sensitive_number_of_records: usize,
+    /// Number of records on the synthetic data
+    pub synthetic_number_of_records: usize,
+    /// Record expansion percentage
+    /// (number of synthetic records / number of sensitive records)
+    pub record_expansion_percentage: f64,
+    /// Sensitive rare combinations that also appear as rare on the synthetic data,
+    /// grouped by combination length
+    pub leakage_count_by_len: AggregatedCountByLenMap,
+    /// Leakage count by length divided by the total number of sensitive combinations
+    /// on that length
+    pub leakage_percentage_by_len: AggregatedMetricByLenMap,
+    /// Combinations that exist on the sensitive data but not on the synthetic data
+    pub number_of_suppressed_combinations: usize,
+    /// Percentage of sensitive combinations that were suppressed
+    pub percentage_of_suppressed_combinations: f64,
+    /// Combinations that exist on the synthetic data but not on the sensitive data
+    pub number_of_fabricated_combinations: usize,
+    /// Percentage of synthetic combinations that were fabricated
+    pub percentage_of_fabricated_combinations: f64,
+    /// Mean absolute error between the sensitive and synthetic combination counts
+    pub combinations_count_mean_abs_error: f64,
+    /// Mean absolute error between the sensitive and synthetic combination counts,
+    /// grouped by combination length
+    pub combinations_count_mean_abs_error_by_len: AggregatedMetricByLenMap,
+    /// Mean proportional error between all the preservation by count buckets
+    pub mean_proportional_error: f64,
+    /// Preservation information grouped by synthetic combination count
+    pub preservation_by_count: PreservationByCountBuckets,
+    /// Preservation information grouped by combination length
+    pub preservation_by_length: PreservationByLengthBuckets,
+    /// Rare combinations on the synthetic data compared with the sensitive counts
+    pub rare_combinations: RareCombinationsComparisonData,
+}
+
+impl EvaluationReport {
+    /// Total leakage count across all the combination lengths
+    pub fn total_leakage_count(&self) -> usize {
+        self.leakage_count_by_len.values().sum()
+    }
+
+    /// Serializes the whole report to a json file
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    pub fn write_to_json(&self, file_path: &str) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write evaluation report to json");
+
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(std::fs::File::create(file_path)?),
+            &self,
+        )?)
+    }
+
+    /// Writes the single valued metrics to the file system in a csv/tsv like format
+    /// (one `metric`/`value` pair per line)
+    /// # Arguments:
+    /// * `summary_path` - File path to be written
+    /// * `summary_delimiter` - Delimiter to use when writing to `summary_path`
+    pub fn write_summary(&self, summary_path: &str, summary_delimiter: char) -> Result<(), Error> {
+        info!("writing file: {}", summary_path);
+
+        let mut file = BufWriter::new(std::fs::File::create(summary_path)?);
+        let metrics: [(&str, String); 12] = [
+            ("resolution", self.resolution.to_string()),
+            ("reporting_length", self.reporting_length.to_string()),
+            (
+                "sensitive_number_of_records",
+                self.sensitive_number_of_records.to_string(),
+            ),
+            (
+                "synthetic_number_of_records",
+                self.synthetic_number_of_records.to_string(),
+            ),
+            (
+                "record_expansion_percentage",
+                self.record_expansion_percentage.to_string(),
+            ),
+            ("leakage_count", self.total_leakage_count().to_string()),
+            (
+                "number_of_suppressed_combinations",
+                self.number_of_suppressed_combinations.to_string(),
+            ),
+            (
+                "percentage_of_suppressed_combinations",
+                self.percentage_of_suppressed_combinations.to_string(),
+            ),
+            (
+                "number_of_fabricated_combinations",
+                self.number_of_fabricated_combinations.to_string(),
+            ),
+            (
+                "percentage_of_fabricated_combinations",
+                self.percentage_of_fabricated_combinations.to_string(),
+            ),
+            (
+                "combinations_count_mean_abs_error",
+                self.combinations_count_mean_abs_error.to_string(),
+            ),
+            (
+                "mean_proportional_error",
+                self.mean_proportional_error.to_string(),
+            ),
+        ];
+
+        file.write_all(format!("metric{}value\n", summary_delimiter).as_bytes())?;
+        for (metric, value) in metrics.iter() {
+            file.write_all(format!("{}{}{}\n", metric, summary_delimiter, value).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the metrics grouped by combination length to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `metrics_by_length_path` - File path to be written
+    /// * `metrics_by_length_delimiter` - Delimiter to use when writing to `metrics_by_length_path`
+    pub fn write_metrics_by_length(
+        &self,
+        metrics_by_length_path: &str,
+        metrics_by_length_delimiter: char,
+    ) -> Result<(), Error> {
+        info!("writing file: {}", metrics_by_length_path);
+
+        let mut file = BufWriter::new(std::fs::File::create(metrics_by_length_path)?);
+
+        file.write_all(
+            format!(
+                "combo_length{}leakage_count{}leakage_percentage{}mean_abs_error\n",
+                metrics_by_length_delimiter,
+                metrics_by_length_delimiter,
+                metrics_by_length_delimiter,
+            )
+            .as_bytes(),
+        )?;
+        for length in self
+            .leakage_count_by_len
+            .keys()
+            .chain(self.combinations_count_mean_abs_error_by_len.keys())
+            .chain(self.preservation_by_length.keys())
+            .unique()
+            .sorted()
+        {
+            file.write_all(
+                format!(
+                    "{}{}{}{}{}{}{}\n",
+                    length,
+                    metrics_by_length_delimiter,
+                    self.leakage_count_by_len.get(length).unwrap_or(&0),
+                    metrics_by_length_delimiter,
+                    self.leakage_percentage_by_len.get(length).unwrap_or(&0.0),
+                    metrics_by_length_delimiter,
+                    self.combinations_count_mean_abs_error_by_len
+                        .get(length)
+                        .unwrap_or(&0.0),
+                )
+                .as_bytes(),
+            )?
+        }
+        Ok(())
+    }
+}
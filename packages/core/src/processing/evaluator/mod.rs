@@ -1,19 +1,26 @@
 mod data_evaluator;
+mod evaluation_report;
 mod preservation_bucket;
 mod preservation_by_count;
 mod preservation_by_length;
 mod rare_combinations_comparison_data;
 mod typedefs;
+mod workload_accuracy;
+
+/// Module to run privacy attacks against the synthetic data
+pub mod privacy;
 
 #[cfg(feature = "pyo3")]
 mod register_pyo3;
 
 pub use data_evaluator::*;
+pub use evaluation_report::*;
 pub use preservation_bucket::*;
 pub use preservation_by_count::*;
 pub use preservation_by_length::*;
 pub use rare_combinations_comparison_data::*;
 pub use typedefs::*;
+pub use workload_accuracy::*;
 
 #[cfg(feature = "pyo3")]
 pub use register_pyo3::*;
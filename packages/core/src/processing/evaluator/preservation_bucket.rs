@@ -1,3 +1,5 @@
+use serde::{ser::SerializeStruct, Serialize};
+
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 
@@ -92,3 +94,26 @@ impl PreservationBucket {
         }
     }
 }
+
+impl Serialize for PreservationBucket {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // expose the means, which are what reports actually look at
+        let mut state = serializer.serialize_struct("PreservationBucket", 5)?;
+
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("mean_combination_count", &self.get_mean_combination_count())?;
+        state.serialize_field(
+            "mean_combination_length",
+            &self.get_mean_combination_length(),
+        )?;
+        state.serialize_field("mean_preservation", &self.get_mean_preservation())?;
+        state.serialize_field(
+            "mean_proportional_error",
+            &self.get_mean_proportional_error(),
+        )?;
+        state.end()
+    }
+}
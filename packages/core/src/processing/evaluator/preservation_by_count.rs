@@ -1,5 +1,6 @@
 use super::{preservation_bucket::PreservationBucket, typedefs::PreservationBucketsMap};
 use itertools::Itertools;
+use serde::Serialize;
 use std::{
     io::{Error, Write},
     ops::{Deref, DerefMut},
@@ -17,6 +18,8 @@ const INITIAL_BIN: usize = 10;
 const BIN_RATIO: usize = 2;
 
 #[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Serialize)]
+#[serde(transparent)]
 /// Wrapping struct mapping the max value allowed in the bucket
 /// to its correspondent PreservationBucket.
 /// In this context a PreservationBucket stores the preservation information
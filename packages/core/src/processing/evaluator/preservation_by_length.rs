@@ -1,5 +1,6 @@
 use super::{preservation_bucket::PreservationBucket, typedefs::PreservationBucketsMap};
 use itertools::Itertools;
+use serde::Serialize;
 use std::{
     io::{Error, Write},
     ops::{Deref, DerefMut},
@@ -11,6 +12,8 @@ use pyo3::prelude::*;
 use crate::processing::aggregator::{AggregatesCountMap, ValueCombination};
 
 #[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Serialize)]
+#[serde(transparent)]
 /// Wrapping to store the preservation buckets grouped
 /// by length
 pub struct PreservationByLengthBuckets {
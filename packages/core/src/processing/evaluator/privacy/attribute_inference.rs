@@ -0,0 +1,141 @@
+use fnv::FnvHashMap;
+use serde::Serialize;
+
+use super::typedefs::PrivacyRecord;
+
+#[derive(Serialize, Debug, Clone)]
+/// Result of an attribute inference attack on a target column.
+/// The attacker knows the quasi-identifier values of a record and predicts
+/// its target value as the most common one among the synthetic records
+/// sharing the same quasi-identifier values
+pub struct AttributeInference {
+    /// Name of the column the attacker tries to infer
+    pub target_column: String,
+    /// Number of sensitive records with a value for the target column
+    pub number_of_attacked_records: usize,
+    /// Number of attacked records whose quasi-identifiers match some synthetic record
+    pub number_of_matched_records: usize,
+    /// Percentage of attacked records whose quasi-identifiers match some synthetic record
+    pub match_percentage: f64,
+    /// Percentage of matched records whose target value was correctly inferred
+    pub success_percentage: f64,
+    /// Percentage of matched records whose target value is the most common
+    /// one on the synthetic data (success without using the quasi-identifiers)
+    pub baseline_success_percentage: f64,
+    /// Success percentage of the same attack on the holdout records, if provided
+    /// (the gap to `success_percentage` is what the attacker learns from the training records)
+    pub holdout_success_percentage: Option<f64>,
+}
+
+/// Outcome of attacking a set of records
+pub(super) struct AttributeInferenceOutcome {
+    pub number_of_attacked_records: usize,
+    pub number_of_matched_records: usize,
+    pub number_of_successes: usize,
+    pub number_of_baseline_successes: usize,
+}
+
+impl AttributeInferenceOutcome {
+    #[inline]
+    pub fn calc_percentage(&self, count: usize) -> f64 {
+        if self.number_of_matched_records > 0 {
+            (count as f64) * 100.0 / (self.number_of_matched_records as f64)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Attribute inference attack built from the synthetic records
+pub(super) struct AttributeInferenceAttack<'data> {
+    quasi_identifiers: &'data [usize],
+    target: usize,
+    prediction_by_quasi_identifiers: FnvHashMap<PrivacyRecord<'data>, &'data str>,
+    baseline_prediction: Option<&'data str>,
+}
+
+impl<'data> AttributeInferenceAttack<'data> {
+    /// Returns a new AttributeInferenceAttack
+    /// # Arguments
+    /// * `synthetic_records` - Synthetic records available to the attacker
+    /// * `quasi_identifiers` - Column indexes known by the attacker
+    /// * `target` - Column index the attacker tries to infer
+    pub fn new(
+        synthetic_records: &[PrivacyRecord<'data>],
+        quasi_identifiers: &'data [usize],
+        target: usize,
+    ) -> AttributeInferenceAttack<'data> {
+        let mut counts_by_quasi_identifiers: FnvHashMap<
+            PrivacyRecord<'data>,
+            FnvHashMap<&'data str, usize>,
+        > = FnvHashMap::default();
+        let mut baseline_counts: FnvHashMap<&'data str, usize> = FnvHashMap::default();
+
+        for record in synthetic_records.iter() {
+            if let Some(target_value) = record[target] {
+                *counts_by_quasi_identifiers
+                    .entry(Self::project(record, quasi_identifiers))
+                    .or_default()
+                    .entry(target_value)
+                    .or_default() += 1;
+                *baseline_counts.entry(target_value).or_default() += 1;
+            }
+        }
+
+        AttributeInferenceAttack {
+            quasi_identifiers,
+            target,
+            prediction_by_quasi_identifiers: counts_by_quasi_identifiers
+                .into_iter()
+                .filter_map(|(key, counts)| Self::most_common(&counts).map(|v| (key, v)))
+                .collect(),
+            baseline_prediction: Self::most_common(&baseline_counts),
+        }
+    }
+
+    /// Attacks every record with a value for the target column
+    /// # Arguments
+    /// * `records` - Records to be attacked
+    pub fn attack(&self, records: &[PrivacyRecord]) -> AttributeInferenceOutcome {
+        let mut outcome = AttributeInferenceOutcome {
+            number_of_attacked_records: 0,
+            number_of_matched_records: 0,
+            number_of_successes: 0,
+            number_of_baseline_successes: 0,
+        };
+
+        for record in records.iter() {
+            if let Some(target_value) = record[self.target] {
+                outcome.number_of_attacked_records += 1;
+
+                if let Some(prediction) = self
+                    .prediction_by_quasi_identifiers
+                    .get(&Self::project(record, self.quasi_identifiers))
+                {
+                    outcome.number_of_matched_records += 1;
+                    if *prediction == target_value {
+                        outcome.number_of_successes += 1;
+                    }
+                    if self.baseline_prediction == Some(target_value) {
+                        outcome.number_of_baseline_successes += 1;
+                    }
+                }
+            }
+        }
+        outcome
+    }
+
+    #[inline]
+    fn project<'value>(record: &[Option<&'value str>], columns: &[usize]) -> PrivacyRecord<'value> {
+        columns.iter().map(|column| record[*column]).collect()
+    }
+
+    /// Most common value (ties are broken by the smallest value, so the attack is deterministic)
+    #[inline]
+    fn most_common(counts: &FnvHashMap<&'data str, usize>) -> Option<&'data str> {
+        counts
+            .iter()
+            .max_by(|(v1, c1), (v2, c2)| c1.cmp(c2).then_with(|| v2.cmp(v1)))
+            .map(|(value, _)| *value)
+    }
+}
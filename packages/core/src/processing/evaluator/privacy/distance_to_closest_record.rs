@@ -0,0 +1,139 @@
+use serde::Serialize;
+
+use super::typedefs::PrivacyRecord;
+
+#[derive(Serialize, Debug, Clone)]
+/// Distribution of the distances from the records of one dataset
+/// to their closest records on another dataset.
+/// The distance between two records is the number of columns whose values differ
+/// (an empty value only matches another empty value)
+pub struct DistanceToClosestRecord {
+    /// Number of records the distances were calculated for
+    pub number_of_records: usize,
+    /// Number of compared columns (maximum possible distance)
+    pub number_of_columns: usize,
+    /// Number of records with an identical record on the other dataset (distance 0)
+    pub number_of_exact_matches: usize,
+    /// Percentage of records with an identical record on the other dataset
+    pub exact_match_percentage: f64,
+    /// Mean distance divided by the number of columns (between 0 and 1)
+    pub mean_normalized_distance: f64,
+    /// 5th percentile of the distances
+    pub percentile_5: usize,
+    /// Median of the distances
+    pub median: usize,
+    /// Number of records by distance (the vector index is the distance)
+    pub count_by_distance: Vec<usize>,
+}
+
+impl DistanceToClosestRecord {
+    /// Returns a new DistanceToClosestRecord summarizing the distances
+    /// # Arguments
+    /// * `distances` - Distance from each record to its closest record
+    /// * `number_of_columns` - Number of compared columns
+    pub fn new(distances: &[usize], number_of_columns: usize) -> DistanceToClosestRecord {
+        let mut count_by_distance = vec![0; number_of_columns + 1];
+
+        for distance in distances.iter() {
+            count_by_distance[*distance] += 1;
+        }
+
+        let number_of_records = distances.len();
+        let number_of_exact_matches = count_by_distance[0];
+        let (exact_match_percentage, mean_normalized_distance) = if number_of_records > 0 {
+            (
+                (number_of_exact_matches as f64) * 100.0 / (number_of_records as f64),
+                (distances.iter().sum::<usize>() as f64)
+                    / ((number_of_records * number_of_columns.max(1)) as f64),
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        DistanceToClosestRecord {
+            number_of_records,
+            number_of_columns,
+            number_of_exact_matches,
+            exact_match_percentage,
+            mean_normalized_distance,
+            percentile_5: DistanceToClosestRecord::calc_percentile(&count_by_distance, 5),
+            median: DistanceToClosestRecord::calc_percentile(&count_by_distance, 50),
+            count_by_distance,
+        }
+    }
+
+    /// Calculates the distance from each record in `records` to its
+    /// closest record in `other_records`. If `other_records` is empty,
+    /// the distance will be the number of columns
+    /// # Arguments
+    /// * `records` - Records to calculate the distances for
+    /// * `other_records` - Records to search for the closest ones
+    /// * `number_of_columns` - Number of compared columns
+    pub fn calc_distances(
+        records: &[PrivacyRecord],
+        other_records: &[PrivacyRecord],
+        number_of_columns: usize,
+    ) -> Vec<usize> {
+        records
+            .iter()
+            .map(|record| {
+                let mut closest = number_of_columns;
+
+                for other_record in other_records.iter() {
+                    if closest == 0 {
+                        break;
+                    }
+                    closest = DistanceToClosestRecord::calc_bounded_distance(
+                        record,
+                        other_record,
+                        closest,
+                    );
+                }
+                closest
+            })
+            .collect()
+    }
+
+    /// Number of columns whose values differ, stopping the count at `bound`
+    #[inline]
+    fn calc_bounded_distance(
+        record: &PrivacyRecord,
+        other_record: &PrivacyRecord,
+        bound: usize,
+    ) -> usize {
+        let mut distance = 0;
+
+        for (value, other_value) in record.iter().zip(other_record.iter()) {
+            if value != other_value {
+                distance += 1;
+                if distance >= bound {
+                    break;
+                }
+            }
+        }
+        distance
+    }
+
+    /// Nearest-rank percentile from the counts by distance
+    #[inline]
+    fn calc_percentile(count_by_distance: &[usize], percentage: usize) -> usize {
+        let number_of_records: usize = count_by_distance.iter().sum();
+
+        if number_of_records == 0 {
+            return 0;
+        }
+
+        let rank = ((percentage * number_of_records) as f64 / 100.0)
+            .ceil()
+            .max(1.0) as usize;
+        let mut cumulative_count = 0;
+
+        for (distance, count) in count_by_distance.iter().enumerate() {
+            cumulative_count += count;
+            if cumulative_count >= rank {
+                return distance;
+            }
+        }
+        count_by_distance.len() - 1
+    }
+}
@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+use super::distance_to_closest_record::DistanceToClosestRecord;
+
+#[derive(Serialize, Debug, Clone)]
+/// Result of a distance based membership inference attack.
+/// The attacker flags a record as part of the data used for synthesis
+/// when it is close to some synthetic record. Records used for synthesis
+/// (training) and records left out of it (holdout) are attacked, so the attack
+/// can only succeed if synthetic records are closer to training records
+pub struct MembershipInference {
+    /// Distances from each training record to its closest synthetic record
+    pub training_dcr: DistanceToClosestRecord,
+    /// Distances from each holdout record to its closest synthetic record
+    pub holdout_dcr: DistanceToClosestRecord,
+    /// Area under the ROC curve of the attack
+    /// (0.5 means the attack is no better than random guessing)
+    pub auc: f64,
+    /// Maximum difference between the true positive rate and the false positive rate
+    /// across all the distance thresholds (0 means the attacker has no advantage)
+    pub advantage: f64,
+}
+
+impl MembershipInference {
+    /// Returns a new MembershipInference scoring the attack
+    /// # Arguments
+    /// * `training_dcr` - Distances from each training record to its closest synthetic record
+    /// * `holdout_dcr` - Distances from each holdout record to its closest synthetic record
+    pub fn new(
+        training_dcr: DistanceToClosestRecord,
+        holdout_dcr: DistanceToClosestRecord,
+    ) -> MembershipInference {
+        let (auc, advantage) = MembershipInference::calc_auc_and_advantage(
+            &training_dcr.count_by_distance,
+            &holdout_dcr.count_by_distance,
+        );
+
+        MembershipInference {
+            training_dcr,
+            holdout_dcr,
+            auc,
+            advantage,
+        }
+    }
+
+    #[inline]
+    fn calc_auc_and_advantage(
+        training_count_by_distance: &[usize],
+        holdout_count_by_distance: &[usize],
+    ) -> (f64, f64) {
+        let n_training: usize = training_count_by_distance.iter().sum();
+        let n_holdout: usize = holdout_count_by_distance.iter().sum();
+
+        if n_training == 0 || n_holdout == 0 {
+            return (0.5, 0.0);
+        }
+
+        let n_training_f64 = n_training as f64;
+        let n_holdout_f64 = n_holdout as f64;
+        let mut auc = 0.0;
+        let mut advantage: f64 = 0.0;
+        let mut training_at_most = 0;
+        let mut holdout_at_most = 0;
+
+        for (training_count, holdout_count) in training_count_by_distance
+            .iter()
+            .zip(holdout_count_by_distance.iter())
+        {
+            // training records at this distance are ranked above every holdout record
+            // farther away, and tie with the ones at the same distance
+            let holdout_farther = n_holdout - holdout_at_most - holdout_count;
+
+            auc += (*training_count as f64)
+                * ((holdout_farther as f64) + 0.5 * (*holdout_count as f64));
+
+            training_at_most += training_count;
+            holdout_at_most += holdout_count;
+            advantage = advantage.max(
+                (training_at_most as f64) / n_training_f64
+                    - (holdout_at_most as f64) / n_holdout_f64,
+            );
+        }
+        (auc / (n_training_f64 * n_holdout_f64), advantage)
+    }
+}
@@ -0,0 +1,13 @@
+mod attribute_inference;
+mod distance_to_closest_record;
+mod membership_inference;
+mod privacy_evaluation_report;
+mod privacy_evaluator;
+mod typedefs;
+
+pub use attribute_inference::*;
+pub use distance_to_closest_record::*;
+pub use membership_inference::*;
+pub use privacy_evaluation_report::*;
+pub use privacy_evaluator::*;
+pub use typedefs::*;
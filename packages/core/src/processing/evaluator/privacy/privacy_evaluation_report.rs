@@ -0,0 +1,173 @@
+use log::info;
+use serde::Serialize;
+use std::io::{BufWriter, Error, Write};
+
+use super::{
+    attribute_inference::AttributeInference, distance_to_closest_record::DistanceToClosestRecord,
+    membership_inference::MembershipInference,
+};
+use crate::utils::time::ElapsedDurationLogger;
+
+#[derive(Serialize)]
+/// Results of the privacy attacks run against the synthetic data,
+/// gathered so they can be exported and reviewed together
+pub struct PrivacyEvaluationReport {
+    /// Number of compared columns
+    pub number_of_columns: usize,
+    /// Number of records on the sensitive data (used for synthesis)
+    pub sensitive_number_of_records: usize,
+    /// Number of records on the synthetic data
+    pub synthetic_number_of_records: usize,
+    /// Number of records on the holdout data (left out of synthesis), if provided
+    pub holdout_number_of_records: Option<usize>,
+    /// Columns known by the attacker on the attribute inference attacks
+    pub quasi_identifiers: Vec<String>,
+    /// Distances from each synthetic record to its closest sensitive record
+    pub synthetic_dcr: DistanceToClosestRecord,
+    /// Membership inference attack results (only when holdout data is provided)
+    pub membership_inference: Option<MembershipInference>,
+    /// Attribute inference attack results, one per target column
+    pub attribute_inference: Vec<AttributeInference>,
+}
+
+impl PrivacyEvaluationReport {
+    /// Serializes the whole report to a json file
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    pub fn write_to_json(&self, file_path: &str) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write privacy report to json");
+
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(std::fs::File::create(file_path)?),
+            &self,
+        )?)
+    }
+
+    /// Writes the single valued metrics to the file system in a csv/tsv like format
+    /// (one `metric`/`value` pair per line)
+    /// # Arguments:
+    /// * `summary_path` - File path to be written
+    /// * `summary_delimiter` - Delimiter to use when writing to `summary_path`
+    pub fn write_summary(&self, summary_path: &str, summary_delimiter: char) -> Result<(), Error> {
+        info!("writing file: {}", summary_path);
+
+        let mut file = BufWriter::new(std::fs::File::create(summary_path)?);
+        let mut metrics: Vec<(&str, String)> = vec![
+            ("number_of_columns", self.number_of_columns.to_string()),
+            (
+                "sensitive_number_of_records",
+                self.sensitive_number_of_records.to_string(),
+            ),
+            (
+                "synthetic_number_of_records",
+                self.synthetic_number_of_records.to_string(),
+            ),
+            (
+                "synthetic_number_of_exact_matches",
+                self.synthetic_dcr.number_of_exact_matches.to_string(),
+            ),
+            (
+                "synthetic_exact_match_percentage",
+                self.synthetic_dcr.exact_match_percentage.to_string(),
+            ),
+            (
+                "synthetic_dcr_percentile_5",
+                self.synthetic_dcr.percentile_5.to_string(),
+            ),
+            (
+                "synthetic_dcr_median",
+                self.synthetic_dcr.median.to_string(),
+            ),
+            (
+                "synthetic_dcr_mean_normalized",
+                self.synthetic_dcr.mean_normalized_distance.to_string(),
+            ),
+        ];
+
+        if let Some(membership_inference) = &self.membership_inference {
+            metrics.extend([
+                (
+                    "holdout_number_of_records",
+                    membership_inference
+                        .holdout_dcr
+                        .number_of_records
+                        .to_string(),
+                ),
+                (
+                    "membership_inference_auc",
+                    membership_inference.auc.to_string(),
+                ),
+                (
+                    "membership_inference_advantage",
+                    membership_inference.advantage.to_string(),
+                ),
+                (
+                    "training_exact_match_percentage",
+                    membership_inference
+                        .training_dcr
+                        .exact_match_percentage
+                        .to_string(),
+                ),
+                (
+                    "holdout_exact_match_percentage",
+                    membership_inference
+                        .holdout_dcr
+                        .exact_match_percentage
+                        .to_string(),
+                ),
+            ]);
+        }
+
+        file.write_all(format!("metric{}value\n", summary_delimiter).as_bytes())?;
+        for (metric, value) in metrics.iter() {
+            file.write_all(format!("{}{}{}\n", metric, summary_delimiter, value).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the attribute inference results to the file system
+    /// in a csv/tsv like format (one target column per line)
+    /// # Arguments:
+    /// * `attribute_inference_path` - File path to be written
+    /// * `attribute_inference_delimiter` - Delimiter to use when writing to `attribute_inference_path`
+    pub fn write_attribute_inference(
+        &self,
+        attribute_inference_path: &str,
+        attribute_inference_delimiter: char,
+    ) -> Result<(), Error> {
+        info!("writing file: {}", attribute_inference_path);
+
+        let mut file = BufWriter::new(std::fs::File::create(attribute_inference_path)?);
+        let d = attribute_inference_delimiter;
+
+        file.write_all(
+            format!(
+                "target_column{d}number_of_attacked_records{d}number_of_matched_records{d}match_percentage{d}success_percentage{d}baseline_success_percentage{d}holdout_success_percentage\n",
+                d = d
+            )
+            .as_bytes(),
+        )?;
+        for attribute_inference in self.attribute_inference.iter() {
+            file.write_all(
+                format!(
+                    "{}{d}{}{d}{}{d}{}{d}{}{d}{}{d}{}\n",
+                    attribute_inference.target_column,
+                    attribute_inference.number_of_attacked_records,
+                    attribute_inference.number_of_matched_records,
+                    attribute_inference.match_percentage,
+                    attribute_inference.success_percentage,
+                    attribute_inference.baseline_success_percentage,
+                    attribute_inference
+                        .holdout_success_percentage
+                        .map(|p| p.to_string())
+                        .unwrap_or_default(),
+                    d = d
+                )
+                .as_bytes(),
+            )?
+        }
+        Ok(())
+    }
+}
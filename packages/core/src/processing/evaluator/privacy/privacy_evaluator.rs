@@ -0,0 +1,266 @@
+use fnv::FnvHashMap;
+use log::warn;
+
+use super::{
+    attribute_inference::{AttributeInference, AttributeInferenceAttack},
+    distance_to_closest_record::DistanceToClosestRecord,
+    membership_inference::MembershipInference,
+    privacy_evaluation_report::PrivacyEvaluationReport,
+    typedefs::PrivacyRecord,
+};
+use crate::{
+    data_block::{DataBlock, DataBlockHeadersSlice},
+    processing::generator::GeneratedData,
+    utils::{strings::transform_for_insensitive_cmp, time::ElapsedDurationLogger},
+};
+
+/// Runs privacy attacks against the synthetic data,
+/// using the sensitive data as ground truth
+pub struct PrivacyEvaluator {
+    quasi_identifiers: Vec<String>,
+    target_columns: Vec<String>,
+}
+
+impl PrivacyEvaluator {
+    /// Returns a new PrivacyEvaluator
+    /// # Arguments
+    /// * `quasi_identifiers` - Columns known by the attacker on the attribute inference attacks
+    /// * `target_columns` - Columns the attacker tries to infer (if empty, every column that is not a quasi-identifier)
+    #[inline]
+    pub fn new(quasi_identifiers: &[String], target_columns: &[String]) -> PrivacyEvaluator {
+        PrivacyEvaluator {
+            quasi_identifiers: quasi_identifiers.to_vec(),
+            target_columns: target_columns.to_vec(),
+        }
+    }
+
+    /// Runs the privacy attacks and reports their results.
+    /// The generated data is matched with the sensitive data by header name,
+    /// so it should be evaluated before numeric bins are mapped back to values
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data used for synthesis
+    /// * `holdout_data_block` - Sensitive records left out of synthesis (enables the membership inference attack)
+    /// * `generated_data` - Synthetic data generated from `sensitive_data_block`
+    /// * `empty_value` - Value used for empty values on the generated data
+    pub fn calc_privacy_evaluation_report(
+        &self,
+        sensitive_data_block: &DataBlock,
+        holdout_data_block: Option<&DataBlock>,
+        generated_data: &GeneratedData,
+        empty_value: &str,
+    ) -> Result<PrivacyEvaluationReport, String> {
+        let _duration_logger = ElapsedDurationLogger::new("privacy evaluation");
+        let headers = &sensitive_data_block.headers;
+        let number_of_columns = headers.len();
+        let quasi_identifiers = self.find_columns(headers, &self.quasi_identifiers)?;
+        let targets = if self.target_columns.is_empty() {
+            (0..number_of_columns)
+                .filter(|column_index| !quasi_identifiers.contains(column_index))
+                .collect()
+        } else {
+            self.find_columns(headers, &self.target_columns)?
+        };
+
+        if let Some(column_index) = targets.iter().find(|t| quasi_identifiers.contains(t)) {
+            return Err(format!(
+                "column \"{}\" cannot be both a quasi-identifier and a target column",
+                headers[*column_index]
+            ));
+        }
+
+        let sensitive_records = Self::data_block_records(sensitive_data_block, headers);
+        let holdout_records =
+            holdout_data_block.map(|data_block| Self::data_block_records(data_block, headers));
+        let synthetic_records = Self::generated_data_records(generated_data, headers, empty_value)?;
+
+        let synthetic_dcr = DistanceToClosestRecord::new(
+            &DistanceToClosestRecord::calc_distances(
+                &synthetic_records,
+                &sensitive_records,
+                number_of_columns,
+            ),
+            number_of_columns,
+        );
+        let membership_inference = holdout_records.as_ref().map(|holdout_records| {
+            MembershipInference::new(
+                DistanceToClosestRecord::new(
+                    &DistanceToClosestRecord::calc_distances(
+                        &sensitive_records,
+                        &synthetic_records,
+                        number_of_columns,
+                    ),
+                    number_of_columns,
+                ),
+                DistanceToClosestRecord::new(
+                    &DistanceToClosestRecord::calc_distances(
+                        holdout_records,
+                        &synthetic_records,
+                        number_of_columns,
+                    ),
+                    number_of_columns,
+                ),
+            )
+        });
+        let attribute_inference = if quasi_identifiers.is_empty() {
+            Vec::default()
+        } else {
+            targets
+                .iter()
+                .map(|target| {
+                    let attack = AttributeInferenceAttack::new(
+                        &synthetic_records,
+                        &quasi_identifiers,
+                        *target,
+                    );
+                    let outcome = attack.attack(&sensitive_records);
+
+                    AttributeInference {
+                        target_column: (*headers[*target]).clone(),
+                        number_of_attacked_records: outcome.number_of_attacked_records,
+                        number_of_matched_records: outcome.number_of_matched_records,
+                        match_percentage: if outcome.number_of_attacked_records > 0 {
+                            (outcome.number_of_matched_records as f64) * 100.0
+                                / (outcome.number_of_attacked_records as f64)
+                        } else {
+                            0.0
+                        },
+                        success_percentage: outcome.calc_percentage(outcome.number_of_successes),
+                        baseline_success_percentage: outcome
+                            .calc_percentage(outcome.number_of_baseline_successes),
+                        holdout_success_percentage: holdout_records.as_ref().map(
+                            |holdout_records| {
+                                let holdout_outcome = attack.attack(holdout_records);
+
+                                holdout_outcome.calc_percentage(holdout_outcome.number_of_successes)
+                            },
+                        ),
+                    }
+                })
+                .collect()
+        };
+
+        Ok(PrivacyEvaluationReport {
+            number_of_columns,
+            sensitive_number_of_records: sensitive_records.len(),
+            synthetic_number_of_records: synthetic_records.len(),
+            holdout_number_of_records: holdout_records.as_ref().map(|records| records.len()),
+            quasi_identifiers: quasi_identifiers
+                .iter()
+                .map(|column_index| (*headers[*column_index]).clone())
+                .collect(),
+            synthetic_dcr,
+            membership_inference,
+            attribute_inference,
+        })
+    }
+
+    #[inline]
+    fn find_columns(
+        &self,
+        headers: &DataBlockHeadersSlice,
+        columns: &[String],
+    ) -> Result<Vec<usize>, String> {
+        let column_index_by_header: FnvHashMap<String, usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(column_index, header)| (transform_for_insensitive_cmp(header), column_index))
+            .collect();
+
+        columns
+            .iter()
+            .map(|column| {
+                column_index_by_header
+                    .get(&transform_for_insensitive_cmp(column))
+                    .cloned()
+                    .ok_or_else(|| format!("column \"{}\" not found on the sensitive data", column))
+            })
+            .collect()
+    }
+
+    /// Aligns the data block records to `headers`, ignoring columns not in `headers`
+    #[inline]
+    fn data_block_records<'data>(
+        data_block: &'data DataBlock,
+        headers: &DataBlockHeadersSlice,
+    ) -> Vec<PrivacyRecord<'data>> {
+        let columns = Self::map_columns(&data_block.headers, headers);
+
+        data_block
+            .records
+            .iter()
+            .map(|record| {
+                let mut values = vec![None; headers.len()];
+
+                for value in record.values.iter() {
+                    if let Some(column_index) = columns[value.column_index] {
+                        values[column_index] = Some(value.value.as_str());
+                    }
+                }
+                values
+            })
+            .collect()
+    }
+
+    /// Aligns the generated records to `headers`, ignoring columns not in `headers`
+    #[inline]
+    fn generated_data_records<'data>(
+        generated_data: &'data GeneratedData,
+        headers: &DataBlockHeadersSlice,
+        empty_value: &str,
+    ) -> Result<Vec<PrivacyRecord<'data>>, String> {
+        let generated_headers = match generated_data.synthetic_data.first() {
+            Some(generated_headers) => generated_headers,
+            None => return Ok(Vec::default()),
+        };
+        let columns = Self::map_columns(generated_headers, headers);
+        let number_of_mapped_columns = columns.iter().filter(|c| c.is_some()).count();
+
+        if number_of_mapped_columns == 0 {
+            return Err(
+                "the synthetic data has no column in common with the sensitive data".to_owned(),
+            );
+        }
+        if number_of_mapped_columns < headers.len() {
+            warn!(
+                "{} sensitive columns are missing on the synthetic data and will be compared as empty",
+                headers.len() - number_of_mapped_columns
+            );
+        }
+
+        Ok(generated_data
+            .synthetic_data
+            .iter()
+            .skip(1)
+            .map(|record| {
+                let mut values = vec![None; headers.len()];
+
+                for (value, column) in record.iter().zip(columns.iter()) {
+                    if let Some(column_index) = column {
+                        if !value.is_empty() && value.as_str() != empty_value {
+                            values[*column_index] = Some(value.as_str());
+                        }
+                    }
+                }
+                values
+            })
+            .collect())
+    }
+
+    /// Maps each header in `from_headers` to its index in `to_headers`
+    #[inline]
+    fn map_columns(
+        from_headers: &DataBlockHeadersSlice,
+        to_headers: &DataBlockHeadersSlice,
+    ) -> Vec<Option<usize>> {
+        let column_index_by_header: FnvHashMap<&str, usize> = to_headers
+            .iter()
+            .enumerate()
+            .map(|(column_index, header)| (header.as_str(), column_index))
+            .collect();
+
+        from_headers
+            .iter()
+            .map(|header| column_index_by_header.get(header.as_str()).cloned())
+            .collect()
+    }
+}
@@ -0,0 +1,2 @@
+/// Record values aligned to the sensitive headers (`None` for empty values)
+pub type PrivacyRecord<'data> = Vec<Option<&'data str>>;
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::io::{Error, Write};
 
 #[cfg(feature = "pyo3")]
@@ -9,7 +10,7 @@ use crate::{
 };
 
 #[cfg_attr(feature = "pyo3", pyclass)]
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 /// Represents a single combination comparison
 pub struct CombinationComparison {
     /// Length of the combination
@@ -79,6 +80,8 @@ impl CombinationComparison {
 }
 
 #[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Serialize)]
+#[serde(transparent)]
 /// Computed rare combination comparisons between the synthetic
 /// and sensitive datasets for all records
 pub struct RareCombinationsComparisonData {
@@ -129,7 +132,7 @@ impl RareCombinationsComparisonData {
                     rare_combinations.push(CombinationComparison::new(
                         agg.len(),
                         combination_str.clone(),
-                        *record_index,
+                        record_index,
                         count.count,
                         sensitive_count,
                     ));
@@ -1,5 +1,15 @@
 use super::preservation_bucket::PreservationBucket;
 use fnv::FnvHashMap;
+use std::sync::Arc;
 
 /// Maps a value to its correspondent PreservationBucket
 pub type PreservationBucketsMap = FnvHashMap<usize, PreservationBucket>;
+
+/// Column names of a marginal query (e.g. `["Age", "Region", "Diagnosis"]`)
+pub type WorkloadQuery = Vec<String>;
+
+/// Slice of WorkloadQuery
+pub type WorkloadQuerySlice = [String];
+
+/// Maps the values of a marginal query cell (ordered as the query columns) to its count
+pub type WorkloadCellCountMap = FnvHashMap<Vec<Arc<String>>, usize>;
@@ -0,0 +1,193 @@
+use super::typedefs::{WorkloadCellCountMap, WorkloadQuery};
+use fnv::FnvHashSet;
+use log::info;
+use serde::Serialize;
+use std::{
+    io::{Error, Write},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+#[derive(Serialize, Debug, Clone)]
+/// Accuracy of the synthetic answers to a single marginal query,
+/// compared with the exact sensitive answers for every cell
+pub struct WorkloadQueryAccuracy {
+    /// Columns of the marginal query
+    pub query: WorkloadQuery,
+    /// Number of cells present on the sensitive or synthetic answers
+    pub number_of_cells: usize,
+    /// Sum of the sensitive cell counts
+    pub sensitive_total: usize,
+    /// Sum of the synthetic cell counts
+    pub synthetic_total: usize,
+    /// Total variation distance between the sensitive and synthetic
+    /// cell distributions (between 0 and 1)
+    pub total_variation_distance: f64,
+    /// Maximum `|sensitive_count - synthetic_count|` across all cells
+    pub max_abs_error: usize,
+    /// Mean `|sensitive_count - synthetic_count|` across all cells
+    pub mean_abs_error: f64,
+    /// Mean `|sensitive_count - synthetic_count| / sensitive_count`
+    /// across the cells present on the sensitive answers
+    pub mean_relative_error: f64,
+}
+
+impl WorkloadQueryAccuracy {
+    /// Returns a new WorkloadQueryAccuracy comparing the query answers
+    /// # Arguments
+    /// * `query` - Columns of the marginal query
+    /// * `sensitive_counts` - Exact sensitive count of each cell
+    /// * `synthetic_counts` - Synthetic count of each cell
+    pub fn new(
+        query: WorkloadQuery,
+        sensitive_counts: &WorkloadCellCountMap,
+        synthetic_counts: &WorkloadCellCountMap,
+    ) -> WorkloadQueryAccuracy {
+        let cells: FnvHashSet<&Vec<Arc<String>>> = sensitive_counts
+            .keys()
+            .chain(synthetic_counts.keys())
+            .collect();
+        let sensitive_total: usize = sensitive_counts.values().sum();
+        let synthetic_total: usize = synthetic_counts.values().sum();
+        let proportion = |count: usize, total: usize| {
+            if total > 0 {
+                (count as f64) / (total as f64)
+            } else {
+                0.0
+            }
+        };
+        let mut total_variation_distance = 0.0;
+        let mut max_abs_error = 0;
+        let mut abs_error_sum = 0;
+        let mut relative_error_sum = 0.0;
+
+        for cell in cells.iter() {
+            let sensitive_count = sensitive_counts.get(*cell).cloned().unwrap_or_default();
+            let synthetic_count = synthetic_counts.get(*cell).cloned().unwrap_or_default();
+            let abs_error = sensitive_count.abs_diff(synthetic_count);
+
+            total_variation_distance += (proportion(sensitive_count, sensitive_total)
+                - proportion(synthetic_count, synthetic_total))
+            .abs();
+            max_abs_error = max_abs_error.max(abs_error);
+            abs_error_sum += abs_error;
+            if sensitive_count > 0 {
+                relative_error_sum += (abs_error as f64) / (sensitive_count as f64);
+            }
+        }
+
+        WorkloadQueryAccuracy {
+            query,
+            number_of_cells: cells.len(),
+            sensitive_total,
+            synthetic_total,
+            total_variation_distance: total_variation_distance / 2.0,
+            max_abs_error,
+            mean_abs_error: proportion(abs_error_sum, cells.len()),
+            mean_relative_error: if sensitive_counts.is_empty() {
+                0.0
+            } else {
+                relative_error_sum / (sensitive_counts.len() as f64)
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+/// Accuracy of each query in a workload of marginal queries
+pub struct WorkloadAccuracy {
+    queries_accuracy: Vec<WorkloadQueryAccuracy>,
+}
+
+impl WorkloadAccuracy {
+    /// Returns a new WorkloadAccuracy
+    /// # Arguments
+    /// * `queries_accuracy` - Accuracy of each query in the workload
+    #[inline]
+    pub fn new(queries_accuracy: Vec<WorkloadQueryAccuracy>) -> WorkloadAccuracy {
+        WorkloadAccuracy { queries_accuracy }
+    }
+
+    /// Mean total variation distance across all the queries
+    pub fn calc_mean_total_variation_distance(&self) -> f64 {
+        if self.queries_accuracy.is_empty() {
+            0.0
+        } else {
+            self.queries_accuracy
+                .iter()
+                .map(|q| q.total_variation_distance)
+                .sum::<f64>()
+                / (self.queries_accuracy.len() as f64)
+        }
+    }
+
+    /// Serializes the workload accuracy to a json file
+    /// # Arguments:
+    /// * `file_path` - File path to be written
+    pub fn write_to_json(&self, file_path: &str) -> Result<(), Error> {
+        info!("writing file: {}", file_path);
+
+        Ok(serde_json::to_writer_pretty(
+            std::io::BufWriter::new(std::fs::File::create(file_path)?),
+            &self,
+        )?)
+    }
+
+    /// Writes the accuracy of each query to the file system in a csv/tsv like format
+    /// # Arguments:
+    /// * `workload_accuracy_path` - File path to be written
+    /// * `workload_accuracy_delimiter` - Delimiter to use when writing to `workload_accuracy_path`
+    /// * `query_delimiter` - Delimiter used to join the query columns
+    pub fn write_workload_accuracy(
+        &self,
+        workload_accuracy_path: &str,
+        workload_accuracy_delimiter: char,
+        query_delimiter: &str,
+    ) -> Result<(), Error> {
+        info!("writing file: {}", workload_accuracy_path);
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(workload_accuracy_path)?);
+        let d = workload_accuracy_delimiter;
+
+        file.write_all(
+            format!(
+                "query{d}number_of_cells{d}sensitive_total{d}synthetic_total{d}total_variation_distance{d}max_abs_error{d}mean_abs_error{d}mean_relative_error\n",
+                d = d
+            )
+            .as_bytes(),
+        )?;
+        for query_accuracy in self.queries_accuracy.iter() {
+            file.write_all(
+                format!(
+                    "{}{d}{}{d}{}{d}{}{d}{}{d}{}{d}{}{d}{}\n",
+                    query_accuracy.query.join(query_delimiter),
+                    query_accuracy.number_of_cells,
+                    query_accuracy.sensitive_total,
+                    query_accuracy.synthetic_total,
+                    query_accuracy.total_variation_distance,
+                    query_accuracy.max_abs_error,
+                    query_accuracy.mean_abs_error,
+                    query_accuracy.mean_relative_error,
+                    d = d
+                )
+                .as_bytes(),
+            )?
+        }
+        Ok(())
+    }
+}
+
+impl Deref for WorkloadAccuracy {
+    type Target = Vec<WorkloadQueryAccuracy>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.queries_accuracy
+    }
+}
+
+impl DerefMut for WorkloadAccuracy {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.queries_accuracy
+    }
+}
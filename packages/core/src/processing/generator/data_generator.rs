@@ -1,18 +1,27 @@
 use super::generated_data::GeneratedData;
+use super::parent_child_generated_data::ParentChildGeneratedData;
 use super::synthesizers::RowSeededSynthesizer;
 use super::synthesizers::SynthesizedRecords;
 use super::synthesizers::UnseededSynthesizer;
 use super::synthesizers::ValueSeededSynthesizer;
 use super::OversamplingParameters;
 use log::info;
+use std::iter;
 use std::sync::Arc;
 
 use crate::data_block::DataBlock;
 use crate::data_block::DataBlockHeaders;
+use crate::data_block::DateTimeMetadataMap;
 use crate::data_block::MultiValueColumnMetadataMap;
+use crate::data_block::NumericBinsMetadataMap;
 use crate::data_block::RawData;
 use crate::processing::aggregator::AggregatedData;
 use crate::processing::generator::synthesizers::AggregateSeededSynthesizer;
+use crate::processing::generator::synthesizers::ConstraintRules;
+use crate::processing::generator::synthesizers::MarginalModelSynthesizer;
+use crate::processing::generator::synthesizers::ParentChildLink;
+use crate::processing::generator::synthesizers::ParentChildSynthesizer;
+use crate::processing::generator::synthesizers::SynthesisConditions;
 use crate::processing::generator::synthesizers::SynthesizerCacheKey;
 use crate::utils::reporting::ReportProgress;
 use crate::utils::reporting::StoppableResult;
@@ -20,14 +29,45 @@ use crate::utils::time::ElapsedDurationLogger;
 
 /// Process a data block and generates new synthetic data
 #[derive(Default)]
-pub struct Generator {}
+pub struct Generator {
+    /// Seed used to initialize the random number generators used for sampling,
+    /// the same seed and inputs will always produce the same synthetic data
+    /// - If `None` a random seed will be used on every generation
+    seed: Option<u64>,
+    /// Rules used to exclude sampled attributes leading to invalid combinations
+    /// (enforced by the row, value and aggregate seeded methods)
+    constraint_rules: Option<Arc<ConstraintRules>>,
+}
 
 impl Generator {
+    /// Returns a new Generator
+    /// # Arguments
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    #[inline]
+    pub fn new(seed: Option<u64>) -> Generator {
+        Generator {
+            seed,
+            constraint_rules: None,
+        }
+    }
+
+    /// Returns the generator enforcing `constraint_rules` while sampling attributes
+    /// # Arguments
+    /// * `constraint_rules` - Rules used to exclude sampled attributes leading to invalid combinations
     #[inline]
+    pub fn with_constraint_rules(mut self, constraint_rules: ConstraintRules) -> Generator {
+        self.constraint_rules = Some(Arc::new(constraint_rules));
+        self
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn build_generated_data(
         &self,
         headers: &DataBlockHeaders,
         multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+        numeric_bins_metadata_map: NumericBinsMetadataMap,
+        date_time_metadata_map: DateTimeMetadataMap,
         number_of_records: usize,
         mut synthesized_records: SynthesizedRecords,
         empty_value: Arc<String>,
@@ -53,7 +93,13 @@ impl Generator {
 
         info!("expansion ratio: {:.4?}", expansion_ratio);
 
-        GeneratedData::new(result, expansion_ratio, multi_value_column_metadata_map)
+        GeneratedData::new(
+            result,
+            expansion_ratio,
+            multi_value_column_metadata_map,
+            numeric_bins_metadata_map,
+            date_time_metadata_map,
+        )
     }
 
     /// Synthesize data using the row seeded method
@@ -72,6 +118,40 @@ impl Generator {
         empty_value: &str,
         progress_reporter: &mut Option<T>,
     ) -> StoppableResult<GeneratedData>
+    where
+        T: ReportProgress,
+    {
+        self.generate_row_seeded_with_conditions(
+            data_block,
+            resolution,
+            cache_max_size,
+            empty_value,
+            None,
+            progress_reporter,
+        )
+    }
+
+    /// Synthesize data using the row seeded method, only generating records
+    /// satisfying the conditions (if any)
+    ///
+    /// Records are seeded from the sensitive rows matching the conditions and
+    /// the consolidation and suppression steps are not performed
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `conditions` - Values required on every record or target number of records per value (if `None` the whole population is synthesized)
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn generate_row_seeded_with_conditions<T>(
+        &self,
+        data_block: &Arc<DataBlock>,
+        resolution: usize,
+        cache_max_size: usize,
+        empty_value: &str,
+        conditions: Option<SynthesisConditions>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<GeneratedData>
     where
         T: ReportProgress,
     {
@@ -85,11 +165,16 @@ impl Generator {
             Arc::new(data_block.calc_attr_rows()),
             resolution,
             cache_max_size,
+            self.seed,
+            conditions,
+            self.constraint_rules.clone(),
         );
 
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
+            data_block.numeric_bins_metadata_map.clone(),
+            data_block.date_time_metadata_map.clone(),
             data_block.number_of_records(),
             synth.run(progress_reporter)?,
             empty_value_arc,
@@ -126,11 +211,14 @@ impl Generator {
             resolution,
             cache_max_size,
             empty_value_arc.clone(),
+            self.seed,
         );
 
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
+            data_block.numeric_bins_metadata_map.clone(),
+            data_block.date_time_metadata_map.clone(),
             data_block.number_of_records(),
             synth.run(progress_reporter)?,
             empty_value_arc,
@@ -170,11 +258,15 @@ impl Generator {
             resolution,
             cache_max_size,
             oversampling_parameters,
+            self.seed,
+            self.constraint_rules.clone(),
         );
 
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
+            data_block.numeric_bins_metadata_map.clone(),
+            data_block.date_time_metadata_map.clone(),
             data_block.number_of_records(),
             synth.run(progress_reporter)?,
             empty_value_arc,
@@ -206,6 +298,47 @@ impl Generator {
         target_number_of_records: Option<usize>,
         progress_reporter: &mut Option<T>,
     ) -> StoppableResult<GeneratedData>
+    where
+        T: ReportProgress,
+    {
+        self.generate_aggregate_seeded_with_conditions(
+            empty_value,
+            aggregated_data,
+            use_synthetic_counts,
+            weight_selection_percentile,
+            aggregate_counts_scale_factor,
+            target_number_of_records,
+            None,
+            progress_reporter,
+        )
+    }
+
+    /// Synthesize data using the aggregate seeded method, only generating records
+    /// satisfying the conditions (if any)
+    ///
+    /// Every record is seeded with the required values before sampling
+    /// the remaining attributes from the aggregates conditioned on them
+    /// # Arguments
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `aggregated_data` - Aggregated data where data should be synthesized from
+    /// * `use_synthetic_counts` - Whether synthetic counts should be used to balance the sampling process or not
+    /// * `weight_selection_percentile` - Percentile used for the weight selection (default of 95 if `None`)
+    /// * `aggregate_counts_scale_factor` - Multiplier for aggregate counts before synthesis. If `None` use raw counts
+    /// * `target_number_of_records` - Total number of records to be synthesized when values are required on every record. If `None` use the aggregate count of the required values
+    /// * `conditions` - Values required on every record or target number of records per value (if `None` the whole population is synthesized)
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_aggregate_seeded_with_conditions<T>(
+        &self,
+        empty_value: &str,
+        aggregated_data: Arc<AggregatedData>,
+        use_synthetic_counts: bool,
+        weight_selection_percentile: Option<usize>,
+        aggregate_counts_scale_factor: Option<f64>,
+        target_number_of_records: Option<usize>,
+        conditions: Option<SynthesisConditions>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<GeneratedData>
     where
         T: ReportProgress,
     {
@@ -221,6 +354,9 @@ impl Generator {
             use_synthetic_counts,
             weight_selection_percentile,
             target_number_of_records,
+            self.seed,
+            conditions,
+            self.constraint_rules.clone(),
         );
 
         Ok(self.build_generated_data(
@@ -228,12 +364,162 @@ impl Generator {
             scaled_aggregated_data
                 .multi_value_column_metadata_map
                 .clone(),
+            scaled_aggregated_data.numeric_bins_metadata_map.clone(),
+            scaled_aggregated_data.date_time_metadata_map.clone(),
             scaled_aggregated_data.number_of_records,
             synth.run(progress_reporter)?,
             empty_value_arc,
         ))
     }
 
+    /// Synthesize data sampling from a graphical model fit to the
+    /// (usually noisy) marginals of the aggregated data
+    /// # Arguments
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `aggregated_data` - Aggregated data where the marginals are taken from
+    /// * `max_clique_size` - Maximum number of columns on each model clique, 1 to 3 (default of 2 if `None`)
+    /// * `target_number_of_records` - Total number of records to be synthesized (defaults to the number of records on the aggregated data)
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn generate_marginal_model<T>(
+        &self,
+        empty_value: &str,
+        aggregated_data: Arc<AggregatedData>,
+        max_clique_size: Option<usize>,
+        target_number_of_records: Option<usize>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<GeneratedData>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("marginal model generation");
+
+        info!("starting marginal model generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let mut synth = MarginalModelSynthesizer::new(
+            aggregated_data.clone(),
+            max_clique_size,
+            target_number_of_records,
+            self.seed,
+        );
+
+        Ok(self.build_generated_data(
+            &aggregated_data.headers,
+            aggregated_data.multi_value_column_metadata_map.clone(),
+            aggregated_data.numeric_bins_metadata_map.clone(),
+            aggregated_data.date_time_metadata_map.clone(),
+            aggregated_data.number_of_records,
+            synth.run(progress_reporter)?,
+            empty_value_arc,
+        ))
+    }
+
+    /// Synthesize a parent table and a child table linked to it. Parents are
+    /// synthesized first using the row seeded method (together with their number
+    /// of children), then the children of each synthetic parent are sampled
+    /// from the child attributes conditioned on the parent attributes
+    ///
+    /// Synthetic parents get sequential keys, referenced by the foreign key of their children
+    /// # Arguments
+    /// * `link` - Parent and child tables and how their records are linked
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `max_children_per_parent` - Maximum number of children of each parent (children over it are ignored)
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn generate_parent_child<T>(
+        &self,
+        link: &ParentChildLink,
+        resolution: usize,
+        cache_max_size: usize,
+        max_children_per_parent: usize,
+        empty_value: &str,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<ParentChildGeneratedData>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("parent/child generation");
+
+        info!("starting parent/child generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let mut synth = ParentChildSynthesizer::new(
+            link,
+            resolution,
+            cache_max_size,
+            max_children_per_parent,
+            self.seed,
+        );
+        let parents_with_children = synth.run(progress_reporter)?;
+        let mut parents = RawData::default();
+        let mut children = RawData::default();
+
+        parents.push(
+            iter::once(Arc::new(link.parent_key.clone()))
+                .chain(synth.parent_headers().iter().cloned())
+                .collect(),
+        );
+        children.push(
+            iter::once(Arc::new(link.foreign_key.clone()))
+                .chain(synth.child_headers().iter().cloned())
+                .collect(),
+        );
+
+        for (parent_index, (synthesized_parent, synthesized_children)) in
+            parents_with_children.iter().enumerate()
+        {
+            let key = Arc::new((parent_index + 1).to_string());
+
+            parents.push(
+                iter::once(key.clone())
+                    .chain(
+                        SynthesizerCacheKey::new(synth.parent_headers().len(), synthesized_parent)
+                            .format_record(&empty_value_arc),
+                    )
+                    .collect(),
+            );
+            children.extend(synthesized_children.iter().map(|synthesized_child| {
+                iter::once(key.clone())
+                    .chain(
+                        SynthesizerCacheKey::new(synth.child_headers().len(), synthesized_child)
+                            .format_record(&empty_value_arc),
+                    )
+                    .collect()
+            }));
+        }
+
+        let parents_expansion_ratio = (parents.len() - 1) as f64 / synth.number_of_parents() as f64;
+        let children_expansion_ratio =
+            (children.len() - 1) as f64 / synth.number_of_children() as f64;
+
+        info!(
+            "expansion ratio: {:.4?} (parents), {:.4?} (children)",
+            parents_expansion_ratio, children_expansion_ratio
+        );
+
+        Ok(ParentChildGeneratedData::new(
+            GeneratedData::new(
+                parents,
+                parents_expansion_ratio,
+                link.parent_data_block
+                    .multi_value_column_metadata_map
+                    .clone(),
+                link.parent_data_block.numeric_bins_metadata_map.clone(),
+                link.parent_data_block.date_time_metadata_map.clone(),
+            ),
+            GeneratedData::new(
+                children,
+                children_expansion_ratio,
+                link.child_data_block
+                    .multi_value_column_metadata_map
+                    .clone(),
+                link.child_data_block.numeric_bins_metadata_map.clone(),
+                link.child_data_block.date_time_metadata_map.clone(),
+            ),
+        ))
+    }
+
     #[inline]
     fn scale_aggregates_if_necessary(
         aggregated_data: Arc<AggregatedData>,
@@ -11,9 +11,11 @@ use crate::data_block::CsvRecord;
 
 use crate::{
     data_block::{
-        CsvIOError, DataBlock, MultiValueColumnMetadataMap, RawData, RawDataMultiValueColumnJoiner,
+        format_bin_edge, format_date_time_label, CsvIOError, DataBlock, DateTimeMetadataMap,
+        DateTimeOutputFormats, MultiValueColumnMetadataMap, NumericBinOutputMode,
+        NumericBinsMetadataMap, RawData, RawDataMultiValueColumnJoiner,
     },
-    utils::time::ElapsedDurationLogger,
+    utils::{random::new_rng, strings::transform_for_insensitive_cmp, time::ElapsedDurationLogger},
 };
 
 #[cfg_attr(feature = "pyo3", pyclass)]
@@ -25,6 +27,10 @@ pub struct GeneratedData {
     pub expansion_ratio: f64,
     /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
     pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Maps a normalized binned numeric header name to its computed bins
+    pub numeric_bins_metadata_map: NumericBinsMetadataMap,
+    /// Maps a normalized date/time header name to the granularity its values were generalized to
+    pub date_time_metadata_map: DateTimeMetadataMap,
 }
 
 impl GeneratedData {
@@ -35,6 +41,8 @@ impl GeneratedData {
             synthetic_data: RawData::default(),
             expansion_ratio: 0.0,
             multi_value_column_metadata_map: MultiValueColumnMetadataMap::default(),
+            numeric_bins_metadata_map: NumericBinsMetadataMap::default(),
+            date_time_metadata_map: DateTimeMetadataMap::default(),
         }
     }
 
@@ -44,19 +52,95 @@ impl GeneratedData {
     /// * `expansion_ratio` - `Synthetic data length / Sensitive data length` (header not included)
     /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name (such as A_a1)
     /// to its corresponding metadata
+    /// * `numeric_bins_metadata_map` - Maps a normalized binned numeric header name to its computed bins
+    /// * `date_time_metadata_map` - Maps a normalized date/time header name to its granularity
     #[inline]
     pub fn new(
         synthetic_data: RawData,
         expansion_ratio: f64,
         multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+        numeric_bins_metadata_map: NumericBinsMetadataMap,
+        date_time_metadata_map: DateTimeMetadataMap,
     ) -> GeneratedData {
         GeneratedData {
             synthetic_data,
             expansion_ratio,
             multi_value_column_metadata_map,
+            numeric_bins_metadata_map,
+            date_time_metadata_map,
+        }
+    }
+
+    /// Maps the bin labels of binned numeric columns back to numeric values,
+    /// so they are written this way to the synthetic output
+    /// # Arguments
+    /// * `output_mode` - How the bin labels should be mapped
+    /// * `seed` - Seed used to sample values inside the bins (if `None` a random seed will be used)
+    pub fn map_numeric_bins(&mut self, output_mode: NumericBinOutputMode, seed: Option<u64>) {
+        if output_mode == NumericBinOutputMode::Label || self.synthetic_data.is_empty() {
+            return;
+        }
+
+        let mut rng = new_rng(seed);
+        let column_bins: Vec<_> = self.synthetic_data[0]
+            .iter()
+            .map(|header| self.numeric_bins_metadata_map.get(header))
+            .collect();
+
+        for record in self.synthetic_data.iter_mut().skip(1) {
+            for (value, bins) in record.iter_mut().zip(column_bins.iter()) {
+                if let Some(bin) = bins.and_then(|b| b.find_bin_by_label(value)) {
+                    *value = Arc::new(format_bin_edge(match output_mode {
+                        NumericBinOutputMode::UniformSample => bin.sample_uniform_value(&mut rng),
+                        _ => bin.representative_value(),
+                    }));
+                }
+            }
         }
     }
 
+    #[inline]
+    fn render_date_time_columns(
+        &self,
+        date_time_output_formats: &DateTimeOutputFormats,
+    ) -> RawData {
+        let output_formats: DateTimeOutputFormats = date_time_output_formats
+            .iter()
+            .map(|(h, format)| (transform_for_insensitive_cmp(h), format.clone()))
+            .collect();
+        let column_formats: Vec<_> = self.synthetic_data[0]
+            .iter()
+            .map(|header| {
+                self.date_time_metadata_map
+                    .get(header)
+                    .zip(output_formats.get(&transform_for_insensitive_cmp(header)))
+            })
+            .collect();
+
+        self.synthetic_data
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                if i == 0 {
+                    // keep headers untouched
+                    return record.clone();
+                }
+                record
+                    .iter()
+                    .zip(column_formats.iter())
+                    .map(|(value, column_format)| {
+                        column_format
+                            .and_then(|(granularity, format)| {
+                                format_date_time_label(value, granularity, format)
+                            })
+                            .map(Arc::new)
+                            .unwrap_or_else(|| value.clone())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     #[inline]
     fn _write_synthetic_data<T: Write>(
         &self,
@@ -65,22 +149,31 @@ impl GeneratedData {
         empty_value: &str,
         join_multi_value_columns: bool,
         long_form: bool,
+        date_time_output_formats: Option<DateTimeOutputFormats>,
     ) -> Result<(), CsvIOError> {
         let mut wtr = WriterBuilder::new()
             .delimiter(delimiter as u8)
             .from_writer(writer);
+        let rendered_synthetic_data;
         let joined_synthetic_data;
 
+        let rendered_data = match date_time_output_formats {
+            Some(formats) if !formats.is_empty() && !self.synthetic_data.is_empty() => {
+                rendered_synthetic_data = self.render_date_time_columns(&formats);
+                &rendered_synthetic_data
+            }
+            _ => &self.synthetic_data,
+        };
         let synthetic_data = if join_multi_value_columns {
             joined_synthetic_data = RawDataMultiValueColumnJoiner::new(
-                &self.synthetic_data,
+                rendered_data,
                 &self.multi_value_column_metadata_map,
                 &Arc::new(empty_value.to_owned()),
             )
             .join();
             &joined_synthetic_data
         } else {
-            &self.synthetic_data
+            rendered_data
         };
 
         // write header and records
@@ -169,6 +262,7 @@ impl GeneratedData {
     /// * `empty_value` - Empty values will be replaced by this
     /// * `join_multi_value_columns` - Whether multi value columns should be joined back together or not
     /// * `long_form` - Pivots column headers and value pairs to key-value row entries.
+    /// * `date_time_output_formats` - Output format by date/time column name (others keep their labels)
     pub fn write_synthetic_data(
         &self,
         path: &str,
@@ -176,6 +270,7 @@ impl GeneratedData {
         empty_value: &str,
         join_multi_value_columns: bool,
         long_form: bool,
+        date_time_output_formats: Option<DateTimeOutputFormats>,
     ) -> Result<(), CsvIOError> {
         let _duration_logger = ElapsedDurationLogger::new("write synthetic data");
 
@@ -191,6 +286,7 @@ impl GeneratedData {
             empty_value,
             join_multi_value_columns,
             long_form,
+            date_time_output_formats,
         )
     }
 
@@ -200,12 +296,14 @@ impl GeneratedData {
     /// * `empty_value` - Empty values will be replaced by this
     /// * `join_multi_value_columns` - Whether multi value columns should be joined back together or not
     /// * `long_form` - Pivots column headers and value pairs to key-value row entries.
+    /// * `date_time_output_formats` - Output format by date/time column name (others keep their labels)
     pub fn synthetic_data_to_string(
         &self,
         delimiter: char,
         empty_value: &str,
         join_multi_value_columns: bool,
         long_form: bool,
+        date_time_output_formats: Option<DateTimeOutputFormats>,
     ) -> Result<String, CsvIOError> {
         let mut csv_data = Vec::default();
 
@@ -215,6 +313,7 @@ impl GeneratedData {
             empty_value,
             join_multi_value_columns,
             long_form,
+            date_time_output_formats,
         )?;
 
         Ok(String::from_utf8_lossy(&csv_data).to_string())
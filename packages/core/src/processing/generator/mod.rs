@@ -1,5 +1,6 @@
 mod data_generator;
 mod generated_data;
+mod parent_child_generated_data;
 mod synthesizers;
 
 #[cfg(feature = "pyo3")]
@@ -7,6 +8,7 @@ mod register_pyo3;
 
 pub use data_generator::*;
 pub use generated_data::*;
+pub use parent_child_generated_data::*;
 pub use synthesizers::*;
 
 #[cfg(feature = "pyo3")]
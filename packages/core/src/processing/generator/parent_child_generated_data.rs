@@ -0,0 +1,22 @@
+use super::generated_data::GeneratedData;
+
+/// Synthetic parent and child tables generated by the Generator.
+/// The first column of the parent table is the synthetic parent key,
+/// referenced by the first column (foreign key) of the child table
+pub struct ParentChildGeneratedData {
+    /// Synthetic parent table
+    pub parents: GeneratedData,
+    /// Synthetic child table
+    pub children: GeneratedData,
+}
+
+impl ParentChildGeneratedData {
+    /// Returns a new ParentChildGeneratedData
+    /// # Arguments
+    /// * `parents` - Synthetic parent table
+    /// * `children` - Synthetic child table
+    #[inline]
+    pub fn new(parents: GeneratedData, children: GeneratedData) -> ParentChildGeneratedData {
+        ParentChildGeneratedData { parents, children }
+    }
+}
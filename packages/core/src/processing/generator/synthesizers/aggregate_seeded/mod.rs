@@ -1,13 +1,16 @@
 use itertools::Itertools;
+use log::warn;
 use statrs::statistics::{Data, OrderStatistics};
 use std::sync::Arc;
 
 use crate::{
-    data_block::{DataBlockHeaders, DataBlockValue},
+    data_block::DataBlockValue,
     processing::{
         aggregator::{AggregatedData, ValueCombination},
         generator::synthesizers::{
             consolidate_parameters::ConsolidateParameters,
+            constraint_rules::ConstraintRules,
+            synthesis_conditions::SynthesisConditions,
             traits::{Consolidate, ConsolidateContext, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
@@ -18,6 +21,7 @@ use crate::{
     utils::{
         collections::{sample_weighted, sort_unstable_f64},
         math::calc_percentage,
+        random::{new_rng, SeededRng},
         reporting::{ReportProgress, StoppableResult},
     },
 };
@@ -35,6 +39,8 @@ pub struct AggregateSeededSynthesizer {
     single_attr_counts: AttributeCountMap,
     /// Percentage already completed on the consolidation step
     consolidate_percentage: f64,
+    /// Random number generator used for sampling
+    rng: SeededRng,
 }
 
 impl AggregateSeededSynthesizer {
@@ -46,13 +52,31 @@ impl AggregateSeededSynthesizer {
     /// the sampling process or not
     /// * `target_number_of_records` - Total number of records to be synthesized.
     /// If `None` sample from all available counts
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    /// * `conditions` - If set, only records satisfying the conditions are synthesized (when values are required on every record and `target_number_of_records` is `None`, their aggregate count is used)
+    /// * `constraint_rules` - Rules used to exclude attributes leading to invalid combinations (if `None` any combination is allowed)
     #[inline]
     pub fn new(
         aggregated_data: Arc<AggregatedData>,
         use_synthetic_counts: bool,
         weight_selection_percentile: Option<usize>,
         target_number_of_records: Option<usize>,
+        seed: Option<u64>,
+        conditions: Option<SynthesisConditions>,
+        constraint_rules: Option<Arc<ConstraintRules>>,
     ) -> AggregateSeededSynthesizer {
+        let target_number_of_records = match &conditions {
+            Some(SynthesisConditions::RequiredValues(values)) => {
+                target_number_of_records.or_else(|| {
+                    Some(AggregateSeededSynthesizer::calc_required_values_count(
+                        &aggregated_data,
+                        values,
+                    ))
+                })
+            }
+            _ => target_number_of_records,
+        };
+
         AggregateSeededSynthesizer {
             single_attr_counts: aggregated_data.calc_single_attribute_counts(),
             weight_selection_percentile: weight_selection_percentile
@@ -63,8 +87,11 @@ impl AggregateSeededSynthesizer {
                 None,
                 target_number_of_records,
                 use_synthetic_counts,
+                conditions.map(Arc::new),
+                constraint_rules,
             ),
             consolidate_percentage: 0.0,
+            rng: new_rng(seed),
         }
     }
 
@@ -100,6 +127,32 @@ impl AggregateSeededSynthesizer {
         Ok(synthesized_records)
     }
 
+    /// Number of records containing all the required `values`.
+    /// If there are more values than the reporting length, the smallest
+    /// count of the reported sub-combinations is used
+    fn calc_required_values_count(
+        aggregated_data: &AggregatedData,
+        values: &[Arc<DataBlockValue>],
+    ) -> usize {
+        let count = values
+            .iter()
+            .cloned()
+            .combinations(values.len().min(aggregated_data.reporting_length))
+            .map(|comb| {
+                aggregated_data
+                    .aggregates_count
+                    .get(&ValueCombination::new(comb))
+                    .map_or(0, |count| count.count)
+            })
+            .min()
+            .unwrap_or(0);
+
+        if count == 0 {
+            warn!("the required values are not present on the aggregated data, no records will be synthesized");
+        }
+        count
+    }
+
     #[inline]
     fn calc_weight_for_single_combination(
         &self,
@@ -167,11 +220,6 @@ impl AggregateSeededSynthesizer {
 }
 
 impl SynthesisData for AggregateSeededSynthesizer {
-    #[inline]
-    fn get_headers(&self) -> &DataBlockHeaders {
-        &self.consolidate_parameters.aggregated_data.headers
-    }
-
     #[inline]
     fn get_single_attr_counts(&self) -> &AttributeCountMap {
         // get all the single attribute counts
@@ -228,10 +276,7 @@ impl Consolidate for AggregateSeededSynthesizer {
                 {
                     let mut current_comb = last_processed.clone();
 
-                    current_comb.extend(
-                        attr.clone(),
-                        &self.consolidate_parameters.aggregated_data.headers,
-                    );
+                    current_comb.extend(attr.clone());
 
                     Some((
                         attr.clone(),
@@ -257,7 +302,7 @@ impl Consolidate for AggregateSeededSynthesizer {
             })
             .collect();
 
-        sample_weighted(&counts)
+        sample_weighted(&counts, &mut self.rng)
     }
 
     #[inline]
@@ -1,7 +1,9 @@
 use super::cache::{SynthesizerCache, SynthesizerCacheKey};
+use super::constraint_rules::ConstraintRules;
 use super::typedefs::{
     AttributeCountMap, NotAllowedAttrSet, SynthesizedRecord, SynthesizerSeedSlice,
 };
+use rand::Rng;
 use std::sync::Arc;
 
 use crate::data_block::{
@@ -20,6 +22,8 @@ pub struct AttributeRowsSampler {
     cache: SynthesizerCache<Arc<AttributeRows>>,
     /// Reporting resolution used for data synthesis
     resolution: usize,
+    /// Rules used to exclude attributes leading to invalid combinations
+    constraint_rules: Option<Arc<ConstraintRules>>,
 }
 
 impl AttributeRowsSampler {
@@ -28,17 +32,20 @@ impl AttributeRowsSampler {
     /// * `data_block` - Reference to the original data block
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache` - Cache to store attribute intersections and speedup the processing
+    /// * `constraint_rules` - Rules used to exclude attributes leading to invalid combinations (if `None` any combination is allowed)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
         resolution: usize,
         cache: SynthesizerCache<Arc<AttributeRows>>,
+        constraint_rules: Option<Arc<ConstraintRules>>,
     ) -> AttributeRowsSampler {
         AttributeRowsSampler {
             headers_len: data_block.headers.len(),
             records_len: data_block.records.len(),
             cache,
             resolution,
+            constraint_rules,
         }
     }
 
@@ -55,13 +62,15 @@ impl AttributeRowsSampler {
     /// * `current_seed` - Current seed/record used for sampling
     /// * `not_allowed_attr_set` - Attributes not allowed to be sampled
     /// * `attr_rows_map` - Maps a data block value to all the rows where it occurs
+    /// * `rng` - Random number generator used for sampling
     #[inline]
-    pub fn sample_next_attr_from_seed(
+    pub fn sample_next_attr_from_seed<R: Rng>(
         &mut self,
         synthesized_record: &SynthesizedRecord,
         current_seed: &SynthesizerSeedSlice,
         not_allowed_attr_set: &NotAllowedAttrSet,
         attr_rows_map: &AttributeRowsMap,
+        rng: &mut R,
     ) -> Option<Arc<DataBlockValue>> {
         let counts = self.calc_next_attr_count(
             synthesized_record,
@@ -69,7 +78,7 @@ impl AttributeRowsSampler {
             not_allowed_attr_set,
             attr_rows_map,
         );
-        sample_weighted(&counts)
+        sample_weighted(&counts, rng)
     }
 
     #[inline]
@@ -104,10 +113,23 @@ impl AttributeRowsSampler {
         }
     }
 
+    #[inline]
+    fn is_allowed_by_constraints(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        value: &DataBlockValue,
+    ) -> bool {
+        match &self.constraint_rules {
+            Some(constraint_rules) => constraint_rules.allows(synthesized_record, value),
+            None => true,
+        }
+    }
+
     #[inline]
     fn gen_attr_count_map(
         &mut self,
         cache_key: &SynthesizerCacheKey,
+        synthesized_record: &SynthesizedRecord,
         current_seed: &SynthesizerSeedSlice,
         current_attrs_rows: &AttributeRowsSlice,
         not_allowed_attr_set: &NotAllowedAttrSet,
@@ -118,7 +140,10 @@ impl AttributeRowsSampler {
         for value in current_seed.iter() {
             // if attribute has been already processed or is not
             // allowed, skip
-            if !cache_key.has_column(value.column_index) && !not_allowed_attr_set.contains(value) {
+            if !cache_key.has_column(value.column_index)
+                && !not_allowed_attr_set.contains(value)
+                && self.is_allowed_by_constraints(synthesized_record, value)
+            {
                 let new_cache_key = cache_key.new_with_value(value);
                 let count = match self.cache.get(&new_cache_key) {
                     Some(cached_value) => cached_value.len(),
@@ -156,6 +181,7 @@ impl AttributeRowsSampler {
 
         self.gen_attr_count_map(
             &cache_key,
+            synthesized_record,
             current_seed,
             &current_attrs_rows,
             not_allowed_attr_set,
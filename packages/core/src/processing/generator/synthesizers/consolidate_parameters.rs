@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use super::{constraint_rules::ConstraintRules, synthesis_conditions::SynthesisConditions};
 use crate::processing::aggregator::AggregatedData;
 
 /// Define the parameters needed for data consolidation
@@ -17,6 +18,11 @@ pub struct ConsolidateParameters {
     /// Whether or not the synthetic counts should be used to
     /// control the sampling process aggregate seeded
     pub use_synthetic_counts: bool,
+    /// Conditions used to seed each record, when set `target_number_of_records`
+    /// is the number of records seeded with the required values
+    pub conditions: Option<Arc<SynthesisConditions>>,
+    /// Rules used to exclude sampled attributes leading to invalid combinations
+    pub constraint_rules: Option<Arc<ConstraintRules>>,
 }
 
 impl ConsolidateParameters {
@@ -29,6 +35,8 @@ impl ConsolidateParameters {
     /// If `None` sample from all available counts
     /// * `use_synthetic_counts` - Whether or not the synthetic counts should be used to
     /// control the aggregate seeded sampling process
+    /// * `conditions` - Conditions used to seed each record (if `None` the full population is synthesized)
+    /// * `constraint_rules` - Rules used to exclude sampled attributes leading to invalid combinations (if `None` any combination is allowed)
     #[inline]
     pub fn new(
         aggregated_data: Arc<AggregatedData>,
@@ -36,6 +44,8 @@ impl ConsolidateParameters {
         oversampling_tries: Option<usize>,
         target_number_of_records: Option<usize>,
         use_synthetic_counts: bool,
+        conditions: Option<Arc<SynthesisConditions>>,
+        constraint_rules: Option<Arc<ConstraintRules>>,
     ) -> ConsolidateParameters {
         ConsolidateParameters {
             aggregated_data,
@@ -43,6 +53,8 @@ impl ConsolidateParameters {
             oversampling_tries,
             target_number_of_records,
             use_synthetic_counts,
+            conditions,
+            constraint_rules,
         }
     }
 }
@@ -55,6 +67,8 @@ impl Default for ConsolidateParameters {
             oversampling_tries: None,
             target_number_of_records: None,
             use_synthetic_counts: false,
+            conditions: None,
+            constraint_rules: None,
         }
     }
 }
@@ -0,0 +1,317 @@
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+use super::typedefs::SynthesizedRecord;
+use crate::{
+    data_block::{DataBlockHeaders, DataBlockHeadersSlice, DataBlockValue},
+    processing::generator::GeneratedData,
+};
+
+/// Rule every synthesized record should satisfy
+#[derive(Debug, Clone)]
+pub enum ConstraintRule {
+    /// Both values can not appear on the same record
+    /// (`deny {header_name}:{value} & {header_name}:{value}`)
+    DeniedPair(Arc<DataBlockValue>, Arc<DataBlockValue>),
+    /// Records containing the first value should contain the second one
+    /// (`if {header_name}:{value} then {header_name}:{value}`)
+    Implication(Arc<DataBlockValue>, Arc<DataBlockValue>),
+    /// The first column can only be set on records where the second column is set
+    /// (`column {header_name} requires {header_name}`)
+    ColumnDependency(usize, usize),
+}
+
+impl ConstraintRule {
+    /// Columns the rule depends on
+    #[inline]
+    pub fn columns(&self) -> [usize; 2] {
+        match self {
+            ConstraintRule::DeniedPair(v1, v2) | ConstraintRule::Implication(v1, v2) => {
+                [v1.column_index, v2.column_index]
+            }
+            ConstraintRule::ColumnDependency(column, required_column) => {
+                [*column, *required_column]
+            }
+        }
+    }
+
+    /// Formats the rule using the header names, in the same way it is parsed
+    /// # Arguments
+    /// * `headers` - Data block headers
+    pub fn as_str_using_headers(&self, headers: &DataBlockHeadersSlice) -> String {
+        match self {
+            ConstraintRule::DeniedPair(v1, v2) => format!(
+                "deny {} & {}",
+                v1.as_str_using_headers(headers),
+                v2.as_str_using_headers(headers)
+            ),
+            ConstraintRule::Implication(condition, consequence) => format!(
+                "if {} then {}",
+                condition.as_str_using_headers(headers),
+                consequence.as_str_using_headers(headers)
+            ),
+            ConstraintRule::ColumnDependency(column, required_column) => format!(
+                "column {} requires {}",
+                headers[*column], headers[*required_column]
+            ),
+        }
+    }
+
+    /// Whether a record violates the rule
+    /// # Arguments
+    /// * `record_value` - Returns the record value on a given column (`None` if empty)
+    #[inline]
+    fn is_violated_by<'a, F>(&self, record_value: F) -> bool
+    where
+        F: Fn(usize) -> Option<&'a str>,
+    {
+        let has_value =
+            |value: &DataBlockValue| record_value(value.column_index) == Some(value.value.as_str());
+
+        match self {
+            ConstraintRule::DeniedPair(v1, v2) => has_value(v1) && has_value(v2),
+            ConstraintRule::Implication(condition, consequence) => {
+                has_value(condition) && !has_value(consequence)
+            }
+            ConstraintRule::ColumnDependency(column, required_column) => {
+                record_value(*column).is_some() && record_value(*required_column).is_none()
+            }
+        }
+    }
+}
+
+/// Number of synthetic records violating the constraint rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolations {
+    /// Number of records violating at least one rule
+    pub number_of_records_with_violations: usize,
+    /// Number of records violating each rule (in the same order as the rules)
+    pub violations_by_rule: Vec<usize>,
+}
+
+/// Set of hard constraints used to forbid invalid value combinations
+/// on the synthetic data
+///
+/// Rules are defined one per line (empty lines and lines starting with `#` are ignored):
+/// - `deny Pregnant:yes & Sex:male`
+/// - `if Pregnant:yes then Sex:female`
+/// - `column PregnancyWeeks requires Pregnant`
+#[derive(Debug, Clone)]
+pub struct ConstraintRules {
+    /// Data block headers the rules were defined with
+    headers: DataBlockHeaders,
+    /// Rules every synthesized record should satisfy
+    rules: Vec<ConstraintRule>,
+    /// Maps a column index to the rules that depend on it
+    rules_by_column: FnvHashMap<usize, Vec<usize>>,
+}
+
+impl ConstraintRules {
+    /// Returns new ConstraintRules
+    /// # Arguments
+    /// * `headers` - Data block headers the rules were defined with
+    /// * `rules` - Rules every synthesized record should satisfy
+    pub fn new(headers: DataBlockHeaders, rules: Vec<ConstraintRule>) -> ConstraintRules {
+        let mut rules_by_column: FnvHashMap<usize, Vec<usize>> = FnvHashMap::default();
+
+        for (rule_index, rule) in rules.iter().enumerate() {
+            for column in rule.columns() {
+                let column_rules = rules_by_column.entry(column).or_default();
+
+                if !column_rules.contains(&rule_index) {
+                    column_rules.push(rule_index);
+                }
+            }
+        }
+
+        ConstraintRules {
+            headers,
+            rules,
+            rules_by_column,
+        }
+    }
+
+    /// Parses the rules from `rules_str` (one rule per line)
+    /// # Arguments
+    /// * `headers` - Data block headers used to look up the column indexes
+    /// * `rules_str` - Rules to be parsed
+    pub fn from_str_using_headers(
+        headers: &DataBlockHeadersSlice,
+        rules_str: &str,
+    ) -> Result<ConstraintRules, String> {
+        let mut rules = Vec::default();
+
+        for (line_index, line) in rules_str.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            rules.push(
+                ConstraintRules::parse_rule(headers, line)
+                    .map_err(|err| format!("invalid rule on line {}: {}", line_index + 1, err))?,
+            );
+        }
+        Ok(ConstraintRules::new(headers.to_vec(), rules))
+    }
+
+    #[inline]
+    pub fn headers(&self) -> &DataBlockHeadersSlice {
+        &self.headers
+    }
+
+    #[inline]
+    pub fn rules(&self) -> &[ConstraintRule] {
+        &self.rules
+    }
+
+    /// Whether `value` can be added to the `synthesized_record` without
+    /// violating any rule (only the rules depending on the value column are checked)
+    ///
+    /// Since records are built one value at a time, the condition of an implication
+    /// is only allowed after its consequence, like a column after the one it requires
+    /// # Arguments
+    /// * `synthesized_record` - Record synthesized so far
+    /// * `value` - Candidate value
+    pub fn allows(&self, synthesized_record: &SynthesizedRecord, value: &DataBlockValue) -> bool {
+        let record_value = |column_index: usize| {
+            if column_index == value.column_index {
+                Some(value.value.as_str())
+            } else {
+                synthesized_record
+                    .iter()
+                    .find(|v| v.column_index == column_index)
+                    .map(|v| v.value.as_str())
+            }
+        };
+
+        match self.rules_by_column.get(&value.column_index) {
+            Some(rule_indexes) => rule_indexes
+                .iter()
+                .all(|rule_index| !self.rules[*rule_index].is_violated_by(record_value)),
+            None => true,
+        }
+    }
+
+    /// Counts the records of the generated data violating the rules.
+    /// Generated columns are matched to the rules by header name, so this should
+    /// be called before numeric bins are mapped back to numeric values
+    /// # Arguments
+    /// * `generated_data` - Synthetic data to be validated
+    /// * `empty_value` - Empty values on the synthetic data are represented by this
+    pub fn count_violations(
+        &self,
+        generated_data: &GeneratedData,
+        empty_value: &str,
+    ) -> ConstraintViolations {
+        let mut violations = ConstraintViolations {
+            number_of_records_with_violations: 0,
+            violations_by_rule: vec![0; self.rules.len()],
+        };
+
+        if let Some(generated_headers) = generated_data.synthetic_data.first() {
+            let column_by_header: FnvHashMap<&str, usize> = self
+                .headers
+                .iter()
+                .enumerate()
+                .map(|(column_index, header)| (header.as_str(), column_index))
+                .collect();
+            // maps each generated column to the column index used by the rules
+            let columns: Vec<Option<usize>> = generated_headers
+                .iter()
+                .map(|header| column_by_header.get(header.as_str()).cloned())
+                .collect();
+
+            for record in generated_data.synthetic_data.iter().skip(1) {
+                let mut record_values: Vec<Option<&str>> = vec![None; self.headers.len()];
+
+                for (value, column) in record.iter().zip(columns.iter()) {
+                    if let Some(column_index) = column {
+                        if !value.is_empty() && value.as_str() != empty_value {
+                            record_values[*column_index] = Some(value.as_str());
+                        }
+                    }
+                }
+
+                let mut has_violations = false;
+
+                for (rule, count) in self
+                    .rules
+                    .iter()
+                    .zip(violations.violations_by_rule.iter_mut())
+                {
+                    if rule.is_violated_by(|column_index| record_values[column_index]) {
+                        *count += 1;
+                        has_violations = true;
+                    }
+                }
+
+                if has_violations {
+                    violations.number_of_records_with_violations += 1;
+                }
+            }
+        }
+        violations
+    }
+
+    #[inline]
+    fn parse_column(headers: &DataBlockHeadersSlice, header_name: &str) -> Result<usize, String> {
+        headers
+            .iter()
+            .position(|header| header.as_str() == header_name)
+            .ok_or_else(|| format!("\"{}\" is not one of the headers", header_name))
+    }
+
+    #[inline]
+    fn parse_value(
+        headers: &DataBlockHeadersSlice,
+        str_value: &str,
+    ) -> Result<Arc<DataBlockValue>, String> {
+        DataBlockValue::from_str_using_headers(headers, str_value.trim())
+            .map(Arc::new)
+            .map_err(|err| err.to_string())
+    }
+
+    fn parse_rule(headers: &DataBlockHeadersSlice, line: &str) -> Result<ConstraintRule, String> {
+        let rule = if let Some(rule) = line.strip_prefix("deny ") {
+            let (v1, v2) = rule
+                .split_once(" & ")
+                .ok_or("denied pairs should be formatted as \"deny {value} & {value}\"")?;
+
+            ConstraintRule::DeniedPair(
+                ConstraintRules::parse_value(headers, v1)?,
+                ConstraintRules::parse_value(headers, v2)?,
+            )
+        } else if let Some(rule) = line.strip_prefix("if ") {
+            let (condition, consequence) = rule
+                .split_once(" then ")
+                .ok_or("implications should be formatted as \"if {value} then {value}\"")?;
+
+            ConstraintRule::Implication(
+                ConstraintRules::parse_value(headers, condition)?,
+                ConstraintRules::parse_value(headers, consequence)?,
+            )
+        } else if let Some(rule) = line.strip_prefix("column ") {
+            let (column, required_column) = rule.split_once(" requires ").ok_or(
+                "column dependencies should be formatted as \"column {header} requires {header}\"",
+            )?;
+
+            ConstraintRule::ColumnDependency(
+                ConstraintRules::parse_column(headers, column.trim())?,
+                ConstraintRules::parse_column(headers, required_column.trim())?,
+            )
+        } else {
+            return Err(format!(
+                "\"{}\" should start with \"deny\", \"if\" or \"column\"",
+                line
+            ));
+        };
+        let [column, other_column] = rule.columns();
+
+        if column == other_column {
+            return Err(format!("\"{}\" should refer to different columns", line));
+        }
+        Ok(rule)
+    }
+}
@@ -0,0 +1,265 @@
+use log::debug;
+use std::collections::VecDeque;
+
+use super::{
+    marginal_table::MarginalTable,
+    noisy_marginals::{NoisyMarginals, EMPTY_VALUE_INDEX},
+};
+
+/// Cliques with more cells than this are reduced to two-way cliques
+const MAX_CLIQUE_CELLS: usize = 1_000_000;
+
+/// Number of iterative proportional fitting rounds used to
+/// estimate each clique from its noisy measurements
+const FITTING_ROUNDS: usize = 10;
+
+/// Count added to every clique cell before fitting its measurements
+const PSEUDO_COUNT: f64 = 0.1;
+
+/// Clique of the junction tree, used to sample `column` given
+/// the values already sampled for the `conditioned_on` columns
+#[derive(Debug, Clone)]
+pub struct ModelClique {
+    /// Column sampled from this clique
+    pub column: usize,
+    /// Columns (parent and grandparent on the dependency tree)
+    /// this clique shares with the previous cliques
+    pub conditioned_on: Vec<usize>,
+    /// Calibrated counts over `conditioned_on` followed by `column`
+    pub table: MarginalTable,
+}
+
+/// Junction tree built from the noisy marginals, with the cliques
+/// ordered so every separator is sampled before the clique using it
+pub struct JunctionTree {
+    pub cliques: Vec<ModelClique>,
+}
+
+impl JunctionTree {
+    /// Selects the marginals and estimates the cliques of the model:
+    ///
+    /// 1. Builds the maximum spanning tree (Chow-Liu) over the columns weighting
+    ///    the edges by the mutual information of the two-way estimates
+    /// 2. Each column forms a clique with its parent (and its grandparent if
+    ///    `max_clique_size` is 3 and three-way marginals were reported)
+    /// 3. Each clique is estimated by fitting the lower order estimates it covers
+    ///    and calibrated against its parent clique on the separator
+    /// # Arguments
+    /// * `marginals` - Noisy marginals to build the model from
+    /// * `max_clique_size` - Maximum number of columns on a clique (1 to 3)
+    pub fn new(marginals: &NoisyMarginals, max_clique_size: usize) -> JunctionTree {
+        let one_way: Vec<MarginalTable> = (0..marginals.number_of_columns())
+            .map(|column| marginals.marginal(&[column]))
+            .collect();
+        let parents = JunctionTree::build_dependency_tree(marginals, &one_way, max_clique_size);
+        let mut cliques: Vec<ModelClique> = Vec::with_capacity(parents.len());
+        let mut clique_index_by_column = vec![0; marginals.number_of_columns()];
+
+        for (column, parent) in parents {
+            let mut conditioned_on = Vec::default();
+
+            if let Some(parent) = parent {
+                let parent_clique: &ModelClique = &cliques[clique_index_by_column[parent]];
+
+                conditioned_on.push(parent);
+
+                if let Some(grandparent) = parent_clique.conditioned_on.first() {
+                    let cells = marginals.domain_size(column)
+                        * marginals.domain_size(parent)
+                        * marginals.domain_size(*grandparent);
+
+                    if max_clique_size >= 3
+                        && marginals.reporting_length() >= 3
+                        && cells <= MAX_CLIQUE_CELLS
+                    {
+                        conditioned_on.push(*grandparent);
+                    }
+                }
+            }
+
+            let mut table = JunctionTree::estimate_clique(
+                marginals,
+                &one_way,
+                &[conditioned_on.as_slice(), &[column]].concat(),
+            );
+
+            JunctionTree::fill_empty_conditionals(&mut table, &one_way[column]);
+
+            let separator = if conditioned_on.is_empty() {
+                MarginalTable::new(
+                    Vec::default(),
+                    Vec::default(),
+                    marginals.number_of_records(),
+                )
+            } else {
+                cliques[clique_index_by_column[conditioned_on[0]]]
+                    .table
+                    .project(&conditioned_on)
+            };
+
+            table.fit_to(&separator);
+
+            debug!(
+                "marginal model clique: column {} conditioned on {:?}",
+                column, conditioned_on
+            );
+
+            clique_index_by_column[column] = cliques.len();
+            cliques.push(ModelClique {
+                column,
+                conditioned_on,
+                table,
+            });
+        }
+
+        JunctionTree { cliques }
+    }
+
+    /// Returns the columns in sampling order, paired with their parent
+    /// on the Chow-Liu tree (`None` for the root of each component)
+    fn build_dependency_tree(
+        marginals: &NoisyMarginals,
+        one_way: &[MarginalTable],
+        max_clique_size: usize,
+    ) -> Vec<(usize, Option<usize>)> {
+        let n_columns = marginals.number_of_columns();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::default(); n_columns];
+
+        if max_clique_size >= 2 && marginals.reporting_length() >= 2 {
+            let mut edges: Vec<(f64, usize, usize)> = Vec::default();
+
+            for a in 0..n_columns {
+                for b in (a + 1)..n_columns {
+                    let mi = JunctionTree::estimate_clique(marginals, one_way, &[a, b])
+                        .mutual_information();
+
+                    if mi > 0.0 {
+                        edges.push((mi, a, b));
+                    }
+                }
+            }
+
+            // Kruskal, from the highest to the lowest mutual information
+            edges.sort_by(|e1, e2| {
+                e2.0.partial_cmp(&e1.0)
+                    .unwrap()
+                    .then((e1.1, e1.2).cmp(&(e2.1, e2.2)))
+            });
+
+            let mut components: Vec<usize> = (0..n_columns).collect();
+
+            for (_, a, b) in edges {
+                let root_a = JunctionTree::find_component(&mut components, a);
+                let root_b = JunctionTree::find_component(&mut components, b);
+
+                if root_a != root_b {
+                    components[root_a] = root_b;
+                    adjacency[a].push(b);
+                    adjacency[b].push(a);
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(n_columns);
+        let mut visited = vec![false; n_columns];
+
+        for root in 0..n_columns {
+            if visited[root] {
+                continue;
+            }
+
+            let mut queue = VecDeque::from([(root, None)]);
+
+            visited[root] = true;
+
+            while let Some((column, parent)) = queue.pop_front() {
+                order.push((column, parent));
+
+                for neighbor in adjacency[column].iter() {
+                    if !visited[*neighbor] {
+                        visited[*neighbor] = true;
+                        queue.push_back((*neighbor, Some(column)));
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    #[inline]
+    fn find_component(components: &mut [usize], mut column: usize) -> usize {
+        while components[column] != column {
+            components[column] = components[components[column]];
+            column = components[column];
+        }
+        column
+    }
+
+    /// Estimates the clique counts from the noisy marginal over its columns,
+    /// refining it to match the lower order estimates it covers, so noisy
+    /// measurements that contradict each other are made consistent
+    fn estimate_clique(
+        marginals: &NoisyMarginals,
+        one_way: &[MarginalTable],
+        columns: &[usize],
+    ) -> MarginalTable {
+        let mut table = marginals.marginal(columns);
+        let mut measurements: Vec<MarginalTable> = Vec::default();
+
+        if columns.len() == 1 {
+            return table;
+        }
+
+        // combinations suppressed by noise would otherwise remain
+        // as zeros, since fitting only rescales the existing counts
+        for value in table.values_mut() {
+            *value += PSEUDO_COUNT;
+        }
+
+        if columns.len() > 2 {
+            for i in 0..columns.len() {
+                for j in (i + 1)..columns.len() {
+                    measurements.push(JunctionTree::estimate_clique(
+                        marginals,
+                        one_way,
+                        &[columns[i], columns[j]],
+                    ));
+                }
+            }
+        }
+        measurements.extend(columns.iter().map(|column| one_way[*column].clone()));
+
+        for _ in 0..FITTING_ROUNDS {
+            for measurement in measurements.iter() {
+                table.fit_to(measurement);
+            }
+        }
+        table
+    }
+
+    /// Conditionals without any mass (e.g. suppressed by noise) fall back
+    /// to the one-way distribution of the sampled column
+    fn fill_empty_conditionals(table: &mut MarginalTable, column_one_way: &MarginalTable) {
+        let column_size = column_one_way.values().len();
+        let one_way_total = column_one_way.total();
+        let fallback: Vec<f64> = if one_way_total > 0.0 {
+            column_one_way
+                .values()
+                .iter()
+                .map(|v| v / one_way_total)
+                .collect()
+        } else {
+            let mut only_empty = vec![0.0; column_size];
+
+            only_empty[EMPTY_VALUE_INDEX] = 1.0;
+            only_empty
+        };
+
+        // the sampled column is the last one, so each conditional is contiguous
+        for slice in table.values_mut().chunks_mut(column_size) {
+            if slice.iter().sum::<f64>() <= 0.0 {
+                slice.copy_from_slice(&fallback);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+/// Dense table storing the (estimated) counts for every combination
+/// of values from a set of columns.
+///
+/// Each column value is represented by its index on the column domain,
+/// and cells are stored in row major order
+#[derive(Debug, Clone)]
+pub struct MarginalTable {
+    /// Columns covered by this table
+    columns: Vec<usize>,
+    /// Domain size of each column
+    shape: Vec<usize>,
+    /// Count for each cell
+    values: Vec<f64>,
+}
+
+impl MarginalTable {
+    /// Returns a new MarginalTable with all the cells set to `fill`
+    /// # Arguments
+    /// * `columns` - Columns covered by the table
+    /// * `shape` - Domain size of each column
+    /// * `fill` - Initial value of every cell
+    pub fn new(columns: Vec<usize>, shape: Vec<usize>, fill: f64) -> MarginalTable {
+        let size = shape.iter().product();
+
+        MarginalTable {
+            columns,
+            shape,
+            values: vec![fill; size],
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    #[inline]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [f64] {
+        &mut self.values
+    }
+
+    /// Sum of all the cells
+    #[inline]
+    pub fn total(&self) -> f64 {
+        self.values.iter().sum()
+    }
+
+    /// Cell position for the given value indexes (one per column)
+    #[inline]
+    pub fn cell_index(&self, value_indexes: &[usize]) -> usize {
+        value_indexes
+            .iter()
+            .zip(self.shape.iter())
+            .fold(0, |acc, (value_index, size)| acc * size + value_index)
+    }
+
+    /// Value indexes (one per column) for the given cell position
+    #[inline]
+    pub fn value_indexes(&self, mut cell_index: usize) -> Vec<usize> {
+        let mut value_indexes = vec![0; self.shape.len()];
+
+        for (i, size) in self.shape.iter().enumerate().rev() {
+            value_indexes[i] = cell_index % size;
+            cell_index /= size;
+        }
+        value_indexes
+    }
+
+    #[inline]
+    pub fn get(&self, value_indexes: &[usize]) -> f64 {
+        self.values[self.cell_index(value_indexes)]
+    }
+
+    #[inline]
+    pub fn set(&mut self, value_indexes: &[usize], value: f64) {
+        let cell_index = self.cell_index(value_indexes);
+
+        self.values[cell_index] = value;
+    }
+
+    #[inline]
+    pub fn add(&mut self, value_indexes: &[usize], value: f64) {
+        let cell_index = self.cell_index(value_indexes);
+
+        self.values[cell_index] += value;
+    }
+
+    /// Sets negative cells to zero
+    #[inline]
+    pub fn clamp_negatives(&mut self) {
+        for v in self.values.iter_mut() {
+            if *v < 0.0 {
+                *v = 0.0;
+            }
+        }
+    }
+
+    /// Maps each cell of this table to the cell of a table
+    /// covering `columns` (which should be a subset of this table columns)
+    fn projection_map(&self, columns: &[usize]) -> (Vec<usize>, Vec<usize>) {
+        let positions: Vec<usize> = columns
+            .iter()
+            .map(|c| {
+                self.columns
+                    .iter()
+                    .position(|own| own == c)
+                    .expect("projected columns should be covered by the table")
+            })
+            .collect();
+        let shape: Vec<usize> = positions.iter().map(|p| self.shape[*p]).collect();
+        let cell_map = (0..self.values.len())
+            .map(|cell_index| {
+                let value_indexes = self.value_indexes(cell_index);
+
+                positions
+                    .iter()
+                    .zip(shape.iter())
+                    .fold(0, |acc, (p, size)| acc * size + value_indexes[*p])
+            })
+            .collect();
+
+        (cell_map, shape)
+    }
+
+    /// Sums out all the columns not in `columns`
+    /// # Arguments
+    /// * `columns` - Columns to keep, in the order they should appear on the result
+    pub fn project(&self, columns: &[usize]) -> MarginalTable {
+        let (cell_map, shape) = self.projection_map(columns);
+        let mut result = MarginalTable::new(columns.to_vec(), shape, 0.0);
+
+        for (cell_index, target_index) in cell_map.iter().enumerate() {
+            result.values[*target_index] += self.values[cell_index];
+        }
+        result
+    }
+
+    /// Iterative proportional fitting step: rescales the cells so the
+    /// marginal of this table over the `target` columns matches `target`
+    /// # Arguments
+    /// * `target` - Marginal to be matched (its columns should be covered by this table)
+    pub fn fit_to(&mut self, target: &MarginalTable) {
+        let (cell_map, _) = self.projection_map(&target.columns);
+        let mut current = vec![0.0; target.values.len()];
+
+        for (cell_index, target_index) in cell_map.iter().enumerate() {
+            current[*target_index] += self.values[cell_index];
+        }
+        for (cell_index, target_index) in cell_map.iter().enumerate() {
+            let current_value = current[*target_index];
+
+            self.values[cell_index] = if current_value > 0.0 {
+                self.values[cell_index] * target.values[*target_index] / current_value
+            } else {
+                // no mass to rescale, so spread the target evenly
+                let cells_on_target = (self.values.len() / target.values.len()) as f64;
+
+                target.values[*target_index] / cells_on_target
+            };
+        }
+    }
+
+    /// Mutual information (in nats) between the two columns of a two-way table
+    pub fn mutual_information(&self) -> f64 {
+        assert!(
+            self.shape.len() == 2,
+            "mutual information requires a two-way table"
+        );
+
+        let total = self.total();
+
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        let rows = self.project(&self.columns[0..1]);
+        let cols = self.project(&self.columns[1..2]);
+        let mut mi = 0.0;
+
+        for i in 0..self.shape[0] {
+            for j in 0..self.shape[1] {
+                let joint = self.get(&[i, j]) / total;
+
+                if joint > 0.0 {
+                    mi += joint
+                        * (joint / ((rows.values[i] / total) * (cols.values[j] / total))).ln();
+                }
+            }
+        }
+        mi.max(0.0)
+    }
+}
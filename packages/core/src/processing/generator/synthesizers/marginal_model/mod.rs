@@ -0,0 +1,170 @@
+mod junction_tree;
+mod marginal_table;
+mod noisy_marginals;
+
+use log::info;
+use rand::distributions::{Distribution, WeightedIndex};
+use std::sync::Arc;
+
+use self::{
+    junction_tree::JunctionTree,
+    noisy_marginals::{NoisyMarginals, EMPTY_VALUE_INDEX},
+};
+use crate::{
+    processing::{
+        aggregator::AggregatedData,
+        generator::synthesizers::typedefs::{SynthesizedRecord, SynthesizedRecords},
+    },
+    utils::{
+        math::calc_percentage,
+        random::{new_rng, SeededRng},
+        reporting::{ReportProgress, StoppableResult},
+    },
+};
+
+const DEFAULT_MAX_CLIQUE_SIZE: usize = 2;
+
+/// Conditional distributions of a clique column, one for each
+/// combination of values of the columns it is conditioned on
+struct CliqueSampler {
+    column: usize,
+    conditioned_on: Vec<usize>,
+    conditioned_on_shape: Vec<usize>,
+    /// `None` when the conditional does not have any mass
+    distributions: Vec<Option<WeightedIndex<f64>>>,
+}
+
+/// Represents all the information required to perform the marginal
+/// model synthesis (MST/AIM-style)
+///
+/// Instead of consolidating records from the aggregates, a graphical model
+/// (junction tree) is fit to the (noisy) one, two and three-way marginals
+/// and records are sampled from it
+pub struct MarginalModelSynthesizer {
+    /// Aggregated data (usually with DP noise) to fit the model from
+    aggregated_data: Arc<AggregatedData>,
+    /// Maximum number of columns on each model clique
+    max_clique_size: usize,
+    /// Total number of records to be synthesized
+    target_number_of_records: Option<usize>,
+    /// Random number generator used for sampling
+    rng: SeededRng,
+}
+
+impl MarginalModelSynthesizer {
+    /// Returns a new MarginalModelSynthesizer
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data to synthesize from
+    /// * `max_clique_size` - Maximum number of columns on each clique, 1 to 3 (default of 2 if `None`)
+    /// * `target_number_of_records` - Total number of records to be synthesized (defaults to the number of records on the aggregated data)
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    #[inline]
+    pub fn new(
+        aggregated_data: Arc<AggregatedData>,
+        max_clique_size: Option<usize>,
+        target_number_of_records: Option<usize>,
+        seed: Option<u64>,
+    ) -> MarginalModelSynthesizer {
+        MarginalModelSynthesizer {
+            aggregated_data,
+            max_clique_size: max_clique_size
+                .unwrap_or(DEFAULT_MAX_CLIQUE_SIZE)
+                .clamp(1, 3),
+            target_number_of_records,
+            rng: new_rng(seed),
+        }
+    }
+
+    /// Fits the model to the aggregates and samples the records from it
+    /// Returns the synthesized records
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn run<T>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<SynthesizedRecords>
+    where
+        T: ReportProgress,
+    {
+        let marginals = NoisyMarginals::new(&self.aggregated_data);
+        let number_of_records = self
+            .target_number_of_records
+            .unwrap_or_else(|| marginals.number_of_records().round() as usize);
+        let mut synthesized_records = SynthesizedRecords::with_capacity(number_of_records);
+
+        if number_of_records == 0 || marginals.number_of_columns() == 0 {
+            return Ok(synthesized_records);
+        }
+
+        info!(
+            "fitting marginal model with cliques of up to {} columns",
+            self.max_clique_size
+        );
+
+        let samplers = MarginalModelSynthesizer::build_samplers(&JunctionTree::new(
+            &marginals,
+            self.max_clique_size,
+        ));
+        let mut value_indexes = vec![EMPTY_VALUE_INDEX; marginals.number_of_columns()];
+
+        for n_processed in 0..number_of_records {
+            let mut record = SynthesizedRecord::default();
+
+            for sampler in samplers.iter() {
+                let value_index = self.sample_value_index(sampler, &value_indexes);
+
+                value_indexes[sampler.column] = value_index;
+
+                if let Some(value) = marginals.value(sampler.column, value_index) {
+                    record.insert(value.clone());
+                }
+            }
+            synthesized_records.push(record);
+
+            if let Some(r) = progress_reporter.as_mut() {
+                r.report(calc_percentage(
+                    (n_processed + 1) as f64,
+                    number_of_records as f64,
+                ))?;
+            }
+        }
+        Ok(synthesized_records)
+    }
+
+    fn build_samplers(junction_tree: &JunctionTree) -> Vec<CliqueSampler> {
+        junction_tree
+            .cliques
+            .iter()
+            .map(|clique| {
+                let shape = clique.table.shape();
+                let column_size = shape[shape.len() - 1];
+
+                CliqueSampler {
+                    column: clique.column,
+                    conditioned_on: clique.conditioned_on.clone(),
+                    conditioned_on_shape: shape[..shape.len() - 1].to_vec(),
+                    distributions: clique
+                        .table
+                        .values()
+                        .chunks(column_size)
+                        .map(|weights| WeightedIndex::new(weights).ok())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn sample_value_index(&mut self, sampler: &CliqueSampler, value_indexes: &[usize]) -> usize {
+        let distribution_index = sampler
+            .conditioned_on
+            .iter()
+            .zip(sampler.conditioned_on_shape.iter())
+            .fold(0, |acc, (column, size)| acc * size + value_indexes[*column]);
+
+        match &sampler.distributions[distribution_index] {
+            Some(distribution) => distribution.sample(&mut self.rng),
+            None => EMPTY_VALUE_INDEX,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+use super::marginal_table::MarginalTable;
+use crate::{data_block::DataBlockValue, processing::aggregator::AggregatedData};
+
+/// Index of the empty value on every column domain
+pub const EMPTY_VALUE_INDEX: usize = 0;
+
+/// Counts of the value combinations reported for a given set of columns,
+/// keyed by the value indexes (ordered by column)
+type CountsByValueIndexes = FnvHashMap<Vec<usize>, f64>;
+
+/// Builds marginal tables from the (possibly noisy) aggregated counts
+///
+/// Aggregates only report combinations of non-empty values, so the counts
+/// where some columns are empty are derived from the lower order aggregates
+pub struct NoisyMarginals {
+    /// Number of records the marginals should add up to
+    number_of_records: f64,
+    /// Values of each column, where the index `EMPTY_VALUE_INDEX` means empty
+    domains: Vec<Vec<Option<Arc<DataBlockValue>>>>,
+    /// Reported counts grouped by the set of columns of the combination
+    counts_by_columns: FnvHashMap<Vec<usize>, CountsByValueIndexes>,
+    /// Maximum length of the reported combinations
+    reporting_length: usize,
+}
+
+impl NoisyMarginals {
+    /// Returns a new NoisyMarginals
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data (with DP noise or not) to build the marginals from
+    pub fn new(aggregated_data: &AggregatedData) -> NoisyMarginals {
+        let mut domains: Vec<Vec<Option<Arc<DataBlockValue>>>> =
+            vec![vec![None]; aggregated_data.headers.len()];
+        let mut value_indexes: FnvHashMap<Arc<DataBlockValue>, usize> = FnvHashMap::default();
+        let mut single_values: Vec<&Arc<DataBlockValue>> = aggregated_data
+            .aggregates_count
+            .keys()
+            .filter(|comb| comb.len() == 1)
+            .map(|comb| &comb[0])
+            .collect();
+
+        // sort so sampling is reproducible between runs
+        single_values.sort();
+
+        for value in single_values {
+            let domain = &mut domains[value.column_index];
+
+            value_indexes.insert(value.clone(), domain.len());
+            domain.push(Some(value.clone()));
+        }
+
+        let mut counts_by_columns: FnvHashMap<Vec<usize>, CountsByValueIndexes> =
+            FnvHashMap::default();
+
+        for (comb, count) in aggregated_data.aggregates_count.iter() {
+            let mut indexed: Vec<(usize, usize)> = Vec::with_capacity(comb.len());
+
+            for value in comb.iter() {
+                match value_indexes.get(value) {
+                    Some(index) => indexed.push((value.column_index, *index)),
+                    // the single value was suppressed, so the combination
+                    // can not be represented on the domains
+                    None => break,
+                }
+            }
+
+            if indexed.len() == comb.len() {
+                indexed.sort_unstable();
+
+                counts_by_columns
+                    .entry(indexed.iter().map(|(column, _)| *column).collect())
+                    .or_default()
+                    .insert(
+                        indexed.iter().map(|(_, index)| *index).collect(),
+                        count.count as f64,
+                    );
+            }
+        }
+
+        NoisyMarginals {
+            number_of_records: aggregated_data
+                .protected_number_of_records
+                .unwrap_or(aggregated_data.number_of_records) as f64,
+            domains,
+            counts_by_columns,
+            reporting_length: aggregated_data.reporting_length,
+        }
+    }
+
+    #[inline]
+    pub fn number_of_records(&self) -> f64 {
+        self.number_of_records
+    }
+
+    #[inline]
+    pub fn number_of_columns(&self) -> usize {
+        self.domains.len()
+    }
+
+    #[inline]
+    pub fn reporting_length(&self) -> usize {
+        self.reporting_length
+    }
+
+    /// Number of values of `column` (including the empty value)
+    #[inline]
+    pub fn domain_size(&self, column: usize) -> usize {
+        self.domains[column].len()
+    }
+
+    /// Value of `column` at `value_index` (`None` if empty)
+    #[inline]
+    pub fn value(&self, column: usize, value_index: usize) -> &Option<Arc<DataBlockValue>> {
+        &self.domains[column][value_index]
+    }
+
+    /// Builds the marginal table over `columns`, which should have at
+    /// most `reporting_length` columns
+    ///
+    /// Cells with empty values are derived by inclusion-exclusion and
+    /// negative counts (caused by noise) are clamped to zero
+    /// # Arguments
+    /// * `columns` - Columns covered by the marginal
+    pub fn marginal(&self, columns: &[usize]) -> MarginalTable {
+        assert!(
+            columns.len() <= self.reporting_length,
+            "marginals can not have more columns than the reporting length"
+        );
+
+        let shape: Vec<usize> = columns.iter().map(|c| self.domain_size(*c)).collect();
+        let mut table = MarginalTable::new(columns.to_vec(), shape, 0.0);
+
+        // first fill each cell with the count of records containing at least
+        // its non-empty values (the cell with all values empty holds the total)
+        for subset in 0..(1_usize << columns.len()) {
+            let positions: Vec<usize> = (0..columns.len())
+                .filter(|p| subset & (1 << p) != 0)
+                .collect();
+
+            if positions.is_empty() {
+                table.set(
+                    &vec![EMPTY_VALUE_INDEX; columns.len()],
+                    self.number_of_records,
+                );
+                continue;
+            }
+
+            let mut subset_columns: Vec<(usize, usize)> =
+                positions.iter().map(|p| (columns[*p], *p)).collect();
+
+            subset_columns.sort_unstable();
+
+            if let Some(counts) = self
+                .counts_by_columns
+                .get(&subset_columns.iter().map(|(c, _)| *c).collect::<Vec<_>>())
+            {
+                let mut cell = vec![EMPTY_VALUE_INDEX; columns.len()];
+
+                for (value_indexes, count) in counts.iter() {
+                    for ((_, p), value_index) in subset_columns.iter().zip(value_indexes.iter()) {
+                        cell[*p] = *value_index;
+                    }
+                    table.set(&cell, *count);
+                }
+            }
+        }
+
+        // then turn the "at least" counts into exact counts, one column at a time:
+        // records with an empty value are the ones not having any other value
+        for position in 0..columns.len() {
+            for cell_index in 0..table.values().len() {
+                let mut cell = table.value_indexes(cell_index);
+
+                if cell[position] != EMPTY_VALUE_INDEX {
+                    continue;
+                }
+
+                let mut non_empty_sum = 0.0;
+
+                for value_index in 1..table.shape()[position] {
+                    cell[position] = value_index;
+                    non_empty_sum += table.get(&cell);
+                }
+                cell[position] = EMPTY_VALUE_INDEX;
+                table.add(&cell, -non_empty_sum);
+            }
+        }
+
+        table.clamp_negatives();
+        table
+    }
+}
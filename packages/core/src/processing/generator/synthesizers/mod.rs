@@ -2,8 +2,12 @@ mod aggregate_seeded;
 mod attribute_rows_sampler;
 mod cache;
 mod consolidate_parameters;
+mod constraint_rules;
+mod marginal_model;
 mod oversampling_parameters;
+mod parent_child;
 mod row_seeded;
+mod synthesis_conditions;
 mod traits;
 mod typedefs;
 mod unseeded;
@@ -11,8 +15,12 @@ mod value_seeded;
 
 pub use aggregate_seeded::*;
 pub use cache::*;
+pub use constraint_rules::*;
+pub use marginal_model::*;
 pub use oversampling_parameters::*;
+pub use parent_child::*;
 pub use row_seeded::*;
+pub use synthesis_conditions::*;
 pub use typedefs::*;
 pub use unseeded::*;
 pub use value_seeded::*;
@@ -0,0 +1,465 @@
+mod parent_child_link;
+
+pub use parent_child_link::*;
+
+use fnv::FnvHashMap;
+use log::{info, warn};
+use rand::{seq::SliceRandom, Rng};
+use std::sync::Arc;
+
+use crate::{
+    data_block::{
+        AttributeRows, AttributeRowsMap, DataBlock, DataBlockHeaders, DataBlockHeadersSlice,
+        DataBlockParts, DataBlockRecord, DataBlockValue,
+    },
+    processing::{
+        aggregator::ValueCombination,
+        generator::synthesizers::{
+            attribute_rows_sampler::AttributeRowsSampler,
+            cache::SynthesizerCache,
+            row_seeded::RowSeededSynthesizer,
+            typedefs::{NotAllowedAttrSet, SynthesizedRecord, SynthesizedRecords, SynthesizerSeed},
+        },
+    },
+    utils::{
+        collections::{ordered_vec_intersection, sample_weighted},
+        math::calc_percentage,
+        random::{new_rng_for_stream, resolve_seed, SeededRng},
+        reporting::{ReportProgress, StoppableResult},
+        time::ElapsedDurationLogger,
+    },
+};
+
+/// Header of the column added to the parent table with the number of children
+/// of each parent, so it is synthesized together with the parent attributes
+/// (suffixed with a number if a parent column already has this name)
+const NUMBER_OF_CHILDREN_HEADER: &str = "number_of_children";
+
+/// Synthesized parent record and the child records linked to it
+pub type SynthesizedParentWithChildren = (SynthesizedRecord, SynthesizedRecords);
+
+/// Represents all the information required to synthesize a parent table
+/// and a child table linked to it
+pub struct ParentChildSynthesizer {
+    /// Parent attributes, with the (bounded) number of children of each parent
+    /// on the last column
+    parent_data_block: Arc<DataBlock>,
+    /// Child attributes, preceded by the attributes of their parent
+    /// (at most `max_children_per_parent` children of each parent)
+    child_data_block: Arc<DataBlock>,
+    /// Maps a child data block value to all the rows where it occurs
+    child_attr_rows_map: AttributeRowsMap,
+    /// Number of parent attribute columns (the child attributes start at this index)
+    parent_headers_len: usize,
+    /// Reporting resolution used for data synthesis
+    resolution: usize,
+    /// Maximum cache size allowed
+    cache_max_size: usize,
+    /// Seed used to derive the random number generators for each child record
+    seed: u64,
+    /// Random number generator used for the sequential steps
+    rng: SeededRng,
+}
+
+impl ParentChildSynthesizer {
+    /// Returns a new ParentChildSynthesizer
+    /// # Arguments
+    /// * `link` - Parent and child tables and how their records are linked
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `max_children_per_parent` - Maximum number of children of each parent (children over it are ignored)
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    pub fn new(
+        link: &ParentChildLink,
+        resolution: usize,
+        cache_max_size: usize,
+        max_children_per_parent: usize,
+        seed: Option<u64>,
+    ) -> ParentChildSynthesizer {
+        let seed = resolve_seed(seed);
+        let parent_columns = link.calc_parent_attribute_columns();
+        let child_columns = link.calc_child_attribute_columns();
+        let parent_headers_len = parent_columns.len();
+        let parent_records: Vec<SynthesizerSeed> = link
+            .parent_data_block
+            .records
+            .iter()
+            .map(|record| {
+                ParentChildSynthesizer::project_values(&record.values, &parent_columns, 0)
+            })
+            .collect();
+        let mut parent_headers =
+            ParentChildSynthesizer::project_headers(&link.parent_data_block, &parent_columns);
+        let mut parent_records_with_children = Vec::default();
+        let mut child_records = Vec::default();
+
+        for (parent_record, children) in parent_records
+            .iter()
+            .zip(link.calc_children_by_parent().iter())
+        {
+            let children = &children[..children.len().min(max_children_per_parent)];
+            let mut parent_values = parent_record.clone();
+
+            parent_values.push(Arc::new(DataBlockValue::new(
+                parent_headers_len,
+                Arc::new(children.len().to_string()),
+            )));
+            parent_records_with_children.push(Arc::new(DataBlockRecord::new(parent_values)));
+
+            for child_index in children.iter() {
+                let mut child_values = parent_record.clone();
+
+                child_values.extend(ParentChildSynthesizer::project_values(
+                    &link.child_data_block.records[*child_index].values,
+                    &child_columns,
+                    parent_headers_len,
+                ));
+                child_records.push(Arc::new(DataBlockRecord::new(child_values)));
+            }
+        }
+
+        let mut child_headers = parent_headers.clone();
+
+        child_headers.extend(ParentChildSynthesizer::project_headers(
+            &link.child_data_block,
+            &child_columns,
+        ));
+        parent_headers.push(Arc::new(
+            ParentChildSynthesizer::gen_number_of_children_header(&parent_headers),
+        ));
+
+        let child_data_block = Arc::new(ParentChildSynthesizer::new_data_block(
+            child_headers,
+            child_records,
+        ));
+
+        ParentChildSynthesizer {
+            parent_data_block: Arc::new(ParentChildSynthesizer::new_data_block(
+                parent_headers,
+                parent_records_with_children,
+            )),
+            child_attr_rows_map: child_data_block.calc_attr_rows(),
+            child_data_block,
+            parent_headers_len,
+            resolution,
+            cache_max_size,
+            seed,
+            // stream 0 is used for the sequential steps, child records use the following ones
+            rng: new_rng_for_stream(seed, 0),
+        }
+    }
+
+    /// Headers of the synthesized parent records
+    #[inline]
+    pub fn parent_headers(&self) -> &DataBlockHeadersSlice {
+        &self.parent_data_block.headers[..self.parent_headers_len]
+    }
+
+    /// Headers of the synthesized child records
+    #[inline]
+    pub fn child_headers(&self) -> &DataBlockHeadersSlice {
+        &self.child_data_block.headers[self.parent_headers_len..]
+    }
+
+    /// Number of sensitive parent records
+    #[inline]
+    pub fn number_of_parents(&self) -> usize {
+        self.parent_data_block.number_of_records()
+    }
+
+    /// Number of sensitive child records used for synthesis
+    /// (linked to a parent and within the bound of children per parent)
+    #[inline]
+    pub fn number_of_children(&self) -> usize {
+        self.child_data_block.number_of_records()
+    }
+
+    /// Synthesizes the parent records first, and then the children of each
+    /// synthesized parent, sampled from the child attributes conditioned
+    /// on the parent attributes.
+    /// Returns the synthesized parents with their children
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn run<T>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<Vec<SynthesizedParentWithChildren>>
+    where
+        T: ReportProgress,
+    {
+        let mut synthesized_parents = self.synthesize_parents(progress_reporter)?;
+        let numbers_of_children = self.take_numbers_of_children(&mut synthesized_parents);
+
+        self.synthesize_children(synthesized_parents, numbers_of_children, progress_reporter)
+    }
+
+    #[inline]
+    fn new_data_block(headers: DataBlockHeaders, records: Vec<Arc<DataBlockRecord>>) -> DataBlock {
+        DataBlock::new(DataBlockParts {
+            headers,
+            records,
+            ..DataBlockParts::default()
+        })
+    }
+
+    /// Returns a header for the number of children that does not
+    /// clash with any of the `parent_headers`
+    #[inline]
+    fn gen_number_of_children_header(parent_headers: &DataBlockHeadersSlice) -> String {
+        let mut header = NUMBER_OF_CHILDREN_HEADER.to_owned();
+        let mut suffix = 0;
+
+        while parent_headers.iter().any(|h| **h == header) {
+            suffix += 1;
+            header = format!("{}_{}", NUMBER_OF_CHILDREN_HEADER, suffix);
+        }
+        header
+    }
+
+    #[inline]
+    fn project_headers(data_block: &DataBlock, columns: &[usize]) -> DataBlockHeaders {
+        columns
+            .iter()
+            .map(|column_index| data_block.headers[*column_index].clone())
+            .collect()
+    }
+
+    /// Keeps only the values on `columns`, moving each one to
+    /// `offset` + the position of its column on `columns`
+    #[inline]
+    fn project_values(
+        values: &[Arc<DataBlockValue>],
+        columns: &[usize],
+        offset: usize,
+    ) -> SynthesizerSeed {
+        values
+            .iter()
+            .filter_map(|value| {
+                columns
+                    .iter()
+                    .position(|column_index| *column_index == value.column_index)
+                    .map(|position| {
+                        Arc::new(DataBlockValue::new(position + offset, value.value.clone()))
+                    })
+            })
+            .collect()
+    }
+
+    fn synthesize_parents<T>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<SynthesizedRecords>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("parents synthesis");
+
+        info!("synthesizing parent records...");
+
+        RowSeededSynthesizer::new(
+            self.parent_data_block.clone(),
+            Arc::new(self.parent_data_block.calc_attr_rows()),
+            self.resolution,
+            self.cache_max_size,
+            Some(self.rng.gen()),
+            None,
+            None,
+        )
+        .run(progress_reporter)
+    }
+
+    /// Removes the number of children from the synthesized parents, returning it.
+    /// When it was suppressed, it is sampled from the numbers of children of the other parents
+    fn take_numbers_of_children(
+        &mut self,
+        synthesized_parents: &mut SynthesizedRecords,
+    ) -> Vec<usize> {
+        let mut numbers_of_children: Vec<Option<usize>> = Vec::default();
+        let mut parents_by_number_of_children: FnvHashMap<usize, usize> = FnvHashMap::default();
+
+        for synthesized_parent in synthesized_parents.iter_mut() {
+            let number_of_children = synthesized_parent
+                .iter()
+                .find(|value| value.column_index == self.parent_headers_len)
+                .cloned();
+
+            numbers_of_children.push(number_of_children.map(|value| {
+                synthesized_parent.remove(&value);
+
+                let number_of_children = value.value.parse::<usize>().unwrap_or(0);
+
+                *parents_by_number_of_children
+                    .entry(number_of_children)
+                    .or_default() += 1;
+                number_of_children
+            }));
+        }
+
+        numbers_of_children
+            .iter()
+            .map(|number_of_children| match number_of_children {
+                Some(n) => *n,
+                None => sample_weighted(&parents_by_number_of_children, &mut self.rng).unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Selects the parent values the children will be conditioned on, keeping
+    /// the values (in column order) that still match at least `resolution` child rows.
+    /// Returns the selected values and the child rows matching them
+    #[inline]
+    fn calc_children_condition(
+        &self,
+        parent_values: &SynthesizerSeed,
+    ) -> (SynthesizerSeed, AttributeRows) {
+        let mut condition = SynthesizerSeed::default();
+        let mut matching_rows: AttributeRows = (0..self.child_data_block.records.len()).collect();
+
+        for value in parent_values.iter() {
+            let rows = match self.child_attr_rows_map.get(value) {
+                Some(rows) => ordered_vec_intersection(&matching_rows, rows),
+                None => continue,
+            };
+
+            if rows.len() >= self.resolution {
+                condition.push(value.clone());
+                matching_rows = rows;
+            }
+        }
+        (condition, matching_rows)
+    }
+
+    fn synthesize_children<T>(
+        &mut self,
+        synthesized_parents: SynthesizedRecords,
+        numbers_of_children: Vec<usize>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<Vec<SynthesizedParentWithChildren>>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("children synthesis");
+        let total = numbers_of_children.iter().sum::<usize>();
+        let total_f64 = total as f64;
+        let mut sampler = AttributeRowsSampler::new(
+            self.child_data_block.clone(),
+            self.resolution,
+            SynthesizerCache::new(self.cache_max_size),
+            None,
+        );
+        let not_allowed_attr_set = NotAllowedAttrSet::default();
+        // condition, shuffled child rows matching it and the next one to be used
+        let mut rows_by_parent: FnvHashMap<
+            SynthesizerSeed,
+            (SynthesizerSeed, AttributeRows, usize),
+        > = FnvHashMap::default();
+        let mut n_processed = 0;
+        let mut n_skipped_parents = 0;
+        let mut n_skipped_children = 0;
+        let mut result = Vec::with_capacity(synthesized_parents.len());
+
+        info!("synthesizing {} child records...", total);
+
+        for (synthesized_parent, number_of_children) in
+            synthesized_parents.into_iter().zip(numbers_of_children)
+        {
+            let mut children = SynthesizedRecords::default();
+
+            if number_of_children > 0 {
+                // keep the column order, so sampling is reproducible
+                let mut parent_values: SynthesizerSeed =
+                    synthesized_parent.iter().cloned().collect();
+
+                parent_values.sort_by_key(|value| value.column_index);
+
+                if !rows_by_parent.contains_key(&parent_values) {
+                    let (condition, mut rows) = self.calc_children_condition(&parent_values);
+
+                    if rows.len() < self.resolution {
+                        // synthesizing from less rows than the resolution
+                        // would disclose rare combinations
+                        warn!(
+                            "only {} child rows match {}, which is less than the resolution, skipping child records",
+                            rows.len(),
+                            ValueCombination::new(condition.clone())
+                        );
+                        rows.clear();
+                    }
+                    rows.shuffle(&mut self.rng);
+                    rows_by_parent.insert(parent_values.clone(), (condition, rows, 0));
+                }
+
+                let (condition, rows, next_row) = rows_by_parent.get_mut(&parent_values).unwrap();
+
+                if rows.is_empty() {
+                    // the parent is kept, but none of its children can be synthesized
+                    n_processed += number_of_children;
+                    n_skipped_parents += 1;
+                    n_skipped_children += number_of_children;
+
+                    if let Some(r) = progress_reporter.as_mut() {
+                        r.report(calc_percentage(n_processed as f64, total_f64))?;
+                    }
+                    result.push((synthesized_parent, children));
+                    continue;
+                }
+
+                for _ in 0..number_of_children {
+                    n_processed += 1;
+
+                    // each child record has its own stream
+                    let mut rng = new_rng_for_stream(self.seed, n_processed as u64);
+                    // only the child attributes are sampled, parent attributes
+                    // on the record come from the synthesized parent
+                    let current_seed: SynthesizerSeed = self.child_data_block.records
+                        [rows[*next_row % rows.len()]]
+                    .values
+                    .iter()
+                    .filter(|value| value.column_index >= self.parent_headers_len)
+                    .cloned()
+                    .collect();
+                    let mut synthesized_record: SynthesizedRecord =
+                        condition.iter().cloned().collect();
+
+                    *next_row += 1;
+
+                    while let Some(value) = sampler.sample_next_attr_from_seed(
+                        &synthesized_record,
+                        &current_seed,
+                        &not_allowed_attr_set,
+                        &self.child_attr_rows_map,
+                        &mut rng,
+                    ) {
+                        synthesized_record.insert(value);
+                    }
+
+                    children.push(
+                        synthesized_record
+                            .iter()
+                            .filter(|value| value.column_index >= self.parent_headers_len)
+                            .map(|value| {
+                                Arc::new(DataBlockValue::new(
+                                    value.column_index - self.parent_headers_len,
+                                    value.value.clone(),
+                                ))
+                            })
+                            .collect(),
+                    );
+
+                    if let Some(r) = progress_reporter.as_mut() {
+                        r.report(calc_percentage(n_processed as f64, total_f64))?;
+                    }
+                }
+            }
+            result.push((synthesized_parent, children));
+        }
+
+        if n_skipped_parents > 0 {
+            warn!(
+                "{} child records of {} synthesized parents were not synthesized, since less child rows than the resolution match them",
+                n_skipped_children, n_skipped_parents
+            );
+        }
+        Ok(result)
+    }
+}
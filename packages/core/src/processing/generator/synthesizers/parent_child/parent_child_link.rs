@@ -0,0 +1,148 @@
+use fnv::FnvHashMap;
+use log::warn;
+use std::sync::Arc;
+
+use crate::{data_block::DataBlock, utils::strings::transform_for_insensitive_cmp};
+
+/// Links the records of a child table to the records of its parent table
+/// (e.g. encounters to patients), so both can be synthesized together
+#[derive(Debug)]
+pub struct ParentChildLink {
+    /// Parent table, with records joined by the parent key
+    pub parent_data_block: Arc<DataBlock>,
+    /// Child table, with records kept separate and the foreign key as subject ID
+    pub child_data_block: Arc<DataBlock>,
+    /// Name of the parent key column
+    pub parent_key: String,
+    /// Name of the foreign key column on the child table
+    pub foreign_key: String,
+    /// Parent record index of each child record (`None` if no parent has its foreign key)
+    pub parent_index_by_child: Vec<Option<usize>>,
+}
+
+impl ParentChildLink {
+    /// Returns a new ParentChildLink, matching the foreign key of each
+    /// child record with the parent key
+    /// # Arguments
+    /// * `parent_data_block` - Parent table, created using the parent key as subject ID (`SubjectRecordsMode::Join`)
+    /// * `child_data_block` - Child table, created using the foreign key as subject ID (`SubjectRecordsMode::Keep`)
+    /// * `parent_key` - Name of the parent key column
+    /// * `foreign_key` - Name of the foreign key column on the child table
+    pub fn new(
+        parent_data_block: Arc<DataBlock>,
+        child_data_block: Arc<DataBlock>,
+        parent_key: &str,
+        foreign_key: &str,
+    ) -> Result<ParentChildLink, String> {
+        let parent_ids = match (
+            &parent_data_block.subject_ids,
+            &parent_data_block.subject_index_by_record,
+        ) {
+            (Some(parent_ids), None) => parent_ids,
+            _ => {
+                return Err(
+                    "parent records should be joined by the parent key (used as subject ID)"
+                        .to_owned(),
+                );
+            }
+        };
+        let (child_foreign_keys, subject_index_by_child) = match (
+            &child_data_block.subject_ids,
+            &child_data_block.subject_index_by_record,
+        ) {
+            (Some(child_foreign_keys), Some(subject_index_by_child)) => {
+                (child_foreign_keys, subject_index_by_child)
+            }
+            _ => {
+                return Err(
+                    "child records should be kept separate by the foreign key (used as subject ID)"
+                        .to_owned(),
+                );
+            }
+        };
+        let parent_index_by_id: FnvHashMap<&str, usize> = parent_ids
+            .iter()
+            .enumerate()
+            .map(|(parent_index, id)| (id.as_str(), parent_index))
+            .collect();
+        let parent_index_by_child: Vec<Option<usize>> = subject_index_by_child
+            .iter()
+            .map(|subject_index| {
+                parent_index_by_id
+                    .get(child_foreign_keys[*subject_index].as_str())
+                    .cloned()
+            })
+            .collect();
+        let link = ParentChildLink {
+            parent_data_block,
+            child_data_block,
+            parent_key: parent_key.to_owned(),
+            foreign_key: foreign_key.to_owned(),
+            parent_index_by_child,
+        };
+        let number_of_orphan_children = link.number_of_orphan_children();
+
+        if number_of_orphan_children == link.parent_index_by_child.len() {
+            return Err("no child record references a parent record".to_owned());
+        }
+        if number_of_orphan_children > 0 {
+            warn!(
+                "{} child records reference missing parent records and will be ignored",
+                number_of_orphan_children
+            );
+        }
+        Ok(link)
+    }
+
+    /// Number of child records whose foreign key does not match any parent
+    #[inline]
+    pub fn number_of_orphan_children(&self) -> usize {
+        self.parent_index_by_child
+            .iter()
+            .filter(|parent_index| parent_index.is_none())
+            .count()
+    }
+
+    /// Groups the child record indexes by parent (the vector index is the parent record index)
+    #[inline]
+    pub fn calc_children_by_parent(&self) -> Vec<Vec<usize>> {
+        let mut children_by_parent = vec![Vec::default(); self.parent_data_block.records.len()];
+
+        for (child_index, parent_index) in self.parent_index_by_child.iter().enumerate() {
+            if let Some(parent_index) = parent_index {
+                children_by_parent[*parent_index].push(child_index);
+            }
+        }
+        children_by_parent
+    }
+
+    /// Indexes of the parent table columns used as attributes (the parent key is not)
+    #[inline]
+    pub fn calc_parent_attribute_columns(&self) -> Vec<usize> {
+        ParentChildLink::calc_attribute_columns(&self.parent_data_block, &self.parent_key)
+    }
+
+    /// Indexes of the child table columns used as attributes (the foreign key is not)
+    #[inline]
+    pub fn calc_child_attribute_columns(&self) -> Vec<usize> {
+        ParentChildLink::calc_attribute_columns(&self.child_data_block, &self.foreign_key)
+    }
+
+    #[inline]
+    fn calc_attribute_columns(data_block: &DataBlock, key: &str) -> Vec<usize> {
+        let key_for_cmp = transform_for_insensitive_cmp(key);
+
+        data_block
+            .headers
+            .iter()
+            .enumerate()
+            .filter_map(|(column_index, header)| {
+                if transform_for_insensitive_cmp(header) == key_for_cmp {
+                    None
+                } else {
+                    Some(column_index)
+                }
+            })
+            .collect()
+    }
+}
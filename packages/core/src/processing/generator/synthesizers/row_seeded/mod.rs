@@ -1,27 +1,33 @@
 mod seeded_rows_synthesizer;
 
+use fnv::FnvHashMap;
 use itertools::{izip, Itertools};
-use log::info;
+use log::{info, warn};
+use rand::seq::SliceRandom;
 use seeded_rows_synthesizer::SeededRowsSynthesizer;
 use std::sync::Arc;
 
 use crate::{
-    data_block::{AttributeRowsMap, DataBlock, DataBlockHeaders, DataBlockValue},
+    data_block::{AttributeRows, AttributeRowsMap, DataBlock, DataBlockValue},
     processing::{
         aggregator::ValueCombination,
         generator::synthesizers::{
             attribute_rows_sampler::AttributeRowsSampler,
             cache::SynthesizerCache,
             consolidate_parameters::ConsolidateParameters,
+            constraint_rules::ConstraintRules,
+            synthesis_conditions::SynthesisConditions,
             traits::{Consolidate, ConsolidateContext, Suppress, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
-                SynthesizedRecords, SynthesizedRecordsSlice,
+                SynthesizedRecords, SynthesizedRecordsSlice, SynthesizerSeed,
             },
         },
     },
     utils::{
-        math::calc_percentage,
+        collections::ordered_vec_intersection,
+        math::{calc_percentage, uround_down},
+        random::{new_rng_for_stream, resolve_seed, SeededRng},
         reporting::{ReportProgress, StoppableResult},
         threading::get_number_of_threads,
         time::ElapsedDurationLogger,
@@ -48,6 +54,15 @@ pub struct RowSeededSynthesizer {
     consolidate_percentage: f64,
     /// Percentage already completed on the suppression step
     suppress_percentage: f64,
+    /// Seed used to derive the random number generators for each record
+    seed: u64,
+    /// Random number generator used for consolidation and suppression
+    rng: SeededRng,
+    /// Conditions the synthesized records should satisfy (if `None` the whole
+    /// population is synthesized)
+    conditions: Option<SynthesisConditions>,
+    /// Rules used to exclude attributes leading to invalid combinations
+    constraint_rules: Option<Arc<ConstraintRules>>,
 }
 
 impl RowSeededSynthesizer {
@@ -57,17 +72,25 @@ impl RowSeededSynthesizer {
     /// * `attr_rows_map` - Maps a data block value to all the rows where it occurs
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache_max_size` - Maximum cache size allowed
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    /// * `conditions` - If set, only records satisfying the conditions are synthesized (when values are required on every record, the number of sensitive rows containing them rounded down to the resolution is used)
+    /// * `constraint_rules` - Rules used to exclude attributes leading to invalid combinations (if `None` any combination is allowed)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
         attr_rows_map: Arc<AttributeRowsMap>,
         resolution: usize,
         cache_max_size: usize,
+        seed: Option<u64>,
+        conditions: Option<SynthesisConditions>,
+        constraint_rules: Option<Arc<ConstraintRules>>,
     ) -> RowSeededSynthesizer {
+        let seed = resolve_seed(seed);
         let consolidate_sampler = AttributeRowsSampler::new(
             data_block.clone(),
             resolution,
             SynthesizerCache::new(cache_max_size),
+            constraint_rules.clone(),
         );
 
         RowSeededSynthesizer {
@@ -83,6 +106,11 @@ impl RowSeededSynthesizer {
             synthesize_percentage: 0.0,
             consolidate_percentage: 0.0,
             suppress_percentage: 0.0,
+            seed,
+            // stream 0 is used for the sequential steps, records use the following ones
+            rng: new_rng_for_stream(seed, 0),
+            conditions,
+            constraint_rules,
         }
     }
 
@@ -100,7 +128,17 @@ impl RowSeededSynthesizer {
     {
         let mut synthesized_records: SynthesizedRecords = SynthesizedRecords::new();
 
-        if !self.data_block.records.is_empty() {
+        if let Some(conditions) = self.conditions.clone() {
+            // consolidation and suppression balance the attribute counts of the
+            // whole population, so they are not performed for part of it
+            if !self.data_block.records.is_empty() {
+                self.synthesize_rows_with_conditions(
+                    &conditions,
+                    &mut synthesized_records,
+                    progress_reporter,
+                )?;
+            }
+        } else if !self.data_block.records.is_empty() {
             let mut rows_synthesizers: Vec<SeededRowsSynthesizer> = self.build_rows_synthesizers();
 
             self.synthesize_percentage = 0.0;
@@ -117,7 +155,10 @@ impl RowSeededSynthesizer {
             self.consolidate(
                 &mut synthesized_records,
                 progress_reporter,
-                ConsolidateParameters::default(),
+                ConsolidateParameters {
+                    constraint_rules: self.constraint_rules.clone(),
+                    ..ConsolidateParameters::default()
+                },
             )?;
             self.suppress(&mut synthesized_records, progress_reporter)?;
         }
@@ -130,15 +171,21 @@ impl RowSeededSynthesizer {
             .ceil() as usize;
         let mut rows_synthesizers: Vec<SeededRowsSynthesizer> = Vec::default();
 
-        for c in &self.data_block.records.iter().chunks(chunk_size) {
+        for (i, c) in (&self.data_block.records.iter().chunks(chunk_size))
+            .into_iter()
+            .enumerate()
+        {
             rows_synthesizers.push(SeededRowsSynthesizer::new(
                 AttributeRowsSampler::new(
                     self.data_block.clone(),
                     self.resolution,
                     SynthesizerCache::new(self.cache_max_size),
+                    self.constraint_rules.clone(),
                 ),
                 c.cloned().collect(),
                 self.attr_rows_map.clone(),
+                self.seed,
+                i * chunk_size,
             ));
         }
         rows_synthesizers
@@ -174,6 +221,102 @@ impl RowSeededSynthesizer {
         Ok(())
     }
 
+    /// Rows of the data block containing all the `values`
+    #[inline]
+    fn calc_matching_rows(&self, values: &[Arc<DataBlockValue>]) -> AttributeRows {
+        let mut matching_rows: Option<AttributeRows> = None;
+
+        for value in values.iter() {
+            let rows = match self.attr_rows_map.get(value) {
+                Some(rows) => rows,
+                None => return AttributeRows::new(),
+            };
+
+            matching_rows = Some(match matching_rows {
+                Some(current_rows) => ordered_vec_intersection(&current_rows, rows),
+                None => rows.clone(),
+            });
+        }
+        matching_rows.unwrap_or_default()
+    }
+
+    fn synthesize_rows_with_conditions<T>(
+        &mut self,
+        conditions: &SynthesisConditions,
+        synthesized_records: &mut SynthesizedRecords,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<()>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("conditional rows synthesis");
+        let number_of_records = match conditions {
+            SynthesisConditions::RequiredValues(values) => uround_down(
+                self.calc_matching_rows(values).len() as f64,
+                self.resolution as f64,
+            ),
+            SynthesisConditions::TargetCounts(_) => 0,
+        };
+        let record_seeds = conditions.build_record_seeds(number_of_records);
+        let total = record_seeds.len() as f64;
+        let not_allowed_attr_set = NotAllowedAttrSet::default();
+        // shuffled sensitive rows matching each seed and the next one to be used
+        let mut rows_by_seed: FnvHashMap<SynthesizerSeed, (AttributeRows, usize)> =
+            FnvHashMap::default();
+
+        info!(
+            "synthesizing {} rows seeded with the conditions...",
+            record_seeds.len()
+        );
+
+        for (i, record_seed) in record_seeds.iter().enumerate() {
+            if !rows_by_seed.contains_key(record_seed) {
+                let mut rows = self.calc_matching_rows(record_seed);
+
+                if rows.len() < self.resolution {
+                    // synthesizing from less rows than the resolution
+                    // would disclose rare combinations
+                    warn!(
+                        "only {} sensitive rows match {}, which is less than the resolution, skipping records",
+                        rows.len(),
+                        ValueCombination::new(record_seed.clone())
+                    );
+                    rows.clear();
+                }
+                rows.shuffle(&mut self.rng);
+                rows_by_seed.insert(record_seed.clone(), (rows, 0));
+            }
+
+            let (rows, next_row) = rows_by_seed.get_mut(record_seed).unwrap();
+
+            if !rows.is_empty() {
+                // each record has its own stream, like when synthesizing all the rows
+                let mut rng = new_rng_for_stream(self.seed, (i + 1) as u64);
+                let current_seed = &self.data_block.records[rows[*next_row % rows.len()]].values;
+                let mut synthesized_record: SynthesizedRecord =
+                    record_seed.iter().cloned().collect();
+
+                *next_row += 1;
+
+                while let Some(value) = self.consolidate_sampler.sample_next_attr_from_seed(
+                    &synthesized_record,
+                    current_seed,
+                    &not_allowed_attr_set,
+                    &self.attr_rows_map,
+                    &mut rng,
+                ) {
+                    synthesized_record.insert(value);
+                }
+                synthesized_records.push(synthesized_record);
+            }
+
+            if let Some(r) = progress_reporter.as_mut() {
+                r.report(calc_percentage((i + 1) as f64, total))?;
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn update_synthesize_progress<T>(
         &mut self,
@@ -202,11 +345,6 @@ impl RowSeededSynthesizer {
 }
 
 impl SynthesisData for RowSeededSynthesizer {
-    #[inline]
-    fn get_headers(&self) -> &DataBlockHeaders {
-        &self.data_block.headers
-    }
-
     #[inline]
     fn get_single_attr_counts(&self) -> &AttributeCountMap {
         // get all the single attribute counts
@@ -255,6 +393,7 @@ impl Consolidate for RowSeededSynthesizer {
             &consolidate_context.current_seed,
             not_allowed_attr_set,
             &self.attr_rows_map,
+            &mut self.rng,
         )
     }
 
@@ -297,4 +436,9 @@ impl Suppress for RowSeededSynthesizer {
             })
             .unwrap_or_else(|| Ok(()))
     }
+
+    #[inline]
+    fn get_rng(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
 }
@@ -1,3 +1,4 @@
+use rand::Rng;
 use std::sync::Arc;
 
 use crate::{
@@ -10,6 +11,7 @@ use crate::{
     },
     utils::{
         collections::flat_map_unwrap_or_default,
+        random::new_rng_for_stream,
         reporting::{
             ReportProgress, SendableProgressReporter, SendableProgressReporterRef, StoppableResult,
         },
@@ -26,6 +28,8 @@ pub struct SeededRowsSynthesizer {
     pub sampler: AttributeRowsSampler,
     pub records: DataBlockRecords,
     pub attr_rows_map: Arc<AttributeRowsMap>,
+    pub seed: u64,
+    pub first_record_index: usize,
 }
 
 impl SeededRowsSynthesizer {
@@ -34,11 +38,15 @@ impl SeededRowsSynthesizer {
         sampler: AttributeRowsSampler,
         records: DataBlockRecords,
         attr_rows_map: Arc<AttributeRowsMap>,
+        seed: u64,
+        first_record_index: usize,
     ) -> SeededRowsSynthesizer {
         SeededRowsSynthesizer {
             sampler,
             records,
             attr_rows_map,
+            seed,
+            first_record_index,
         }
     }
 
@@ -105,15 +113,19 @@ impl SeededRowsSynthesizer {
         let mut synthesized_records = SynthesizedRecords::default();
         let records = self.records.clone();
 
-        for seed in records.iter() {
-            synthesized_records.push(self.synthesize_row(seed));
+        for (i, seed) in records.iter().enumerate() {
+            // each record has its own stream, so the result does not
+            // depend on how records are split across threads
+            let mut rng = new_rng_for_stream(self.seed, (self.first_record_index + i + 1) as u64);
+
+            synthesized_records.push(self.synthesize_row(seed, &mut rng));
             SendableProgressReporter::update_progress(progress_reporter, 1.0)?;
         }
         Ok(synthesized_records)
     }
 
     #[inline]
-    fn synthesize_row(&mut self, seed: &DataBlockRecord) -> SynthesizedRecord {
+    fn synthesize_row<R: Rng>(&mut self, seed: &DataBlockRecord, rng: &mut R) -> SynthesizedRecord {
         let current_seed: &SynthesizerSeed = &seed.values;
         let mut synthesized_record = SynthesizedRecord::default();
         let not_allowed_attr_set = NotAllowedAttrSet::default();
@@ -124,6 +136,7 @@ impl SeededRowsSynthesizer {
                 current_seed,
                 &not_allowed_attr_set,
                 &self.attr_rows_map,
+                rng,
             );
 
             match next {
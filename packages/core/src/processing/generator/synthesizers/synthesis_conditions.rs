@@ -0,0 +1,108 @@
+use fnv::FnvHashSet;
+use std::sync::Arc;
+
+use super::typedefs::SynthesizerSeed;
+use crate::data_block::{DataBlockHeadersSlice, DataBlockValue};
+
+/// Constraints the synthesized records should satisfy,
+/// used to synthesize only part of the population
+#[derive(Debug, Clone)]
+pub enum SynthesisConditions {
+    /// Every synthesized record contains all the values
+    /// (e.g. only records with `Region:North`)
+    RequiredValues(Vec<Arc<DataBlockValue>>),
+    /// Synthesizes exactly `count` records containing each value
+    /// (e.g. a stratified count per value of one column)
+    TargetCounts(Vec<(Arc<DataBlockValue>, usize)>),
+}
+
+impl SynthesisConditions {
+    /// Parses a value formatted as `{header_name}:{value}`
+    /// # Arguments
+    /// * `headers` - Data block headers used to look up the column index
+    /// * `str_value` - Value to be parsed
+    pub fn parse_value(
+        headers: &DataBlockHeadersSlice,
+        str_value: &str,
+    ) -> Result<Arc<DataBlockValue>, String> {
+        DataBlockValue::from_str_using_headers(headers, str_value)
+            .map(Arc::new)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Returns new conditions requiring all the `values` on every synthesized record
+    /// # Arguments
+    /// * `values` - Values required on every record, at most one per column
+    pub fn new_required_values(
+        values: Vec<Arc<DataBlockValue>>,
+    ) -> Result<SynthesisConditions, String> {
+        if values.is_empty() {
+            return Err("at least one required value should be provided".to_owned());
+        }
+
+        let mut columns: FnvHashSet<usize> = FnvHashSet::default();
+
+        if !values
+            .iter()
+            .all(|value| columns.insert(value.column_index))
+        {
+            return Err("required values should belong to different columns".to_owned());
+        }
+
+        let mut values = values;
+
+        values.sort();
+        Ok(SynthesisConditions::RequiredValues(values))
+    }
+
+    /// Returns new conditions synthesizing `count` records for each value
+    /// # Arguments
+    /// * `target_counts` - Number of records to be synthesized containing each value
+    pub fn new_target_counts(
+        target_counts: Vec<(Arc<DataBlockValue>, usize)>,
+    ) -> Result<SynthesisConditions, String> {
+        if target_counts.is_empty() {
+            return Err("at least one target count should be provided".to_owned());
+        }
+
+        let mut values: FnvHashSet<&Arc<DataBlockValue>> = FnvHashSet::default();
+
+        if !target_counts.iter().all(|(value, _)| values.insert(value)) {
+            return Err("target counts should not repeat values".to_owned());
+        }
+
+        // keep the seeds order stable between runs, so sampling is reproducible
+        let mut target_counts = target_counts;
+
+        target_counts.sort_by(|(v1, _), (v2, _)| {
+            v1.column_index
+                .cmp(&v2.column_index)
+                .then_with(|| v1.value.cmp(&v2.value))
+        });
+        Ok(SynthesisConditions::TargetCounts(target_counts))
+    }
+
+    /// Total number of records set by the conditions, if any
+    #[inline]
+    pub fn number_of_records(&self) -> Option<usize> {
+        match self {
+            SynthesisConditions::RequiredValues(_) => None,
+            SynthesisConditions::TargetCounts(target_counts) => {
+                Some(target_counts.iter().map(|(_, count)| count).sum())
+            }
+        }
+    }
+
+    /// Builds the values each synthesized record should be seeded with
+    /// # Arguments
+    /// * `number_of_records` - Number of records to be synthesized when values are required on every record (ignored for target counts)
+    pub fn build_record_seeds(&self, number_of_records: usize) -> Vec<SynthesizerSeed> {
+        match self {
+            SynthesisConditions::RequiredValues(values) => vec![values.clone(); number_of_records],
+            SynthesisConditions::TargetCounts(target_counts) => target_counts
+                .iter()
+                .flat_map(|(value, count)| (0..*count).map(move |_| vec![value.clone()]))
+                .collect(),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
             typedefs::{
                 AvailableAttrsMap, NotAllowedAttrSet, RawCombinationsCountMap, RawCombinationsSet,
                 SynthesizedRecord, SynthesizedRecords, SynthesizedRecordsSlice, SynthesizerSeed,
+                SynthesizerSeedSlice,
             },
         },
     },
@@ -96,7 +97,7 @@ pub trait Consolidate: SynthesisData {
         let mut local_synthetic_counts = RawCombinationsCountMap::default();
 
         // add the new sampled value to the combination
-        current_comb.extend(value.clone(), self.get_headers());
+        current_comb.extend(value.clone());
 
         if parameters.use_synthetic_counts || parameters.oversampling_ratio.is_some() {
             // process all combinations lengths up the reporting length
@@ -152,11 +153,32 @@ pub trait Consolidate: SynthesisData {
         true
     }
 
+    #[inline]
+    fn consume_available_attr(
+        &self,
+        consolidate_context: &mut ConsolidateContext,
+        not_allowed_attr_set: &mut NotAllowedAttrSet,
+        value: &Arc<DataBlockValue>,
+    ) {
+        match consolidate_context.available_attrs.get(value).cloned() {
+            Some(next_count) if next_count > 1 => {
+                consolidate_context
+                    .available_attrs
+                    .insert(value.clone(), next_count - 1);
+            }
+            _ => {
+                consolidate_context.available_attrs.remove(value);
+                not_allowed_attr_set.insert(value.clone());
+            }
+        }
+    }
+
     #[inline]
     fn consolidate_record(
         &mut self,
         consolidate_context: &mut ConsolidateContext,
         parameters: &ConsolidateParameters,
+        record_seed: &SynthesizerSeedSlice,
     ) -> SynthesizedRecord {
         let mut not_allowed_attr_set: NotAllowedAttrSet =
             self.calc_not_allowed_attrs(&consolidate_context.available_attrs);
@@ -166,6 +188,24 @@ pub trait Consolidate: SynthesisData {
         let oversampling_tries = parameters.oversampling_tries.unwrap_or(1);
         let mut n_tries = oversampling_tries;
 
+        // values required by the conditions are always added first,
+        // so the next attributes are sampled conditioned on them
+        for value in record_seed.iter() {
+            if !self.add_value_to_synthetic_record(
+                &mut synthesized_record,
+                value.clone(),
+                &mut consolidate_context.synthetic_counts,
+                &mut last_processed,
+                &mut processed_combinations,
+                parameters,
+            ) {
+                // required even if it leads to oversampling
+                last_processed.extend(value.clone());
+                synthesized_record.insert(value.clone());
+            }
+            self.consume_available_attr(consolidate_context, &mut not_allowed_attr_set, value);
+        }
+
         loop {
             let next = self.sample_next_attr(
                 consolidate_context,
@@ -174,6 +214,14 @@ pub trait Consolidate: SynthesisData {
                 &not_allowed_attr_set,
             );
 
+            if let (Some(value), Some(constraint_rules)) = (&next, &parameters.constraint_rules) {
+                if !constraint_rules.allows(&synthesized_record, value) {
+                    // exclude the candidate for this record and sample again
+                    not_allowed_attr_set.insert(value.clone());
+                    continue;
+                }
+            }
+
             match next {
                 None => break,
                 Some(value) => {
@@ -185,16 +233,11 @@ pub trait Consolidate: SynthesisData {
                         &mut processed_combinations,
                         parameters,
                     ) {
-                        let next_count = *consolidate_context.available_attrs.get(&value).unwrap();
-
-                        if next_count <= 1 {
-                            consolidate_context.available_attrs.remove(&value);
-                            not_allowed_attr_set.insert(value.clone());
-                        } else {
-                            consolidate_context
-                                .available_attrs
-                                .insert(value.clone(), next_count - 1);
-                        }
+                        self.consume_available_attr(
+                            consolidate_context,
+                            &mut not_allowed_attr_set,
+                            &value,
+                        );
                         n_tries = oversampling_tries;
                     } else {
                         n_tries -= 1;
@@ -227,8 +270,20 @@ pub trait Consolidate: SynthesisData {
 
         while !consolidate_context.available_attrs.is_empty() {
             self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
-            synthesized_records
-                .push(self.consolidate_record(&mut consolidate_context, &parameters));
+
+            let synthesized_record =
+                self.consolidate_record(&mut consolidate_context, &parameters, &[]);
+
+            if synthesized_record.is_empty() {
+                // none of the remaining attributes can be sampled
+                // (e.g. they are all excluded by the constraint rules)
+                info!(
+                    "{} attributes could not be consolidated",
+                    consolidate_context.available_attrs.values().sum::<isize>()
+                );
+                break;
+            }
+            synthesized_records.push(synthesized_record);
             n_processed =
                 (total - consolidate_context.available_attrs.values().sum::<isize>()) as usize;
         }
@@ -266,8 +321,11 @@ pub trait Consolidate: SynthesisData {
             }
 
             self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
-            synthesized_records
-                .push(self.consolidate_record(&mut consolidate_context, &parameters));
+            synthesized_records.push(self.consolidate_record(
+                &mut consolidate_context,
+                &parameters,
+                &[],
+            ));
             n_processed += 1;
         }
         self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
@@ -275,6 +333,62 @@ pub trait Consolidate: SynthesisData {
         Ok(())
     }
 
+    #[inline]
+    fn consolidate_with_conditions<T>(
+        &mut self,
+        synthesized_records: &mut SynthesizedRecords,
+        parameters: ConsolidateParameters,
+        original_available_attrs: AvailableAttrsMap,
+        mut consolidate_context: ConsolidateContext,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<()>
+    where
+        T: ReportProgress,
+    {
+        let record_seeds = parameters
+            .conditions
+            .as_ref()
+            .unwrap()
+            .build_record_seeds(parameters.target_number_of_records.unwrap_or(0));
+        let total_f64 = record_seeds.len() as f64;
+
+        info!(
+            "consolidating {} records seeded with the conditions...",
+            record_seeds.len()
+        );
+
+        for (n_processed, record_seed) in record_seeds.iter().enumerate() {
+            let seed_comb = ValueCombination::new(record_seed.clone());
+            let seed_exhausted = match consolidate_context.synthetic_counts.get(&seed_comb) {
+                Some(synthetic_count) => {
+                    match parameters.aggregated_data.aggregates_count.get(&seed_comb) {
+                        Some(count) => *synthetic_count >= count.count,
+                        None => true,
+                    }
+                }
+                None => false,
+            };
+
+            // if there are no more attributes available, or the records containing
+            // the seed have already been synthesized, reset the counts to the original ones
+            if consolidate_context.available_attrs.is_empty() || seed_exhausted {
+                consolidate_context.available_attrs = original_available_attrs.clone();
+                // make sure to clean the synthetic counts as well
+                consolidate_context.synthetic_counts.clear();
+            }
+
+            self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
+            synthesized_records.push(self.consolidate_record(
+                &mut consolidate_context,
+                &parameters,
+                record_seed,
+            ));
+        }
+        self.update_consolidate_progress(record_seeds.len(), total_f64, progress_reporter)?;
+
+        Ok(())
+    }
+
     fn consolidate<T>(
         &mut self,
         synthesized_records: &mut SynthesizedRecords,
@@ -296,7 +410,15 @@ pub trait Consolidate: SynthesisData {
             synthetic_counts: RawCombinationsCountMap::default(),
         };
 
-        if parameters.target_number_of_records.is_none() {
+        if parameters.conditions.is_some() {
+            self.consolidate_with_conditions(
+                synthesized_records,
+                parameters,
+                consolidate_context.available_attrs.clone(),
+                consolidate_context,
+                progress_reporter,
+            )
+        } else if parameters.target_number_of_records.is_none() {
             self.consolidate_with_available_attributes(
                 synthesized_records,
                 parameters,
@@ -1,7 +1,7 @@
 use super::SynthesisData;
 use fnv::FnvHashMap;
 use log::info;
-use rand::{prelude::SliceRandom, thread_rng};
+use rand::prelude::SliceRandom;
 use std::sync::Arc;
 
 use crate::{
@@ -9,6 +9,7 @@ use crate::{
     processing::generator::synthesizers::typedefs::{SynthesizedRecord, SynthesizedRecords},
     utils::{
         math::iround_down,
+        random::SeededRng,
         reporting::{ReportProgress, StoppableResult},
         time::ElapsedDurationLogger,
     },
@@ -68,7 +69,7 @@ pub trait Suppress: SynthesisData {
         let total = synthesized_records.len() as f64;
         let mut n_processed = 0;
 
-        synthesized_records.shuffle(&mut thread_rng());
+        synthesized_records.shuffle(self.get_rng());
 
         for r in synthesized_records.iter_mut() {
             let mut new_record = SynthesizedRecord::default();
@@ -105,4 +106,6 @@ pub trait Suppress: SynthesisData {
     ) -> StoppableResult<()>
     where
         T: ReportProgress;
+
+    fn get_rng(&mut self) -> &mut SeededRng;
 }
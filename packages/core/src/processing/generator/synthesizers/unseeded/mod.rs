@@ -9,6 +9,7 @@ use crate::{
     processing::generator::synthesizers::{cache::SynthesizerCache, typedefs::SynthesizedRecords},
     utils::{
         math::calc_percentage,
+        random::resolve_seed,
         reporting::{ReportProgress, StoppableResult},
         threading::get_number_of_threads,
         time::ElapsedDurationLogger,
@@ -29,6 +30,8 @@ pub struct UnseededSynthesizer {
     empty_value: Arc<String>,
     /// Percentage already completed on the row synthesis step
     synthesize_percentage: f64,
+    /// Seed used to derive the random number generators for each record
+    seed: u64,
 }
 
 impl UnseededSynthesizer {
@@ -39,6 +42,7 @@ impl UnseededSynthesizer {
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache_max_size` - Maximum cache size allowed
     /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
@@ -46,6 +50,7 @@ impl UnseededSynthesizer {
         resolution: usize,
         cache_max_size: usize,
         empty_value: Arc<String>,
+        seed: Option<u64>,
     ) -> UnseededSynthesizer {
         UnseededSynthesizer {
             data_block,
@@ -54,6 +59,7 @@ impl UnseededSynthesizer {
             cache_max_size,
             empty_value,
             synthesize_percentage: 0.0,
+            seed: resolve_seed(seed),
         }
     }
 
@@ -89,6 +95,7 @@ impl UnseededSynthesizer {
     #[inline]
     fn build_rows_synthesizers(&self) -> Vec<UnseededRowsSynthesizer> {
         let mut total_size = self.data_block.records.len();
+        let mut first_record_index = 0;
         let chunk_size = ((total_size as f64) / (get_number_of_threads() as f64)).ceil() as usize;
         let mut rows_synthesizers: Vec<UnseededRowsSynthesizer> = Vec::default();
 
@@ -101,8 +108,11 @@ impl UnseededSynthesizer {
                     chunk_size,
                     self.attr_rows_map_by_column.clone(),
                     self.empty_value.clone(),
+                    self.seed,
+                    first_record_index,
                 ));
                 total_size -= chunk_size;
+                first_record_index += chunk_size;
             } else {
                 rows_synthesizers.push(UnseededRowsSynthesizer::new(
                     SynthesizerCache::new(self.cache_max_size),
@@ -111,6 +121,8 @@ impl UnseededSynthesizer {
                     total_size,
                     self.attr_rows_map_by_column.clone(),
                     self.empty_value.clone(),
+                    self.seed,
+                    first_record_index,
                 ));
                 break;
             }
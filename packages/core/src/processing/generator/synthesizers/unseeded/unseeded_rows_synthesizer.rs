@@ -1,4 +1,4 @@
-use rand::{prelude::SliceRandom, thread_rng};
+use rand::{prelude::SliceRandom, Rng};
 use std::sync::Arc;
 
 use crate::{
@@ -15,6 +15,7 @@ use crate::{
     },
     utils::{
         collections::{flat_map_unwrap_or_default, ordered_vec_intersection, sample_weighted},
+        random::new_rng_for_stream,
         reporting::{SendableProgressReporter, SendableProgressReporterRef, StoppableResult},
     },
 };
@@ -35,10 +36,13 @@ pub struct UnseededRowsSynthesizer {
     column_indexes: Vec<usize>,
     attr_rows_map_by_column: Arc<AttributeRowsByColumnMap>,
     empty_value: Arc<String>,
+    seed: u64,
+    first_record_index: usize,
 }
 
 impl UnseededRowsSynthesizer {
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cache: SynthesizerCache<Arc<AttributeRows>>,
         data_block: Arc<DataBlock>,
@@ -46,6 +50,8 @@ impl UnseededRowsSynthesizer {
         chunk_size: usize,
         attr_rows_map_by_column: Arc<AttributeRowsByColumnMap>,
         empty_value: Arc<String>,
+        seed: u64,
+        first_record_index: usize,
     ) -> UnseededRowsSynthesizer {
         UnseededRowsSynthesizer {
             cache,
@@ -55,6 +61,8 @@ impl UnseededRowsSynthesizer {
             column_indexes: attr_rows_map_by_column.keys().cloned().collect(),
             attr_rows_map_by_column,
             empty_value,
+            seed,
+            first_record_index,
         }
     }
 
@@ -119,18 +127,26 @@ impl UnseededRowsSynthesizer {
         T: ReportProgress,
     {
         let mut synthesized_records = SynthesizedRecords::default();
-        let mut shuffled_column_indexes = self.column_indexes.clone();
 
-        for _ in 0..self.chunk_size {
-            shuffled_column_indexes.shuffle(&mut thread_rng());
-            synthesized_records.push(self.synthesize_row(&shuffled_column_indexes));
+        for i in 0..self.chunk_size {
+            // each record has its own stream, so the result does not
+            // depend on how records are split across threads
+            let mut rng = new_rng_for_stream(self.seed, (self.first_record_index + i) as u64);
+            let mut shuffled_column_indexes = self.column_indexes.clone();
+
+            shuffled_column_indexes.shuffle(&mut rng);
+            synthesized_records.push(self.synthesize_row(&shuffled_column_indexes, &mut rng));
             SendableProgressReporter::update_progress(progress_reporter, 1.0)?;
         }
         Ok(synthesized_records)
     }
 
     #[inline]
-    fn synthesize_row(&mut self, shuffled_column_indexes: &[usize]) -> SynthesizedRecord {
+    fn synthesize_row<R: Rng>(
+        &mut self,
+        shuffled_column_indexes: &[usize],
+        rng: &mut R,
+    ) -> SynthesizedRecord {
         let mut synthesized_record = SynthesizedRecord::default();
         let mut current_attrs_rows: Arc<AttributeRows> =
             Arc::new((0..self.data_block.number_of_records()).collect());
@@ -140,6 +156,7 @@ impl UnseededRowsSynthesizer {
                 &synthesized_record,
                 *column_index,
                 &current_attrs_rows,
+                rng,
             ) {
                 current_attrs_rows = next_attrs_rows;
                 synthesized_record.insert(sample);
@@ -149,11 +166,12 @@ impl UnseededRowsSynthesizer {
     }
 
     #[inline]
-    fn sample_next_attr_from_column(
+    fn sample_next_attr_from_column<R: Rng>(
         &mut self,
         synthesized_record: &SynthesizedRecord,
         column_index: usize,
         current_attrs_rows: &AttributeRowsSlice,
+        rng: &mut R,
     ) -> Option<(Arc<AttributeRows>, Arc<DataBlockValue>)> {
         let cache_key = SynthesizerCacheKey::new(self.data_block.headers.len(), synthesized_record);
         let empty_block_value =
@@ -185,7 +203,7 @@ impl UnseededRowsSynthesizer {
             if rows.len() < self.resolution {
                 // if the combination containing the attribute appears in less
                 // than resolution rows, we can't use it so we tag it as an empty value
-                rows_with_empty_values.extend(rows.iter());
+                rows_with_empty_values.extend(rows.iter().cloned());
             } else if **value != *empty_block_value {
                 // if we can use the combination containing the attribute
                 // gather its count for sampling
@@ -198,7 +216,7 @@ impl UnseededRowsSynthesizer {
             counts.insert(empty_block_value, rows_with_empty_values.len());
         }
 
-        sample_weighted(&counts).map(|sampled_value| {
+        sample_weighted(&counts, rng).map(|sampled_value| {
             (
                 values_to_sample.remove(&sampled_value).unwrap(),
                 sampled_value,
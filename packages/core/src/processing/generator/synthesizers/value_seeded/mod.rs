@@ -2,13 +2,14 @@ use super::OversamplingParameters;
 use std::sync::Arc;
 
 use crate::{
-    data_block::{AttributeRowsMap, DataBlock, DataBlockHeaders, DataBlockValue},
+    data_block::{AttributeRowsMap, DataBlock, DataBlockValue},
     processing::{
         aggregator::ValueCombination,
         generator::synthesizers::{
             attribute_rows_sampler::AttributeRowsSampler,
             cache::SynthesizerCache,
             consolidate_parameters::ConsolidateParameters,
+            constraint_rules::ConstraintRules,
             traits::{Consolidate, ConsolidateContext, Suppress, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
@@ -18,6 +19,7 @@ use crate::{
     },
     utils::{
         math::calc_percentage,
+        random::{new_rng, SeededRng},
         reporting::{ReportProgress, StoppableResult},
     },
 };
@@ -40,6 +42,8 @@ pub struct ValueSeededSynthesizer {
     consolidate_percentage: f64,
     /// Percentage already completed on the suppression step
     suppress_percentage: f64,
+    /// Random number generator used for sampling
+    rng: SeededRng,
 }
 
 impl ValueSeededSynthesizer {
@@ -51,6 +55,8 @@ impl ValueSeededSynthesizer {
     /// * `cache_max_size` - Maximum cache size allowed
     /// * `oversampling_parameters` - Parameters used to control oversampling
     /// (if `None`, oversampling will be unlimited)
+    /// * `seed` - Seed used for sampling (if `None` a random seed will be used)
+    /// * `constraint_rules` - Rules used to exclude attributes leading to invalid combinations (if `None` any combination is allowed)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
@@ -58,11 +64,14 @@ impl ValueSeededSynthesizer {
         resolution: usize,
         cache_max_size: usize,
         oversampling_parameters: Option<OversamplingParameters>,
+        seed: Option<u64>,
+        constraint_rules: Option<Arc<ConstraintRules>>,
     ) -> ValueSeededSynthesizer {
         let consolidate_sampler = AttributeRowsSampler::new(
             data_block.clone(),
             resolution,
             SynthesizerCache::new(cache_max_size),
+            constraint_rules.clone(),
         );
 
         ValueSeededSynthesizer {
@@ -82,11 +91,17 @@ impl ValueSeededSynthesizer {
                         params.oversampling_tries,
                         None,
                         false,
+                        None,
+                        constraint_rules.clone(),
                     )
                 })
-                .unwrap_or_default(),
+                .unwrap_or_else(|| ConsolidateParameters {
+                    constraint_rules,
+                    ..ConsolidateParameters::default()
+                }),
             consolidate_percentage: 0.0,
             suppress_percentage: 0.0,
+            rng: new_rng(seed),
         }
     }
 
@@ -127,11 +142,6 @@ impl ValueSeededSynthesizer {
 }
 
 impl SynthesisData for ValueSeededSynthesizer {
-    #[inline]
-    fn get_headers(&self) -> &DataBlockHeaders {
-        &self.data_block.headers
-    }
-
     #[inline]
     fn get_single_attr_counts(&self) -> &AttributeCountMap {
         // get all the single attribute counts
@@ -190,6 +200,7 @@ impl Consolidate for ValueSeededSynthesizer {
             &consolidate_context.current_seed,
             not_allowed_attr_set,
             &self.attr_rows_map,
+            &mut self.rng,
         )
     }
 
@@ -232,4 +243,9 @@ impl Suppress for ValueSeededSynthesizer {
             })
             .unwrap_or_else(|| Ok(()))
     }
+
+    #[inline]
+    fn get_rng(&mut self) -> &mut SeededRng {
+        &mut self.rng
+    }
 }
@@ -67,16 +67,18 @@ pub fn sort_unstable_f64(v: &mut [f64]) {
 /// Returns `None` if all the counts are 0 or the map is empty
 /// # Arguments
 /// * `counts` - Maps a key to its count for sampling
+/// * `rng` - Random number generator used for sampling
 #[inline]
-pub fn sample_weighted<K>(counts: &FnvHashMap<K, usize>) -> Option<K>
+pub fn sample_weighted<K, R>(counts: &FnvHashMap<K, usize>, rng: &mut R) -> Option<K>
 where
     K: Clone,
+    R: Rng,
 {
     let mut res: Option<K> = None;
     let total: usize = counts.values().sum();
 
     if total != 0 {
-        let random = rand::thread_rng().gen_range(1..=total);
+        let random = rng.gen_range(1..=total);
         let mut current_sum: usize = 0;
 
         for (value, count) in counts.iter().sorted_by_key(|(_, c)| **c) {
@@ -17,3 +17,13 @@ pub fn uround_down(value: f64, multiple: f64) -> usize {
 pub fn calc_percentage(n_processed: f64, total: f64) -> f64 {
     n_processed * 100.0 / total
 }
+
+/// Number of ways to choose `k` elements out of `n` (0 if `k > n`)
+#[inline]
+pub fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        0
+    } else {
+        (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+    }
+}
@@ -7,6 +7,9 @@ pub mod collections;
 /// Module for math utilities
 pub mod math;
 
+/// Module for random number generation utilities
+pub mod random;
+
 /// Module for strings utilities
 pub mod strings;
 
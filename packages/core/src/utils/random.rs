@@ -0,0 +1,38 @@
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+/// Random number generator used by all the sampling steps
+/// (same seed always produces the same sequence, regardless of the platform)
+pub type SeededRng = ChaCha12Rng;
+
+/// Returns `seed` if it is set, otherwise generates a new random seed
+/// # Arguments
+/// * `seed` - Optional seed
+#[inline]
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| thread_rng().gen())
+}
+
+/// Creates a new random number generator
+/// # Arguments
+/// * `seed` - Seed used to initialize the generator (if `None` a random seed is used)
+#[inline]
+pub fn new_rng(seed: Option<u64>) -> SeededRng {
+    SeededRng::seed_from_u64(resolve_seed(seed))
+}
+
+/// Creates a new random number generator for an independent stream of `seed`.
+///
+/// This should be used when sampling happens in parallel, so every unit
+/// of work (e.g. a record) gets its own stream and the results do not
+/// depend on how the work is split across threads
+/// # Arguments
+/// * `seed` - Seed used to initialize the generator
+/// * `stream` - Stream index
+#[inline]
+pub fn new_rng_for_stream(seed: u64, stream: u64) -> SeededRng {
+    let mut rng = SeededRng::seed_from_u64(seed);
+
+    rng.set_stream(stream);
+    rng
+}
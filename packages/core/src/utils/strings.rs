@@ -13,3 +13,9 @@ pub fn normalize_reserved_delimiters(value: &str) -> String {
 pub fn transform_for_insensitive_cmp(value: &str) -> String {
     value.trim().to_lowercase()
 }
+
+/// Reverts the changes made by `normalize_reserved_delimiters`
+#[inline]
+pub fn denormalize_reserved_delimiters(value: &str) -> String {
+    value.replace("<semicolon>", ";").replace("<colon>", ":")
+}
@@ -1,6 +1,4 @@
-use sds_core::data_block::{
-    DataBlock, DataBlockRecord, DataBlockValue, MultiValueColumnMetadataMap,
-};
+use sds_core::data_block::{DataBlock, DataBlockParts, DataBlockRecord, DataBlockValue};
 use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use crate::utils::read_test_data_block;
@@ -46,11 +44,11 @@ fn valid_all_columns_no_sensitive_zeros_no_record_limit() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
-                MultiValueColumnMetadataMap::default(),
-                expected_records,
-            )
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
 
@@ -91,11 +89,11 @@ fn valid_selected_columns_with_sensitive_zeros_no_record_limit() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
-                MultiValueColumnMetadataMap::default(),
-                expected_records
-            )
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
 
@@ -128,10 +126,10 @@ fn valid_selected_columns_with_sensitive_zeros_and_record_limit() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
-                MultiValueColumnMetadataMap::default(),
-                expected_records
-            )
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
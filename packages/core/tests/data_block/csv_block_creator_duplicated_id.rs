@@ -1,7 +1,10 @@
 use csv::ReaderBuilder;
 use itertools::Itertools;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlockCreator, RawData, RawDataMultiValueColumnJoiner},
+    data_block::{
+        CsvDataBlockCreator, DataBlockCreateOptions, DataBlockCreator, RawData,
+        RawDataMultiValueColumnJoiner, SubjectRecordsMode,
+    },
     processing::generator::SynthesizerCacheKey,
 };
 use std::sync::Arc;
@@ -20,11 +23,10 @@ fn invalid_duplicated_id_missing_id() {
         ReaderBuilder::new()
             .delimiter(DELIMITER)
             .from_path(get_path_on_resources(MISSING_ID_CSV_FILE_PATH)),
-        Some("ID".to_owned()),
-        &[],
-        &[].iter().cloned().collect(),
-        &[],
-        0,
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            ..DataBlockCreateOptions::default()
+        },
     );
 
     assert!(data_block.is_err());
@@ -40,11 +42,10 @@ fn invalid_duplicated_id_missing_multi_value_cols() {
         ReaderBuilder::new()
             .delimiter(DELIMITER)
             .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
-        Some("ID".to_owned()),
-        &[],
-        &[].iter().cloned().collect(),
-        &[],
-        0,
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            ..DataBlockCreateOptions::default()
+        },
     );
 
     assert!(data_block.is_err());
@@ -60,19 +61,18 @@ fn valid_duplicated_id() {
         ReaderBuilder::new()
             .delimiter(DELIMITER)
             .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
-        Some("ID".to_owned()),
-        &[],
-        &[
-            ("A".to_owned(), ";".to_owned()),
-            ("B".to_owned(), ";".to_owned()),
-            ("C".to_owned(), ";".to_owned()),
-            ("D".to_owned(), "|".to_owned()),
-        ]
-        .iter()
-        .cloned()
-        .collect(),
-        &[],
-        0,
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            multi_value_columns: [
+                ("A".to_owned(), ";".to_owned()),
+                ("B".to_owned(), ";".to_owned()),
+                ("C".to_owned(), ";".to_owned()),
+                ("D".to_owned(), "|".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+            ..DataBlockCreateOptions::default()
+        },
     )
     .unwrap();
     let mut raw_data = RawData::default();
@@ -108,3 +108,51 @@ fn valid_duplicated_id() {
             == expected
     );
 }
+
+#[test]
+fn valid_duplicated_id_kept_as_separate_records() {
+    let data_block = CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            subject_records_mode: SubjectRecordsMode::Keep,
+            ..DataBlockCreateOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert!(data_block.number_of_records() == 12);
+    assert!(data_block.subject_index_by_record == Some(vec![0, 1, 2, 3, 4, 5, 1, 6, 4, 7, 8, 4]));
+    assert!(
+        data_block.calc_records_by_subject()
+            == Some(vec![
+                vec![0],
+                vec![1, 6],
+                vec![2],
+                vec![3],
+                vec![4, 8, 11],
+                vec![5],
+                vec![7],
+                vec![9],
+                vec![10],
+            ])
+    );
+}
+
+#[test]
+fn invalid_kept_subject_records_missing_id() {
+    let data_block = CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(MISSING_ID_CSV_FILE_PATH)),
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            subject_records_mode: SubjectRecordsMode::Keep,
+            ..DataBlockCreateOptions::default()
+        },
+    );
+
+    assert!(data_block.is_err());
+}
@@ -1,5 +1,5 @@
 use sds_core::data_block::{
-    DataBlock, DataBlockRecord, DataBlockValue, MultiValueColumnMetadata,
+    DataBlock, DataBlockParts, DataBlockRecord, DataBlockValue, MultiValueColumnMetadata,
     MultiValueColumnMetadataMap,
 };
 use std::{str::FromStr, sync::Arc};
@@ -153,11 +153,12 @@ fn valid_all_columns_no_sensitive_zeros() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
                 multi_value_column_metadata_map,
-                expected_records
-            )
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
 
@@ -258,11 +259,12 @@ fn valid_selected_columns_no_sensitive_zeros() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
                 multi_value_column_metadata_map,
-                expected_records
-            )
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
 
@@ -390,10 +392,11 @@ fn valid_selected_columns_with_sensitive_zeros() {
 
     assert!(
         *data_block
-            == DataBlock::new(
-                expected_headers,
+            == DataBlock::new(DataBlockParts {
+                headers: expected_headers,
                 multi_value_column_metadata_map,
-                expected_records
-            )
+                records: expected_records,
+                ..DataBlockParts::default()
+            })
     );
 }
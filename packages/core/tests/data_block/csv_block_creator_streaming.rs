@@ -0,0 +1,82 @@
+use csv::{Error, ReaderBuilder};
+use sds_core::data_block::{
+    CsvDataBlockCreator, CsvDataBlockCreatorError, CsvRecord, DataBlock, DataBlockCreateOptions,
+    DataBlockCreator,
+};
+use std::sync::Arc;
+
+const TEST_CSV: &str = "A,B,C\na1,b1,c1\na1,b2,c1\na2,b1,\na1,b1,c2\n";
+
+const TEST_CSV_WITH_INVALID_LAST_RECORD: &str = "A,B\na1,b1\na2,b2\na3\n";
+
+fn create_from_str(
+    data: &str,
+    record_limit: usize,
+) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+    CsvDataBlockCreator::create(
+        Ok(ReaderBuilder::new().from_reader(data.as_bytes())),
+        &DataBlockCreateOptions {
+            record_limit,
+            ..DataBlockCreateOptions::default()
+        },
+    )
+}
+
+/// Creator that can only provide all the records at once
+struct InMemoryDataBlockCreator;
+
+impl DataBlockCreator for InMemoryDataBlockCreator {
+    type InputType = Vec<CsvRecord>;
+    type ErrorType = Error;
+
+    fn get_headers(input: &mut Self::InputType) -> Result<CsvRecord, Self::ErrorType> {
+        Ok(input.remove(0))
+    }
+
+    fn get_records(input: &mut Self::InputType) -> Result<Vec<CsvRecord>, Self::ErrorType> {
+        Ok(std::mem::take(input))
+    }
+}
+
+#[test]
+fn valid_repeated_values_are_shared() {
+    let data_block = create_from_str(TEST_CSV, 0).unwrap();
+    let records = &data_block.records;
+
+    assert!(records.len() == 4);
+    // a1 on column A
+    assert!(Arc::ptr_eq(&records[0].values[0], &records[1].values[0]));
+    assert!(Arc::ptr_eq(&records[0].values[0], &records[3].values[0]));
+    // b1 on column B
+    assert!(Arc::ptr_eq(&records[0].values[1], &records[2].values[1]));
+    assert!(!Arc::ptr_eq(&records[0].values[1], &records[1].values[1]));
+    // c1 on column C
+    assert!(Arc::ptr_eq(&records[0].values[2], &records[1].values[2]));
+}
+
+#[test]
+fn valid_reading_stops_at_record_limit() {
+    // the invalid record is never read
+    let data_block = create_from_str(TEST_CSV_WITH_INVALID_LAST_RECORD, 2).unwrap();
+
+    assert!(data_block.number_of_records() == 2);
+    assert!(create_from_str(TEST_CSV_WITH_INVALID_LAST_RECORD, 0).is_err());
+}
+
+#[test]
+fn valid_creator_without_streaming_support() {
+    let input: Vec<CsvRecord> = TEST_CSV
+        .lines()
+        .map(|line| line.split(',').map(|value| value.to_owned()).collect())
+        .collect();
+    let data_block = InMemoryDataBlockCreator::create(
+        Ok(input),
+        &DataBlockCreateOptions {
+            record_limit: 3,
+            ..DataBlockCreateOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert!(*data_block == *create_from_str(TEST_CSV, 3).unwrap());
+}
@@ -0,0 +1,161 @@
+use csv::ReaderBuilder;
+use sds_core::{
+    data_block::{
+        CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreateOptions,
+        DataBlockCreator, DateTimeColumnSpec, DateTimeGranularity,
+    },
+    processing::generator::GeneratedData,
+};
+use std::sync::Arc;
+
+use crate::utils::get_path_on_resources;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_date_time_generalization.csv";
+
+fn read_generalized_data_block(
+    date_time_columns: &[(&str, Option<&str>, DateTimeGranularity)],
+) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+    CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(TEST_FILE_PATH)),
+        &DataBlockCreateOptions {
+            use_columns: vec!["Timestamp".to_owned(), "Day".to_owned(), "Value".to_owned()],
+            date_time_columns: date_time_columns
+                .iter()
+                .map(|(column, input_format, granularity)| {
+                    (
+                        column.to_string(),
+                        DateTimeColumnSpec::new(
+                            input_format.map(|f| f.to_owned()),
+                            granularity.clone(),
+                        ),
+                    )
+                })
+                .collect(),
+            ..DataBlockCreateOptions::default()
+        },
+    )
+}
+
+fn column_values(data_block: &DataBlock, column_index: usize) -> Vec<String> {
+    data_block
+        .records
+        .iter()
+        .map(|r| {
+            r.values
+                .iter()
+                .find(|v| v.column_index == column_index)
+                .map(|v| (*v.value).clone())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[test]
+fn valid_year_quarter_and_month_generalization() {
+    for (granularity, expected) in [
+        (DateTimeGranularity::Year, ["2021", "2021", "2022", ""]),
+        (
+            DateTimeGranularity::Quarter,
+            ["2021-Q3", "2021-Q4", "2022-Q1", ""],
+        ),
+        (
+            DateTimeGranularity::Month,
+            ["2021-07", "2021-11", "2022-01", ""],
+        ),
+    ] {
+        let data_block =
+            read_generalized_data_block(&[("timestamp", None, granularity.clone())]).unwrap();
+
+        assert!(column_values(&data_block, 0) == expected);
+        assert!(
+            data_block.date_time_metadata_map[&Arc::new("Timestamp".to_owned())] == granularity
+        );
+    }
+}
+
+#[test]
+fn valid_week_day_of_week_and_hour_bucket_generalization() {
+    let data_block = read_generalized_data_block(&[
+        ("Timestamp", None, DateTimeGranularity::HourBucket(6)),
+        ("Day", None, DateTimeGranularity::DayOfWeek),
+    ])
+    .unwrap();
+
+    assert!(column_values(&data_block, 0) == ["06h-11h", "18h-23h", "06h-11h", ""]);
+    assert!(column_values(&data_block, 1) == ["Monday", "Saturday", "Sunday", "Tuesday"]);
+
+    let data_block =
+        read_generalized_data_block(&[("Day", None, DateTimeGranularity::Week)]).unwrap();
+
+    assert!(column_values(&data_block, 1) == ["2021-W27", "2021-W46", "2021-W52", "2021-W27"]);
+}
+
+#[test]
+fn invalid_date_time_generalization() {
+    // values not matching the provided input format
+    assert!(
+        read_generalized_data_block(&[("Day", Some("%Y/%m/%d"), DateTimeGranularity::Month)])
+            .is_err()
+    );
+    // not a date/time column
+    assert!(read_generalized_data_block(&[("Value", None, DateTimeGranularity::Month)]).is_err());
+    // invalid hour bucket size
+    assert!(read_generalized_data_block(&[(
+        "Timestamp",
+        None,
+        DateTimeGranularity::HourBucket(0)
+    )])
+    .is_err());
+}
+
+#[test]
+fn valid_date_time_output_format() {
+    let data_block = read_generalized_data_block(&[
+        ("Timestamp", None, DateTimeGranularity::Month),
+        ("Day", None, DateTimeGranularity::DayOfWeek),
+    ])
+    .unwrap();
+    let generated_data = GeneratedData::new(
+        vec![
+            data_block.headers.clone(),
+            ["2021-07", "Sunday", "a"]
+                .map(|v| Arc::new(v.to_owned()))
+                .to_vec(),
+        ],
+        1.0,
+        data_block.multi_value_column_metadata_map.clone(),
+        data_block.numeric_bins_metadata_map.clone(),
+        data_block.date_time_metadata_map.clone(),
+    );
+
+    assert!(
+        generated_data
+            .synthetic_data_to_string(',', "", false, false, None)
+            .unwrap()
+            == "Timestamp,Day,Value\n2021-07,Sunday,a\n"
+    );
+    assert!(
+        generated_data
+            .synthetic_data_to_string(
+                ',',
+                "",
+                false,
+                false,
+                Some(
+                    [
+                        ("timestamp".to_owned(), "%d/%m/%Y".to_owned()),
+                        ("Day".to_owned(), "%a".to_owned())
+                    ]
+                    .iter()
+                    .cloned()
+                    .collect()
+                )
+            )
+            .unwrap()
+            == "Timestamp,Day,Value\n01/07/2021,Sun,a\n"
+    );
+}
@@ -4,8 +4,14 @@ mod csv_block_creator_duplicated_id;
 
 mod csv_block_creator_multi_value;
 
+mod csv_block_creator_streaming;
+
 mod csv_block_creator;
 
+mod date_time_generalization;
+
+mod numeric_binning;
+
 mod raw_data;
 
 mod value;
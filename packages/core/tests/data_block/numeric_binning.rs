@@ -0,0 +1,230 @@
+use csv::ReaderBuilder;
+use sds_core::{
+    data_block::{
+        CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreateOptions,
+        DataBlockCreator, NumericBinOutputMode, NumericBinningStrategy,
+    },
+    dp::{DpParameters, NoisyCountThreshold},
+    processing::{aggregator::Aggregator, generator::GeneratedData},
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::get_path_on_resources;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_numeric_binning.csv";
+
+fn read_binned_data_block(
+    numeric_binning: &HashMap<String, NumericBinningStrategy>,
+) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+    CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(TEST_FILE_PATH)),
+        &DataBlockCreateOptions {
+            use_columns: vec!["Age".to_owned(), "Income".to_owned(), "City".to_owned()],
+            numeric_binning: numeric_binning.clone(),
+            ..DataBlockCreateOptions::default()
+        },
+    )
+}
+
+fn column_values(data_block: &DataBlock, column_index: usize) -> Vec<String> {
+    data_block
+        .records
+        .iter()
+        .map(|r| {
+            r.values
+                .iter()
+                .find(|v| v.column_index == column_index)
+                .map(|v| (*v.value).clone())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[test]
+fn valid_fixed_width_binning() {
+    let data_block = read_binned_data_block(
+        &[("age".to_owned(), NumericBinningStrategy::FixedWidth(10.0))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .unwrap();
+
+    assert!(
+        column_values(&data_block, 0)
+            == vec!["[20, 30)", "[30, 40)", "[40, 50)", "[10, 20)", "[60, 70]", "[20, 30)"]
+    );
+    assert!(data_block.numeric_bins_metadata_map.len() == 1);
+    assert!(
+        data_block.numeric_bins_metadata_map[&Arc::new("Age".to_owned())]
+            .bins
+            .len()
+            == 6
+    );
+    assert!(data_block.numeric_bins_metadata_map[&Arc::new("Age".to_owned())].data_derived);
+}
+
+#[test]
+fn valid_quantile_binning() {
+    let data_block = read_binned_data_block(
+        &[("Income".to_owned(), NumericBinningStrategy::Quantile(2))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .unwrap();
+
+    assert!(
+        column_values(&data_block, 1)
+            == vec![
+                "[900, 2800.5)",
+                "[2800.5, 4100]",
+                "",
+                "[900, 2800.5)",
+                "[2800.5, 4100]",
+                "[2800.5, 4100]"
+            ]
+    );
+}
+
+#[test]
+fn valid_edges_binning() {
+    let data_block = read_binned_data_block(
+        &[(
+            "Age".to_owned(),
+            NumericBinningStrategy::Edges(vec![20.0, 30.0, 50.0]),
+        )]
+        .iter()
+        .cloned()
+        .collect(),
+    )
+    .unwrap();
+
+    // values outside the edges fall into the first/last bins
+    assert!(
+        column_values(&data_block, 0)
+            == vec!["[20, 30)", "[30, 50]", "[30, 50]", "[20, 30)", "[30, 50]", "[20, 30)"]
+    );
+    assert!(!data_block.numeric_bins_metadata_map[&Arc::new("Age".to_owned())].data_derived);
+}
+
+fn aggregate_binned_with_dp(numeric_binning: &HashMap<String, NumericBinningStrategy>) {
+    Aggregator::new(read_binned_data_block(numeric_binning).unwrap())
+        .aggregate_with_dp(
+            2,
+            &DpParameters::new(1.0, 0.001, 99, 0.1),
+            NoisyCountThreshold::Fixed(HashMap::default()),
+            &mut None::<sds_core::utils::reporting::LoggerProgressReporter>,
+        )
+        .unwrap();
+}
+
+#[test]
+fn valid_dp_with_edges_binning() {
+    aggregate_binned_with_dp(
+        &[(
+            "Age".to_owned(),
+            NumericBinningStrategy::Edges(vec![20.0, 30.0, 50.0]),
+        )]
+        .iter()
+        .cloned()
+        .collect(),
+    );
+}
+
+#[test]
+#[should_panic]
+fn invalid_dp_with_quantile_binning() {
+    aggregate_binned_with_dp(
+        &[("Income".to_owned(), NumericBinningStrategy::Quantile(2))]
+            .iter()
+            .cloned()
+            .collect(),
+    );
+}
+
+#[test]
+fn invalid_binning() {
+    assert!(read_binned_data_block(
+        &[("City".to_owned(), NumericBinningStrategy::FixedWidth(10.0))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .is_err());
+    assert!(read_binned_data_block(
+        &[(
+            "Age".to_owned(),
+            NumericBinningStrategy::Edges(vec![30.0, 20.0])
+        )]
+        .iter()
+        .cloned()
+        .collect(),
+    )
+    .is_err());
+    assert!(read_binned_data_block(
+        &[("Age".to_owned(), NumericBinningStrategy::Quantile(0))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .is_err());
+}
+
+#[test]
+fn valid_map_numeric_bins_to_values() {
+    let data_block = read_binned_data_block(
+        &[("Age".to_owned(), NumericBinningStrategy::FixedWidth(10.0))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .unwrap();
+    let aggregated_data = Aggregator::new(data_block.clone())
+        .aggregate(
+            2,
+            &mut None::<sds_core::utils::reporting::LoggerProgressReporter>,
+        )
+        .unwrap();
+    let synthetic_data = vec![
+        data_block.headers.clone(),
+        ["[20, 30)", "[900, 2800.5)", "x"]
+            .map(|v| Arc::new(v.to_owned()))
+            .to_vec(),
+        ["[60, 70]", "", "y"]
+            .map(|v| Arc::new(v.to_owned()))
+            .to_vec(),
+    ];
+
+    assert!(aggregated_data.numeric_bins_metadata_map == data_block.numeric_bins_metadata_map);
+
+    let mut representative = GeneratedData::new(
+        synthetic_data.clone(),
+        1.0,
+        aggregated_data.multi_value_column_metadata_map.clone(),
+        aggregated_data.numeric_bins_metadata_map.clone(),
+        aggregated_data.date_time_metadata_map.clone(),
+    );
+
+    representative.map_numeric_bins(NumericBinOutputMode::Representative, None);
+    assert!(representative.synthetic_data_to_vec("", false)[1] == vec!["25", "[900, 2800.5)", "x"]);
+    assert!(representative.synthetic_data_to_vec("", false)[2] == vec!["65", "", "y"]);
+
+    let mut uniform = GeneratedData::new(
+        synthetic_data,
+        1.0,
+        aggregated_data.multi_value_column_metadata_map.clone(),
+        aggregated_data.numeric_bins_metadata_map.clone(),
+        aggregated_data.date_time_metadata_map.clone(),
+    );
+
+    uniform.map_numeric_bins(NumericBinOutputMode::UniformSample, None);
+
+    let sampled_age = uniform.synthetic_data[1][0].parse::<f64>().unwrap();
+
+    assert!((20.0..30.0).contains(&sampled_age));
+}
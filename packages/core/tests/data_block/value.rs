@@ -38,3 +38,44 @@ fn invalid_from_str() {
     assert!(DataBlockValue::from_str("invalid").is_err());
     assert!(DataBlockValue::from_str("invalid:a1").is_err());
 }
+
+#[test]
+fn valid_value_equality() {
+    let a1 = DataBlockValue::new(0, Arc::new(String::from("a1")));
+    let a2 = DataBlockValue::new(0, Arc::new(String::from("a2")));
+
+    // the same value is always equal, no matter how it was created
+    assert!(a1 == get_value_0());
+    assert!(a1 == DataBlockValue::from_str("0:a1").unwrap());
+    assert!(a1 != a2);
+    assert!(a1 < a2);
+    // values are compared per column
+    assert!(a1 != DataBlockValue::new(1, Arc::new(String::from("a1"))));
+}
+
+#[test]
+fn valid_values_sorted_by_column() {
+    let mut values = [
+        DataBlockValue::from_str("2:a").unwrap(),
+        DataBlockValue::from_str("0:z").unwrap(),
+        DataBlockValue::from_str("1:m").unwrap(),
+    ];
+
+    values.sort();
+
+    assert!(
+        values
+            .iter()
+            .map(|v| v.column_index)
+            .collect::<Vec<usize>>()
+            == vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn valid_json() {
+    let json = serde_json::to_string(&get_value_1()).unwrap();
+
+    assert!(json == "{\"column_index\":1,\"value\":\"b2\"}");
+    assert!(serde_json::from_str::<DataBlockValue>(&json).unwrap() == get_value_1());
+}
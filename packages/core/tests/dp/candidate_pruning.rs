@@ -0,0 +1,171 @@
+use sds_core::{
+    dp::{
+        CombinationsCountMapByLen, DpParameters, InputValueByLen, NoiseAggregator,
+        NoisyCountThreshold,
+    },
+    processing::aggregator::{AggregatedData, Aggregator},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::{assert_map_equals, gen_combinations_count_map, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+const SEEDED_TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const TOLERANCE: f64 = 1e-9;
+
+fn dp_parameters(
+    candidate_min_upper_bound: Option<f64>,
+    max_candidates_per_len: Option<usize>,
+) -> DpParameters {
+    DpParameters {
+        seed: Some(42),
+        candidate_min_upper_bound,
+        max_candidates_per_len,
+        ..DpParameters::new(1.0, 0.001, 99, 0.1)
+    }
+}
+
+fn get_noise_aggregator(
+    candidate_min_upper_bound: Option<f64>,
+    max_candidates_per_len: Option<usize>,
+) -> NoiseAggregator {
+    NoiseAggregator::new(
+        read_test_data_block(
+            TEST_FILE_PATH,
+            DELIMITER,
+            None,
+            &[],
+            &HashMap::default(),
+            &[],
+            0,
+        ),
+        3,
+        &dp_parameters(candidate_min_upper_bound, max_candidates_per_len),
+        NoisyCountThreshold::Fixed(InputValueByLen::default()),
+    )
+}
+
+fn noisy_1_counts() -> CombinationsCountMapByLen {
+    let mut noisy_aggregates_by_len = CombinationsCountMapByLen::default();
+
+    noisy_aggregates_by_len.insert(
+        1,
+        gen_combinations_count_map(&[
+            ("a1", 10.0),
+            ("a2", 2.0),
+            ("b1", 8.0),
+            ("b2", 3.0),
+            ("c1", 6.0),
+            ("d1", 9.0),
+            ("d2", 1.0),
+        ]),
+    );
+    noisy_aggregates_by_len
+}
+
+fn aggregate_with_dp(max_candidates_per_len: Option<usize>) -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        SEEDED_TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &dp_parameters(None, max_candidates_per_len),
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+}
+
+#[test]
+pub fn validate_pruning_keeps_all_candidates_above_bound() {
+    let unpruned = get_noise_aggregator(None, None);
+    let pruned = get_noise_aggregator(Some(0.0), None);
+    let mut noisy_aggregates_by_len = CombinationsCountMapByLen::default();
+
+    for l in 1..=3 {
+        let candidates = unpruned.gen_all_current_aggregates(&noisy_aggregates_by_len, l);
+
+        assert_map_equals(
+            &pruned.gen_all_current_aggregates(&noisy_aggregates_by_len, l),
+            &candidates,
+        );
+        noisy_aggregates_by_len.insert(l, candidates);
+    }
+}
+
+#[test]
+pub fn validate_pruning_by_min_upper_bound() {
+    let na = get_noise_aggregator(Some(5.0), None);
+    let mut noisy_aggregates_by_len = noisy_1_counts();
+
+    assert_map_equals(
+        &na.gen_all_current_aggregates(&noisy_aggregates_by_len, 2),
+        &gen_combinations_count_map(&[
+            ("a1;b1", 0.0),
+            ("a1;c1", 0.0),
+            ("a1;d1", 0.0),
+            ("b1;c1", 0.0),
+            ("b1;d1", 0.0),
+            ("c1;d1", 0.0),
+        ]),
+    );
+
+    noisy_aggregates_by_len.insert(
+        2,
+        gen_combinations_count_map(&[
+            ("a1;b1", 7.0),
+            ("a1;c1", 5.0),
+            ("a1;d1", 8.0),
+            ("b1;c1", 4.0),
+            ("b1;d1", 6.0),
+            ("c1;d1", 5.0),
+        ]),
+    );
+
+    assert_map_equals(
+        &na.gen_all_current_aggregates(&noisy_aggregates_by_len, 3),
+        &gen_combinations_count_map(&[("a1;b1;d1", 0.0), ("a1;c1;d1", 0.0)]),
+    );
+}
+
+#[test]
+pub fn validate_pruning_by_max_candidates() {
+    let na = get_noise_aggregator(None, Some(3));
+
+    assert_map_equals(
+        &na.gen_all_current_aggregates(&noisy_1_counts(), 2),
+        &gen_combinations_count_map(&[("a1;b1", 0.0), ("a1;d1", 0.0), ("b1;d1", 0.0)]),
+    );
+}
+
+#[test]
+pub fn validate_pruning_spends_no_extra_budget() {
+    let unpruned = aggregate_with_dp(None);
+    let pruned = aggregate_with_dp(Some(20));
+
+    assert!(
+        pruned
+            .aggregates_count
+            .keys()
+            .filter(|comb| comb.len() == 3)
+            .count()
+            <= 20
+    );
+    assert!(
+        (pruned.privacy_accountant.unwrap().total_rho()
+            - unpruned.privacy_accountant.unwrap().total_rho())
+        .abs()
+            < TOLERANCE
+    );
+}
@@ -0,0 +1,137 @@
+use sds_core::{
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::aggregator::{AggregatedData, Aggregator},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const TOLERANCE: f64 = 1e-9;
+
+// large enough for no combination length to be completely suppressed
+const EPSILON: f64 = 3.0;
+
+fn aggregate_with_dp(
+    column_weights: Option<HashMap<String, f64>>,
+    excluded_column_pairs: Option<Vec<(String, String)>>,
+) -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &DpParameters {
+            seed: Some(42),
+            column_weights,
+            excluded_column_pairs,
+            ..DpParameters::new(EPSILON, 0.001, 99, 0.1)
+        },
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+}
+
+fn column_weights(weights: &[(&str, f64)]) -> Option<HashMap<String, f64>> {
+    Some(
+        weights
+            .iter()
+            .map(|(column_name, weight)| (column_name.to_string(), *weight))
+            .collect(),
+    )
+}
+
+#[test]
+fn validate_equal_weights_do_not_change_noise() {
+    let unweighted = aggregate_with_dp(None, None);
+    let weighted = aggregate_with_dp(
+        column_weights(&[("A", 2.0), ("B", 2.0), ("C", 2.0), ("D", 2.0), ("E", 2.0)]),
+        None,
+    );
+    let unweighted_noise = unweighted.marginal_noise_standard_deviation_as_str(";");
+    let weighted_noise = weighted.marginal_noise_standard_deviation_as_str(";");
+
+    assert!(!unweighted_noise.is_empty());
+    assert!(weighted_noise.len() == unweighted_noise.len());
+    for (marginal, standard_deviation) in unweighted_noise.iter() {
+        assert!((weighted_noise[marginal] - standard_deviation).abs() < TOLERANCE);
+    }
+    assert!(weighted.aggregates_count.len() == unweighted.aggregates_count.len());
+}
+
+#[test]
+fn validate_priority_column_receives_less_noise() {
+    let unweighted = aggregate_with_dp(None, None);
+    let weighted = aggregate_with_dp(column_weights(&[("A", 4.0)]), None);
+    let unweighted_accountant = unweighted.privacy_accountant.as_ref().unwrap();
+    let weighted_accountant = weighted.privacy_accountant.as_ref().unwrap();
+
+    // the budget is redistributed, not increased
+    assert!(
+        (weighted_accountant.total_rho() - unweighted_accountant.total_rho()).abs() < TOLERANCE
+    );
+
+    for comb_len in 1..=3 {
+        let unweighted_std = unweighted.count_noise_by_len[&comb_len].standard_deviation;
+        let weighted_noise = &weighted.count_noise_by_len[&comb_len];
+        // column "A" has index 0
+        let with_priority = weighted_noise.marginal_standard_deviation(0..comb_len);
+        let without_priority = weighted_noise.marginal_standard_deviation(1..=comb_len);
+
+        assert!(with_priority < unweighted_std);
+        assert!(without_priority > unweighted_std);
+        assert!((without_priority / with_priority - 4.0).abs() < TOLERANCE);
+    }
+
+    for (marginal, standard_deviation) in weighted.marginal_noise_standard_deviation_as_str(";") {
+        let comb_len = marginal.split(';').count();
+        let noise = &weighted.count_noise_by_len[&comb_len];
+
+        if marginal.split(';').any(|column_name| column_name == "A") {
+            assert!((standard_deviation - noise.standard_deviation / 4.0).abs() < TOLERANCE);
+        } else {
+            assert!((standard_deviation - noise.standard_deviation).abs() < TOLERANCE);
+        }
+    }
+}
+
+#[test]
+fn validate_excluded_column_pairs() {
+    let aggregated_data = aggregate_with_dp(
+        None,
+        Some(vec![
+            ("A".to_string(), "B".to_string()),
+            ("E".to_string(), "C".to_string()),
+        ]),
+    );
+    let has_columns = |comb_columns: &[usize], a: usize, b: usize| {
+        comb_columns.contains(&a) && comb_columns.contains(&b)
+    };
+
+    assert!(aggregated_data
+        .aggregates_count
+        .keys()
+        .any(|comb| comb.len() == 3));
+    assert!(aggregated_data.aggregates_count.keys().all(|comb| {
+        let comb_columns: Vec<usize> = comb.iter().map(|attr| attr.column_index).collect();
+
+        !has_columns(&comb_columns, 0, 1) && !has_columns(&comb_columns, 2, 4)
+    }));
+}
+
+#[test]
+#[should_panic]
+fn validate_unknown_weighted_column() {
+    aggregate_with_dp(column_weights(&[("unknown", 2.0)]), None);
+}
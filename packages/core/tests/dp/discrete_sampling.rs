@@ -0,0 +1,117 @@
+use rand::prelude::Distribution;
+use sds_core::{
+    dp::{
+        DiscreteGaussian, DiscreteLaplace, DpParameters, InputValueByLen, NoisyCountThreshold,
+        PrivacyStage,
+    },
+    processing::aggregator::{AggregatedData, Aggregator},
+    utils::{random::new_rng, reporting::LoggerProgressReporter},
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const NUMBER_OF_SAMPLES: usize = 50000;
+
+const TOLERANCE: f64 = 1e-9;
+
+fn sample_mean_and_variance<D: Distribution<i64>>(distribution: &D) -> (f64, f64) {
+    let mut rng = new_rng(Some(42));
+    let samples: Vec<f64> = (0..NUMBER_OF_SAMPLES)
+        .map(|_| distribution.sample(&mut rng) as f64)
+        .collect();
+    let mean = samples.iter().sum::<f64>() / (NUMBER_OF_SAMPLES as f64);
+    let variance =
+        samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / (NUMBER_OF_SAMPLES as f64);
+
+    (mean, variance)
+}
+
+fn aggregate_with_dp(use_discrete_noise: bool) -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &DpParameters {
+            seed: Some(42),
+            use_discrete_noise: Some(use_discrete_noise),
+            ..DpParameters::new(1.0, 0.001, 99, 0.1)
+        },
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+}
+
+#[test]
+fn validate_discrete_gaussian_moments() {
+    for sigma in [0.5, 3.0, 25.0] {
+        let (mean, variance) = sample_mean_and_variance(&DiscreteGaussian::new(sigma));
+
+        // for sigma >= 1 the variance is very close to sigma^2,
+        // for smaller values it is always below sigma^2
+        assert!(mean.abs() < 0.05 * sigma.max(1.0));
+        assert!(variance < 1.05 * sigma * sigma);
+        assert!(sigma < 1.0 || variance > 0.95 * sigma * sigma);
+    }
+}
+
+#[test]
+fn validate_discrete_laplace_moments() {
+    for scale in [0.5, 2.0, 10.0] {
+        let (mean, variance) = sample_mean_and_variance(&DiscreteLaplace::new(scale));
+        let p: f64 = (-1.0 / scale).exp();
+        let expected_variance = 2.0 * p / ((1.0 - p) * (1.0 - p));
+
+        assert!(mean.abs() < 0.05 * scale.max(1.0));
+        assert!((variance - expected_variance).abs() < 0.05 * expected_variance);
+    }
+}
+
+#[test]
+fn validate_discrete_noise_is_reproducible() {
+    let distribution = DiscreteGaussian::new(4.0);
+    let mut rng_1 = new_rng(Some(42));
+    let mut rng_2 = new_rng(Some(42));
+
+    for _ in 0..100 {
+        assert!(distribution.sample(&mut rng_1) == distribution.sample(&mut rng_2));
+    }
+}
+
+#[test]
+fn validate_aggregation_with_discrete_noise() {
+    let continuous = aggregate_with_dp(false);
+    let discrete = aggregate_with_dp(true);
+    let continuous_accountant = continuous.privacy_accountant.unwrap();
+    let discrete_accountant = discrete.privacy_accountant.unwrap();
+
+    assert!(discrete.protected_number_of_records.is_some());
+    assert!(!discrete.aggregates_count.is_empty());
+    // the same noise scales are accounted for with both backends
+    assert!(
+        discrete_accountant.invocations.len() == continuous_accountant.invocations.len()
+            && discrete_accountant
+                .invocations
+                .iter()
+                .zip(continuous_accountant.invocations.iter())
+                .all(|(d, c)| d.stage == c.stage
+                    && (d.stage != PrivacyStage::CombinationCounts
+                        || (d.mechanism.rho() - c.mechanism.rho()).abs() < TOLERANCE))
+    );
+    assert!(
+        (discrete_accountant.total_rho() - continuous_accountant.total_rho()).abs() < TOLERANCE
+    );
+    assert!(discrete_accountant.consumed_delta() == continuous_accountant.consumed_delta());
+}
@@ -0,0 +1,276 @@
+use sds_core::{
+    dp::{
+        AccuracyMode, DpParameters, DpPlanTarget, DpPlanner, InputValueByLen, NoisyCountThreshold,
+        PrivacyMechanism, PrivacyStage, DEFAULT_RDP_ORDERS,
+    },
+    processing::aggregator::Aggregator,
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const TOLERANCE: f64 = 1e-6;
+
+fn planner(accuracy_mode: &AccuracyMode) -> DpPlanner {
+    DpPlanner::new(3, accuracy_mode, 10000, 8, 5.0, Some(0.001), 0.1, None).unwrap()
+}
+
+fn adaptive_threshold() -> NoisyCountThreshold {
+    NoisyCountThreshold::Adaptive(InputValueByLen::default())
+}
+
+#[test]
+fn validate_accuracy_mode_sigma_proportions() {
+    assert!(AccuracyMode::Balanced.sigma_proportions(3) == vec![1.0, 1.0, 1.0]);
+    assert!(
+        AccuracyMode::PrioritizeLongCombinations.sigma_proportions(3)
+            == vec![1.0, 1.0 / 2.0, 1.0 / 3.0]
+    );
+    assert!(
+        AccuracyMode::PrioritizeShortCombinations.sigma_proportions(3)
+            == vec![1.0 / 3.0, 1.0 / 2.0, 1.0]
+    );
+    assert!(AccuracyMode::from_name("balanced").unwrap() == AccuracyMode::Balanced);
+    assert!(AccuracyMode::from_name("unknown").is_err());
+}
+
+#[test]
+fn validate_epsilon_and_noise_round_trip() {
+    let planner = planner(&AccuracyMode::PrioritizeLongCombinations);
+    let plan = planner
+        .plan(&DpPlanTarget::Epsilon(4.0), &adaptive_threshold())
+        .unwrap();
+    let target_std = plan.by_len[2].noise_standard_deviation;
+    let solved = planner
+        .plan(
+            &DpPlanTarget::NoiseStandardDeviation([(3, target_std)].into_iter().collect()),
+            &adaptive_threshold(),
+        )
+        .unwrap();
+
+    assert!((solved.epsilon - 4.0).abs() < TOLERANCE);
+    for (planned, solved) in plan.by_len.iter().zip(solved.by_len.iter()) {
+        assert!((planned.sigma - solved.sigma).abs() < TOLERANCE);
+    }
+
+    // the most restrictive target is the one met with equality
+    let solved = planner
+        .plan(
+            &DpPlanTarget::NoiseStandardDeviation(
+                [(1, 100.0), (3, target_std)].into_iter().collect(),
+            ),
+            &adaptive_threshold(),
+        )
+        .unwrap();
+
+    assert!((solved.epsilon - 4.0).abs() < TOLERANCE);
+    assert!(solved.by_len[0].noise_standard_deviation < 100.0);
+}
+
+#[test]
+fn validate_plan_matches_aggregation_noise() {
+    let plan = DpPlanner::new(
+        3,
+        &AccuracyMode::Balanced,
+        100,
+        8,
+        5.0,
+        Some(0.001),
+        0.1,
+        None,
+    )
+    .unwrap()
+    .plan(&DpPlanTarget::Epsilon(1.0), &adaptive_threshold())
+    .unwrap();
+    let aggregated_data = Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &DpParameters {
+            seed: Some(42),
+            ..DpParameters::new(1.0, 0.001, 99, 0.1)
+        },
+        adaptive_threshold(),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap();
+
+    // the noise by unit of sensitivity only depends on the parameters
+    for invocation in aggregated_data.privacy_accountant.unwrap().invocations {
+        if let (
+            PrivacyStage::CombinationCounts,
+            Some(comb_len),
+            PrivacyMechanism::Gaussian {
+                sigma,
+                l2_sensitivity,
+            },
+        ) = (
+            invocation.stage,
+            invocation.combination_length,
+            invocation.mechanism,
+        ) {
+            assert!((sigma / l2_sensitivity - plan.by_len[comb_len - 1].sigma).abs() < TOLERANCE);
+        }
+    }
+}
+
+#[test]
+fn validate_planned_epsilon_matches_accountant() {
+    // planned from the shape of the test data (200 records, 5 columns)
+    let planner = DpPlanner::new(
+        3,
+        &AccuracyMode::PrioritizeLongCombinations,
+        200,
+        5,
+        2.0,
+        Some(0.001),
+        0.1,
+        None,
+    )
+    .unwrap();
+    let plan = planner
+        .plan(
+            &DpPlanTarget::NoiseStandardDeviation([(1, 2.0)].into_iter().collect()),
+            &adaptive_threshold(),
+        )
+        .unwrap();
+    let aggregated_data = Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &DpParameters {
+            seed: Some(42),
+            sigma_proportions: Some(plan.sigma_proportions.clone()),
+            ..DpParameters::new(plan.epsilon, plan.delta, 99, 0.1)
+        },
+        adaptive_threshold(),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap();
+    let accountant = aggregated_data.privacy_accountant.unwrap();
+
+    // the zCDP conversion spends exactly the planned budget
+    assert!((accountant.approximate_dp_epsilon(&[]) - plan.epsilon).abs() < TOLERANCE);
+    // and the reported epsilon (which also tries the Rényi DP conversion) is never above it
+    assert!(accountant.report(&DEFAULT_RDP_ORDERS).epsilon <= plan.epsilon + TOLERANCE);
+}
+
+#[test]
+fn validate_threshold_matches_aggregation_parameters() {
+    let plan = planner(&AccuracyMode::Balanced)
+        .plan(&DpPlanTarget::Epsilon(1.0), &adaptive_threshold())
+        .unwrap();
+    let discrete_plan = planner(&AccuracyMode::Balanced)
+        .with_discrete_noise(true)
+        .plan(&DpPlanTarget::Epsilon(1.0), &adaptive_threshold())
+        .unwrap();
+    let user_level_plan = planner(&AccuracyMode::Balanced)
+        .with_max_records_per_subject(2)
+        .plan(&DpPlanTarget::Epsilon(1.0), &adaptive_threshold())
+        .unwrap();
+
+    // the discrete gaussian shifts the 1-counts threshold by 1
+    assert!((discrete_plan.by_len[0].threshold - plan.by_len[0].threshold - 1.0).abs() < TOLERANCE);
+    for comb_len in 2..=3 {
+        assert!(
+            discrete_plan.by_len[comb_len - 1].threshold == plan.by_len[comb_len - 1].threshold
+        );
+    }
+
+    // each subject can add up to 2 to the same combination
+    for (plan_by_len, user_level_by_len) in plan.by_len.iter().zip(user_level_plan.by_len.iter()) {
+        assert!(
+            (user_level_by_len.noise_standard_deviation
+                - plan_by_len.noise_standard_deviation * 2.0_f64.sqrt())
+            .abs()
+                < TOLERANCE
+        );
+    }
+    assert!(user_level_plan.by_len[0].threshold > plan.by_len[0].threshold + 1.0);
+}
+
+#[test]
+fn validate_expected_fabricated_combinations() {
+    let planner = DpPlanner::new(3, &AccuracyMode::Balanced, 50, 6, 10.0, None, 0.1, None).unwrap();
+    let adaptive = planner
+        .plan(&DpPlanTarget::Epsilon(1.0), &adaptive_threshold())
+        .unwrap();
+    let fixed = planner
+        .plan(
+            &DpPlanTarget::Epsilon(1.0),
+            &NoisyCountThreshold::Fixed([(2, 10.0), (3, 10.0)].into_iter().collect()),
+        )
+        .unwrap();
+
+    assert!(adaptive.by_len[0].expected_fabricated_combinations == 0.0);
+    for comb_len in 2..=3 {
+        let adaptive_by_len = &adaptive.by_len[comb_len - 1];
+        let fixed_by_len = &fixed.by_len[comb_len - 1];
+
+        assert!(adaptive_by_len.threshold == 0.0);
+        assert!(adaptive_by_len.expected_fabricated_combinations > 0.0);
+        assert!(fixed_by_len.threshold == 10.0);
+        assert!(
+            fixed_by_len.expected_fabricated_combinations
+                < adaptive_by_len.expected_fabricated_combinations
+        );
+    }
+}
+
+#[test]
+fn validate_planner_errors() {
+    let mode = AccuracyMode::Balanced;
+
+    assert!(DpPlanner::new(0, &mode, 100, 8, 5.0, None, 0.1, None).is_err());
+    assert!(DpPlanner::new(9, &mode, 100, 8, 5.0, None, 0.1, None).is_err());
+    assert!(DpPlanner::new(
+        3,
+        &AccuracyMode::Custom(vec![1.0]),
+        100,
+        8,
+        5.0,
+        None,
+        0.1,
+        None
+    )
+    .is_err());
+    assert!(DpPlanner::new(3, &mode, 1, 8, 5.0, None, 0.1, None).is_err());
+    assert!(DpPlanner::new(3, &mode, 100, 8, 5.0, None, 1.0, None).is_err());
+
+    let planner = planner(&mode);
+
+    assert!(planner
+        .plan(&DpPlanTarget::Epsilon(0.0), &adaptive_threshold())
+        .is_err());
+    assert!(planner
+        .plan(
+            &DpPlanTarget::NoiseStandardDeviation(InputValueByLen::default()),
+            &adaptive_threshold()
+        )
+        .is_err());
+    assert!(planner
+        .plan(
+            &DpPlanTarget::NoiseStandardDeviation([(4, 1.0)].into_iter().collect()),
+            &adaptive_threshold()
+        )
+        .is_err());
+}
@@ -0,0 +1,113 @@
+use fnv::FnvHashMap;
+use sds_core::{
+    data_block::DataBlock,
+    dp::{DpParameters, InputValueByLen, LeastSquaresConsistency, NoisyCountThreshold},
+    processing::{
+        aggregator::{AggregatedCount, AggregatedData, AggregatesCountMap, Aggregator},
+        evaluator::Evaluator,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::{gen_value_combination, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+fn gen_aggregates_count_map(tuples: &[(&str, usize)]) -> AggregatesCountMap {
+    tuples
+        .iter()
+        .map(|(value_str, count)| {
+            (
+                Arc::new(gen_value_combination(value_str)),
+                AggregatedCount {
+                    count: *count,
+                    ..AggregatedCount::default()
+                },
+            )
+        })
+        .collect()
+}
+
+fn get_count(aggregates_count: &AggregatesCountMap, value_str: &str) -> usize {
+    aggregates_count[&gen_value_combination(value_str)].count
+}
+
+fn aggregate_with_dp(
+    data_block: &Arc<DataBlock>,
+    seed: u64,
+    use_least_squares_consistency: bool,
+) -> AggregatedData {
+    Aggregator::new(data_block.clone())
+        .aggregate_with_dp(
+            3,
+            &DpParameters {
+                seed: Some(seed),
+                use_least_squares_consistency: Some(use_least_squares_consistency),
+                ..DpParameters::new(1.0, 0.001, 99, 0.1)
+            },
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+#[test]
+fn validate_equal_weights_projection() {
+    let mut aggregates_count =
+        gen_aggregates_count_map(&[("a1", 10), ("b1", 20), ("a1;b1", 14), ("a2", 3)]);
+
+    LeastSquaresConsistency::new(100, 1e-6).project(&mut aggregates_count, &FnvHashMap::default());
+
+    // only a1;b1 <= a1 is violated, so both move to their mean
+    assert!(get_count(&aggregates_count, "a1") == 12);
+    assert!(get_count(&aggregates_count, "a1;b1") == 12);
+    assert!(get_count(&aggregates_count, "b1") == 20);
+    assert!(get_count(&aggregates_count, "a2") == 3);
+}
+
+#[test]
+fn validate_weighted_projection() {
+    let mut aggregates_count = gen_aggregates_count_map(&[("a1", 10), ("a1;b1", 20)]);
+    let noise_variance_by_len: FnvHashMap<usize, f64> =
+        [(1, 1.0), (2, 9.0)].iter().cloned().collect();
+
+    LeastSquaresConsistency::new(100, 1e-6).project(&mut aggregates_count, &noise_variance_by_len);
+
+    // the noisier length moves 9 times more
+    assert!(get_count(&aggregates_count, "a1") == 11);
+    assert!(get_count(&aggregates_count, "a1;b1") == 11);
+}
+
+#[test]
+fn validate_consistency_reduces_mean_abs_error() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let sensitive_aggregated_data = Aggregator::new(data_block.clone())
+        .aggregate(3, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+    let evaluator = Evaluator::default();
+    let (mut error, mut consistent_error) = (0.0, 0.0);
+
+    for seed in 0..10 {
+        error += evaluator.calc_combinations_count_mean_abs_error(
+            &sensitive_aggregated_data,
+            &aggregate_with_dp(&data_block, seed, false),
+        );
+        consistent_error += evaluator.calc_combinations_count_mean_abs_error(
+            &sensitive_aggregated_data,
+            &aggregate_with_dp(&data_block, seed, true),
+        );
+    }
+
+    assert!(consistent_error < error);
+}
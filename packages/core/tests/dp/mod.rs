@@ -1,5 +1,19 @@
 mod analytic_gaussian;
 
+mod candidate_pruning;
+
+mod column_weights;
+
+mod discrete_sampling;
+
+mod dp_planner;
+
+mod least_squares_consistency;
+
 mod noise_aggregator;
 
 mod percentile;
+
+mod privacy_accountant;
+
+mod user_level_dp;
@@ -0,0 +1,159 @@
+use sds_core::{
+    dp::{
+        DpParameters, InputValueByLen, NoisyCountThreshold, PrivacyAccountant, PrivacyMechanism,
+        PrivacyMechanismInvocation, PrivacyStage, DEFAULT_RDP_ORDERS,
+    },
+    processing::aggregator::{AggregatedData, Aggregator},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+const TOLERANCE: f64 = 1e-9;
+
+const EPSILON_TOLERANCE: f64 = 1e-6;
+
+fn aggregate_with_dp(reporting_length: usize) -> AggregatedData {
+    aggregate_with_dp_and_epsilon(reporting_length, 1.0)
+}
+
+fn aggregate_with_dp_and_epsilon(reporting_length: usize, epsilon: f64) -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        reporting_length,
+        &DpParameters {
+            seed: Some(42),
+            ..DpParameters::new(epsilon, 0.001, 99, 0.1)
+        },
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+}
+
+#[test]
+fn validate_mechanism_spend() {
+    let gaussian = PrivacyMechanism::Gaussian {
+        sigma: 2.0,
+        l2_sensitivity: 2.0,
+    };
+    let laplace = PrivacyMechanism::Laplace { epsilon: 0.5 };
+
+    assert!((gaussian.rho() - 0.5).abs() < TOLERANCE);
+    assert!((gaussian.rdp_epsilon(4.0) - 2.0).abs() < TOLERANCE);
+    assert!((laplace.rho() - 0.125).abs() < TOLERANCE);
+    assert!((laplace.rdp_epsilon(2.0) - 0.25).abs() < TOLERANCE);
+    // RDP of a pure DP mechanism is never above its epsilon
+    assert!((laplace.rdp_epsilon(100.0) - 0.5).abs() < TOLERANCE);
+}
+
+#[test]
+fn validate_accountant_composition() {
+    let mut accountant = PrivacyAccountant::new(1e-6);
+
+    accountant.record(PrivacyMechanismInvocation::new(
+        PrivacyStage::CombinationCounts,
+        Some(1),
+        PrivacyMechanism::Gaussian {
+            sigma: 1.0,
+            l2_sensitivity: 1.0,
+        },
+        0.0,
+    ));
+    accountant.record(PrivacyMechanismInvocation::new(
+        PrivacyStage::NumberOfRecords,
+        None,
+        PrivacyMechanism::Laplace { epsilon: 1.0 },
+        0.0,
+    ));
+
+    let report = accountant.report(&DEFAULT_RDP_ORDERS);
+    let zcdp_epsilon = 1.0 + 2.0 * (1e6_f64).ln().sqrt();
+
+    assert!((report.rho - 1.0).abs() < TOLERANCE);
+    assert!(report.rdp.len() == DEFAULT_RDP_ORDERS.len());
+    assert!(report.epsilon > 0.0 && report.epsilon <= zcdp_epsilon);
+    assert!(report.delta == 1e-6);
+
+    // consuming all the delta leaves nothing for the conversion
+    accountant.record(PrivacyMechanismInvocation::new(
+        PrivacyStage::CombinationCounts,
+        Some(1),
+        PrivacyMechanism::Gaussian {
+            sigma: 1.0,
+            l2_sensitivity: 1.0,
+        },
+        1e-6,
+    ));
+    assert!(accountant.approximate_dp_epsilon(&DEFAULT_RDP_ORDERS) == f64::INFINITY);
+}
+
+#[test]
+fn validate_dp_aggregation_records_invocations() {
+    let aggregated_data = aggregate_with_dp(3);
+    let accountant = aggregated_data.privacy_accountant.as_ref().unwrap();
+    let count_by_stage = |stage: PrivacyStage| {
+        accountant
+            .invocations
+            .iter()
+            .filter(|invocation| invocation.stage == stage)
+            .count()
+    };
+
+    assert!(accountant.delta == 0.001);
+    assert!(count_by_stage(PrivacyStage::PercentileSelection) == 3);
+    assert!(count_by_stage(PrivacyStage::CombinationCounts) <= 3);
+    assert!(count_by_stage(PrivacyStage::NumberOfRecords) == 1);
+    assert!(accountant.total_rho() > 0.0);
+    assert!(accountant.consumed_delta() <= 0.0005);
+    assert!(accountant.report(&DEFAULT_RDP_ORDERS).epsilon <= 1.0 + EPSILON_TOLERANCE);
+}
+
+#[test]
+fn validate_dp_aggregation_reports_configured_epsilon() {
+    for epsilon in [0.5, 1.0, 4.0] {
+        let report = aggregate_with_dp_and_epsilon(3, epsilon)
+            .privacy_accountant
+            .unwrap()
+            .report(&DEFAULT_RDP_ORDERS);
+
+        // lengths whose combinations were all suppressed spend nothing,
+        // but the spend is never above the configured budget
+        assert!(report.epsilon > 0.0 && report.epsilon <= epsilon + EPSILON_TOLERANCE);
+    }
+}
+
+#[test]
+fn validate_accountant_survives_json() {
+    let aggregated_data = aggregate_with_dp(2);
+    let json = serde_json::to_string(&aggregated_data).unwrap();
+    let read_aggregated_data: AggregatedData = serde_json::from_str(&json).unwrap();
+
+    let accountant = aggregated_data.privacy_accountant.unwrap();
+    let read_accountant = read_aggregated_data.privacy_accountant.unwrap();
+
+    assert!(read_accountant.invocations.len() == accountant.invocations.len());
+    assert!((read_accountant.total_rho() - accountant.total_rho()).abs() < TOLERANCE);
+
+    // aggregates without an accountant (e.g. written by older versions) are still valid
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    value.as_object_mut().unwrap().remove("privacy_accountant");
+
+    let legacy_aggregated_data: AggregatedData = serde_json::from_value(value).unwrap();
+
+    assert!(legacy_aggregated_data.privacy_accountant.is_none());
+}
@@ -0,0 +1,106 @@
+use csv::ReaderBuilder;
+use sds_core::{
+    data_block::{
+        CsvDataBlockCreator, DataBlock, DataBlockCreateOptions, DataBlockCreator,
+        SubjectRecordsMode,
+    },
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold, PrivacyMechanism, PrivacyStage},
+    processing::aggregator::{AggregatedData, Aggregator},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::sync::Arc;
+
+use crate::utils::get_path_on_resources;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_subject_records.csv";
+
+fn read_data_block(subject_records_mode: SubjectRecordsMode) -> Arc<DataBlock> {
+    CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(TEST_FILE_PATH)),
+        &DataBlockCreateOptions {
+            subject_id: Some("ID".to_owned()),
+            subject_records_mode,
+            use_columns: vec!["A".to_owned(), "B".to_owned()],
+            ..DataBlockCreateOptions::default()
+        },
+    )
+    .unwrap()
+}
+
+fn aggregate_with_dp(
+    data_block: Arc<DataBlock>,
+    max_records_per_subject: Option<usize>,
+) -> AggregatedData {
+    Aggregator::new(data_block)
+        .aggregate_with_dp(
+            2,
+            &DpParameters {
+                number_of_records_epsilon_proportion: Some(0.05),
+                seed: Some(42),
+                max_records_per_subject,
+                ..DpParameters::new(100.0, 0.001, 99, 0.1)
+            },
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+fn count_of(aggregated_data: &AggregatedData, combination: &str) -> usize {
+    aggregated_data
+        .aggregates_count_as_str(";")
+        .get(combination)
+        .cloned()
+        .unwrap_or(0)
+}
+
+#[test]
+fn valid_subject_contribution_is_bounded() {
+    let data_block = read_data_block(SubjectRecordsMode::Keep);
+    let aggregated_data = aggregate_with_dp(data_block.clone(), Some(2));
+
+    assert!(data_block.number_of_records() == 160);
+    // a single subject has 40 records with A:heavy, but can only contribute with 2
+    assert!(count_of(&aggregated_data, "A:heavy") <= 2);
+    // the remaining subjects have 2 records each, so they are fully used
+    assert!(
+        count_of(&aggregated_data, "A:a1")
+            + count_of(&aggregated_data, "A:a2")
+            + count_of(&aggregated_data, "A:a3")
+            > 100
+    );
+    // 2 sampled records from each one of the 61 subjects
+    assert!((aggregated_data.protected_number_of_records.unwrap() as f64 - 122.0).abs() < 20.0);
+}
+
+#[test]
+fn valid_subject_level_noise_accounts_for_contribution() {
+    let aggregated_data = aggregate_with_dp(read_data_block(SubjectRecordsMode::Keep), Some(2));
+    let accountant = aggregated_data.privacy_accountant.unwrap();
+
+    for invocation in accountant.invocations.iter() {
+        if let (
+            PrivacyStage::CombinationCounts,
+            PrivacyMechanism::Gaussian { l2_sensitivity, .. },
+        ) = (&invocation.stage, &invocation.mechanism)
+        {
+            // each subject adds 2 records with 2 attributes (4 combinations of length 1
+            // and 2 of length 2), up to 2 times to the same combination
+            assert!(*l2_sensitivity > 2.0_f64.sqrt());
+        }
+    }
+}
+
+#[test]
+fn valid_subject_sampling_is_reproducible() {
+    let data_block = read_data_block(SubjectRecordsMode::Keep);
+
+    assert!(
+        aggregate_with_dp(data_block.clone(), None).aggregates_count_as_str(";")
+            == aggregate_with_dp(data_block, None).aggregates_count_as_str(";")
+    );
+}
@@ -0,0 +1,151 @@
+use sds_core::{
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::aggregator::{
+        AggregatedData, AggregatesCompression, Aggregator, AGGREGATED_DATA_BINARY_VERSION,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, io::ErrorKind};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+const TOLERANCE: f64 = 1e-9;
+
+fn aggregate_with_dp() -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        3,
+        &DpParameters {
+            seed: Some(42),
+            ..DpParameters::new(1.0, 0.001, 99, 0.1)
+        },
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+}
+
+fn assert_same_aggregated_data(a: &AggregatedData, b: &AggregatedData) {
+    assert!(a.headers == b.headers);
+    assert!(a.number_of_records == b.number_of_records);
+    assert!(a.protected_number_of_records == b.protected_number_of_records);
+    assert!(a.reporting_length == b.reporting_length);
+    assert!(a.records_sensitivity_by_len == b.records_sensitivity_by_len);
+    // the accountant goes through json, which may round the last float digit
+    assert!(
+        (a.privacy_accountant.as_ref().unwrap().total_rho()
+            - b.privacy_accountant.as_ref().unwrap().total_rho())
+        .abs()
+            < TOLERANCE
+    );
+    assert!(a.aggregates_count.len() == b.aggregates_count.len());
+
+    for (comb, count) in a.aggregates_count.iter() {
+        let other = &b.aggregates_count[comb];
+
+        assert!(count.count == other.count);
+        assert!(count.contained_in_records == other.contained_in_records);
+    }
+}
+
+#[test]
+fn validate_binary_round_trip() {
+    let aggregated_data = aggregate_with_dp();
+    let json_len = serde_json::to_vec(&aggregated_data).unwrap().len();
+
+    for compression in [
+        AggregatesCompression::None,
+        AggregatesCompression::Gzip,
+        AggregatesCompression::Zstd,
+    ] {
+        let bytes = aggregated_data.to_binary_bytes(compression).unwrap();
+
+        assert!(AggregatedData::is_binary(&bytes));
+        assert!(bytes.len() < json_len);
+        assert_same_aggregated_data(
+            &aggregated_data,
+            &AggregatedData::from_binary_bytes(&bytes).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn validate_binary_header() {
+    let bytes = aggregate_with_dp()
+        .to_binary_bytes(AggregatesCompression::Gzip)
+        .unwrap();
+
+    assert!(&bytes[..6] == b"SDSAGG");
+    assert!(u16::from_le_bytes([bytes[6], bytes[7]]) == AGGREGATED_DATA_BINARY_VERSION);
+    assert!(bytes[8] == 1);
+
+    let mut newer_version = bytes.clone();
+
+    newer_version[6..8].copy_from_slice(&(AGGREGATED_DATA_BINARY_VERSION + 1).to_le_bytes());
+    assert!(
+        AggregatedData::from_binary_bytes(&newer_version)
+            .err()
+            .unwrap()
+            .kind()
+            == ErrorKind::InvalidData
+    );
+
+    let mut unknown_compression = bytes.clone();
+
+    unknown_compression[8] = 9;
+    assert!(AggregatedData::from_binary_bytes(&unknown_compression).is_err());
+    assert!(AggregatedData::from_binary_bytes(b"{\"headers\":[]}").is_err());
+    assert!(AggregatedData::from_binary_bytes(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn validate_binary_hostile_lengths() {
+    let bytes = aggregate_with_dp()
+        .to_binary_bytes(AggregatesCompression::None)
+        .unwrap();
+    let mut offset = 9;
+    let mut metadata_len = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = bytes[offset];
+
+        offset += 1;
+        metadata_len |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+
+    let mut hostile = bytes[..offset + metadata_len].to_vec();
+    let mut n_values = usize::MAX >> 1;
+
+    while n_values >= 0x80 {
+        hostile.push((n_values as u8) | 0x80);
+        n_values >>= 7;
+    }
+    hostile.push(n_values as u8);
+
+    assert!(AggregatedData::from_binary_bytes(&hostile).is_err());
+}
+
+#[test]
+fn validate_compression_parsing() {
+    assert!("ZSTD".parse::<AggregatesCompression>() == Ok(AggregatesCompression::Zstd));
+    assert!("gzip".parse::<AggregatesCompression>() == Ok(AggregatesCompression::Gzip));
+    assert!(AggregatesCompression::None.to_string() == "none");
+    assert!("lz4".parse::<AggregatesCompression>().is_err());
+}
@@ -0,0 +1,121 @@
+use fnv::FnvHashMap;
+use sds_core::{
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::aggregator::{AggregatedData, Aggregator, RecordsSet},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const REPORTING_LENGTH: usize = 3;
+
+fn aggregate(path: &str, reporting_length: usize) -> AggregatedData {
+    Aggregator::new(read_test_data_block(
+        path,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate(reporting_length, &mut None::<LoggerProgressReporter>)
+    .unwrap()
+}
+
+fn records_by_combination(aggregated_data: &AggregatedData) -> FnvHashMap<String, RecordsSet> {
+    aggregated_data
+        .aggregates_count
+        .iter()
+        .map(|(comb, count)| {
+            (
+                comb.as_str_using_headers(&aggregated_data.headers, ";"),
+                count.contained_in_records.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn validate_merge_partitions_with_different_columns() {
+    let merged = aggregate("test_merge_partition_1.csv", REPORTING_LENGTH)
+        .merge(&aggregate("test_merge_partition_2.csv", REPORTING_LENGTH))
+        .unwrap();
+    let expected = aggregate("test_merge_partitions.csv", REPORTING_LENGTH);
+
+    assert!(merged.headers == expected.headers);
+    assert!(merged.number_of_records == expected.number_of_records);
+    assert!(merged.protected_number_of_records.is_none());
+    assert!(merged.aggregates_count_as_str(";") == expected.aggregates_count_as_str(";"));
+    assert!(records_by_combination(&merged) == records_by_combination(&expected));
+    assert!(merged.records_sensitivity_by_len == expected.records_sensitivity_by_len);
+}
+
+#[test]
+fn validate_merge_errors() {
+    let aggregated_data = aggregate("test_merge_partition_1.csv", REPORTING_LENGTH);
+    let mut protected = aggregated_data.clone();
+
+    protected.protect_with_k_anonymity(2);
+
+    assert!(aggregated_data
+        .merge(&aggregate("test_merge_partition_2.csv", 2))
+        .is_err());
+    assert!(aggregated_data.merge(&protected).is_err());
+    assert!(Aggregator::new(read_test_data_block(
+        "test_merge_partition_2.csv",
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .aggregate_with_dp(
+        REPORTING_LENGTH,
+        &DpParameters {
+            seed: Some(42),
+            ..DpParameters::new(1.0, 0.001, 99, 0.1)
+        },
+        NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+        &mut None::<LoggerProgressReporter>,
+    )
+    .unwrap()
+    .merge(&aggregated_data)
+    .is_err());
+}
+
+#[test]
+fn validate_diff() {
+    let old = aggregate("test_merge_partition_1.csv", REPORTING_LENGTH);
+    let new = aggregate("test_merge_partition_2.csv", REPORTING_LENGTH);
+    let diff = old.diff(&new, ";");
+    let changed: FnvHashMap<String, isize> = diff
+        .changed
+        .iter()
+        .map(|count_diff| (count_diff.combination.clone(), count_diff.delta()))
+        .collect();
+
+    assert!(changed.len() == 3);
+    assert!(changed["A:a1"] == -1);
+    assert!(changed["C:c1"] == -1);
+    assert!(changed["A:a1;C:c1"] == -1);
+    assert!(diff
+        .removed
+        .iter()
+        .any(|count_diff| count_diff.combination == "B:b1" && count_diff.delta() == -3));
+    assert!(diff
+        .added
+        .iter()
+        .any(|count_diff| count_diff.combination == "D:d1" && count_diff.new_count == 2));
+    // sorted by length and then by combination
+    assert!(diff.added[0].combination == "D:d1");
+    assert!(diff.added.last().unwrap().combination == "A:a2;C:c2;D:d1");
+
+    assert!(old.diff(&old, ";").added.is_empty());
+    assert!(old.diff(&old, ";").removed.is_empty());
+    assert!(old.diff(&old, ";").changed.is_empty());
+}
@@ -0,0 +1,153 @@
+use sds_core::{
+    data_block::NumericColumnBins,
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::{
+        aggregator::{
+            AggregatedData, AggregatedDataRelease, Aggregator,
+            AGGREGATED_DATA_RELEASE_SCHEMA_VERSION,
+        },
+        generator::Generator,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, io::ErrorKind, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+fn dp_parameters() -> DpParameters {
+    DpParameters {
+        seed: Some(42),
+        ..DpParameters::new(1.0, 0.001, 99, 0.1)
+    }
+}
+
+fn aggregator() -> Aggregator {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+}
+
+fn aggregate_with_dp() -> AggregatedData {
+    aggregator()
+        .aggregate_with_dp(
+            3,
+            &dp_parameters(),
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+#[test]
+fn validate_release_only_contains_dp_safe_fields() {
+    let aggregated_data = aggregate_with_dp();
+    let release = aggregated_data.to_release(Some(&dp_parameters())).unwrap();
+    let json = serde_json::to_string(&release).unwrap();
+
+    assert!(AggregatedDataRelease::is_release(json.as_bytes()));
+    assert!(release.release_schema_version == AGGREGATED_DATA_RELEASE_SCHEMA_VERSION);
+    assert!(
+        Some(release.protected_number_of_records) == aggregated_data.protected_number_of_records
+    );
+    assert!(release.dp_parameters.unwrap().seed.is_none());
+    assert!(release.aggregates_count.len() == aggregated_data.aggregates_count.len());
+
+    for (comb, count) in aggregated_data.aggregates_count.iter() {
+        assert!(release.aggregates_count[comb] == count.count);
+    }
+
+    for sensitive_field in [
+        "\"number_of_records\"",
+        "\"records_sensitivity_by_len\"",
+        "\"contained_in_records\"",
+    ] {
+        assert!(!json.contains(sensitive_field));
+    }
+}
+
+#[test]
+fn validate_release_requires_dp() {
+    let aggregated_data = aggregator()
+        .aggregate(3, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+
+    assert!(aggregated_data.to_release(None).err().unwrap().kind() == ErrorKind::InvalidInput);
+}
+
+#[test]
+fn validate_release_rejects_data_derived_bins() {
+    let mut aggregated_data = aggregate_with_dp();
+
+    // e.g. aggregates read from a file written before bins were tracked
+    aggregated_data.numeric_bins_metadata_map.insert(
+        aggregated_data.headers[0].clone(),
+        NumericColumnBins {
+            bins: Vec::default(),
+            data_derived: true,
+        },
+    );
+    assert!(aggregated_data.to_release(None).err().unwrap().kind() == ErrorKind::InvalidInput);
+
+    aggregated_data
+        .numeric_bins_metadata_map
+        .values_mut()
+        .for_each(|column_bins| column_bins.data_derived = false);
+    assert!(aggregated_data.to_release(None).is_ok());
+}
+
+#[test]
+fn validate_release_newer_schema_version() {
+    let mut release = aggregate_with_dp().to_release(None).unwrap();
+    let file_path = std::env::temp_dir().join("sds_newer_aggregated_data_release.json");
+    let file_path = file_path.to_str().unwrap();
+
+    release.release_schema_version = AGGREGATED_DATA_RELEASE_SCHEMA_VERSION + 1;
+    release.write_to_json(file_path).unwrap();
+
+    let result = AggregatedDataRelease::read_from_json(file_path);
+
+    std::fs::remove_file(file_path).unwrap();
+    assert!(result.err().unwrap().kind() == ErrorKind::InvalidData);
+}
+
+#[test]
+fn validate_aggregate_seeded_generation_from_release() {
+    let release: AggregatedDataRelease = serde_json::from_str(
+        &serde_json::to_string(&aggregate_with_dp().to_release(None).unwrap()).unwrap(),
+    )
+    .unwrap();
+    let protected_number_of_records = release.protected_number_of_records;
+    let aggregated_data = AggregatedData::from(release);
+
+    assert!(aggregated_data.number_of_records == protected_number_of_records);
+    assert!(aggregated_data.records_sensitivity_by_len.is_empty());
+    assert!(aggregated_data
+        .aggregates_count
+        .values()
+        .all(|count| count.contained_in_records.is_empty()));
+
+    let generated_data = Generator::new(Some(42))
+        .generate_aggregate_seeded(
+            "",
+            Arc::new(aggregated_data),
+            true,
+            None,
+            None,
+            None,
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap();
+
+    // headers + synthesized records
+    assert!(generated_data.synthetic_data.len() > 1);
+}
@@ -0,0 +1,194 @@
+use fnv::FnvHashMap;
+use sds_core::{
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::aggregator::{
+        AggregatedData, AggregatesCompression, Aggregator, CountNoise, DEFAULT_CONFIDENCE_LEVEL,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::{gen_value_combination, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+fn dp_parameters() -> DpParameters {
+    DpParameters {
+        seed: Some(42),
+        ..DpParameters::new(1.0, 0.001, 99, 0.1)
+    }
+}
+
+fn aggregator() -> Aggregator {
+    Aggregator::new(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+}
+
+fn aggregate_with_dp() -> AggregatedData {
+    aggregator()
+        .aggregate_with_dp(
+            3,
+            &dp_parameters(),
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+#[test]
+fn validate_count_noise_confidence_interval() {
+    let noise = CountNoise {
+        standard_deviation: 10.0,
+        l1_sensitivity: 4,
+        column_weights: FnvHashMap::default(),
+        post_processed: false,
+    };
+    let comb = gen_value_combination("a1;b1");
+    let interval_95 = noise.confidence_interval(&comb, 100, 0.95);
+    let interval_99 = noise.confidence_interval(&comb, 100, 0.99);
+    let clamped = noise.confidence_interval(&comb, 5, 0.95);
+
+    assert!((interval_95.lower - (100.0 - 19.5996)).abs() < 1e-3);
+    assert!((interval_95.upper - (100.0 + 19.5996)).abs() < 1e-3);
+    assert!(interval_99.lower < interval_95.lower && interval_99.upper > interval_95.upper);
+    assert!(clamped.lower == 0.0);
+}
+
+#[test]
+fn validate_dp_aggregates_have_confidence_intervals() {
+    let aggregated_data = aggregate_with_dp();
+
+    assert!(aggregated_data.count_noise_by_len.len() == 3);
+    for comb_len in 1..=3 {
+        let noise = &aggregated_data.count_noise_by_len[&comb_len];
+
+        assert!(noise.standard_deviation > 0.0);
+        assert!(noise.l1_sensitivity > 0);
+    }
+    for (comb, count) in aggregated_data.aggregates_count.iter() {
+        let interval = aggregated_data
+            .calc_count_confidence_interval(comb, DEFAULT_CONFIDENCE_LEVEL)
+            .unwrap();
+
+        assert!(interval.lower >= 0.0);
+        assert!(interval.lower <= count.count as f64 && count.count as f64 <= interval.upper);
+    }
+    assert!(
+        aggregated_data
+            .aggregates_count_confidence_interval_as_str(";", DEFAULT_CONFIDENCE_LEVEL)
+            .len()
+            == aggregated_data.aggregates_count.len()
+    );
+}
+
+#[test]
+fn validate_non_dp_aggregates_have_no_confidence_intervals() {
+    let aggregated_data = aggregator()
+        .aggregate(3, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+
+    assert!(aggregated_data.count_noise_by_len.is_empty());
+    assert!(aggregated_data
+        .aggregates_count_confidence_interval_as_str(";", DEFAULT_CONFIDENCE_LEVEL)
+        .is_empty());
+
+    let tsv = aggregated_data
+        .write_aggregates_to_string('\t', ";", None, Some(DEFAULT_CONFIDENCE_LEVEL))
+        .unwrap();
+
+    assert!(!tsv.lines().next().unwrap().contains("ci_lower"));
+}
+
+#[test]
+fn validate_confidence_intervals_are_written() {
+    let aggregated_data = aggregate_with_dp();
+    let without_intervals = aggregated_data
+        .write_aggregates_to_string('\t', ";", None, None)
+        .unwrap();
+    let with_intervals = aggregated_data
+        .write_aggregates_to_string('\t', ";", None, Some(DEFAULT_CONFIDENCE_LEVEL))
+        .unwrap();
+    let mut lines = with_intervals.lines();
+
+    assert!(!without_intervals.contains("ci_lower"));
+    assert!(lines.next().unwrap().ends_with("\tci_lower\tci_upper"));
+    assert!(lines.all(|line| line.split('\t').count() == 4));
+}
+
+#[test]
+fn validate_count_noise_is_kept_on_serialization() {
+    let aggregated_data = aggregate_with_dp();
+    let from_binary = AggregatedData::from_binary_bytes(
+        &aggregated_data
+            .to_binary_bytes(AggregatesCompression::None)
+            .unwrap(),
+    )
+    .unwrap();
+    let from_release: AggregatedData = aggregated_data
+        .to_release(Some(&dp_parameters()))
+        .unwrap()
+        .into();
+
+    assert!(from_binary.count_noise_by_len == aggregated_data.count_noise_by_len);
+    assert!(from_release.count_noise_by_len == aggregated_data.count_noise_by_len);
+}
+
+#[test]
+fn validate_consistent_aggregates_have_no_confidence_intervals() {
+    let dp_parameters = DpParameters {
+        use_least_squares_consistency: Some(true),
+        ..dp_parameters()
+    };
+    let aggregated_data = aggregator()
+        .aggregate_with_dp(
+            3,
+            &dp_parameters,
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap();
+
+    // the noise is kept, but it does not describe the consistent counts
+    assert!(aggregated_data.count_noise_by_len.len() == 3);
+    assert!(aggregated_data
+        .count_noise_by_len
+        .values()
+        .all(|noise| noise.post_processed));
+    assert!(aggregated_data
+        .aggregates_count
+        .keys()
+        .all(|comb| aggregated_data
+            .calc_count_confidence_interval(comb, DEFAULT_CONFIDENCE_LEVEL)
+            .is_none()));
+    assert!(aggregated_data
+        .aggregates_count_confidence_interval_as_str(";", DEFAULT_CONFIDENCE_LEVEL)
+        .is_empty());
+
+    let tsv = aggregated_data
+        .write_aggregates_to_string('\t', ";", None, Some(DEFAULT_CONFIDENCE_LEVEL))
+        .unwrap();
+
+    assert!(!tsv.contains("ci_lower"));
+
+    // the flag is kept when the aggregates are serialized
+    let from_binary = AggregatedData::from_binary_bytes(
+        &aggregated_data
+            .to_binary_bytes(AggregatesCompression::None)
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert!(from_binary.count_noise_by_len == aggregated_data.count_noise_by_len);
+    assert!(from_binary
+        .aggregates_count_confidence_interval_as_str(";", DEFAULT_CONFIDENCE_LEVEL)
+        .is_empty());
+}
@@ -1 +1,11 @@
+mod aggregated_data_binary;
+
+mod aggregated_data_merge;
+
+mod aggregated_data_release;
+
+mod count_noise;
+
+mod records_set;
+
 mod value_combination;
@@ -0,0 +1,82 @@
+use sds_core::{
+    processing::aggregator::{AggregatedCount, Aggregator, RecordsSet},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::{gen_value_combination, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+#[test]
+fn validate_records_set_operations() {
+    let mut records: RecordsSet = [5, 1, 3].iter().cloned().collect();
+    let other: RecordsSet = [3, 7].iter().cloned().collect();
+
+    assert!(!records.insert(3));
+    assert!(records.insert(0));
+    assert!(records.contains(5) && !records.contains(2));
+
+    records.union_with(&other);
+    assert!(records.iter().collect::<Vec<usize>>() == vec![0, 1, 3, 5, 7]);
+
+    records.difference_with(&other);
+    assert!(records.iter().collect::<Vec<usize>>() == vec![0, 1, 5]);
+
+    assert!(records.remove(5));
+    assert!(!records.remove(5));
+    assert!(records.len() == 2);
+    assert!(!records.is_empty());
+}
+
+#[test]
+fn validate_records_set_json() {
+    let records: RecordsSet = [2, 10, 4].iter().cloned().collect();
+    let json = serde_json::to_string(&records).unwrap();
+
+    // still stored as a plain list of indexes
+    assert!(json == "[2,4,10]");
+    assert!(serde_json::from_str::<RecordsSet>(&json).unwrap() == records);
+    assert!(serde_json::from_str::<RecordsSet>("[10,2,4,2]").unwrap() == records);
+    assert!(serde_json::from_str::<RecordsSet>("[4294967296]").is_err());
+
+    let count: AggregatedCount =
+        serde_json::from_str("{\"count\":3,\"contained_in_records\":[2,10,4]}").unwrap();
+
+    assert!(count.count == 3);
+    assert!(count.contained_in_records == records);
+}
+
+#[test]
+fn validate_unique_and_rare_records_analysis() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let aggregated_data = Aggregator::new(data_block)
+        .aggregate(2, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+    let a1_records =
+        &aggregated_data.aggregates_count[&gen_value_combination("a1")].contained_in_records;
+
+    assert!(a1_records.iter().collect::<Vec<usize>>() == vec![0, 2]);
+    assert!(aggregated_data.calc_number_of_records_with_unique_combinations() == 2);
+    assert!(aggregated_data.calc_number_of_records_with_rare_combinations(3) == 3);
+
+    let (unique_by_len, rare_by_len) =
+        aggregated_data.calc_records_with_unique_rare_combinations_by_len(3);
+
+    // records are kept only on the shortest length that isolates them
+    assert!(unique_by_len.len() == 1);
+    assert!(unique_by_len[&1].iter().collect::<Vec<usize>>() == vec![0, 1]);
+    // and records with unique combinations are not counted as rare
+    assert!(rare_by_len.len() == 1);
+    assert!(rare_by_len[&1].iter().collect::<Vec<usize>>() == vec![2]);
+}
@@ -1,5 +1,5 @@
 use sds_core::{data_block::DataBlockValue, processing::aggregator::ValueCombination};
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 #[test]
 pub fn validate_contains_comb() {
@@ -53,3 +53,11 @@ pub fn validate_contains_comb() {
         Arc::new(DataBlockValue::new(5, Arc::new("f2".into()))),
     ])));
 }
+
+#[test]
+pub fn validate_from_str_sorts_by_column() {
+    let combination = ValueCombination::from_str("2:c1;0:a1;1:b1").unwrap();
+
+    assert!(combination.to_string() == "0:a1;1:b1;2:c1");
+    assert!(combination == ValueCombination::from_str("0:a1;1:b1;2:c1").unwrap());
+}
@@ -0,0 +1,109 @@
+use sds_core::{
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        evaluator::Evaluator,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+const RESOLUTION: usize = 2;
+
+fn aggregate_test_data(record_limit: usize) -> AggregatedData {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        record_limit,
+    );
+
+    Aggregator::new(data_block)
+        .aggregate(3, &mut None::<LoggerProgressReporter>)
+        .unwrap()
+}
+
+#[test]
+fn validate_evaluation_report_metrics() {
+    let sensitive = aggregate_test_data(0);
+    // synthetic data mimicking the first two sensitive records
+    let synthetic = aggregate_test_data(2);
+    let evaluator = Evaluator::default();
+    let report = evaluator.calc_evaluation_report(&sensitive, &synthetic, RESOLUTION, ";", true);
+
+    assert!(report.resolution == RESOLUTION);
+    assert!(report.reporting_length == 3);
+    assert!(report.sensitive_number_of_records == 3);
+    assert!(report.synthetic_number_of_records == 2);
+    assert!((report.record_expansion_percentage + 100.0 / 3.0).abs() < 1e-9);
+
+    // b1, d1, a2, b2 and d2 are rare on both datasets
+    assert!(report.leakage_count_by_len[&1] == 5);
+    assert!(
+        report.leakage_count_by_len
+            == evaluator.calc_leakage_count_by_len(&sensitive, &synthetic, RESOLUTION)
+    );
+    assert!(report.total_leakage_count() == report.leakage_count_by_len.values().sum::<usize>());
+
+    // every combination from the third record also exists on the first one
+    assert!(report.number_of_suppressed_combinations == 0);
+    assert!(report.number_of_fabricated_combinations == 0);
+    assert!(
+        report.combinations_count_mean_abs_error
+            == evaluator.calc_combinations_count_mean_abs_error(&sensitive, &synthetic)
+    );
+    assert!(
+        report.mean_proportional_error
+            == evaluator
+                .calc_preservation_by_count(&sensitive, &synthetic, RESOLUTION)
+                .calc_mean_proportional_error()
+    );
+    assert!(report.preservation_by_length.len() == 3);
+
+    // all synthetic combinations are rare
+    assert!(
+        report.rare_combinations.rare_combinations.len()
+            == synthetic
+                .aggregates_count
+                .values()
+                .map(|count| count.contained_in_records.len())
+                .sum::<usize>()
+    );
+    assert!(report
+        .rare_combinations
+        .rare_combinations
+        .iter()
+        .all(|c| c.sensitive_count % RESOLUTION == 0));
+}
+
+#[test]
+fn validate_evaluation_report_json() {
+    let sensitive = aggregate_test_data(0);
+    let report =
+        Evaluator::default().calc_evaluation_report(&sensitive, &sensitive, RESOLUTION, ";", false);
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert!(json["number_of_suppressed_combinations"] == 0);
+    assert!(json["combinations_count_mean_abs_error"] == 0.0);
+    assert!(json["record_expansion_percentage"] == 0.0);
+    // buckets report the means instead of the raw sums
+    assert!(json["preservation_by_length"]["1"]["mean_preservation"] == 1.0);
+    assert!(json["preservation_by_length"]["1"]["mean_proportional_error"] == 0.0);
+    assert!(json["preservation_by_count"]
+        .as_object()
+        .unwrap()
+        .values()
+        .all(|bucket| bucket.get("size").is_some()));
+    assert!(
+        json["rare_combinations"].as_array().unwrap().len()
+            == report.rare_combinations.rare_combinations.len()
+    );
+}
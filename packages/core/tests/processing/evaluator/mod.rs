@@ -0,0 +1,5 @@
+mod evaluation_report;
+
+mod privacy_evaluation;
+
+mod workload_accuracy;
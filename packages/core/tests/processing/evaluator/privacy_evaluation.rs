@@ -0,0 +1,208 @@
+use sds_core::{
+    data_block::{DataBlock, DataBlockParts},
+    processing::{
+        evaluator::privacy::{
+            DistanceToClosestRecord, MembershipInference, PrivacyEvaluationReport, PrivacyEvaluator,
+        },
+        generator::GeneratedData,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_privacy_evaluation.csv";
+
+const NUMBER_OF_TRAINING_RECORDS: usize = 150;
+
+fn read_training_and_holdout() -> (DataBlock, DataBlock) {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let split = |records: &[_]| {
+        DataBlock::new(DataBlockParts {
+            headers: data_block.headers.clone(),
+            multi_value_column_metadata_map: data_block.multi_value_column_metadata_map.clone(),
+            numeric_bins_metadata_map: data_block.numeric_bins_metadata_map.clone(),
+            date_time_metadata_map: data_block.date_time_metadata_map.clone(),
+            records: records.to_vec(),
+            ..DataBlockParts::default()
+        })
+    };
+
+    (
+        split(&data_block.records[..NUMBER_OF_TRAINING_RECORDS]),
+        split(&data_block.records[NUMBER_OF_TRAINING_RECORDS..]),
+    )
+}
+
+/// Synthetic data copying the training records, with `replaced_column` set to `?`
+fn copy_records(training: &DataBlock, replaced_column: Option<usize>) -> GeneratedData {
+    let mut synthetic_data = training.to_raw_data(&Arc::new(String::default()));
+
+    if let Some(column_index) = replaced_column {
+        for record in synthetic_data.iter_mut().skip(1) {
+            record[column_index] = Arc::new("?".to_owned());
+        }
+    }
+
+    GeneratedData::new(
+        synthetic_data,
+        1.0,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+}
+
+fn evaluate(
+    quasi_identifiers: &[&str],
+    training: &DataBlock,
+    holdout: Option<&DataBlock>,
+    generated_data: &GeneratedData,
+) -> Result<PrivacyEvaluationReport, String> {
+    PrivacyEvaluator::new(
+        &quasi_identifiers
+            .iter()
+            .map(|qi| qi.to_string())
+            .collect::<Vec<String>>(),
+        &[],
+    )
+    .calc_privacy_evaluation_report(training, holdout, generated_data, "")
+}
+
+#[test]
+fn validate_distance_to_closest_record() {
+    let records = vec![
+        vec![Some("a1"), Some("b1"), None],
+        vec![Some("a2"), Some("b2"), Some("c2")],
+        vec![Some("a3"), Some("b3"), Some("c3")],
+    ];
+    let other_records = vec![
+        vec![Some("a1"), Some("b1"), None],
+        vec![Some("a2"), Some("b2"), Some("c1")],
+    ];
+    let distances = DistanceToClosestRecord::calc_distances(&records, &other_records, 3);
+
+    assert!(distances == [0, 1, 3]);
+    assert!(DistanceToClosestRecord::calc_distances(&records, &[], 3) == [3, 3, 3]);
+
+    let dcr = DistanceToClosestRecord::new(&[0, 0, 1, 2, 3], 3);
+
+    assert!(dcr.number_of_records == 5);
+    assert!(dcr.number_of_exact_matches == 2);
+    assert!((dcr.exact_match_percentage - 40.0).abs() < 1e-9);
+    assert!((dcr.mean_normalized_distance - 0.4).abs() < 1e-9);
+    assert!(dcr.percentile_5 == 0);
+    assert!(dcr.median == 1);
+    assert!(dcr.count_by_distance == [2, 1, 1, 1]);
+}
+
+#[test]
+fn validate_membership_inference_scores() {
+    let separated = MembershipInference::new(
+        DistanceToClosestRecord::new(&[0, 0, 0], 2),
+        DistanceToClosestRecord::new(&[2, 2], 2),
+    );
+
+    assert!((separated.auc - 1.0).abs() < 1e-9);
+    assert!((separated.advantage - 1.0).abs() < 1e-9);
+
+    let indistinguishable = MembershipInference::new(
+        DistanceToClosestRecord::new(&[0, 1, 2], 2),
+        DistanceToClosestRecord::new(&[0, 1, 2], 2),
+    );
+
+    assert!((indistinguishable.auc - 0.5).abs() < 1e-9);
+    assert!(indistinguishable.advantage.abs() < 1e-9);
+}
+
+#[test]
+fn copied_records_are_exposed() {
+    let (training, holdout) = read_training_and_holdout();
+    let report = evaluate(
+        &["Age", "Sex", "Zip"],
+        &training,
+        Some(&holdout),
+        &copy_records(&training, None),
+    )
+    .unwrap();
+    let membership_inference = report.membership_inference.as_ref().unwrap();
+
+    assert!(report.number_of_columns == 4);
+    assert!(report.sensitive_number_of_records == NUMBER_OF_TRAINING_RECORDS);
+    assert!(report.synthetic_number_of_records == NUMBER_OF_TRAINING_RECORDS);
+    assert!(report.holdout_number_of_records == Some(50));
+    assert!(report.quasi_identifiers == ["Age", "Sex", "Zip"]);
+
+    // every synthetic record is a sensitive record
+    assert!(report.synthetic_dcr.number_of_exact_matches == NUMBER_OF_TRAINING_RECORDS);
+    assert!(
+        membership_inference.training_dcr.number_of_exact_matches == NUMBER_OF_TRAINING_RECORDS
+    );
+    assert!(membership_inference.auc > 0.9);
+    assert!(membership_inference.advantage > 0.8);
+
+    // the diagnosis is the only target
+    assert!(report.attribute_inference.len() == 1);
+
+    let diagnosis = &report.attribute_inference[0];
+
+    assert!(diagnosis.target_column == "Diagnosis");
+    assert!(diagnosis.number_of_attacked_records == NUMBER_OF_TRAINING_RECORDS);
+    assert!(diagnosis.match_percentage == 100.0);
+    assert!(diagnosis.success_percentage > 90.0);
+    assert!(diagnosis.baseline_success_percentage < 50.0);
+    assert!(diagnosis.holdout_success_percentage.unwrap() < diagnosis.success_percentage);
+}
+
+#[test]
+fn perturbed_records_are_not_exact_matches() {
+    let (training, _) = read_training_and_holdout();
+    let age_column = training.calc_column_index_by_name()["Age"];
+    let report = evaluate(
+        &["Sex", "Zip"],
+        &training,
+        None,
+        &copy_records(&training, Some(age_column)),
+    )
+    .unwrap();
+
+    assert!(report.membership_inference.is_none());
+    assert!(report.synthetic_dcr.number_of_exact_matches == 0);
+    assert!(report.synthetic_dcr.percentile_5 == 1);
+    assert!(report.synthetic_dcr.count_by_distance[1] == NUMBER_OF_TRAINING_RECORDS);
+
+    // the age is no longer inferred from sex and zip
+    let age = report
+        .attribute_inference
+        .iter()
+        .find(|attribute_inference| attribute_inference.target_column == "Age")
+        .unwrap();
+
+    assert!(report.attribute_inference.len() == 2);
+    assert!(age.success_percentage == 0.0);
+    assert!(age.holdout_success_percentage.is_none());
+}
+
+#[test]
+fn invalid_privacy_evaluation_columns() {
+    let (training, _) = read_training_and_holdout();
+    let generated_data = copy_records(&training, None);
+
+    assert!(evaluate(&["Age", "Unknown"], &training, None, &generated_data).is_err());
+    assert!(
+        PrivacyEvaluator::new(&["Age".to_owned()], &["age".to_owned()])
+            .calc_privacy_evaluation_report(&training, None, &generated_data, "")
+            .is_err()
+    );
+    assert!(evaluate(&["age", "SEX"], &training, None, &generated_data).is_ok());
+}
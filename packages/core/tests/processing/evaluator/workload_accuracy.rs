@@ -0,0 +1,127 @@
+use sds_core::{
+    data_block::DataBlock,
+    processing::{
+        aggregator::Aggregator,
+        evaluator::{Evaluator, WorkloadQuery},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+fn read_test_data(record_limit: usize) -> Arc<DataBlock> {
+    read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        record_limit,
+    )
+}
+
+fn gen_workload(queries: &[&[&str]]) -> Vec<WorkloadQuery> {
+    queries
+        .iter()
+        .map(|query| query.iter().map(|column| column.to_string()).collect())
+        .collect()
+}
+
+#[test]
+fn validate_workload_accuracy() {
+    let sensitive = read_test_data(0);
+    // synthetic data mimicking the first two sensitive records
+    let synthetic = read_test_data(2);
+    let accuracy = Evaluator::default()
+        .calc_workload_accuracy(
+            &gen_workload(&[&["a"], &["A", "B", "C", "D"]]),
+            &sensitive,
+            &synthetic,
+        )
+        .unwrap();
+
+    assert!(accuracy.len() == 2);
+
+    // a1 -> 2 vs 1, a2 -> 1 vs 1
+    assert!(accuracy[0].query == ["A"]);
+    assert!(accuracy[0].number_of_cells == 2);
+    assert!(accuracy[0].sensitive_total == 3);
+    assert!(accuracy[0].synthetic_total == 2);
+    assert!((accuracy[0].total_variation_distance - 1.0 / 6.0).abs() < 1e-9);
+    assert!(accuracy[0].max_abs_error == 1);
+    assert!((accuracy[0].mean_abs_error - 0.5).abs() < 1e-9);
+    assert!((accuracy[0].mean_relative_error - 0.25).abs() < 1e-9);
+
+    // only the first record has values for every column
+    assert!(accuracy[1].number_of_cells == 1);
+    assert!(accuracy[1].sensitive_total == 1);
+    assert!(accuracy[1].synthetic_total == 1);
+    assert!(accuracy[1].total_variation_distance == 0.0);
+    assert!(accuracy[1].max_abs_error == 0);
+
+    assert!((accuracy.calc_mean_total_variation_distance() - 1.0 / 12.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_workload_accuracy_with_aggregates() {
+    let sensitive = read_test_data(0);
+    let synthetic = read_test_data(2);
+    let synthetic_aggregated_data = Aggregator::new(synthetic.clone())
+        .aggregate(3, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+    let evaluator = Evaluator::default();
+    let workload = gen_workload(&[&["A"], &["A", "B"], &["B", "D", "A"]]);
+    let with_aggregates = evaluator
+        .calc_workload_accuracy_with_aggregates(&workload, &sensitive, &synthetic_aggregated_data)
+        .unwrap();
+    let with_microdata = evaluator
+        .calc_workload_accuracy(&workload, &sensitive, &synthetic)
+        .unwrap();
+
+    // aggregates answer the queries just like the microdata they came from
+    for (a, m) in with_aggregates.iter().zip(with_microdata.iter()) {
+        assert!(a.query == m.query);
+        assert!(a.number_of_cells == m.number_of_cells);
+        assert!(a.synthetic_total == m.synthetic_total);
+        assert!(a.total_variation_distance == m.total_variation_distance);
+        assert!(a.max_abs_error == m.max_abs_error);
+    }
+
+    // longer than the reporting length
+    assert!(evaluator
+        .calc_workload_accuracy_with_aggregates(
+            &gen_workload(&[&["A", "B", "C", "D"]]),
+            &sensitive,
+            &synthetic_aggregated_data
+        )
+        .is_err());
+}
+
+#[test]
+fn invalid_workload_queries() {
+    let sensitive = read_test_data(0);
+    let evaluator = Evaluator::default();
+
+    assert!(evaluator
+        .calc_workload_accuracy(&gen_workload(&[&["A", "E"]]), &sensitive, &sensitive)
+        .is_err());
+    assert!(evaluator
+        .calc_workload_accuracy(&gen_workload(&[&["A", "a"]]), &sensitive, &sensitive)
+        .is_err());
+    assert!(evaluator
+        .calc_workload_accuracy(&gen_workload(&[&[]]), &sensitive, &sensitive)
+        .is_err());
+
+    let identical = evaluator
+        .calc_workload_accuracy(&gen_workload(&[&["B", "C"]]), &sensitive, &sensitive)
+        .unwrap();
+
+    assert!(identical[0].total_variation_distance == 0.0);
+    assert!(identical[0].mean_relative_error == 0.0);
+}
@@ -0,0 +1,262 @@
+use sds_core::{
+    data_block::DataBlock,
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        generator::{Generator, SynthesisConditions},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const RESOLUTION: usize = 2;
+
+const CACHE_MAX_SIZE: usize = 1000;
+
+fn read_data_block() -> Arc<DataBlock> {
+    read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    )
+}
+
+fn aggregate(data_block: &Arc<DataBlock>) -> Arc<AggregatedData> {
+    Arc::new(
+        Aggregator::new(data_block.clone())
+            .aggregate(3, &mut None::<LoggerProgressReporter>)
+            .unwrap(),
+    )
+}
+
+fn required_values(data_block: &Arc<DataBlock>, values: &[&str]) -> SynthesisConditions {
+    SynthesisConditions::new_required_values(
+        values
+            .iter()
+            .map(|value| SynthesisConditions::parse_value(&data_block.headers, value).unwrap())
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn target_counts(data_block: &Arc<DataBlock>, counts: &[(&str, usize)]) -> SynthesisConditions {
+    SynthesisConditions::new_target_counts(
+        counts
+            .iter()
+            .map(|(value, count)| {
+                (
+                    SynthesisConditions::parse_value(&data_block.headers, value).unwrap(),
+                    *count,
+                )
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn generate_aggregate_seeded(
+    aggregated_data: &Arc<AggregatedData>,
+    conditions: SynthesisConditions,
+    seed: u64,
+) -> Vec<Vec<String>> {
+    Generator::new(Some(seed))
+        .generate_aggregate_seeded_with_conditions(
+            "",
+            aggregated_data.clone(),
+            true,
+            None,
+            None,
+            None,
+            Some(conditions),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+        .synthetic_data
+        .iter()
+        .map(|record| record.iter().map(|value| (**value).clone()).collect())
+        .collect()
+}
+
+fn generate_row_seeded(
+    data_block: &Arc<DataBlock>,
+    conditions: SynthesisConditions,
+    seed: u64,
+) -> Vec<Vec<String>> {
+    Generator::new(Some(seed))
+        .generate_row_seeded_with_conditions(
+            data_block,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            "",
+            Some(conditions),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+        .synthetic_data
+        .iter()
+        .map(|record| record.iter().map(|value| (**value).clone()).collect())
+        .collect()
+}
+
+fn count(records: &[Vec<String>], matches: impl Fn(&Vec<String>) -> bool) -> usize {
+    records.iter().filter(|record| matches(record)).count()
+}
+
+#[test]
+fn invalid_conditions() {
+    let data_block = read_data_block();
+
+    assert!(SynthesisConditions::parse_value(&data_block.headers, "F:f1").is_err());
+    assert!(SynthesisConditions::parse_value(&data_block.headers, "A").is_err());
+    assert!(SynthesisConditions::new_required_values(vec![]).is_err());
+    assert!(SynthesisConditions::new_required_values(vec![
+        SynthesisConditions::parse_value(&data_block.headers, "A:a1").unwrap(),
+        SynthesisConditions::parse_value(&data_block.headers, "A:a2").unwrap(),
+    ])
+    .is_err());
+    assert!(SynthesisConditions::new_target_counts(vec![
+        (
+            SynthesisConditions::parse_value(&data_block.headers, "A:a1").unwrap(),
+            10
+        ),
+        (
+            SynthesisConditions::parse_value(&data_block.headers, "A:a1").unwrap(),
+            20
+        ),
+    ])
+    .is_err());
+}
+
+#[test]
+fn valid_aggregate_seeded_with_required_values() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate(&data_block);
+    let synthetic_data = generate_aggregate_seeded(
+        &aggregated_data,
+        required_values(&data_block, &["A:a1"]),
+        42,
+    );
+    let synthetic_records = &synthetic_data[1..];
+    let aggregates_count = aggregated_data.aggregates_count_as_str(";");
+
+    // defaults to the number of records containing the required value
+    assert!(synthetic_records.len() == 110);
+    assert!(synthetic_records.iter().all(|record| record[0] == "a1"));
+
+    // the other attributes should follow the counts conditioned on the required value
+    for (column_index, header) in ["B", "C", "D", "E"].iter().enumerate() {
+        for value in ["b1", "b2", "c1", "c2", "d1", "d2", "e1", "e2"] {
+            let sensitive_count = *aggregates_count
+                .get(&format!("A:a1;{}:{}", header, value))
+                .unwrap_or(&0) as isize;
+            let synthetic_count =
+                count(synthetic_records, |r| r[column_index + 1] == value) as isize;
+
+            assert!((sensitive_count - synthetic_count).abs() <= 11);
+        }
+    }
+}
+
+#[test]
+fn valid_aggregate_seeded_with_multiple_required_values() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate(&data_block);
+    let synthetic_data = generate_aggregate_seeded(
+        &aggregated_data,
+        required_values(&data_block, &["B:b1", "A:a1"]),
+        42,
+    );
+    let synthetic_records = &synthetic_data[1..];
+
+    assert!(synthetic_records.len() == 53);
+    assert!(synthetic_records
+        .iter()
+        .all(|record| record[0] == "a1" && record[1] == "b1"));
+}
+
+#[test]
+fn valid_aggregate_seeded_with_target_counts() {
+    let data_block = read_data_block();
+    let synthetic_data = generate_aggregate_seeded(
+        &aggregate(&data_block),
+        target_counts(&data_block, &[("D:d2", 20), ("D:d1", 30)]),
+        42,
+    );
+    let synthetic_records = &synthetic_data[1..];
+
+    assert!(synthetic_records.len() == 50);
+    assert!(count(synthetic_records, |r| r[3] == "d1") == 30);
+    assert!(count(synthetic_records, |r| r[3] == "d2") == 20);
+}
+
+#[test]
+fn valid_row_seeded_with_required_values() {
+    let data_block = read_data_block();
+    let synthetic_data =
+        generate_row_seeded(&data_block, required_values(&data_block, &["C:c2"]), 42);
+    let synthetic_records = &synthetic_data[1..];
+
+    // 59 sensitive rows, rounded down to the resolution
+    assert!(synthetic_records.len() == 58);
+    assert!(synthetic_records.iter().all(|record| record[2] == "c2"));
+
+    // every synthesized record should be present at least resolution
+    // times on the sensitive data
+    for record in synthetic_records.iter() {
+        let matching_rows = data_block
+            .records
+            .iter()
+            .filter(|sensitive_record| {
+                record.iter().enumerate().all(|(column_index, value)| {
+                    value.is_empty()
+                        || sensitive_record
+                            .values
+                            .iter()
+                            .any(|v| v.column_index == column_index && v.value.as_str() == value)
+                })
+            })
+            .count();
+
+        assert!(matching_rows >= RESOLUTION);
+    }
+}
+
+#[test]
+fn valid_row_seeded_with_target_counts() {
+    let data_block = read_data_block();
+    let synthetic_data = generate_row_seeded(
+        &data_block,
+        target_counts(&data_block, &[("E:e3", 25), ("E:e1", 75)]),
+        42,
+    );
+    let synthetic_records = &synthetic_data[1..];
+
+    assert!(synthetic_records.len() == 100);
+    assert!(count(synthetic_records, |r| r[4] == "e1") == 75);
+    assert!(count(synthetic_records, |r| r[4] == "e3") == 25);
+}
+
+#[test]
+fn valid_conditional_generation_is_reproducible() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate(&data_block);
+    let conditions = target_counts(&data_block, &[("A:a2", 40), ("A:a3", 30)]);
+
+    assert!(
+        generate_aggregate_seeded(&aggregated_data, conditions.clone(), 42)
+            == generate_aggregate_seeded(&aggregated_data, conditions.clone(), 42)
+    );
+    assert!(
+        generate_row_seeded(&data_block, conditions.clone(), 42)
+            == generate_row_seeded(&data_block, conditions, 42)
+    );
+}
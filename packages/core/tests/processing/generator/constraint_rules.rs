@@ -0,0 +1,203 @@
+use sds_core::{
+    data_block::DataBlock,
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::{
+        aggregator::Aggregator,
+        generator::{ConstraintRules, ConstraintViolations, GeneratedData, Generator},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const RESOLUTION: usize = 2;
+
+const CACHE_MAX_SIZE: usize = 1000;
+
+const RULES: &str = "
+# a1 and b1 are frequently seen together on the sensitive data
+deny A:a1 & B:b1
+
+if C:c1 then D:d1
+column E requires C
+";
+
+fn read_data_block() -> Arc<DataBlock> {
+    read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    )
+}
+
+fn generate_all_modes(data_block: &Arc<DataBlock>, generator: &Generator) -> Vec<GeneratedData> {
+    let aggregated_data = Arc::new(
+        Aggregator::new(data_block.clone())
+            .aggregate_with_dp(
+                3,
+                &DpParameters {
+                    seed: Some(42),
+                    ..DpParameters::new(4.0, 0.001, 99, 0.01)
+                },
+                NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+                &mut None::<LoggerProgressReporter>,
+            )
+            .unwrap(),
+    );
+
+    vec![
+        generator
+            .generate_row_seeded(
+                data_block,
+                RESOLUTION,
+                CACHE_MAX_SIZE,
+                "",
+                &mut None::<LoggerProgressReporter>,
+            )
+            .unwrap(),
+        generator
+            .generate_value_seeded(
+                data_block,
+                RESOLUTION,
+                CACHE_MAX_SIZE,
+                "",
+                None,
+                &mut None::<LoggerProgressReporter>,
+            )
+            .unwrap(),
+        generator
+            .generate_aggregate_seeded(
+                "",
+                aggregated_data,
+                true,
+                None,
+                None,
+                None,
+                &mut None::<LoggerProgressReporter>,
+            )
+            .unwrap(),
+    ]
+}
+
+#[test]
+fn valid_constraint_rules_parsing() {
+    let data_block = read_data_block();
+    let constraint_rules =
+        ConstraintRules::from_str_using_headers(&data_block.headers, RULES).unwrap();
+    let rules_str: Vec<String> = constraint_rules
+        .rules()
+        .iter()
+        .map(|rule| rule.as_str_using_headers(&data_block.headers))
+        .collect();
+
+    assert!(
+        rules_str
+            == [
+                "deny A:a1 & B:b1",
+                "if C:c1 then D:d1",
+                "column E requires C"
+            ]
+    );
+}
+
+#[test]
+fn invalid_constraint_rules_parsing() {
+    let data_block = read_data_block();
+
+    for rules in [
+        "deny A:a1 B:b1",
+        "deny A:a1 & F:f1",
+        "deny A:a1 & A:a2",
+        "if C:c1 D:d1",
+        "column E requires F",
+        "column E requires E",
+        "allow A:a1 & B:b1",
+    ] {
+        assert!(ConstraintRules::from_str_using_headers(&data_block.headers, rules).is_err());
+    }
+}
+
+#[test]
+fn valid_constraint_violations_count() {
+    let data_block = read_data_block();
+    let constraint_rules =
+        ConstraintRules::from_str_using_headers(&data_block.headers, RULES).unwrap();
+    let generated_data = Generator::new(Some(42))
+        .generate_row_seeded(
+            &data_block,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            "",
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap();
+    let records = &generated_data.synthetic_data[1..];
+    let violations = constraint_rules.count_violations(&generated_data, "");
+    let expected_violations_by_rule = vec![
+        records
+            .iter()
+            .filter(|r| r[0].as_str() == "a1" && r[1].as_str() == "b1")
+            .count(),
+        records
+            .iter()
+            .filter(|r| r[2].as_str() == "c1" && r[3].as_str() != "d1")
+            .count(),
+        records
+            .iter()
+            .filter(|r| !r[4].is_empty() && r[2].is_empty())
+            .count(),
+    ];
+
+    // without the rules the synthesizer reproduces the sensitive combinations
+    assert!(expected_violations_by_rule.iter().all(|count| *count > 0));
+    assert!(violations.violations_by_rule == expected_violations_by_rule);
+    assert!(
+        violations.number_of_records_with_violations
+            == records
+                .iter()
+                .filter(|r| (r[0].as_str() == "a1" && r[1].as_str() == "b1")
+                    || (r[2].as_str() == "c1" && r[3].as_str() != "d1")
+                    || (!r[4].is_empty() && r[2].is_empty()))
+                .count()
+    );
+}
+
+#[test]
+fn valid_generation_with_constraint_rules() {
+    let data_block = read_data_block();
+    let constraint_rules =
+        ConstraintRules::from_str_using_headers(&data_block.headers, RULES).unwrap();
+    let without_rules = generate_all_modes(&data_block, &Generator::new(Some(42)));
+    let with_rules = generate_all_modes(
+        &data_block,
+        &Generator::new(Some(42)).with_constraint_rules(constraint_rules.clone()),
+    );
+
+    for (generated_without_rules, generated_with_rules) in
+        without_rules.iter().zip(with_rules.iter())
+    {
+        assert!(
+            constraint_rules
+                .count_violations(generated_without_rules, "")
+                .number_of_records_with_violations
+                > 0
+        );
+        assert!(
+            constraint_rules.count_violations(generated_with_rules, "")
+                == ConstraintViolations {
+                    number_of_records_with_violations: 0,
+                    violations_by_rule: vec![0, 0, 0],
+                }
+        );
+        assert!(generated_with_rules.synthetic_data.len() > 100);
+    }
+}
@@ -0,0 +1,194 @@
+use sds_core::{
+    data_block::DataBlock,
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        generator::Generator,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const TARGET_NUMBER_OF_RECORDS: usize = 4000;
+
+fn read_data_block() -> Arc<DataBlock> {
+    read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    )
+}
+
+fn aggregate(data_block: &Arc<DataBlock>, reporting_length: usize) -> Arc<AggregatedData> {
+    Arc::new(
+        Aggregator::new(data_block.clone())
+            .aggregate(reporting_length, &mut None::<LoggerProgressReporter>)
+            .unwrap(),
+    )
+}
+
+fn aggregate_with_dp(data_block: &Arc<DataBlock>) -> Arc<AggregatedData> {
+    Arc::new(
+        Aggregator::new(data_block.clone())
+            .aggregate_with_dp(
+                3,
+                &DpParameters {
+                    seed: Some(42),
+                    ..DpParameters::new(4.0, 0.001, 99, 0.01)
+                },
+                NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+                &mut None::<LoggerProgressReporter>,
+            )
+            .unwrap(),
+    )
+}
+
+fn generate(
+    aggregated_data: &Arc<AggregatedData>,
+    max_clique_size: usize,
+    seed: u64,
+) -> Vec<Vec<String>> {
+    Generator::new(Some(seed))
+        .generate_marginal_model(
+            "",
+            aggregated_data.clone(),
+            Some(max_clique_size),
+            Some(TARGET_NUMBER_OF_RECORDS),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+        .synthetic_data
+        .iter()
+        .map(|record| record.iter().map(|value| (**value).clone()).collect())
+        .collect()
+}
+
+fn frequency(records: &[Vec<String>], matches: impl Fn(&Vec<String>) -> bool) -> f64 {
+    records.iter().filter(|record| matches(record)).count() as f64 / records.len() as f64
+}
+
+#[test]
+fn valid_marginal_model_record_count_and_headers() {
+    let data_block = read_data_block();
+    let synthetic_data = generate(&aggregate(&data_block, 3), 3, 42);
+
+    assert!(synthetic_data.len() == TARGET_NUMBER_OF_RECORDS + 1);
+    assert!(synthetic_data[0] == ["A", "B", "C", "D", "E"]);
+    assert!(synthetic_data.iter().all(|record| record.len() == 5));
+}
+
+#[test]
+fn valid_marginal_model_is_reproducible() {
+    let aggregated_data = aggregate(&read_data_block(), 3);
+
+    assert!(generate(&aggregated_data, 3, 42) == generate(&aggregated_data, 3, 42));
+    assert!(generate(&aggregated_data, 3, 42) != generate(&aggregated_data, 3, 43));
+}
+
+#[test]
+fn valid_marginal_model_preserves_single_attribute_distributions() {
+    let data_block = read_data_block();
+    let sensitive_data: Vec<Vec<String>> = data_block
+        .records
+        .iter()
+        .map(|record| {
+            let mut values = vec![String::default(); data_block.headers.len()];
+
+            for value in record.values.iter() {
+                values[value.column_index] = (*value.value).clone();
+            }
+            values
+        })
+        .collect();
+
+    for max_clique_size in 1..=3 {
+        let synthetic_data = generate(&aggregate(&data_block, 3), max_clique_size, 42);
+        let synthetic_records = &synthetic_data[1..];
+
+        for column_index in 0..data_block.headers.len() {
+            for value in ["", "a1", "b1", "c1", "c2", "d1", "e1"] {
+                let sensitive_frequency = frequency(&sensitive_data, |r| r[column_index] == value);
+                let synthetic_frequency =
+                    frequency(synthetic_records, |r| r[column_index] == value);
+
+                assert!((sensitive_frequency - synthetic_frequency).abs() < 0.03);
+            }
+        }
+    }
+}
+
+#[test]
+fn valid_marginal_model_preserves_pairwise_distributions() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate(&data_block, 2);
+    let synthetic_data = generate(&aggregated_data, 2, 42);
+    let independent_data = generate(&aggregated_data, 1, 42);
+    let sensitive_count = |comb: &str| {
+        aggregated_data
+            .aggregates_count_as_str(";")
+            .get(comb)
+            .cloned()
+            .unwrap_or(0) as f64
+            / aggregated_data.number_of_records as f64
+    };
+    let headers = ["A", "B", "C", "D", "E"];
+    let calc_error = |records: &[Vec<String>]| {
+        let mut error = 0.0;
+
+        for i in 0..headers.len() {
+            for j in (i + 1)..headers.len() {
+                for value_i in ["a1", "a2", "b1", "b2", "c1", "c2", "d1", "d2"] {
+                    for value_j in ["b1", "b2", "c1", "c2", "d1", "d2", "e1", "e2"] {
+                        let comb = format!("{}:{};{}:{}", headers[i], value_i, headers[j], value_j);
+
+                        error += (sensitive_count(&comb)
+                            - frequency(records, |r| r[i] == value_i && r[j] == value_j))
+                        .abs();
+                    }
+                }
+            }
+        }
+        error
+    };
+
+    // the tree structured model should fit the pairs better than
+    // the model assuming all the columns are independent
+    assert!(calc_error(&synthetic_data[1..]) < calc_error(&independent_data[1..]));
+}
+
+#[test]
+fn valid_marginal_model_fits_noisy_single_attribute_counts() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate_with_dp(&data_block);
+    let aggregates_count = aggregated_data.aggregates_count_as_str(";");
+    let number_of_records = aggregated_data.protected_number_of_records.unwrap() as f64;
+
+    for max_clique_size in 1..=3 {
+        let synthetic_data = generate(&aggregated_data, max_clique_size, 42);
+
+        // noisy pairs and triples might contradict the single attribute
+        // counts, but the model should still be consistent with them
+        for (column_index, header) in ["A", "B", "C", "D", "E"].iter().enumerate() {
+            for value in ["a1", "a2", "b1", "b2", "c1", "c2", "d1", "d2", "e1", "e2"] {
+                let noisy_frequency = *aggregates_count
+                    .get(&format!("{}:{}", header, value))
+                    .unwrap_or(&0) as f64
+                    / number_of_records;
+                let synthetic_frequency =
+                    frequency(&synthetic_data[1..], |r| r[column_index] == value);
+
+                assert!((noisy_frequency - synthetic_frequency).abs() < 0.05);
+            }
+        }
+    }
+}
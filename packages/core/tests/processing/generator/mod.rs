@@ -0,0 +1,9 @@
+mod conditional_generation;
+
+mod constraint_rules;
+
+mod marginal_model;
+
+mod parent_child_generation;
+
+mod seeded_generation;
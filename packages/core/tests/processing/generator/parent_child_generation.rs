@@ -0,0 +1,245 @@
+use csv::ReaderBuilder;
+use fnv::{FnvHashMap, FnvHashSet};
+use sds_core::{
+    data_block::{
+        CsvDataBlockCreator, DataBlock, DataBlockCreateOptions, DataBlockCreator, DataBlockParts,
+        SubjectRecordsMode,
+    },
+    processing::generator::{Generator, ParentChildGeneratedData, ParentChildLink},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::sync::Arc;
+
+use crate::utils::get_path_on_resources;
+
+const DELIMITER: u8 = b',';
+
+const PARENTS_FILE_PATH: &str = "test_parent_child_parents.csv";
+
+const CHILDREN_FILE_PATH: &str = "test_parent_child_children.csv";
+
+const KEY: &str = "PatientID";
+
+const RESOLUTION: usize = 5;
+
+const CACHE_MAX_SIZE: usize = 1000;
+
+fn read_data_block(
+    path: &str,
+    subject_id: Option<&str>,
+    subject_records_mode: SubjectRecordsMode,
+) -> Arc<DataBlock> {
+    CsvDataBlockCreator::create(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(path)),
+        &DataBlockCreateOptions {
+            subject_id: subject_id.map(|s| s.to_owned()),
+            subject_records_mode,
+            ..DataBlockCreateOptions::default()
+        },
+    )
+    .unwrap()
+}
+
+fn read_link() -> ParentChildLink {
+    ParentChildLink::new(
+        read_data_block(PARENTS_FILE_PATH, Some(KEY), SubjectRecordsMode::Join),
+        read_data_block(CHILDREN_FILE_PATH, Some(KEY), SubjectRecordsMode::Keep),
+        KEY,
+        KEY,
+    )
+    .unwrap()
+}
+
+fn generate(link: &ParentChildLink, max_children_per_parent: usize) -> ParentChildGeneratedData {
+    Generator::new(Some(42))
+        .generate_parent_child(
+            link,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            max_children_per_parent,
+            "",
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+fn as_strings(generated_data: &ParentChildGeneratedData) -> Vec<Vec<Vec<String>>> {
+    [&generated_data.parents, &generated_data.children]
+        .iter()
+        .map(|table| {
+            table
+                .synthetic_data
+                .iter()
+                .map(|record| record.iter().map(|value| (**value).clone()).collect())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn valid_parent_child_link() {
+    let link = read_link();
+    let children_by_parent = link.calc_children_by_parent();
+
+    // the last two encounters reference missing patients
+    assert!(link.parent_data_block.number_of_records() == 300);
+    assert!(link.child_data_block.number_of_records() == 617);
+    assert!(link.number_of_orphan_children() == 2);
+    assert!(children_by_parent.len() == 300);
+    assert!(children_by_parent.iter().map(|c| c.len()).sum::<usize>() == 615);
+    assert!(link.calc_parent_attribute_columns().len() == 3);
+    assert!(link.calc_child_attribute_columns().len() == 2);
+
+    // every child is linked to the parent with its foreign key
+    let parent_ids = link.parent_data_block.subject_ids.as_ref().unwrap();
+    let foreign_keys = link.child_data_block.subject_ids.as_ref().unwrap();
+    let subject_index_by_child = link
+        .child_data_block
+        .subject_index_by_record
+        .as_ref()
+        .unwrap();
+
+    for (parent_index, children) in children_by_parent.iter().enumerate() {
+        for child_index in children.iter() {
+            assert!(parent_ids[parent_index] == foreign_keys[subject_index_by_child[*child_index]]);
+        }
+    }
+}
+
+#[test]
+fn invalid_parent_child_link() {
+    let parents = read_data_block(PARENTS_FILE_PATH, Some(KEY), SubjectRecordsMode::Join);
+    let children = read_data_block(CHILDREN_FILE_PATH, Some(KEY), SubjectRecordsMode::Keep);
+
+    assert!(ParentChildLink::new(
+        read_data_block(PARENTS_FILE_PATH, None, SubjectRecordsMode::Join),
+        children.clone(),
+        KEY,
+        KEY
+    )
+    .is_err());
+    assert!(ParentChildLink::new(
+        parents.clone(),
+        read_data_block(CHILDREN_FILE_PATH, None, SubjectRecordsMode::Keep),
+        KEY,
+        KEY
+    )
+    .is_err());
+    assert!(ParentChildLink::new(parents.clone(), children, KEY, KEY).is_ok());
+    // no child references the parents
+    assert!(ParentChildLink::new(
+        parents,
+        read_data_block(CHILDREN_FILE_PATH, Some("Ward"), SubjectRecordsMode::Keep),
+        KEY,
+        "Ward"
+    )
+    .is_err());
+}
+
+#[test]
+fn valid_parent_child_generation() {
+    let link = read_link();
+    let generated_data = generate(&link, 10);
+    let tables = as_strings(&generated_data);
+    let (parents, children) = (&tables[0], &tables[1]);
+
+    assert!(parents[0] == ["PatientID", "Sex", "AgeGroup", "Region"]);
+    assert!(children[0] == ["PatientID", "EncounterType", "Ward"]);
+    assert!(parents.len() > 250);
+    assert!(children.len() > 500);
+
+    // synthetic keys are unique and every child references a synthetic parent
+    let parent_keys: FnvHashSet<&String> = parents[1..].iter().map(|p| &p[0]).collect();
+
+    assert!(parent_keys.len() == parents.len() - 1);
+    assert!(children[1..].iter().all(|c| parent_keys.contains(&c[0])));
+
+    // maternity encounters are only seen for female patients
+    let sex_by_key: FnvHashMap<&String, &String> =
+        parents[1..].iter().map(|p| (&p[0], &p[1])).collect();
+
+    assert!(children[1..]
+        .iter()
+        .any(|c| c[1] == "maternity" && sex_by_key[&c[0]] == "F"));
+    assert!(!children[1..]
+        .iter()
+        .any(|c| c[1] == "maternity" && sex_by_key[&c[0]] == "M"));
+
+    // every synthetic parent is present at least resolution times on the sensitive data
+    for parent in parents[1..].iter() {
+        let matching_rows = link
+            .parent_data_block
+            .records
+            .iter()
+            .filter(|sensitive_record| {
+                parent[1..].iter().enumerate().all(|(i, value)| {
+                    value.is_empty()
+                        || sensitive_record.values.iter().any(|v| {
+                            link.parent_data_block.headers[v.column_index].as_str()
+                                == parents[0][i + 1]
+                                && v.value.as_str() == value
+                        })
+                })
+            })
+            .count();
+
+        assert!(matching_rows >= RESOLUTION);
+    }
+}
+
+#[test]
+fn valid_parent_child_generation_with_bounded_children() {
+    let link = read_link();
+    let tables = as_strings(&generate(&link, 2));
+    let mut children_by_key: FnvHashMap<&String, usize> = FnvHashMap::default();
+
+    for child in tables[1][1..].iter() {
+        *children_by_key.entry(&child[0]).or_default() += 1;
+    }
+
+    assert!(!children_by_key.is_empty());
+    assert!(children_by_key.values().all(|count| *count <= 2));
+}
+
+#[test]
+fn valid_parent_child_generation_is_reproducible() {
+    let link = read_link();
+
+    assert!(as_strings(&generate(&link, 10)) == as_strings(&generate(&link, 10)));
+}
+
+#[test]
+fn valid_parent_child_generation_with_number_of_children_column() {
+    let link = read_link();
+    let parents = &link.parent_data_block;
+    let mut headers = parents.headers.clone();
+    let region_index = headers.iter().position(|h| **h == "Region").unwrap();
+
+    // a parent column with the same name as the internal number of children column
+    headers[region_index] = Arc::new("number_of_children".to_owned());
+
+    let link = ParentChildLink::new(
+        Arc::new(DataBlock::new(DataBlockParts {
+            headers,
+            multi_value_column_metadata_map: parents.multi_value_column_metadata_map.clone(),
+            numeric_bins_metadata_map: parents.numeric_bins_metadata_map.clone(),
+            date_time_metadata_map: parents.date_time_metadata_map.clone(),
+            records: parents.records.clone(),
+            subject_index_by_record: parents.subject_index_by_record.clone(),
+            subject_ids: parents.subject_ids.clone(),
+        })),
+        link.child_data_block.clone(),
+        KEY,
+        KEY,
+    )
+    .unwrap();
+    let tables = as_strings(&generate(&link, 10));
+
+    assert!(tables[0][0] == ["PatientID", "Sex", "AgeGroup", "number_of_children"]);
+    assert!(tables[0][1..]
+        .iter()
+        .all(|p| ["", "north", "south"].contains(&p[3].as_str())));
+    assert!(tables[1].len() > 500);
+}
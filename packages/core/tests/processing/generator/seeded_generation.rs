@@ -0,0 +1,149 @@
+use sds_core::{
+    data_block::DataBlock,
+    dp::{DpParameters, InputValueByLen, NoisyCountThreshold},
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        generator::{Generator, OversamplingParameters},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_seeded_generation.csv";
+
+const RESOLUTION: usize = 2;
+
+const CACHE_MAX_SIZE: usize = 1000;
+
+fn read_data_block() -> Arc<DataBlock> {
+    read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    )
+}
+
+fn aggregate_with_dp(data_block: &Arc<DataBlock>, seed: Option<u64>) -> AggregatedData {
+    Aggregator::new(data_block.clone())
+        .aggregate_with_dp(
+            3,
+            &DpParameters {
+                seed,
+                ..DpParameters::new(4.0, 0.001, 99, 0.01)
+            },
+            NoisyCountThreshold::Adaptive(InputValueByLen::default()),
+            &mut None::<LoggerProgressReporter>,
+        )
+        .unwrap()
+}
+
+fn generate_all_modes(data_block: &Arc<DataBlock>, seed: Option<u64>) -> Vec<String> {
+    let generator = Generator::new(seed);
+    let aggregated_data = Arc::new(aggregate_with_dp(data_block, seed));
+
+    [
+        generator.generate_row_seeded(
+            data_block,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            "",
+            &mut None::<LoggerProgressReporter>,
+        ),
+        generator.generate_unseeded(
+            data_block,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            "",
+            &mut None::<LoggerProgressReporter>,
+        ),
+        generator.generate_value_seeded(
+            data_block,
+            RESOLUTION,
+            CACHE_MAX_SIZE,
+            "",
+            Some(OversamplingParameters::new(
+                aggregated_data.clone(),
+                Some(0.1),
+                Some(2),
+            )),
+            &mut None::<LoggerProgressReporter>,
+        ),
+        generator.generate_aggregate_seeded(
+            "",
+            aggregated_data.clone(),
+            true,
+            None,
+            None,
+            Some(150),
+            &mut None::<LoggerProgressReporter>,
+        ),
+        generator.generate_marginal_model(
+            "",
+            aggregated_data,
+            Some(3),
+            None,
+            &mut None::<LoggerProgressReporter>,
+        ),
+    ]
+    .into_iter()
+    .map(|generated_data| {
+        generated_data
+            .unwrap()
+            .synthetic_data_to_string(',', "", false, false, None)
+            .unwrap()
+    })
+    .collect()
+}
+
+#[test]
+fn valid_seeded_dp_aggregation_is_reproducible() {
+    let data_block = read_data_block();
+    let aggregated_data = aggregate_with_dp(&data_block, Some(42));
+    let same_seed_aggregated_data = aggregate_with_dp(&data_block, Some(42));
+    let other_seed_aggregated_data = aggregate_with_dp(&data_block, Some(43));
+
+    assert!(
+        aggregated_data.aggregates_count_as_str(";")
+            == same_seed_aggregated_data.aggregates_count_as_str(";")
+    );
+    assert!(
+        aggregated_data.protected_number_of_records
+            == same_seed_aggregated_data.protected_number_of_records
+    );
+    assert!(
+        aggregated_data.aggregates_count_as_str(";")
+            != other_seed_aggregated_data.aggregates_count_as_str(";")
+    );
+}
+
+#[test]
+fn valid_seeded_generation_is_reproducible() {
+    let data_block = read_data_block();
+    let synthetic_data = generate_all_modes(&data_block, Some(42));
+
+    assert!(synthetic_data == generate_all_modes(&data_block, Some(42)));
+    assert!(synthetic_data != generate_all_modes(&data_block, Some(43)));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn valid_seeded_generation_does_not_depend_on_number_of_threads() {
+    let data_block = read_data_block();
+    let run_with_threads = |n_threads: usize| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .build()
+            .unwrap()
+            .install(|| generate_all_modes(&data_block, Some(42)))
+    };
+
+    assert!(run_with_threads(1) == run_with_threads(4));
+}
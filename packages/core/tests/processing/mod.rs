@@ -1 +1,5 @@
 mod aggregator;
+
+mod evaluator;
+
+mod generator;
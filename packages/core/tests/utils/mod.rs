@@ -1,7 +1,9 @@
 use csv::ReaderBuilder;
 use fnv::FnvHashMap;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlock, DataBlockCreator, DataBlockValue},
+    data_block::{
+        CsvDataBlockCreator, DataBlock, DataBlockCreateOptions, DataBlockCreator, DataBlockValue,
+    },
     dp::CombinationsCountMap,
     processing::aggregator::{ValueCombination, COMBINATIONS_DELIMITER},
 };
@@ -32,11 +34,14 @@ pub fn read_test_data_block<S: AsRef<Path>>(
         ReaderBuilder::new()
             .delimiter(delimiter)
             .from_path(get_path_on_resources(path)),
-        subject_id,
-        use_columns,
-        multi_value_columns,
-        sensitive_zeros,
-        record_limit,
+        &DataBlockCreateOptions {
+            subject_id,
+            use_columns: use_columns.to_vec(),
+            multi_value_columns: multi_value_columns.clone(),
+            sensitive_zeros: sensitive_zeros.to_vec(),
+            record_limit,
+            ..DataBlockCreateOptions::default()
+        },
     )
     .unwrap()
 }
@@ -1,15 +1,8 @@
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use sds_core::dp::AccuracyMode as AccuracyModeEnum;
 use serde::Serialize;
 
-#[derive(Clone, Serialize)]
-pub enum AccuracyModeEnum {
-    PrioritizeLongCombinations,
-    PrioritizeShortCombinations,
-    Balanced,
-    Custom(Vec<f64>),
-}
-
 #[pyclass]
 #[derive(Clone, Serialize)]
 /// This controls how to split the privacy budget for different
@@ -151,20 +144,7 @@ impl AccuracyMode {
     }
 
     pub(crate) fn extract_sigma_proportions(&self, reporting_length: usize) -> Vec<f64> {
-        match &self.mode {
-            AccuracyModeEnum::PrioritizeLongCombinations => (0..reporting_length)
-                .map(|i| 1.0 / ((i + 1) as f64))
-                .collect(),
-            AccuracyModeEnum::PrioritizeShortCombinations => (0..reporting_length)
-                .map(|i| 1.0 / ((reporting_length - i) as f64))
-                .collect(),
-            AccuracyModeEnum::Balanced => {
-                let mut sigma_proportions = Vec::default();
-                sigma_proportions.resize_with(reporting_length, || 1.0);
-                sigma_proportions
-            }
-            AccuracyModeEnum::Custom(sigma_proportions) => sigma_proportions.clone(),
-        }
+        self.mode.sigma_proportions(reporting_length)
     }
 }
 
@@ -21,6 +21,7 @@ use serde::Serialize;
 ///     - use_synthetic_counts: false
 ///     - weight_selection_percentile: 95
 ///     - aggregate_counts_scale_factor: None
+///     - seed: None
 ///
 /// Calling the builder methods will update the parameters in place and return
 /// a reference to the builder itself (so method calls can be chained for configuration).
@@ -42,6 +43,7 @@ pub struct DpAggregateSeededParametersBuilder {
     _use_synthetic_counts: bool,
     _weight_selection_percentile: usize,
     _aggregate_counts_scale_factor: Option<f64>,
+    _seed: Option<u64>,
 }
 
 #[pymethods]
@@ -62,6 +64,7 @@ impl DpAggregateSeededParametersBuilder {
             _use_synthetic_counts: false,
             _weight_selection_percentile: 95,
             _aggregate_counts_scale_factor: None,
+            _seed: None,
         }
     }
 
@@ -294,6 +297,27 @@ impl DpAggregateSeededParametersBuilder {
         slf
     }
 
+    #[inline]
+    #[pyo3(text_signature = "(self, value)")]
+    /// Seed used to initialize the random number generators used
+    /// to add noise to the aggregates (`.fit`) and to sample records (`.sample`).
+    ///
+    /// Using the same dataset, parameters and seed will always produce the same
+    /// differentially private aggregates and synthetic data, which is useful for
+    /// regression testing and audit trails.
+    ///
+    /// By default a new random seed is used every time.
+    ///
+    /// Arguments:
+    ///     * value: int - value to be set
+    ///
+    /// Returns:
+    ///     Self reference to the builder - DpAggregateSeededParametersBuilder
+    pub fn seed(slf: Py<Self>, py: Python, value: u64) -> Py<Self> {
+        slf.borrow_mut(py)._seed = Some(value);
+        slf
+    }
+
     #[pyo3(text_signature = "(self)")]
     /// Validates if the configured parameters, if validation does not pass, throws
     /// an exception.
@@ -383,6 +407,7 @@ impl DpAggregateSeededParametersBuilder {
             use_synthetic_counts: self._use_synthetic_counts,
             weight_selection_percentile: self._weight_selection_percentile,
             aggregate_counts_scale_factor: self._aggregate_counts_scale_factor,
+            seed: self._seed,
         })
     }
 
@@ -22,6 +22,7 @@ pub struct DpAggregateSeededParameters {
     pub(crate) use_synthetic_counts: bool,
     pub(crate) weight_selection_percentile: usize,
     pub(crate) aggregate_counts_scale_factor: Option<f64>,
+    pub(crate) seed: Option<u64>,
 }
 
 #[pymethods]
@@ -1,14 +1,19 @@
 use super::{DpAggregateSeededParameters, DpAggregateSeededParametersBuilder};
-use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
 use sds_core::{
     data_block::DataBlock,
     dp::DpParameters,
     processing::{
-        aggregator::{AggregatedData, AggregatesCountStringMap, Aggregator},
+        aggregator::{
+            AggregatedData, AggregatesCountStringMap, Aggregator, DEFAULT_CONFIDENCE_LEVEL,
+        },
         generator::Generator,
     },
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     dataset::{Dataset, DatasetRawData},
@@ -70,14 +75,19 @@ impl DpAggregateSeededSynthesizer {
         self._aggregated_data = Some(Arc::new(
             Aggregator::new(dataset.data_block.clone()).aggregate_with_dp(
                 self._parameters.reporting_length,
-                &DpParameters::new(
-                    self._parameters.epsilon,
-                    self.delta_value_or_default(&dataset.data_block),
-                    self._parameters.percentile_percentage,
-                    self._parameters.percentile_epsilon_proportion,
-                    Some(self._parameters.sigma_proportions.clone()),
-                    Some(self._parameters.number_of_records_epsilon_proportion),
-                ),
+                &DpParameters {
+                    sigma_proportions: Some(self._parameters.sigma_proportions.clone()),
+                    number_of_records_epsilon_proportion: Some(
+                        self._parameters.number_of_records_epsilon_proportion,
+                    ),
+                    seed: self._parameters.seed,
+                    ..DpParameters::new(
+                        self._parameters.epsilon,
+                        self.delta_value_or_default(&dataset.data_block),
+                        self._parameters.percentile_percentage,
+                        self._parameters.percentile_epsilon_proportion,
+                    )
+                },
                 self._parameters.threshold.clone(),
                 &mut create_progress_reporter(),
             )?,
@@ -104,7 +114,7 @@ impl DpAggregateSeededSynthesizer {
         target_number_of_records: Option<usize>,
         join_multi_value_columns: Option<bool>,
     ) -> PyResult<DatasetRawData> {
-        let generator = Generator::default();
+        let generator = Generator::new(self._parameters.seed);
 
         if let Some(aggregated_data) = &self._aggregated_data {
             let generated_data = generator.generate_aggregate_seeded(
@@ -148,6 +158,51 @@ impl DpAggregateSeededSynthesizer {
             })
     }
 
+    #[pyo3(text_signature = "(self, combination_delimiter, confidence_level=0.95)")]
+    /// Gets the confidence intervals of the differentially private aggregate counts
+    /// computed with the `.fit` method.
+    ///
+    /// The intervals only take into account the noise added to the counts, so they
+    /// are an approximation after the counts are rounded. Counts made consistent
+    /// after the noise was added (least squares consistency) have no intervals.
+    ///
+    /// Arguments:
+    ///     * combination_delimiter: str - combination delimiter to use
+    ///     * confidence_level: Optional[float] - probability of the interval containing
+    ///     the true count (> 0 and < 1) - default is 0.95
+    ///
+    /// Returns:
+    ///     A dictionary with the combination string representation as key
+    ///     and the (lower, upper) interval bounds as value - dict[str, tuple[float, float]]
+    pub fn get_dp_aggregates_confidence_intervals(
+        &self,
+        combination_delimiter: &str,
+        confidence_level: Option<f64>,
+    ) -> PyResult<HashMap<String, (f64, f64)>> {
+        let confidence_level = confidence_level.unwrap_or(DEFAULT_CONFIDENCE_LEVEL);
+
+        if confidence_level <= 0.0 || confidence_level >= 1.0 {
+            return Err(PyValueError::new_err(
+                "confidence_level must be > 0 and < 1",
+            ));
+        }
+
+        self._aggregated_data
+            .as_ref()
+            .map(|ad| {
+                ad.aggregates_count_confidence_interval_as_str(
+                    combination_delimiter,
+                    confidence_level,
+                )
+                .into_iter()
+                .map(|(comb, interval)| (comb, (interval.lower, interval.upper)))
+                .collect()
+            })
+            .ok_or_else(|| {
+                PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
+            })
+    }
+
     #[pyo3(text_signature = "(self)")]
     /// Gets the differentially private number of records computed with the `.fit` method.
     ///
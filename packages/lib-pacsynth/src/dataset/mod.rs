@@ -1,7 +1,7 @@
 use self::dataset_data_block_creator::DatasetDataBlockCreator;
 use pyo3::{exceptions::PyValueError, prelude::*, types::IntoPyDict};
 use sds_core::{
-    data_block::{DataBlock, DataBlockCreator},
+    data_block::{DataBlock, DataBlockCreateOptions, DataBlockCreator},
     processing::aggregator::{AggregatesCountStringMap, Aggregator},
 };
 use std::{collections::HashMap, sync::Arc};
@@ -63,11 +63,14 @@ impl Dataset {
         Ok(Self {
             data_block: DatasetDataBlockCreator::create(
                 Ok(raw_data),
-                subject_id,
-                &use_columns.unwrap_or_default(),
-                &multi_value_columns.unwrap_or_default(),
-                &sensitive_zeros.unwrap_or_default(),
-                record_limit.unwrap_or_default(),
+                &DataBlockCreateOptions {
+                    subject_id,
+                    use_columns: use_columns.unwrap_or_default(),
+                    multi_value_columns: multi_value_columns.unwrap_or_default(),
+                    sensitive_zeros: sensitive_zeros.unwrap_or_default(),
+                    record_limit: record_limit.unwrap_or_default(),
+                    ..DataBlockCreateOptions::default()
+                },
             )?,
         })
     }
@@ -2,7 +2,10 @@ use csv::ReaderBuilder;
 use log::{log_enabled, Level::Debug};
 use pyo3::prelude::*;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreator},
+    data_block::{
+        CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreateOptions,
+        DataBlockCreator, SubjectRecordsMode,
+    },
     dp::DpParameters,
     dp::{InputValueByLen, NoisyCountThreshold},
     processing::{
@@ -45,6 +48,7 @@ impl SDSProcessor {
 impl SDSProcessor {
     #[inline]
     #[new]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         path: &str,
         delimiter: char,
@@ -53,16 +57,25 @@ impl SDSProcessor {
         multi_value_columns: HashMap<String, String>,
         sensitive_zeros: Vec<String>,
         record_limit: usize,
+        keep_subject_records: Option<bool>,
     ) -> Result<SDSProcessor, CsvDataBlockCreatorError> {
         CsvDataBlockCreator::create(
             ReaderBuilder::new()
                 .delimiter(delimiter as u8)
                 .from_path(path),
-            subject_id,
-            &use_columns,
-            &multi_value_columns,
-            &sensitive_zeros,
-            record_limit,
+            &DataBlockCreateOptions {
+                subject_id,
+                subject_records_mode: if keep_subject_records.unwrap_or(false) {
+                    SubjectRecordsMode::Keep
+                } else {
+                    SubjectRecordsMode::Join
+                },
+                use_columns,
+                multi_value_columns,
+                sensitive_zeros,
+                record_limit,
+                ..DataBlockCreateOptions::default()
+            },
         )
         .map(|data_block| SDSProcessor { data_block })
     }
@@ -113,13 +126,14 @@ impl SDSProcessor {
         resolution: usize,
         cache_max_size: usize,
         empty_value: &str,
+        seed: Option<u64>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
         } else {
             None
         };
-        let generator = Generator::default();
+        let generator = Generator::new(seed);
 
         generator.generate_row_seeded(
             &self.data_block,
@@ -135,13 +149,14 @@ impl SDSProcessor {
         resolution: usize,
         cache_max_size: usize,
         empty_value: &str,
+        seed: Option<u64>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
         } else {
             None
         };
-        let generator = Generator::default();
+        let generator = Generator::new(seed);
 
         generator.generate_unseeded(
             &self.data_block,
@@ -158,13 +173,14 @@ impl SDSProcessor {
         cache_max_size: usize,
         empty_value: &str,
         oversampling_parameters: Option<OversamplingParameters>,
+        seed: Option<u64>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
         } else {
             None
         };
-        let generator = Generator::default();
+        let generator = Generator::new(seed);
 
         generator.generate_value_seeded(
             &self.data_block,
@@ -176,6 +192,7 @@ impl SDSProcessor {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_aggregate_seeded(
         &self,
         empty_value: &str,
@@ -184,13 +201,14 @@ impl SDSProcessor {
         weight_selection_percentile: Option<usize>,
         aggregate_counts_scale_factor: Option<f64>,
         target_number_of_records: Option<usize>,
+        seed: Option<u64>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
         } else {
             None
         };
-        let generator = Generator::default();
+        let generator = Generator::new(seed);
 
         generator.generate_aggregate_seeded(
             empty_value,
@@ -202,4 +220,28 @@ impl SDSProcessor {
             &mut progress_reporter,
         )
     }
+
+    pub fn generate_marginal_model(
+        &self,
+        empty_value: &str,
+        aggregated_data: AggregatedData,
+        max_clique_size: Option<usize>,
+        target_number_of_records: Option<usize>,
+        seed: Option<u64>,
+    ) -> StoppableResult<GeneratedData> {
+        let mut progress_reporter = if log_enabled!(Debug) {
+            Some(LoggerProgressReporter::new(Debug))
+        } else {
+            None
+        };
+        let generator = Generator::new(seed);
+
+        generator.generate_marginal_model(
+            empty_value,
+            Arc::new(aggregated_data),
+            max_clique_size,
+            target_number_of_records,
+            &mut progress_reporter,
+        )
+    }
 }
@@ -1,5 +1,8 @@
 use js_sys::{Object, Reflect::set};
-use sds_core::{processing::aggregator::AggregatedData, utils::time::ElapsedDurationLogger};
+use sds_core::{
+    processing::aggregator::{AggregatedData, AggregatesCompression},
+    utils::time::ElapsedDurationLogger,
+};
 use std::{ops::Deref, sync::Arc};
 use wasm_bindgen::{prelude::*, JsCast};
 
@@ -66,12 +69,14 @@ impl WasmAggregateResult {
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> JsResult<String> {
         self.aggregated_data
             .write_aggregates_to_string(
                 aggregates_delimiter,
                 combination_delimiter,
                 case_insensitive_combinations_order,
+                confidence_level,
             )
             .map_err(|err| JsValue::from(err.to_string()))
     }
@@ -82,6 +87,7 @@ impl WasmAggregateResult {
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> JsResult<JsAggregateResult> {
         let _duration_logger =
             ElapsedDurationLogger::new(String::from("aggregate result serialization"));
@@ -100,12 +106,38 @@ impl WasmAggregateResult {
                     aggregates_delimiter,
                     combination_delimiter,
                     case_insensitive_combinations_order,
+                    confidence_level,
                 )?
                 .into(),
         )?;
 
         Ok(JsValue::from(result).unchecked_into::<JsAggregateResult>())
     }
+
+    #[wasm_bindgen(js_name = "toBinary")]
+    pub fn to_binary(&self, compression: &str) -> JsResult<Vec<u8>> {
+        let _duration_logger =
+            ElapsedDurationLogger::new(String::from("aggregate result binary serialization"));
+
+        self.aggregated_data
+            .to_binary_bytes(
+                compression
+                    .parse::<AggregatesCompression>()
+                    .map_err(JsValue::from)?,
+            )
+            .map_err(|err| JsValue::from(err.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "fromBinary")]
+    pub fn from_binary(bytes: &[u8]) -> JsResult<WasmAggregateResult> {
+        let _duration_logger =
+            ElapsedDurationLogger::new(String::from("aggregate result binary deserialization"));
+
+        Ok(WasmAggregateResult::new(Arc::new(
+            AggregatedData::from_binary_bytes(bytes)
+                .map_err(|err| JsValue::from(err.to_string()))?,
+        )))
+    }
 }
 
 impl Deref for WasmAggregateResult {
@@ -103,7 +103,7 @@ impl WasmNavigateResult {
             .flat_map(|values| values.iter().cloned())
             .collect();
 
-        combination.sort_by_key(|k| k.as_str_using_headers(&self.synthetic_data_block.headers));
+        combination.sort_unstable();
 
         sensitive_aggregate_result
             .aggregates_count
@@ -334,6 +334,7 @@ impl WasmSdsContext {
             aggregates_delimiter,
             combination_delimiter,
             case_insensitive_combinations_order,
+            None,
         )
     }
 
@@ -343,11 +344,13 @@ impl WasmSdsContext {
         aggregates_delimiter: char,
         combination_delimiter: &str,
         case_insensitive_combinations_order: Option<bool>,
+        confidence_level: Option<f64>,
     ) -> JsResult<JsAggregateResult> {
         self.get_reportable_aggregate_result()?.to_js(
             aggregates_delimiter,
             combination_delimiter,
             case_insensitive_combinations_order,
+            confidence_level,
         )
     }
 
@@ -362,6 +365,7 @@ impl WasmSdsContext {
             aggregates_delimiter,
             combination_delimiter,
             case_insensitive_combinations_order,
+            None,
         )
     }
 
@@ -10,6 +10,7 @@ pub struct WasmBaseSynthesisParameters {
     pub(crate) resolution: usize,
     pub(crate) cache_max_size: Option<usize>,
     pub(crate) empty_value: Option<String>,
+    pub(crate) seed: Option<u64>,
 }
 
 impl TryFrom<JsBaseSynthesisParameters> for WasmBaseSynthesisParameters {
@@ -1,5 +1,7 @@
 use super::{header_names::HeaderNames, MultiValueColumns};
+use sds_core::data_block::{DateTimeColumnSpec, NumericBinningStrategy, SubjectRecordsMode};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 use crate::utils::js::JsCsvDataParameters;
@@ -10,9 +12,15 @@ use crate::utils::js::JsCsvDataParameters;
 pub struct WasmCsvDataParameters {
     pub(crate) delimiter: char,
     pub(crate) subject_id: Option<String>,
+    #[serde(default)]
+    pub(crate) subject_records_mode: SubjectRecordsMode,
     pub(crate) use_columns: HeaderNames,
     pub(crate) multi_value_columns: MultiValueColumns,
     pub(crate) sensitive_zeros: HeaderNames,
+    #[serde(default)]
+    pub(crate) numeric_binning: HashMap<String, NumericBinningStrategy>,
+    #[serde(default)]
+    pub(crate) date_time_columns: HashMap<String, DateTimeColumnSpec>,
     pub(crate) record_limit: usize,
 }
 
@@ -2,7 +2,10 @@ use super::{WasmBaseSynthesisParameters, WasmOversamplingParameters};
 use csv::ReaderBuilder;
 use js_sys::Function;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlock, DataBlockCreator},
+    data_block::{
+        data_derived_binned_columns, CsvDataBlockCreator, DataBlock, DataBlockCreateOptions,
+        DataBlockCreator,
+    },
     dp::{DpParameters, NoisyCountThreshold},
     processing::{
         aggregator::Aggregator,
@@ -50,11 +53,16 @@ impl WasmSdsProcessor {
             Ok(ReaderBuilder::new()
                 .delimiter(csv_data_params.delimiter as u8)
                 .from_reader(Cursor::new(csv_data))),
-            csv_data_params.subject_id.clone(),
-            &csv_data_params.use_columns,
-            &csv_data_params.multi_value_columns,
-            &csv_data_params.sensitive_zeros,
-            csv_data_params.record_limit,
+            &DataBlockCreateOptions {
+                subject_id: csv_data_params.subject_id.clone(),
+                subject_records_mode: csv_data_params.subject_records_mode,
+                use_columns: csv_data_params.use_columns.clone(),
+                multi_value_columns: csv_data_params.multi_value_columns.clone(),
+                sensitive_zeros: csv_data_params.sensitive_zeros.clone(),
+                numeric_binning: csv_data_params.numeric_binning.clone(),
+                date_time_columns: csv_data_params.date_time_columns.clone(),
+                record_limit: csv_data_params.record_limit,
+            },
         )
         .map_err(|err| JsValue::from(err.to_string()))?;
 
@@ -203,6 +211,20 @@ impl WasmSdsProcessor {
         threshold: JsNoisyCountThreshold,
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmAggregateResult> {
+        let data_derived_columns =
+            data_derived_binned_columns(&self.data_block.numeric_bins_metadata_map);
+
+        if !data_derived_columns.is_empty() {
+            return Err(JsValue::from(format!(
+                "numeric columns binned from the data can not be aggregated with DP, use explicit bin edges for: {}",
+                data_derived_columns
+                    .iter()
+                    .map(|column| column.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )));
+        }
+
         let aggregator = Aggregator::new(self.data_block.clone());
 
         Ok(WasmAggregateResult::new(Arc::new(
@@ -223,7 +245,7 @@ impl WasmSdsProcessor {
         base_parameters: &WasmBaseSynthesisParameters,
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmGenerateResult> {
-        let generator = Generator::default();
+        let generator = Generator::new(base_parameters.seed);
         let (resolution, cache_max_size, empty_value) =
             WasmSdsProcessor::unwrap_base_synthesis_parameters_or_default(base_parameters);
 
@@ -247,7 +269,7 @@ impl WasmSdsProcessor {
         base_parameters: &WasmBaseSynthesisParameters,
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmGenerateResult> {
-        let generator = Generator::default();
+        let generator = Generator::new(base_parameters.seed);
         let (resolution, cache_max_size, empty_value) =
             WasmSdsProcessor::unwrap_base_synthesis_parameters_or_default(base_parameters);
 
@@ -273,7 +295,7 @@ impl WasmSdsProcessor {
         oversampling_parameters: Option<WasmOversamplingParameters>,
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmGenerateResult> {
-        let generator = Generator::default();
+        let generator = Generator::new(base_parameters.seed);
         let (resolution, cache_max_size, empty_value) =
             WasmSdsProcessor::unwrap_base_synthesis_parameters_or_default(base_parameters);
 
@@ -307,7 +329,7 @@ impl WasmSdsProcessor {
         weight_selection_percentile: Option<usize>,
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmGenerateResult> {
-        let generator = Generator::default();
+        let generator = Generator::new(base_parameters.seed);
         let (resolution, _, empty_value) =
             WasmSdsProcessor::unwrap_base_synthesis_parameters_or_default(base_parameters);
 
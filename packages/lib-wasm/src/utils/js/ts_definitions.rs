@@ -10,12 +10,58 @@ export interface IMultiValueColumns {
   [headerName: string]: string
 }
 
+export enum NumericBinningStrategyType {
+  FixedWidth = 'FixedWidth',
+  Quantile = 'Quantile',
+  Edges = 'Edges'
+}
+
+export interface INumericBinningStrategy {
+  type: NumericBinningStrategyType
+  value: number | number[]
+}
+
+export interface INumericBinning {
+  [headerName: string]: INumericBinningStrategy
+}
+
+export enum DateTimeGranularityType {
+  Year = 'Year',
+  Quarter = 'Quarter',
+  Month = 'Month',
+  Week = 'Week',
+  DayOfWeek = 'DayOfWeek',
+  HourBucket = 'HourBucket'
+}
+
+export interface IDateTimeGranularity {
+  type: DateTimeGranularityType
+  value?: number
+}
+
+export interface IDateTimeColumnSpec {
+  inputFormat?: string
+  granularity: IDateTimeGranularity
+}
+
+export interface IDateTimeColumns {
+  [headerName: string]: IDateTimeColumnSpec
+}
+
+export enum SubjectRecordsMode {
+  Join = 'Join',
+  Keep = 'Keep'
+}
+
 export interface ICsvDataParameters {
   delimiter: string
   subjectId?: string
+  subjectRecordsMode?: SubjectRecordsMode
   useColumns: HeaderNames
   multiValueColumns: IMultiValueColumns
   sensitiveZeros: HeaderNames
+  numericBinning?: INumericBinning
+  dateTimeColumns?: IDateTimeColumns
   recordLimit: number
 }
 
@@ -51,6 +97,14 @@ export interface IDpParameters {
   percentileEpsilonProportion: number
   numberOfRecordsEpsilonProportion?: number
   sigmaProportions?: number[]
+  seed?: number
+  maxRecordsPerSubject?: number
+  useLeastSquaresConsistency?: boolean
+  useDiscreteNoise?: boolean
+  candidateMinUpperBound?: number
+  maxCandidatesPerLen?: number
+  columnWeights?: Record<string, number>
+  excludedColumnPairs?: [string, string][]
 }
 
 export interface IOversamplingParameters {
@@ -62,6 +116,7 @@ export interface IBaseSynthesisParameters {
   resolution: usize,
   cacheMaxSize?: number
   emptyValue?: string
+  seed?: number
 }
 
 export interface IGenerateResult {
@@ -21,6 +21,7 @@ def aggregate(config):
     """
 
     subject_id = config['subject_id']
+    keep_subject_records = config['keep_subject_records']
     use_columns = config['use_columns']
     multi_value_columns = config['multi_value_columns']
     reporting_length = config['reporting_length']
@@ -46,6 +47,14 @@ def aggregate(config):
     noise_threshold_type = config['noise_threshold_type']
     noise_threshold_values = config['noise_threshold_values']
     number_of_records_epsilon_proportion = config['number_of_records_epsilon_proportion']
+    max_records_per_subject = config['max_records_per_subject']
+    least_squares_consistency = config['least_squares_consistency']
+    discrete_noise = config['discrete_noise']
+    candidate_min_upper_bound = config['candidate_min_upper_bound']
+    max_candidates_per_len = config['max_candidates_per_len']
+    column_weights = config['column_weights']
+    excluded_column_pairs = config['excluded_column_pairs']
+    seed = config['seed']
 
     logging.info(f'Aggregate {sensitive_microdata_path}')
     start_time = time.time()
@@ -57,7 +66,8 @@ def aggregate(config):
         use_columns,
         multi_value_columns,
         sensitive_zeros,
-        max(record_limit, 0)
+        max(record_limit, 0),
+        keep_subject_records
     )
 
     aggregated_data = sds_processor.aggregate(
@@ -90,7 +100,15 @@ def aggregate(config):
                     percentile_percentage,
                     percentile_epsilon_proportion,
                     sigma_proportions,
-                    number_of_records_epsilon_proportion
+                    number_of_records_epsilon_proportion,
+                    seed,
+                    max_records_per_subject,
+                    least_squares_consistency,
+                    discrete_noise,
+                    candidate_min_upper_bound,
+                    max_candidates_per_len,
+                    column_weights,
+                    excluded_column_pairs
                 ),
                 noise_threshold_values
             )
@@ -103,7 +121,15 @@ def aggregate(config):
                     percentile_percentage,
                     percentile_epsilon_proportion,
                     sigma_proportions,
-                    number_of_records_epsilon_proportion
+                    number_of_records_epsilon_proportion,
+                    seed,
+                    max_records_per_subject,
+                    least_squares_consistency,
+                    discrete_noise,
+                    candidate_min_upper_bound,
+                    max_candidates_per_len,
+                    column_weights,
+                    excluded_column_pairs
                 ),
                 noise_threshold_values
             )
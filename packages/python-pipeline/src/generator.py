@@ -16,6 +16,7 @@ def generate(config):
     """
 
     subject_id = config['subject_id']
+    keep_subject_records = config['keep_subject_records']
     use_columns = config['use_columns']
     multi_value_columns = config['multi_value_columns']
     record_limit = config['record_limit']
@@ -37,6 +38,7 @@ def generate(config):
     weight_selection_percentile = config['weight_selection_percentile']
     aggregate_seeded_counts_scale_factor = config['aggregate_seeded_counts_scale_factor']
     aggregate_seeded_target_number_of_records = config['aggregate_seeded_target_number_of_records']
+    seed = config['seed']
 
     logging.info(f'Generate {sensitive_microdata_path}')
     start_time = time.time()
@@ -50,7 +52,8 @@ def generate(config):
         use_columns,
         multi_value_columns,
         sensitive_zeros,
-        max(record_limit, 0)
+        max(record_limit, 0),
+        keep_subject_records
     )
 
     if synthesis_mode == 'unseeded':
@@ -58,12 +61,14 @@ def generate(config):
             resolution,
             cache_max_size,
             "",
+            seed
         )
     elif synthesis_mode == 'row_seeded':
         generated_data = sds_processor.generate_row_seeded(
             resolution,
             cache_max_size,
             "",
+            seed
         )
     elif synthesis_mode == 'value_seeded':
         if oversampling_ratio != None:
@@ -79,7 +84,8 @@ def generate(config):
             resolution,
             cache_max_size,
             "",
-            oversampling_parameters
+            oversampling_parameters,
+            seed
         )
     elif synthesis_mode == 'aggregate_seeded':
         generated_data = sds_processor.generate_aggregate_seeded(
@@ -88,7 +94,8 @@ def generate(config):
             use_synthetic_counts,
             weight_selection_percentile,
             aggregate_seeded_counts_scale_factor,
-            aggregate_seeded_target_number_of_records
+            aggregate_seeded_target_number_of_records,
+            seed
         )
     else:
         raise ValueError(f'invalid synthesis mode: {synthesis_mode}')
@@ -66,9 +66,12 @@ def runForConfig(config):
     # set based on the number of cores/memory available
     config['parallel_jobs'] = config.get('parallel_jobs', 1)
     config['cache_max_size'] = config.get('cache_max_size', 100000)
+    # random seed (None means a new random seed on every run)
+    config['seed'] = config.get('seed', None)
 
     # numeric parameters controlling synthesis and aggregation
     config['subject_id'] = config.get('subject_id', None)
+    config['keep_subject_records'] = config.get('keep_subject_records', False)
     config['use_columns'] = config.get('use_columns', [])
     config['multi_value_columns'] = config.get('multi_value_columns', {})
     config['record_limit'] = config.get(
@@ -93,6 +96,19 @@ def runForConfig(config):
         int(l): t for l, t in config.get('noise_threshold_values', {}).items()}
     config['number_of_records_epsilon_proportion'] = config.get(
         'number_of_records_epsilon_proportion', None)
+    config['max_records_per_subject'] = config.get(
+        'max_records_per_subject', None)
+    config['least_squares_consistency'] = config.get(
+        'least_squares_consistency', False)
+    config['discrete_noise'] = config.get(
+        'discrete_noise', False)
+    config['candidate_min_upper_bound'] = config.get(
+        'candidate_min_upper_bound', None)
+    config['max_candidates_per_len'] = config.get(
+        'max_candidates_per_len', None)
+    config['column_weights'] = config.get('column_weights', None)
+    config['excluded_column_pairs'] = config.get(
+        'excluded_column_pairs', None)
 
     # parameters affecting the representation and interpretation of values
     config['sensitive_zeros'] = config.get('sensitive_zeros', [])